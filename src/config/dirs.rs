@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static APP_DIRS: OnceLock<AppDirs> = OnceLock::new();
+
+/// 配置目录与数据目录
+#[derive(Debug, Clone)]
+pub struct AppDirs {
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
+}
+
+impl AppDirs {
+    /// 解析目录，优先级：
+    /// 1. 显式指定的 `--data-dir`（其下的 config/ 和 data/）
+    /// 2. 当前目录已有 config/settings.toml 的旧布局
+    /// 3. 平台标准位置（XDG、%APPDATA%、~/Library/Application Support）
+    ///
+    /// 指定配置档案时，配置和数据分别放在上述目录下的 `profiles/<档案名>/` 中，
+    /// 各档案的订阅、API 密钥和数据库互相独立。
+    ///
+    /// 显式指定的 `--config` 目录原样使用，优先于以上规则，也不按配置档案区分。
+    pub fn resolve(data_dir_override: Option<&Path>, config_dir_override: Option<&Path>, profile: Option<&str>) -> Self {
+        let dirs = Self::resolve_base(data_dir_override);
+        let dirs = match profile {
+            Some(name) => Self {
+                config_dir: dirs.config_dir.join("profiles").join(name),
+                data_dir: dirs.data_dir.join("profiles").join(name),
+            },
+            None => dirs,
+        };
+        match config_dir_override {
+            Some(config_dir) => Self {
+                config_dir: config_dir.to_path_buf(),
+                ..dirs
+            },
+            None => dirs,
+        }
+    }
+
+    fn resolve_base(data_dir_override: Option<&Path>) -> Self {
+        if let Some(root) = data_dir_override {
+            return Self::rooted_at(root);
+        }
+
+        if Path::new("config/settings.toml").exists() {
+            return Self::rooted_at(Path::new("."));
+        }
+
+        match directories::ProjectDirs::from("", "", "bsxbot") {
+            Some(proj) => Self {
+                config_dir: proj.config_dir().to_path_buf(),
+                data_dir: proj.data_dir().to_path_buf(),
+            },
+            // 无法确定主目录时退回当前目录
+            None => Self::rooted_at(Path::new(".")),
+        }
+    }
+
+    fn rooted_at(root: &Path) -> Self {
+        Self {
+            config_dir: root.join("config"),
+            data_dir: root.join("data"),
+        }
+    }
+
+    pub fn settings_file(&self) -> PathBuf {
+        self.config_dir.join("settings.toml")
+    }
+
+    pub fn keywords_file(&self) -> PathBuf {
+        self.config_dir.join("keywords.toml")
+    }
+
+    /// 报告模板目录，其中的 report.html.tera / paper.html.tera 覆盖内置模板
+    pub fn templates_dir(&self) -> PathBuf {
+        self.config_dir.join("templates")
+    }
+
+    pub fn papers_dir(&self) -> PathBuf {
+        self.data_dir.join("papers")
+    }
+
+    pub fn images_dir(&self) -> PathBuf {
+        self.data_dir.join("images")
+    }
+
+    pub fn tables_dir(&self) -> PathBuf {
+        self.data_dir.join("tables")
+    }
+
+    pub fn reports_dir(&self) -> PathBuf {
+        self.data_dir.join("reports")
+    }
+
+    pub fn site_dir(&self) -> PathBuf {
+        self.data_dir.join("site")
+    }
+
+    pub fn backups_dir(&self) -> PathBuf {
+        self.data_dir.join("backups")
+    }
+
+    pub fn logs_dir(&self) -> PathBuf {
+        self.data_dir.join("logs")
+    }
+
+    pub fn default_database(&self) -> PathBuf {
+        self.data_dir.join("papers.db")
+    }
+
+    /// 将数据库中保存的相对路径（相对数据目录）解析为实际路径
+    ///
+    /// 旧版本保存的是相对当前目录的 `data/...` 路径，找不到时按原样使用。
+    pub fn resolve_data_path(&self, stored: &str) -> PathBuf {
+        let path = Path::new(stored);
+        if path.is_absolute() {
+            return path.to_path_buf();
+        }
+        let resolved = self.data_dir.join(path);
+        if !resolved.exists() && stored.starts_with("data/") && path.exists() {
+            return path.to_path_buf();
+        }
+        resolved
+    }
+}
+
+/// 检查配置档案名称：只能包含字母、数字、`-` 和 `_`，用作目录名
+pub fn parse_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("配置档案名称不能为空".to_string());
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("配置档案名称 '{}' 只能包含字母、数字、- 和 _", name));
+    }
+    Ok(name.to_string())
+}
+
+/// 在程序启动时确定目录，之后的调用均返回同一结果
+pub fn init(data_dir_override: Option<&Path>, config_dir_override: Option<&Path>, profile: Option<&str>) -> &'static AppDirs {
+    APP_DIRS.get_or_init(|| AppDirs::resolve(data_dir_override, config_dir_override, profile))
+}
+
+/// 获取当前目录配置，未初始化时按默认规则解析
+pub fn get() -> &'static AppDirs {
+    APP_DIRS.get_or_init(|| AppDirs::resolve(None, None, None))
+}
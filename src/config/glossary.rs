@@ -0,0 +1,63 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 术语表：英文术语 -> 指定译法，来自 glossary.toml
+///
+/// ```toml
+/// [terms]
+/// transformer = "Transformer"
+/// "attention mechanism" = "注意力机制"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Glossary {
+    #[serde(default)]
+    pub terms: BTreeMap<String, String>,
+}
+
+impl Glossary {
+    pub fn load() -> Result<Self> {
+        let config_path = super::dirs::get().config_dir.join("glossary.toml");
+
+        if !config_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(config_path)?;
+        let glossary: Glossary = toml::from_str(&content)?;
+        Ok(glossary)
+    }
+
+    /// 原文中出现的术语（不区分大小写）
+    pub fn matching_terms(&self, text: &str) -> Vec<(&str, &str)> {
+        let lower = text.to_lowercase();
+        self.terms
+            .iter()
+            .filter(|(term, _)| lower.contains(&term.to_lowercase()))
+            .map(|(term, rendering)| (term.as_str(), rendering.as_str()))
+            .collect()
+    }
+
+    /// 生成注入提示词的术语要求，原文未出现任何术语时返回 None
+    pub fn prompt_rule(&self, text: &str) -> Option<String> {
+        let terms = self.matching_terms(text);
+        if terms.is_empty() {
+            return None;
+        }
+        let list = terms
+            .iter()
+            .map(|(term, rendering)| format!("{} -> {}", term, rendering))
+            .collect::<Vec<_>>()
+            .join("；");
+        Some(format!("以下术语必须使用指定译法：{}", list))
+    }
+
+    /// 检查译文是否使用了指定译法，返回未遵守的术语
+    pub fn violations(&self, source: &str, output: &str) -> Vec<String> {
+        self.matching_terms(source)
+            .into_iter()
+            .filter(|(_, rendering)| !output.contains(rendering))
+            .map(|(term, rendering)| format!("{} -> {}", term, rendering))
+            .collect()
+    }
+}
@@ -1,10 +1,13 @@
 use serde::{Deserialize, Serialize};
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::Path;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Subscription {
     pub name: String,
+    /// 用一段话描述的研究问题，所有 LLM 功能共用
+    #[serde(default)]
+    pub description: String,
     pub keywords: Vec<String>,
     pub sources: Vec<String>,
     pub categories: Vec<String>,
@@ -18,7 +21,7 @@ pub struct KeywordConfig {
 
 impl KeywordConfig {
     pub fn load() -> Result<Self> {
-        let config_path = PathBuf::from("config/keywords.toml");
+        let config_path = super::dirs::get().keywords_file();
 
         if !config_path.exists() {
             return Ok(Self::default());
@@ -29,17 +32,57 @@ impl KeywordConfig {
         Ok(config)
     }
 
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let content = toml::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
     pub fn get_active_subscriptions(&self) -> Vec<&Subscription> {
         self.subscriptions.iter().filter(|s| s.enabled).collect()
     }
 }
 
+impl Subscription {
+    /// 由保存的搜索生成订阅，默认从 arXiv 爬取
+    pub fn from_search(query: &str) -> Self {
+        Self {
+            name: query.to_string(),
+            description: String::new(),
+            keywords: vec![query.to_string()],
+            sources: vec!["arxiv".to_string()],
+            categories: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// 提供给所有 LLM 功能（初筛、相关度判断、摘要等）的订阅上下文
+    ///
+    /// description 是用一段话描述的研究问题，未配置时只列出名称、关键词和分类。
+    pub fn llm_context(&self) -> String {
+        let mut context = format!("订阅名称：{}\n", self.name);
+        if !self.description.trim().is_empty() {
+            context.push_str(&format!("研究问题：{}\n", self.description.trim()));
+        }
+        context.push_str(&format!("关键词：{}\n", self.keywords.join(", ")));
+        if !self.categories.is_empty() {
+            context.push_str(&format!("关注分类：{}\n", self.categories.join(", ")));
+        }
+        context
+    }
+
+    pub fn has_description(&self) -> bool {
+        !self.description.trim().is_empty()
+    }
+}
+
 impl Default for KeywordConfig {
     fn default() -> Self {
         Self {
             subscriptions: vec![
                 Subscription {
                     name: "机器学习".to_string(),
+                    description: "机器学习方法与理论研究，包括深度学习模型、训练算法和神经网络架构".to_string(),
                     keywords: vec![
                         "machine learning".to_string(),
                         "deep learning".to_string(),
@@ -1,9 +1,15 @@
+pub mod dirs;
+pub mod glossary;
 pub mod keywords;
+pub mod settings;
+pub mod validate;
 
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use anyhow::Result;
 
+pub use glossary::Glossary;
 pub use keywords::KeywordConfig;
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -12,6 +18,16 @@ pub struct AppConfig {
     pub translator: TranslatorConfig,
     pub generator: GeneratorConfig,
     pub storage: StorageConfig,
+    #[serde(default)]
+    pub publisher: PublisherConfig,
+    #[serde(default)]
+    pub notifier: NotifierConfig,
+    #[serde(default)]
+    pub notify: NotifyConfig,
+    #[serde(default)]
+    pub notion: NotionConfig,
+    #[serde(default)]
+    pub parser: ParserConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -19,6 +35,79 @@ pub struct CrawlerConfig {
     pub max_papers_per_day: usize,
     pub request_delay_ms: u64,
     pub user_agent: String,
+    /// 下载PDF前用 LLM 根据标题和摘要初筛，剔除明显不相关的论文
+    #[serde(default)]
+    pub llm_triage: bool,
+    /// 初筛使用的模型，留空则沿用 translator.model
+    #[serde(default)]
+    pub triage_model: String,
+    /// 用 LLM 为关键词补充同义词和缩写，扩展爬虫查询（按订阅缓存）
+    #[serde(default)]
+    pub query_expansion: bool,
+    /// PDF 解析后用 LLM 生成 3–5 条要点，显示在报告中
+    #[serde(default)]
+    pub llm_key_points: bool,
+    /// 用 LLM 按订阅的研究重点给论文打相关度标签（core / related / off_topic）
+    #[serde(default)]
+    pub llm_relevance: bool,
+    /// 按数据源覆盖请求礼貌策略，如 `[crawler.politeness.arxiv]`；未配置的数据源使用内置默认值
+    #[serde(default)]
+    pub politeness: HashMap<String, PolitenessConfig>,
+}
+
+/// 单个数据源的请求礼貌策略，由共享的 HTTP 抓取器统一执行
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct PolitenessConfig {
+    /// 相邻两次请求的最小间隔（毫秒）
+    pub delay_ms: u64,
+    /// 同时进行的请求数
+    #[serde(default = "default_politeness_concurrency")]
+    pub max_concurrent: usize,
+    /// 空闲后允许连续发出的请求数
+    #[serde(default = "default_politeness_burst")]
+    pub burst: usize,
+}
+
+fn default_politeness_concurrency() -> usize {
+    1
+}
+
+fn default_politeness_burst() -> usize {
+    1
+}
+
+impl PolitenessConfig {
+    /// 已知数据源的默认策略
+    ///
+    /// - arXiv: 每 3 秒 1 次请求
+    /// - Semantic Scholar: 每 5 分钟 100 次（无 API key）
+    /// - PubMed: 无 API key 每秒 3 次，配置 key 后可改为 `delay_ms = 100`
+    /// - Crossref: 每秒 1 次（导入本地PDF时按 DOI 查询）
+    pub fn builtin(source: &str) -> Option<Self> {
+        let (delay_ms, max_concurrent, burst) = match source {
+            "arxiv" => (3000, 1, 1),
+            "semantic_scholar" => (3000, 1, 1),
+            "pubmed" => (334, 3, 3),
+            "crossref" => (1000, 1, 1),
+            _ => return None,
+        };
+        Some(Self { delay_ms, max_concurrent, burst })
+    }
+}
+
+impl CrawlerConfig {
+    /// 数据源的礼貌策略：配置覆盖 > 内置默认值 > 全局 request_delay_ms
+    pub fn politeness_for(&self, source: &str) -> PolitenessConfig {
+        self.politeness
+            .get(source)
+            .copied()
+            .or_else(|| PolitenessConfig::builtin(source))
+            .unwrap_or(PolitenessConfig {
+                delay_ms: self.request_delay_ms,
+                max_concurrent: 1,
+                burst: 1,
+            })
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -30,6 +119,66 @@ pub struct TranslatorConfig {
     pub target_language: String,
     #[serde(default)]
     pub proxy: String,
+    /// Ollama 服务地址，仅在 api_provider = "ollama" 时使用
+    #[serde(default = "default_ollama_host")]
+    pub ollama_host: String,
+    /// 同时进行的翻译请求数
+    #[serde(default = "default_max_concurrent")]
+    pub max_concurrent: usize,
+    /// 每分钟请求上限，0 表示使用服务商的默认值
+    #[serde(default)]
+    pub requests_per_minute: u32,
+    /// 翻译论文时要求接口返回 JSON（response_format / Ollama format），接口不支持时可关闭
+    #[serde(default = "default_json_mode")]
+    pub json_mode: bool,
+    /// 单次翻译请求的估算 token 上限，超出的文本按句子切分后分段翻译
+    #[serde(default = "default_max_chunk_tokens")]
+    pub max_chunk_tokens: usize,
+    /// 生成论文向量的嵌入模型（similar 命令使用），留空表示不生成
+    #[serde(default)]
+    pub embedding_model: String,
+    /// 备用服务商，主服务商持续返回 429/5xx 时按顺序切换
+    #[serde(default)]
+    pub fallbacks: Vec<FallbackProviderConfig>,
+}
+
+/// 备用翻译服务商配置，字段含义同 TranslatorConfig
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FallbackProviderConfig {
+    pub api_provider: String,
+    #[serde(default)]
+    pub api_key: String,
+    #[serde(default)]
+    pub api_url: String,
+    pub model: String,
+    #[serde(default = "default_ollama_host")]
+    pub ollama_host: String,
+    #[serde(default)]
+    pub requests_per_minute: u32,
+}
+
+impl FallbackProviderConfig {
+    /// 非 Ollama 服务商必须填写 api_url，没有可回退的默认地址
+    pub fn missing_api_url(&self) -> bool {
+        crate::translator::Provider::from_name(&self.api_provider) != crate::translator::Provider::Ollama
+            && self.api_url.trim().is_empty()
+    }
+}
+
+fn default_json_mode() -> bool {
+    true
+}
+
+fn default_max_chunk_tokens() -> usize {
+    1500
+}
+
+fn default_max_concurrent() -> usize {
+    4
+}
+
+fn default_ollama_host() -> String {
+    "http://localhost:11434".to_string()
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -38,17 +187,302 @@ pub struct GeneratorConfig {
     pub max_papers_per_report: usize,
     pub include_images: bool,
     pub include_formulas: bool,
+    /// 报告中只显示这些章节（按标题匹配，留空显示全部）
+    #[serde(default)]
+    pub pinned_sections: Vec<String>,
+    /// 报告中隐藏的章节
+    #[serde(default)]
+    pub hidden_sections: Vec<String>,
+    /// 报告只收录不低于该相关度标签的论文（core / related），留空不过滤；未打标签的论文始终收录
+    #[serde(default)]
+    pub min_relevance: String,
+    /// 报告隐藏相关度（0~1）低于该值的论文，0 表示不过滤；没有相关度记录的论文始终收录
+    #[serde(default)]
+    pub min_relevance_score: f32,
+    /// 报告中隐藏可信度低于该值（0~1）的章节、公式和表格，0 表示不过滤
+    #[serde(default)]
+    pub min_confidence: f32,
+    /// 自定义模板变量，在报告模板中以 {{ 名称 }} 引用（如 report_title）
+    #[serde(default)]
+    pub template_vars: HashMap<String, String>,
+    /// HTML 报告配色：auto（跟随系统深色模式）、light 或 dark
+    #[serde(default = "default_theme")]
+    pub theme: String,
+    /// 追加到报告样式之后的 CSS 文件，相对路径基于配置目录，留空不使用
+    #[serde(default)]
+    pub custom_css: String,
+    /// 每次爬取后更新的订阅源（reports/feed.xml）收录的最新论文数，0 表示不生成
+    #[serde(default = "default_feed_size")]
+    pub feed_size: usize,
+}
+
+fn default_theme() -> String {
+    "auto".to_string()
+}
+
+fn default_feed_size() -> usize {
+    50
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct StorageConfig {
+    /// 数据库文件路径，留空则使用数据目录下的 papers.db；相对路径相对数据目录
+    #[serde(default)]
     pub database_path: String,
     pub cache_ttl_days: u32,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub sqlite: SqliteConfig,
+}
+
+/// SQLite 连接池和 PRAGMA 设置
+///
+/// 默认使用 WAL 日志模式，爬取写入时报告等命令仍可同时读取；写入冲突时等待 `busy_timeout_secs` 秒，
+/// 而不是立即报「database is locked」。
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SqliteConfig {
+    /// 连接池最大连接数
+    pub max_connections: u32,
+    /// 等待其他连接释放锁的最长时间（秒）
+    pub busy_timeout_secs: u64,
+    /// 日志模式：wal、delete、truncate、persist、memory、off
+    pub journal_mode: String,
+    /// 同步模式：off、normal、full、extra；WAL 模式下 normal 已足够安全
+    pub synchronous: String,
+    /// 其他 PRAGMA，如 `cache_size = "-20000"`
+    pub pragmas: HashMap<String, String>,
+}
+
+impl Default for SqliteConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 5,
+            busy_timeout_secs: 30,
+            journal_mode: "wal".to_string(),
+            synchronous: "normal".to_string(),
+            pragmas: HashMap::new(),
+        }
+    }
+}
+
+/// `db backup` 和爬取前自动备份的设置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    /// 备份目录，留空则使用数据目录下的 backups；相对路径相对数据目录
+    pub dir: String,
+    /// 保留最新的备份数，0 表示全部保留
+    pub keep: usize,
+    /// 每次爬取前自动备份
+    pub before_crawl: bool,
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            dir: "".to_string(),
+            keep: 7,
+            before_crawl: false,
+        }
+    }
+}
+
+/// 报告发布配置：生成报告后上传到 HTTP 端点
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PublisherConfig {
+    pub enabled: bool,
+    /// 上传地址，可包含 {date} 占位符
+    pub endpoint: String,
+    /// "PUT" 或 "POST"
+    pub method: String,
+    /// "html" 只上传报告页面，"tarball" 上传含图片的 tar.gz
+    pub format: String,
+    /// 非空时以 Bearer token 发送 Authorization 头
+    pub auth_token: String,
+}
+
+impl Default for PublisherConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: "".to_string(),
+            method: "PUT".to_string(),
+            format: "html".to_string(),
+            auth_token: "".to_string(),
+        }
+    }
+}
+
+/// `export --notion` 写入的 Notion 数据库
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotionConfig {
+    /// Notion 集成（integration）的密钥，数据库需要共享给该集成
+    pub token: String,
+    /// 数据库 ID，即数据库链接中 32 位的十六进制串
+    pub database_id: String,
+}
+
+/// 报告投递渠道
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    pub email: EmailDigestConfig,
+}
+
+/// SMTP 服务器设置，[notifier] 和 [notify.email] 共用，字段直接写在各自的表中
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SmtpConfig {
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    /// true 使用 STARTTLS（通常为 587 端口），false 使用隐式 TLS（465 端口）
+    pub smtp_starttls: bool,
+    pub smtp_user: String,
+    pub smtp_password: String,
+}
+
+impl Default for SmtpConfig {
+    fn default() -> Self {
+        Self {
+            smtp_host: "".to_string(),
+            smtp_port: 587,
+            smtp_starttls: true,
+            smtp_user: "".to_string(),
+            smtp_password: "".to_string(),
+        }
+    }
+}
+
+impl SmtpConfig {
+    pub fn is_configured(&self) -> bool {
+        !self.smtp_host.trim().is_empty()
+    }
+}
+
+/// 通过 SMTP 把生成的报告发送给收件人；SMTP 相关字段留空时沿用 [notifier] 的设置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct EmailDigestConfig {
+    /// 每次生成报告后自动发送，关闭时仍可用 `report --send` 手动发送
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub smtp: SmtpConfig,
+    /// 发件地址，留空使用 smtp_user
+    pub from: String,
+    pub to: Vec<String>,
+    /// true 把报告作为附件发送，false 把 HTML 报告直接作为邮件正文
+    pub attach: bool,
+    /// 发送失败后的重试次数
+    pub max_retries: u32,
+}
+
+impl Default for EmailDigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            smtp: SmtpConfig::default(),
+            from: "".to_string(),
+            to: Vec::new(),
+            attach: false,
+            max_retries: 3,
+        }
+    }
+}
+
+impl EmailDigestConfig {
+    /// 空缺的 SMTP 设置用 [notifier] 中的值补齐
+    pub fn with_fallback(&self, notifier: &NotifierConfig) -> Self {
+        let mut config = self.clone();
+        if !config.smtp.is_configured() {
+            config.smtp = notifier.smtp.clone();
+            if config.from.trim().is_empty() {
+                config.from = notifier.email_from.clone();
+            }
+        }
+        config
+    }
+}
+
+/// PDF 解析配置
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ParserConfig {
+    /// 按文字坐标重排双栏论文的文本，关闭后使用 pdf_extract 的原始输出顺序
+    pub layout_aware: bool,
+    /// 「Figure N」图注没有对应的嵌入图片（矢量图）时，栅格化图注上方的页面区域
+    pub rasterize_figures: bool,
+    /// 栅格化命令，需兼容 poppler 的 pdftoppm 参数；以 pdfium 特性编译且 libpdfium 可用时不使用
+    pub rasterizer_command: String,
+    pub rasterize_dpi: u32,
+    /// libpdfium 所在目录，留空时从系统库路径加载（仅以 pdfium 特性编译时使用）
+    pub pdfium_library: String,
+    /// 每篇论文保留的关键短语数
+    pub keyphrase_count: usize,
+    /// 像素面积（宽×高）小于该值的嵌入图片视为图标、徽标，不提取
+    pub min_image_area: u32,
+    /// 生成报告时同时解析的 PDF 数，0 表示与 CPU 核数相同
+    pub max_parallel: usize,
+}
+
+impl Default for ParserConfig {
+    fn default() -> Self {
+        Self {
+            layout_aware: true,
+            rasterize_figures: false,
+            rasterizer_command: "pdftoppm".to_string(),
+            rasterize_dpi: 150,
+            pdfium_library: String::new(),
+            keyphrase_count: 10,
+            min_image_area: 4096,
+            max_parallel: 0,
+        }
+    }
+}
+
+impl ParserConfig {
+    /// 实际使用的解析并行度，至少为 1
+    pub fn parallelism(&self) -> usize {
+        if self.max_parallel > 0 {
+            return self.max_parallel;
+        }
+        std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+    }
+}
+
+/// 告警通知渠道：填写 smtp_host 和 email_to 启用邮件，填写 webhook_url 启用 Webhook
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct NotifierConfig {
+    #[serde(flatten)]
+    pub smtp: SmtpConfig,
+    /// 发件地址，留空使用 smtp_user
+    pub email_from: String,
+    pub email_to: Vec<String>,
+    /// 以 JSON `{"text": ...}` POST 到该地址
+    pub webhook_url: String,
+    /// 爬取到新论文时推送论文列表及相关度评分依据
+    pub notify_new_papers: bool,
+}
+
+impl Default for NotifierConfig {
+    fn default() -> Self {
+        Self {
+            smtp: SmtpConfig::default(),
+            email_from: "".to_string(),
+            email_to: Vec::new(),
+            webhook_url: "".to_string(),
+            notify_new_papers: false,
+        }
+    }
 }
 
 impl AppConfig {
     pub fn load() -> Result<Self> {
-        let config_path = PathBuf::from("config/settings.toml");
+        let config_path = dirs::get().settings_file();
 
         if !config_path.exists() {
             return Ok(Self::default());
@@ -56,16 +490,48 @@ impl AppConfig {
 
         let content = std::fs::read_to_string(config_path)?;
         let config: AppConfig = toml::from_str(&content)?;
+        if let Some(i) = config.translator.fallbacks.iter().position(|f| f.missing_api_url()) {
+            anyhow::bail!(
+                "translator.fallbacks[{}] 缺少 api_url（{} 不是 Ollama，需要填写接口地址）",
+                i,
+                config.translator.fallbacks[i].api_provider
+            );
+        }
         Ok(config)
     }
 
-    pub fn save(&self, path: &str) -> Result<()> {
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
         let content = toml::to_string_pretty(self)?;
         std::fs::write(path, content)?;
         Ok(())
     }
 }
 
+impl StorageConfig {
+    /// 数据库文件的实际路径
+    pub fn database_file(&self) -> PathBuf {
+        if self.database_path.trim().is_empty() {
+            dirs::get().default_database()
+        } else {
+            dirs::get().resolve_data_path(self.database_path.trim())
+        }
+    }
+
+    /// sqlx 连接字符串
+    pub fn database_url(&self) -> String {
+        format!("sqlite:{}", self.database_file().display())
+    }
+
+    /// 备份目录的实际路径
+    pub fn backup_dir(&self) -> PathBuf {
+        if self.backup.dir.trim().is_empty() {
+            dirs::get().backups_dir()
+        } else {
+            dirs::get().resolve_data_path(self.backup.dir.trim())
+        }
+    }
+}
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -73,6 +539,12 @@ impl Default for AppConfig {
                 max_papers_per_day: 50,
                 request_delay_ms: 1000,
                 user_agent: "ResearchBot/1.0".to_string(),
+                llm_triage: false,
+                triage_model: "".to_string(),
+                query_expansion: false,
+                llm_key_points: false,
+                llm_relevance: false,
+                politeness: HashMap::new(),
             },
             translator: TranslatorConfig {
                 api_provider: "minimax".to_string(),
@@ -81,17 +553,75 @@ impl Default for AppConfig {
                 model: "MiniMax-M2.5".to_string(),
                 target_language: "zh-CN".to_string(),
                 proxy: "".to_string(),
+                ollama_host: default_ollama_host(),
+                max_concurrent: default_max_concurrent(),
+                requests_per_minute: 0,
+                json_mode: true,
+                max_chunk_tokens: default_max_chunk_tokens(),
+                embedding_model: "".to_string(),
+                fallbacks: Vec::new(),
             },
             generator: GeneratorConfig {
                 ppt_template: "academic".to_string(),
                 max_papers_per_report: 20,
                 include_images: true,
                 include_formulas: true,
+                pinned_sections: Vec::new(),
+                hidden_sections: Vec::new(),
+                min_relevance: "".to_string(),
+                min_relevance_score: 0.0,
+                min_confidence: 0.0,
+                template_vars: HashMap::new(),
+                theme: default_theme(),
+                custom_css: String::new(),
+                feed_size: default_feed_size(),
             },
             storage: StorageConfig {
-                database_path: "./data/papers.db".to_string(),
+                database_path: "".to_string(),
                 cache_ttl_days: 30,
+                backup: BackupConfig::default(),
+                sqlite: SqliteConfig::default(),
             },
+            publisher: PublisherConfig::default(),
+            notifier: NotifierConfig::default(),
+            notify: NotifyConfig::default(),
+            notion: NotionConfig::default(),
+            parser: ParserConfig::default(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback_requires_api_url_unless_ollama() {
+        let translator: TranslatorConfig = toml::from_str(
+            r#"
+            api_provider = "minimax"
+            api_key = "key"
+            api_url = "https://api.minimaxi.com/v1/chat/completions"
+            model = "MiniMax-M2.5"
+            target_language = "zh-CN"
+
+            [[fallbacks]]
+            api_provider = "openai"
+            api_key = "sk-backup-key"
+            model = "gpt-4o-mini"
+
+            [[fallbacks]]
+            api_provider = "openai"
+            api_url = "  "
+            model = "gpt-4o-mini"
+
+            [[fallbacks]]
+            api_provider = "ollama"
+            model = "qwen2.5:7b"
+            "#,
+        )
+        .unwrap();
+        let missing: Vec<bool> = translator.fallbacks.iter().map(|f| f.missing_api_url()).collect();
+        assert_eq!(missing, [true, true, false]);
+    }
+}
@@ -0,0 +1,120 @@
+use anyhow::{anyhow, bail, Context, Result};
+use toml::Value;
+
+use super::{dirs, AppConfig};
+
+/// 配置项的生效值（settings.toml 中未设置的取默认值），`key` 为点分路径，如 `translator.model`；
+/// 为空时返回完整配置
+pub fn get(key: Option<&str>) -> Result<Value> {
+    let effective = Value::try_from(AppConfig::load()?)?;
+    match key {
+        None => Ok(effective),
+        Some(key) => lookup(&effective, key)
+            .cloned()
+            .ok_or_else(|| anyhow!("未知的配置项: {}", key)),
+    }
+}
+
+/// 修改 settings.toml 中的一项并返回新值，文件中的注释和格式保持不变
+///
+/// 值按该项原有的类型解析（字符串无需加引号，数组写为 `["a", "b"]`）；修改后的配置须能正常加载，
+/// 否则不写入。settings.toml 不存在时以默认配置为基础创建。
+pub fn set(key: &str, raw: &str) -> Result<Value> {
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.iter().any(|s| s.trim().is_empty()) {
+        bail!("无效的配置项: {}", key);
+    }
+
+    let current = Value::try_from(AppConfig::load()?)?;
+    let value = parse_value(key, raw, lookup(&current, key))?;
+
+    let path = dirs::get().settings_file();
+    let text = if path.exists() {
+        std::fs::read_to_string(&path)?
+    } else {
+        toml::to_string_pretty(&AppConfig::default())?
+    };
+    let mut doc: toml_edit::DocumentMut = text
+        .parse()
+        .with_context(|| format!("无法解析 {}", path.display()))?;
+
+    let (last, parents) = segments.split_last().expect("key 至少有一段");
+    let mut table = doc.as_table_mut();
+    for segment in parents {
+        let item = table.entry(segment).or_insert_with(|| {
+            let mut created = toml_edit::Table::new();
+            created.set_implicit(true);
+            toml_edit::Item::Table(created)
+        });
+        table = item
+            .as_table_mut()
+            .ok_or_else(|| anyhow!("{} 不是一组配置，无法设置 {}", segment, key))?;
+    }
+    let new_value: toml_edit::Value = value
+        .to_string()
+        .parse()
+        .map_err(|e| anyhow!("无法写入值 {}: {}", value, e))?;
+    match table.get_mut(last).and_then(toml_edit::Item::as_value_mut) {
+        // 保留行尾注释
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = new_value;
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(last, toml_edit::Item::Value(new_value));
+        }
+    }
+
+    let updated = doc.to_string();
+    let config: AppConfig = toml::from_str(&updated).context("修改后的配置无法加载，未保存")?;
+    // 未知的键在反序列化时被忽略，写回前拒绝
+    let saved = lookup(&Value::try_from(&config)?, key)
+        .cloned()
+        .ok_or_else(|| anyhow!("未知的配置项: {}", key))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, updated)?;
+    Ok(saved)
+}
+
+/// 按点分路径查找配置项
+pub fn lookup<'a>(value: &'a Value, key: &str) -> Option<&'a Value> {
+    key.split('.').try_fold(value, |value, segment| value.as_table()?.get(segment))
+}
+
+/// 按配置项原有的类型解析命令行中的值，新增的键按 TOML 值解析，解析不了时视为字符串
+fn parse_value(key: &str, raw: &str, current: Option<&Value>) -> Result<Value> {
+    let value = match current {
+        Some(Value::String(_)) => Value::String(raw.to_string()),
+        Some(Value::Integer(_)) => Value::Integer(
+            raw.trim()
+                .parse()
+                .map_err(|_| anyhow!("{} 需要整数，得到 '{}'", key, raw))?,
+        ),
+        Some(Value::Float(_)) => Value::Float(
+            raw.trim()
+                .parse()
+                .map_err(|_| anyhow!("{} 需要数字，得到 '{}'", key, raw))?,
+        ),
+        Some(Value::Boolean(_)) => Value::Boolean(
+            raw.trim()
+                .parse()
+                .map_err(|_| anyhow!("{} 需要 true 或 false，得到 '{}'", key, raw))?,
+        ),
+        Some(Value::Array(_)) => parse_toml_value(raw)
+            .filter(Value::is_array)
+            .ok_or_else(|| anyhow!("{} 需要数组，如 '[\"a\", \"b\"]'，得到 '{}'", key, raw))?,
+        Some(Value::Table(_)) => bail!("{} 是一组配置，请逐项设置，如 {}.<名称>", key, key),
+        _ => parse_toml_value(raw).unwrap_or_else(|| Value::String(raw.to_string())),
+    };
+    Ok(value)
+}
+
+fn parse_toml_value(raw: &str) -> Option<Value> {
+    toml::from_str::<toml::Table>(&format!("value = {}", raw))
+        .ok()?
+        .remove("value")
+}
@@ -0,0 +1,192 @@
+use serde::Serialize;
+use std::time::Duration;
+use toml::Value;
+
+use super::{dirs, AppConfig, KeywordConfig};
+use crate::crawler::arxiv::API_URL as ARXIV_API;
+use crate::crawler::relevance::RelevanceLabel;
+use crate::translator::{Endpoint, Provider};
+
+/// 测试服务地址时的超时
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// 配置无法加载、取值无效或服务无法连接
+    Error,
+    /// 不影响运行，但相关功能会被跳过或配置被忽略
+    Warning,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfigIssue {
+    pub severity: Severity,
+    /// 配置项或文件
+    pub key: String,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn error(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Error, key: key.into(), message: message.into() }
+    }
+
+    fn warning(key: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { severity: Severity::Warning, key: key.into(), message: message.into() }
+    }
+}
+
+/// 检查 settings.toml 和 keywords.toml：能否加载、未知的配置项、缺少的 API key 和无效的取值；
+/// `check_endpoints` 时再测试翻译服务、arXiv 和 Notion 的地址能否连接
+pub async fn validate(check_endpoints: bool) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let path = dirs::get().settings_file();
+
+    let config = if path.exists() {
+        let parsed = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| Ok(Value::Table(toml::from_str(&text)?)));
+        let file = match parsed {
+            Ok(file) => file,
+            Err(e) => {
+                issues.push(ConfigIssue::error("settings.toml", format!("无法解析: {}", e)));
+                return issues;
+            }
+        };
+        let config: AppConfig = match file.clone().try_into() {
+            Ok(config) => config,
+            Err(e) => {
+                issues.push(ConfigIssue::error("settings.toml", format!("无法加载: {}", e)));
+                return issues;
+            }
+        };
+        if let Ok(effective) = Value::try_from(&config) {
+            let mut unknown = Vec::new();
+            unknown_keys(&file, &effective, "", &mut unknown);
+            for key in unknown {
+                issues.push(ConfigIssue::warning(key, "未知的配置项，将被忽略"));
+            }
+        }
+        config
+    } else {
+        issues.push(ConfigIssue::warning("settings.toml", "文件不存在，使用默认配置（运行 init 生成）"));
+        AppConfig::default()
+    };
+
+    if let Err(e) = KeywordConfig::load() {
+        issues.push(ConfigIssue::error("keywords.toml", format!("无法加载: {}", e)));
+    }
+
+    check_values(&config, &mut issues);
+    if check_endpoints {
+        check_reachable(&config, &mut issues).await;
+    }
+    issues
+}
+
+/// 文件中有、但加载后不存在的键（拼错或已废弃的配置项）
+fn unknown_keys(file: &Value, effective: &Value, prefix: &str, out: &mut Vec<String>) {
+    match (file, effective) {
+        (Value::Table(file), Value::Table(effective)) => {
+            for (key, value) in file {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                match effective.get(key) {
+                    Some(known) => unknown_keys(value, known, &path, out),
+                    None => out.push(path),
+                }
+            }
+        }
+        (Value::Array(file), Value::Array(effective)) => {
+            for (i, (value, known)) in file.iter().zip(effective).enumerate() {
+                unknown_keys(value, known, &format!("{}[{}]", prefix, i), out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn check_values(config: &AppConfig, issues: &mut Vec<ConfigIssue>) {
+    let translator = &config.translator;
+    let primary = Endpoint::primary(translator);
+    if !primary.is_configured() {
+        let key = if translator.model.trim().is_empty() { "translator.model" } else { "translator.api_key" };
+        issues.push(ConfigIssue::warning(key, "未填写，翻译和 LLM 初筛、要点、相关度标签将被跳过"));
+    }
+    for (i, fallback) in translator.fallbacks.iter().enumerate() {
+        if fallback.missing_api_url() {
+            issues.push(ConfigIssue::error(format!("translator.fallbacks[{}].api_url", i), "未填写，非 Ollama 服务商需要接口地址"));
+        } else if !Endpoint::fallback(fallback, translator.max_concurrent).is_configured() {
+            issues.push(ConfigIssue::warning(
+                format!("translator.fallbacks[{}]", i),
+                "缺少模型或 API key，将被忽略",
+            ));
+        }
+    }
+    if !translator.proxy.is_empty() {
+        if let Err(e) = reqwest::Proxy::all(&translator.proxy) {
+            issues.push(ConfigIssue::error("translator.proxy", format!("无效的代理地址: {}", e)));
+        }
+    }
+
+    let min_relevance = config.generator.min_relevance.trim();
+    if !min_relevance.is_empty() && RelevanceLabel::parse(min_relevance).is_none() {
+        issues.push(ConfigIssue::error(
+            "generator.min_relevance",
+            format!("无效的取值 '{}'，可选 core、related、off_topic", min_relevance),
+        ));
+    }
+    if !(0.0..=1.0).contains(&config.generator.min_relevance_score) {
+        issues.push(ConfigIssue::error("generator.min_relevance_score", "应在 0 到 1 之间"));
+    }
+    if !(0.0..=1.0).contains(&config.generator.min_confidence) {
+        issues.push(ConfigIssue::error("generator.min_confidence", "应在 0 到 1 之间"));
+    }
+
+    let notion = &config.notion;
+    if !notion.database_id.is_empty() && notion.token.is_empty() {
+        issues.push(ConfigIssue::warning("notion.token", "已填写 database_id 但缺少 token，无法导出到 Notion"));
+    }
+}
+
+/// 能收到任何 HTTP 响应（包括 401、404）即视为可以连接
+async fn check_reachable(config: &AppConfig, issues: &mut Vec<ConfigIssue>) {
+    let translator = &config.translator;
+    let mut targets: Vec<(String, String, bool)> = Vec::new();
+    let mut endpoints = vec![("translator".to_string(), Endpoint::primary(translator))];
+    for (i, fallback) in translator.fallbacks.iter().enumerate() {
+        endpoints.push((format!("translator.fallbacks[{}]", i), Endpoint::fallback(fallback, translator.max_concurrent)));
+    }
+    for (prefix, endpoint) in endpoints.into_iter().filter(|(_, e)| e.is_configured()) {
+        match endpoint.provider {
+            Provider::Ollama => targets.push((format!("{}.ollama_host", prefix), endpoint.ollama_host, true)),
+            Provider::OpenAiCompatible => targets.push((format!("{}.api_url", prefix), endpoint.api_url, true)),
+        }
+    }
+    targets.push(("arxiv".to_string(), ARXIV_API.to_string(), false));
+    if !config.notion.token.is_empty() {
+        targets.push(("notion".to_string(), "https://api.notion.com/v1/users/me".to_string(), false));
+    }
+
+    let direct = reqwest::Client::builder().timeout(ENDPOINT_TIMEOUT).build();
+    // 翻译请求经过代理时，检查也经过代理
+    let proxied = match reqwest::Proxy::all(&translator.proxy) {
+        Ok(proxy) if !translator.proxy.is_empty() => reqwest::Client::builder().timeout(ENDPOINT_TIMEOUT).proxy(proxy).build(),
+        _ => reqwest::Client::builder().timeout(ENDPOINT_TIMEOUT).build(),
+    };
+    let (Ok(direct), Ok(proxied)) = (direct, proxied) else {
+        issues.push(ConfigIssue::error("translator.proxy", "无法创建 HTTP 客户端"));
+        return;
+    };
+
+    for (key, url, uses_proxy) in targets {
+        if reqwest::Url::parse(&url).is_err() {
+            issues.push(ConfigIssue::error(key, format!("无效的地址 '{}'", url)));
+            continue;
+        }
+        let client = if uses_proxy { &proxied } else { &direct };
+        if let Err(e) = client.get(&url).send().await {
+            issues.push(ConfigIssue::error(key, format!("无法连接 {}: {}", url, e)));
+        }
+    }
+}
@@ -1,9 +1,16 @@
 use anyhow::Result;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn, error};
+use tracing::{info, warn};
 use std::time::Duration;
 
+use super::backfill::MonthWindow;
+use super::fetcher::Fetcher;
+use crate::storage::cache::Cache;
+use crate::storage::dedup::title_similarity;
+
+/// 礼貌策略中使用的数据源名称
+const SOURCE: &str = "arxiv";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArxivPaper {
     pub id: String,
@@ -15,46 +22,147 @@ pub struct ArxivPaper {
     pub categories: Vec<String>,
 }
 
+/// 一次搜索的结果，附带 feed 中的 opensearch 元数据
+#[derive(Debug, Clone, Default)]
+pub struct ArxivSearchResult {
+    pub papers: Vec<ArxivPaper>,
+    /// 匹配查询的论文总数 (opensearch:totalResults)
+    pub total_results: Option<usize>,
+}
+
+/// 检查文件存在且以 PDF 文件头开头
+pub fn is_complete_pdf(path: impl AsRef<std::path::Path>) -> bool {
+    use std::io::Read;
+
+    let mut header = [0u8; 5];
+    std::fs::File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .map(|_| &header == b"%PDF-")
+        .unwrap_or(false)
+}
+
+/// arXiv API 地址
+pub const API_URL: &str = "https://export.arxiv.org/api/query";
+/// 缓存中检索结果的命名空间
+const FEED_CACHE_NAMESPACE: &str = "arxiv_feed";
+/// 可能还有新论文的检索（当天、当月）只短暂缓存，中断后重跑时不重复请求
+const RECENT_FEED_TTL: Duration = Duration::from_secs(3600);
+/// 按标题检索时，标题相似度达到该值才视为同一篇论文
+const TITLE_LOOKUP_THRESHOLD: f64 = 0.9;
+
 pub struct ArxivCrawler {
-    client: Client,
+    fetcher: Fetcher,
     base_url: String,
     max_retries: u32,
+    cache: Option<Cache>,
 }
 
 impl ArxivCrawler {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(60))
-            .user_agent("ResearchBot/1.0 (academic research; mailto:user@example.com)")
-            .build()
-            .unwrap();
-
+    pub fn new(fetcher: Fetcher) -> Self {
         Self {
-            client,
-            base_url: "https://export.arxiv.org/api/query".to_string(),
+            fetcher,
+            base_url: API_URL.to_string(),
             max_retries: 3,
+            cache: None,
         }
     }
 
-    pub async fn search(&self, keywords: &[String], max_results: usize) -> Result<Vec<ArxivPaper>> {
-        // 简化查询，只使用第一个关键词
-        let query = keywords.first()
-            .unwrap_or(&"machine learning".to_string())
-            .replace(" ", "+");
-        let url = format!(
-            "{}?search_query=all:{}&start=0&max_results={}&sortBy=submittedDate&sortOrder=descending",
-            self.base_url, query, max_results
-        );
+    /// 缓存检索结果：已结束的月份按 `cache_ttl_days` 保存，其余只保存一小时
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub async fn search(&self, keywords: &[String], max_results: usize) -> Result<ArxivSearchResult> {
+        self.search_page(&Self::build_query(keywords), 0, max_results, RECENT_FEED_TTL, "descending").await
+    }
+
+    /// 在提交日期窗口内分页搜索，用于回溯爬取
+    ///
+    /// 按提交时间从早到晚排序：续跑当前月份时，新提交的论文排在末尾，已保存的偏移量仍然有效。
+    pub async fn search_window(
+        &self,
+        keywords: &[String],
+        window: &MonthWindow,
+        start: usize,
+        max_results: usize,
+    ) -> Result<ArxivSearchResult> {
+        let query = format!("({}) AND {}", Self::build_query(keywords), window.arxiv_date_filter());
+        let ttl = if window.end < chrono::Local::now().date_naive() {
+            Duration::MAX
+        } else {
+            RECENT_FEED_TTL
+        };
+        self.search_page(&query, start, max_results, ttl, "ascending").await
+    }
+
+    /// 发送一次不返回论文的检索（不使用缓存），检查 API 能否访问
+    pub async fn check_reachable(&self) -> Result<()> {
+        let url = reqwest::Url::parse_with_params(&self.base_url, &[("search_query", "all:test"), ("max_results", "0")])?;
+        let response = self.fetcher.get(SOURCE, url.as_str()).await?;
+        if !response.status.is_success() {
+            anyhow::bail!("arXiv API 返回 {}", response.status);
+        }
+        Ok(())
+    }
+
+    /// 按编号查询论文，如 `2401.12345` 或 `2401.12345v2`
+    pub async fn fetch_by_id(&self, arxiv_id: &str) -> Result<Option<ArxivPaper>> {
+        let url = reqwest::Url::parse_with_params(&self.base_url, &[("id_list", arxiv_id)])?.to_string();
+        let result = self.fetch_feed(&url, RECENT_FEED_TTL).await?;
+        Ok(result.papers.into_iter().next())
+    }
+
+    /// 按标题检索，返回标题几乎一致（忽略大小写和标点）的论文
+    pub async fn find_by_title(&self, title: &str) -> Result<Option<ArxivPaper>> {
+        let query = format!("ti:\"{}\"", title.replace('"', ""));
+        let result = self.search_page(&query, 0, 5, RECENT_FEED_TTL, "descending").await?;
+        Ok(result
+            .papers
+            .into_iter()
+            .find(|paper| title_similarity(&paper.title, title) >= TITLE_LOOKUP_THRESHOLD))
+    }
+
+    async fn search_page(
+        &self,
+        query: &str,
+        start: usize,
+        max_results: usize,
+        cache_ttl: Duration,
+        sort_order: &str,
+    ) -> Result<ArxivSearchResult> {
+        let url = reqwest::Url::parse_with_params(
+            &self.base_url,
+            &[
+                ("search_query", query),
+                ("start", &start.to_string()),
+                ("max_results", &max_results.to_string()),
+                ("sortBy", "submittedDate"),
+                ("sortOrder", sort_order),
+            ],
+        )?
+        .to_string();
+        self.fetch_feed(&url, cache_ttl).await
+    }
+
+    /// 请求 API 并解析 feed，限流时退避重试
+    async fn fetch_feed(&self, url: &str, cache_ttl: Duration) -> Result<ArxivSearchResult> {
+        if let Some(cache) = &self.cache {
+            match cache.get(FEED_CACHE_NAMESPACE, url).await {
+                Ok(Some(body)) => {
+                    info!("命中 arXiv 检索缓存: {}", url);
+                    return self.parse_search_result(&String::from_utf8_lossy(&body));
+                }
+                Ok(None) => {}
+                Err(e) => warn!("读取 arXiv 检索缓存失败: {}", e),
+            }
+        }
 
         info!("正在搜索 arXiv: {}", url);
 
         for attempt in 1..=self.max_retries {
-            // 请求前延迟，arXiv 要求至少3秒间隔
-            let delay = Duration::from_secs(3 * attempt as u64);
-            info!("等待 {}s 后发送请求 (第 {}/{} 次)", delay.as_secs(), attempt, self.max_retries);
-            tokio::time::sleep(delay).await;
-
-            let response = match self.client.get(&url).send().await {
+            // 请求间隔由抓取器按 arXiv 的礼貌策略控制（默认至少3秒）
+            let response = match self.fetcher.get(SOURCE, url).await {
                 Ok(resp) => resp,
                 Err(e) => {
                     warn!("请求失败 (第 {}/{} 次): {}", attempt, self.max_retries, e);
@@ -62,8 +170,8 @@ impl ArxivCrawler {
                 }
             };
 
-            let status = response.status();
-            let text = response.text().await?;
+            let status = response.status;
+            let text = response.text();
 
             info!("arXiv 响应状态: {}, 内容长度: {} 字节", status, text.len());
 
@@ -80,13 +188,55 @@ impl ArxivCrawler {
                 continue;
             }
 
-            let papers = self.parse_arxiv_response(&text)?;
-            info!("找到 {} 篇论文", papers.len());
-            return Ok(papers);
+            let result = self.parse_search_result(&text)?;
+            // 只缓存带有匹配总数的正常 feed，错误页面不缓存
+            if let (Some(cache), Some(_)) = (&self.cache, result.total_results) {
+                if let Err(e) = cache.set_with_ttl(FEED_CACHE_NAMESPACE, url, text.as_bytes(), cache_ttl).await {
+                    warn!("写入 arXiv 检索缓存失败: {}", e);
+                }
+            }
+            return Ok(result);
         }
 
         warn!("arXiv API 请求在 {} 次重试后仍然失败", self.max_retries);
-        Ok(vec![])
+        Ok(ArxivSearchResult::default())
+    }
+
+    fn parse_search_result(&self, text: &str) -> Result<ArxivSearchResult> {
+        let papers = self.parse_arxiv_response(text)?;
+        let total_results = self.extract_feed_count(text, "opensearch:totalResults");
+        info!("找到 {} 篇论文 (共匹配 {:?} 篇)", papers.len(), total_results);
+        Ok(ArxivSearchResult { papers, total_results })
+    }
+
+    /// 从 feed 头部读取 opensearch 计数，标签可能带 xmlns 属性
+    fn extract_feed_count(&self, xml: &str, tag: &str) -> Option<usize> {
+        let header = xml.split("<entry>").next()?;
+        let open = header.find(&format!("<{}", tag))?;
+        let content_start = open + header[open..].find('>')? + 1;
+        let content_end = content_start + header[content_start..].find(&format!("</{}>", tag))?;
+        header[content_start..content_end].trim().parse().ok()
+    }
+
+    /// 将关键词组合为 OR 查询，多词短语加引号按短语匹配
+    fn build_query(keywords: &[String]) -> String {
+        let terms: Vec<String> = keywords
+            .iter()
+            .map(|k| k.trim())
+            .filter(|k| !k.is_empty())
+            .map(|k| {
+                if k.contains(' ') {
+                    format!("all:\"{}\"", k.replace('"', ""))
+                } else {
+                    format!("all:{}", k)
+                }
+            })
+            .collect();
+
+        if terms.is_empty() {
+            return "all:\"machine learning\"".to_string();
+        }
+        terms.join(" OR ")
     }
 
     fn parse_arxiv_response(&self, xml: &str) -> Result<Vec<ArxivPaper>> {
@@ -169,20 +319,6 @@ impl ArxivCrawler {
     }
 
     pub async fn download_pdf(&self, url: &str, save_path: &str) -> Result<()> {
-        info!("下载PDF: {} -> {}", url, save_path);
-
-        let response = self.client.get(url).send().await?;
-
-        if !response.status().is_success() {
-            error!("下载失败，状态码: {}", response.status());
-            return Err(anyhow::anyhow!("下载失败: {}", response.status()));
-        }
-
-        let bytes = response.bytes().await?;
-        tokio::fs::write(save_path, bytes).await?;
-
-        info!("PDF下载完成: {}", save_path);
-
-        Ok(())
+        self.fetcher.download_pdf(SOURCE, url, save_path).await
     }
 }
@@ -0,0 +1,93 @@
+use chrono::{Datelike, NaiveDate};
+
+/// 回溯爬取的一个时间窗口（按自然月切分，首尾两段可能不足一月）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonthWindow {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl MonthWindow {
+    /// 进度记录使用的月份标识，如 `2019-01`
+    pub fn label(&self) -> String {
+        self.start.format("%Y-%m").to_string()
+    }
+
+    /// arXiv 查询的提交日期范围，如 `submittedDate:[201901010000 TO 201901312359]`
+    pub fn arxiv_date_filter(&self) -> String {
+        format!(
+            "submittedDate:[{}0000 TO {}2359]",
+            self.start.format("%Y%m%d"),
+            self.end.format("%Y%m%d")
+        )
+    }
+}
+
+/// 将 `[since, until]` 切分为按月的时间窗口，从早到晚排列
+pub fn month_windows(since: NaiveDate, until: NaiveDate) -> Vec<MonthWindow> {
+    let mut windows = Vec::new();
+    let mut start = since;
+    while start <= until {
+        let next_month = if start.month() == 12 {
+            NaiveDate::from_ymd_opt(start.year() + 1, 1, 1)
+        } else {
+            NaiveDate::from_ymd_opt(start.year(), start.month() + 1, 1)
+        };
+        let Some(next_month) = next_month else { break };
+        let end = next_month.pred_opt().unwrap_or(next_month).min(until);
+        windows.push(MonthWindow { start, end });
+        start = next_month;
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn partial_first_and_last_month() {
+        let windows = month_windows(date(2024, 1, 15), date(2024, 3, 10));
+        assert_eq!(
+            windows,
+            [
+                MonthWindow { start: date(2024, 1, 15), end: date(2024, 1, 31) },
+                MonthWindow { start: date(2024, 2, 1), end: date(2024, 2, 29) },
+                MonthWindow { start: date(2024, 3, 1), end: date(2024, 3, 10) },
+            ]
+        );
+        assert_eq!(windows[1].label(), "2024-02");
+        assert_eq!(windows[0].arxiv_date_filter(), "submittedDate:[202401150000 TO 202401312359]");
+    }
+
+    #[test]
+    fn crosses_year_boundary() {
+        let windows = month_windows(date(2023, 12, 1), date(2024, 1, 31));
+        assert_eq!(
+            windows,
+            [
+                MonthWindow { start: date(2023, 12, 1), end: date(2023, 12, 31) },
+                MonthWindow { start: date(2024, 1, 1), end: date(2024, 1, 31) },
+            ]
+        );
+    }
+
+    #[test]
+    fn single_day_and_empty_range() {
+        assert_eq!(
+            month_windows(date(2024, 5, 31), date(2024, 5, 31)),
+            [MonthWindow { start: date(2024, 5, 31), end: date(2024, 5, 31) }]
+        );
+        assert!(month_windows(date(2024, 6, 1), date(2024, 5, 31)).is_empty());
+    }
+
+    #[test]
+    fn february_in_common_year() {
+        let windows = month_windows(date(2023, 2, 1), date(2023, 2, 28));
+        assert_eq!(windows, [MonthWindow { start: date(2023, 2, 1), end: date(2023, 2, 28) }]);
+    }
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use regex::Regex;
+use serde_json::Value;
+use tracing::info;
+
+use super::fetcher::Fetcher;
+
+/// 礼貌策略中使用的数据源名称
+const SOURCE: &str = "crossref";
+
+/// Crossref 中登记的论文信息
+#[derive(Debug, Clone)]
+pub struct CrossrefWork {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub abstract_text: Option<String>,
+    /// 发表日期，缺少月、日时补 01，如 `2023-07-01`
+    pub published: Option<String>,
+    /// 期刊或会议名称
+    pub venue: Option<String>,
+}
+
+/// 按 DOI 查询 Crossref 的元数据
+pub struct CrossrefClient {
+    fetcher: Fetcher,
+    base_url: String,
+}
+
+impl CrossrefClient {
+    pub fn new(fetcher: Fetcher) -> Self {
+        Self {
+            fetcher,
+            base_url: "https://api.crossref.org/works".to_string(),
+        }
+    }
+
+    /// 查询 DOI 对应的论文，未登记时返回 None
+    pub async fn lookup(&self, doi: &str) -> Result<Option<CrossrefWork>> {
+        let mut url = reqwest::Url::parse(&self.base_url)?;
+        url.path_segments_mut()
+            .map_err(|_| anyhow::anyhow!("无效的 Crossref 地址: {}", self.base_url))?
+            .push(doi);
+
+        info!("查询 Crossref: {}", url);
+        let response = self.fetcher.get(SOURCE, url.as_str()).await?;
+        if response.status.as_u16() == 404 {
+            return Ok(None);
+        }
+        if !response.status.is_success() {
+            anyhow::bail!("Crossref 返回 {}", response.status);
+        }
+        let body: Value = serde_json::from_slice(&response.body)?;
+        Ok(parse_work(&body["message"]))
+    }
+}
+
+fn parse_work(message: &Value) -> Option<CrossrefWork> {
+    let first_string = |key: &str| -> Option<String> {
+        message[key]
+            .as_array()?
+            .first()?
+            .as_str()
+            .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|s| !s.is_empty())
+    };
+
+    let title = first_string("title")?;
+    let authors = message["author"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|author| {
+            let family = author["family"].as_str().unwrap_or_default();
+            let given = author["given"].as_str().unwrap_or_default();
+            let name = format!("{} {}", given, family).trim().to_string();
+            (!name.is_empty()).then_some(name)
+        })
+        .collect();
+
+    Some(CrossrefWork {
+        title,
+        authors,
+        abstract_text: message["abstract"].as_str().map(strip_jats).filter(|s| !s.is_empty()),
+        published: published_date(message),
+        venue: first_string("container-title"),
+    })
+}
+
+/// 依次取正式出版、在线发表和登记的日期
+fn published_date(message: &Value) -> Option<String> {
+    ["published-print", "published-online", "issued"].iter().find_map(|key| {
+        let parts = message[*key]["date-parts"].as_array()?.first()?.as_array()?;
+        let part = |i: usize| parts.get(i).and_then(Value::as_u64);
+        let year = part(0)?;
+        Some(format!("{:04}-{:02}-{:02}", year, part(1).unwrap_or(1), part(2).unwrap_or(1)))
+    })
+}
+
+/// 摘要以 JATS XML 给出（`<jats:p>...</jats:p>`），去掉标签并合并空白
+fn strip_jats(text: &str) -> String {
+    let tags = Regex::new(r"<[^>]+>").unwrap();
+    let plain = tags.replace_all(text, " ");
+    let plain = plain.trim();
+    // 多数摘要以「Abstract」小标题开头
+    let plain = plain.strip_prefix("Abstract").unwrap_or(plain);
+    plain.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use reqwest::{Client, StatusCode};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Semaphore;
+use tracing::{debug, info};
+
+use crate::config::{CrawlerConfig, PolitenessConfig};
+use crate::utils::rate_limiter::RateLimiter;
+
+/// 一次抓取的结果
+pub struct FetchResponse {
+    pub status: StatusCode,
+    pub body: Vec<u8>,
+}
+
+impl FetchResponse {
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).to_string()
+    }
+}
+
+/// 单个数据源的限流状态：请求间隔 + 并发上限
+struct SourceGate {
+    limiter: RateLimiter,
+    permits: Semaphore,
+}
+
+impl SourceGate {
+    fn new(policy: PolitenessConfig) -> Self {
+        Self {
+            limiter: RateLimiter::with_interval(Duration::from_millis(policy.delay_ms), policy.burst),
+            permits: Semaphore::new(policy.max_concurrent.max(1)),
+        }
+    }
+}
+
+/// 所有爬虫共享的 HTTP 抓取器，按数据源执行礼貌策略
+///
+/// 新数据源只需在请求时传入数据源名称，即可使用 `PolitenessConfig` 中的间隔、并发和突发限制。
+pub struct Fetcher {
+    client: Client,
+    config: CrawlerConfig,
+    gates: Mutex<HashMap<String, Arc<SourceGate>>>,
+}
+
+impl Fetcher {
+    pub fn new(config: &CrawlerConfig) -> Self {
+        let user_agent = if config.user_agent.trim().is_empty() {
+            "ResearchBot/1.0 (academic research; mailto:user@example.com)"
+        } else {
+            config.user_agent.trim()
+        };
+        let client = Client::builder()
+            .timeout(Duration::from_secs(60))
+            .user_agent(user_agent)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            client,
+            config: config.clone(),
+            gates: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn gate(&self, source: &str) -> Arc<SourceGate> {
+        let mut gates = self.gates.lock().unwrap();
+        gates
+            .entry(source.to_string())
+            .or_insert_with(|| {
+                let policy = self.config.politeness_for(source);
+                debug!(
+                    "数据源 {} 礼貌策略: 间隔 {}ms, 并发 {}, 突发 {}",
+                    source, policy.delay_ms, policy.max_concurrent, policy.burst
+                );
+                Arc::new(SourceGate::new(policy))
+            })
+            .clone()
+    }
+
+    /// 按数据源的礼貌策略发送 GET 请求并读取完整响应体
+    pub async fn get(&self, source: &str, url: &str) -> Result<FetchResponse> {
+        let gate = self.gate(source);
+        let _permit = gate.permits.acquire().await?;
+        gate.limiter.acquire().await;
+
+        let response = self.client.get(url).send().await?;
+        let status = response.status();
+        let body = response.bytes().await?.to_vec();
+        Ok(FetchResponse { status, body })
+    }
+
+    /// 按数据源的礼貌策略下载PDF，先写临时文件再改名，下载中断时不会留下不完整的PDF
+    pub async fn download_pdf(&self, source: &str, url: &str, save_path: &str) -> Result<()> {
+        info!("下载PDF: {} -> {}", url, save_path);
+
+        let response = self.get(source, url).await?;
+        if !response.status.is_success() {
+            anyhow::bail!("下载失败: {}", response.status);
+        }
+
+        let partial_path = format!("{}.part", save_path);
+        tokio::fs::write(&partial_path, &response.body).await?;
+        tokio::fs::rename(&partial_path, save_path).await?;
+
+        info!("PDF下载完成: {}", save_path);
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use tracing::{info, warn};
+
+use super::arxiv::{ArxivCrawler, ArxivPaper};
+use super::crossref::{CrossrefClient, CrossrefWork};
+use super::fetcher::Fetcher;
+use crate::config::CrawlerConfig;
+use crate::parser::PaperMetadata;
+
+/// 为本地PDF查到的元数据
+#[derive(Debug, Clone)]
+pub struct FoundMetadata {
+    pub title: String,
+    pub authors: Vec<String>,
+    pub abstract_text: Option<String>,
+    pub publish_date: Option<String>,
+    pub venue: Option<String>,
+    /// 在 arXiv 上找到时的编号
+    pub arxiv_id: Option<String>,
+    pub pdf_url: Option<String>,
+}
+
+/// 按PDF中识别到的 arXiv 编号、DOI 或标题查询论文元数据
+pub struct MetadataLookup {
+    arxiv: ArxivCrawler,
+    crossref: CrossrefClient,
+}
+
+impl MetadataLookup {
+    pub fn new(config: &CrawlerConfig) -> Self {
+        Self {
+            arxiv: ArxivCrawler::new(Fetcher::new(config)),
+            crossref: CrossrefClient::new(Fetcher::new(config)),
+        }
+    }
+
+    /// 依次按 arXiv 编号、DOI（Crossref）和标题（arXiv 检索）查询；都查不到或请求失败时返回 None
+    pub async fn find(&self, metadata: &PaperMetadata) -> Option<FoundMetadata> {
+        if let Some(ref arxiv_id) = metadata.arxiv_id {
+            match self.arxiv.fetch_by_id(arxiv_id).await {
+                Ok(Some(paper)) => {
+                    info!("按 arXiv 编号 {} 找到: {}", arxiv_id, paper.title);
+                    return Some(from_arxiv(paper));
+                }
+                Ok(None) => info!("arXiv 中没有编号 {}", arxiv_id),
+                Err(e) => warn!("按 arXiv 编号查询失败: {}", e),
+            }
+        }
+        if let Some(ref doi) = metadata.doi {
+            match self.crossref.lookup(doi).await {
+                Ok(Some(work)) => {
+                    info!("按 DOI {} 找到: {}", doi, work.title);
+                    return Some(from_crossref(work));
+                }
+                Ok(None) => info!("Crossref 中没有 DOI {}", doi),
+                Err(e) => warn!("按 DOI 查询失败: {}", e),
+            }
+        }
+        if let Some(ref title) = metadata.title {
+            match self.arxiv.find_by_title(title).await {
+                Ok(Some(paper)) => {
+                    info!("按标题在 arXiv 找到: {}", paper.title);
+                    return Some(from_arxiv(paper));
+                }
+                Ok(None) => info!("arXiv 中没有标题相同的论文: {}", title),
+                Err(e) => warn!("按标题查询失败: {}", e),
+            }
+        }
+        None
+    }
+}
+
+fn from_arxiv(paper: ArxivPaper) -> FoundMetadata {
+    FoundMetadata {
+        arxiv_id: Some(paper.id.replace("http://arxiv.org/abs/", "")),
+        title: paper.title,
+        authors: paper.authors,
+        abstract_text: Some(paper.summary).filter(|s| !s.is_empty()),
+        publish_date: Some(paper.published),
+        venue: None,
+        pdf_url: Some(paper.pdf_url),
+    }
+}
+
+fn from_crossref(work: CrossrefWork) -> FoundMetadata {
+    FoundMetadata {
+        title: work.title,
+        authors: work.authors,
+        abstract_text: work.abstract_text,
+        publish_date: work.published,
+        venue: work.venue,
+        arxiv_id: None,
+        pdf_url: None,
+    }
+}
@@ -1,3 +1,10 @@
 pub mod arxiv;
+pub mod backfill;
+pub mod crossref;
+pub mod fetcher;
+pub mod lookup;
+pub mod relevance;
 
 pub use arxiv::ArxivCrawler;
+pub use fetcher::Fetcher;
+pub use lookup::MetadataLookup;
@@ -0,0 +1,288 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::config::keywords::Subscription;
+
+/// 论文与订阅的相关度评分，附带可读的评分依据
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RelevanceScore {
+    /// 0.0 ~ 1.0
+    pub score: f32,
+    pub reasons: Vec<String>,
+}
+
+impl RelevanceScore {
+    /// 单行说明，用于日志和推送内容
+    pub fn explanation(&self) -> String {
+        if self.reasons.is_empty() {
+            return "未命中任何关键词或分类".to_string();
+        }
+        self.reasons.join("；")
+    }
+
+    /// 与星标论文相似时加分
+    pub fn add_starred_similarity(&mut self, similarity: f32, starred_title: &str) {
+        self.score = (self.score + STARRED_BONUS * similarity.min(1.0)).min(1.0);
+        self.reasons.push(format!("与星标论文相似 ({:.2}): {}", similarity, starred_title));
+    }
+
+    /// 引用速度加分：最近 `days` 天入库的论文中有 `citing` 篇引用了它，引用越多加分越多
+    pub fn add_citation_velocity(&mut self, citing: usize, days: u32) {
+        if citing == 0 {
+            return;
+        }
+        self.score = (self.score + CITATION_BONUS * citing.min(MAX_CITATION_COUNT) as f32).min(1.0);
+        self.reasons.push(format!("近 {} 天被库中 {} 篇论文引用", days, citing));
+    }
+}
+
+/// 与最相似的星标论文的词频余弦相似度达到该值才计入
+const STARRED_SIMILARITY_THRESHOLD: f32 = 0.3;
+
+/// 相似度为 1 时的加分
+const STARRED_BONUS: f32 = 0.3;
+
+/// 计算引用速度的时间窗口：只统计最近这些天入库的引用论文
+pub const CITATION_WINDOW_DAYS: u32 = 30;
+
+/// 每篇引用的加分，最多按 MAX_CITATION_COUNT 篇计
+const CITATION_BONUS: f32 = 0.1;
+const MAX_CITATION_COUNT: usize = 3;
+
+/// 星标论文的词频向量，爬取开始时构建一次，用于比较新论文与星标论文的相似度
+#[derive(Default)]
+pub struct StarredProfile {
+    papers: Vec<(String, HashMap<String, f32>, f32)>,
+}
+
+impl StarredProfile {
+    /// `papers` 为星标论文的标题和摘要
+    pub fn new(papers: Vec<(String, String)>) -> Self {
+        let papers = papers
+            .into_iter()
+            .filter_map(|(title, abstract_text)| {
+                let vector = term_vector(&format!("{} {}", title, abstract_text));
+                let norm = norm(&vector);
+                (norm > 0.0).then_some((title, vector, norm))
+            })
+            .collect();
+        Self { papers }
+    }
+
+    /// 最相似且相似度不低于阈值的星标论文：(相似度, 标题)
+    pub fn most_similar(&self, title: &str, abstract_text: &str) -> Option<(f32, &str)> {
+        let vector = term_vector(&format!("{} {}", title, abstract_text));
+        let query_norm = norm(&vector);
+        if query_norm == 0.0 {
+            return None;
+        }
+        self.papers
+            .iter()
+            .map(|(starred_title, starred, starred_norm)| {
+                let dot: f32 = vector
+                    .iter()
+                    .filter_map(|(term, weight)| starred.get(term).map(|w| w * weight))
+                    .sum();
+                (dot / (query_norm * starred_norm), starred_title.as_str())
+            })
+            .filter(|(similarity, _)| *similarity >= STARRED_SIMILARITY_THRESHOLD)
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+    }
+}
+
+/// 小写英文单词（长度不少于 3，去掉常见虚词）的词频
+fn term_vector(text: &str) -> HashMap<String, f32> {
+    const STOPWORDS: [&str; 20] = [
+        "the", "and", "for", "with", "that", "this", "are", "from", "our", "can", "which", "these", "has",
+        "have", "been", "not", "its", "into", "also", "using",
+    ];
+    let mut terms = HashMap::new();
+    for word in text.split(|c: char| !c.is_ascii_alphanumeric() && c != '-') {
+        let word = word.trim_matches('-').to_lowercase();
+        if word.len() < 3 || STOPWORDS.contains(&word.as_str()) {
+            continue;
+        }
+        *terms.entry(word).or_insert(0.0) += 1.0;
+    }
+    terms
+}
+
+fn norm(vector: &HashMap<String, f32>) -> f32 {
+    vector.values().map(|w| w * w).sum::<f32>().sqrt()
+}
+
+/// LLM 判定的相关度标签
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RelevanceLabel {
+    /// 与研究方向无关
+    OffTopic,
+    /// 相关，但不是研究重点（如仅作为工具或背景）
+    Related,
+    /// 直接研究订阅关注的问题
+    Core,
+}
+
+impl RelevanceLabel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RelevanceLabel::OffTopic => "off_topic",
+            RelevanceLabel::Related => "related",
+            RelevanceLabel::Core => "core",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "off_topic" => Some(RelevanceLabel::OffTopic),
+            "related" => Some(RelevanceLabel::Related),
+            "core" => Some(RelevanceLabel::Core),
+            _ => None,
+        }
+    }
+
+    /// 中文显示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            RelevanceLabel::OffTopic => "无关",
+            RelevanceLabel::Related => "相关",
+            RelevanceLabel::Core => "核心",
+        }
+    }
+}
+
+/// 按关键词、分类和研究问题描述的命中情况计算相关度
+///
+/// 关键词标题命中记 2 分，摘要命中记 1 分；分类命中、研究问题描述中的
+/// 英文术语命中各额外记 1 分；按满分归一化。与星标论文的相似度和引用速度
+/// 由调用方通过 `add_starred_similarity` / `add_citation_velocity` 追加。
+pub fn score_paper(
+    sub: &Subscription,
+    title: &str,
+    abstract_text: &str,
+    categories: &[String],
+) -> RelevanceScore {
+    let title_lower = title.to_lowercase();
+    let abstract_lower = abstract_text.to_lowercase();
+
+    let mut title_hits = Vec::new();
+    let mut abstract_hits = Vec::new();
+    for keyword in &sub.keywords {
+        let k = keyword.trim().to_lowercase();
+        if k.is_empty() {
+            continue;
+        }
+        if title_lower.contains(&k) {
+            title_hits.push(keyword.as_str());
+        } else if abstract_lower.contains(&k) {
+            abstract_hits.push(keyword.as_str());
+        }
+    }
+
+    let category_hits: Vec<&str> = categories
+        .iter()
+        .filter(|c| sub.categories.contains(c))
+        .map(|c| c.as_str())
+        .collect();
+
+    let description_terms = description_terms(&sub.description);
+    let description_hits: Vec<&str> = description_terms
+        .iter()
+        .filter(|t| title_lower.contains(t.as_str()) || abstract_lower.contains(t.as_str()))
+        .map(|t| t.as_str())
+        .collect();
+
+    let mut reasons = Vec::new();
+    if !title_hits.is_empty() {
+        reasons.push(format!("标题命中关键词: {}", title_hits.join(", ")));
+    }
+    if !abstract_hits.is_empty() {
+        reasons.push(format!("摘要命中关键词: {}", abstract_hits.join(", ")));
+    }
+    if !category_hits.is_empty() {
+        reasons.push(format!("分类匹配: {}", category_hits.join(", ")));
+    }
+    if !description_hits.is_empty() {
+        reasons.push(format!("与研究问题相关: {}", description_hits.join(", ")));
+    }
+
+    let max = (sub.keywords.len() * 2
+        + usize::from(!sub.categories.is_empty())
+        + usize::from(!description_terms.is_empty()))
+    .max(1) as f32;
+    let raw = (title_hits.len() * 2
+        + abstract_hits.len()
+        + usize::from(!category_hits.is_empty())
+        + usize::from(!description_hits.is_empty())) as f32;
+
+    RelevanceScore {
+        score: (raw / max).min(1.0),
+        reasons,
+    }
+}
+
+/// 研究问题描述中的英文术语（长度不少于 5 的单词，去掉常见虚词）
+fn description_terms(description: &str) -> Vec<String> {
+    const STOPWORDS: [&str; 12] = [
+        "about", "which", "their", "there", "these", "those", "using", "based", "paper", "papers",
+        "research", "study",
+    ];
+    let mut terms: Vec<String> = description
+        .split(|c: char| !c.is_ascii_alphanumeric() && c != '-')
+        .filter(|w| w.len() >= 5)
+        .map(|w| w.to_lowercase())
+        .filter(|w| !STOPWORDS.contains(&w.as_str()))
+        .collect();
+    terms.sort();
+    terms.dedup();
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn starred() -> StarredProfile {
+        StarredProfile::new(vec![
+            (
+                "Speculative decoding for large language models".to_string(),
+                "We accelerate large language model inference with speculative decoding and a draft model.".to_string(),
+            ),
+            (
+                "Protein structure prediction".to_string(),
+                "A diffusion model predicts protein backbone structure from sequence.".to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn finds_most_similar_starred_paper() {
+        let profile = starred();
+        let (similarity, title) = profile
+            .most_similar(
+                "Faster speculative decoding",
+                "A new draft model makes speculative decoding of large language models faster.",
+            )
+            .unwrap();
+        assert_eq!(title, "Speculative decoding for large language models");
+        assert!((STARRED_SIMILARITY_THRESHOLD..=1.0).contains(&similarity));
+    }
+
+    #[test]
+    fn unrelated_paper_has_no_starred_match() {
+        let profile = starred();
+        assert!(profile.most_similar("Graph coloring bounds", "We prove new chromatic number bounds.").is_none());
+        assert!(StarredProfile::default().most_similar("anything", "").is_none());
+    }
+
+    #[test]
+    fn signals_add_reasons_and_cap_score() {
+        let mut score = RelevanceScore { score: 0.9, reasons: Vec::new() };
+        score.add_citation_velocity(0, 30);
+        assert!(score.reasons.is_empty());
+
+        score.add_starred_similarity(0.5, "Some paper");
+        score.add_citation_velocity(5, 30);
+        assert_eq!(score.score, 1.0);
+        assert_eq!(score.explanation(), "与星标论文相似 (0.50): Some paper；近 30 天被库中 5 篇论文引用");
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::storage::models::Paper;
+
+/// 导出为 RIS（Zotero / EndNote / Mendeley 均可导入），每条以 `ER  -` 结束
+///
+/// `keywords` 为论文ID到关键短语的映射，写入 KW 字段。
+pub fn to_ris(papers: &[Paper], keywords: &HashMap<i64, Vec<String>>) -> String {
+    let mut out = String::new();
+    for paper in papers {
+        let mut field = |tag: &str, value: &str| {
+            let value = single_line(value);
+            if !value.is_empty() {
+                out.push_str(&format!("{}  - {}\r\n", tag, value));
+            }
+        };
+        // 有会议或期刊时按期刊文章导入，否则为预印本
+        field("TY", if paper.venue.is_some() { "JOUR" } else { "UNPB" });
+        field("TI", &paper.title);
+        for author in authors(paper) {
+            field("AU", &author);
+        }
+        if let Some(venue) = &paper.venue {
+            field("T2", venue);
+        }
+        if let Some(title_zh) = &paper.title_zh {
+            field("TT", title_zh);
+        }
+        if let Some(abstract_text) = &paper.abstract_text {
+            field("AB", abstract_text);
+        }
+        if let Some(abstract_zh) = &paper.abstract_zh {
+            field("N1", &format!("中文摘要: {}", abstract_zh));
+        }
+        if let Some((year, month, day)) = publish_date(paper) {
+            field("PY", &year);
+            field("DA", &format!("{}/{}/{}/", year, month, day));
+        }
+        if let Some(id) = paper.id {
+            for keyword in keywords.get(&id).into_iter().flatten() {
+                field("KW", keyword);
+            }
+        }
+        field("UR", &landing_url(paper));
+        if let Some(pdf_url) = &paper.pdf_url {
+            field("L1", pdf_url);
+        }
+        field("DB", &paper.source);
+        field("ID", &paper.source_id);
+        out.push_str("ER  - \r\n\r\n");
+    }
+    out
+}
+
+/// 导出为 BibTeX，引用键形如 `vaswani2017attention`，重复时追加 a、b ...
+pub fn to_bibtex(papers: &[Paper], keywords: &HashMap<i64, Vec<String>>) -> String {
+    let mut used_keys = HashSet::new();
+    let mut out = String::new();
+    for paper in papers {
+        let date = publish_date(paper);
+        let base_key = citation_key(paper, date.as_ref().map(|(year, _, _)| year.as_str()));
+        let mut key = base_key.clone();
+        for suffix in 'a'..='z' {
+            if used_keys.insert(key.clone()) {
+                break;
+            }
+            key = format!("{}{}", base_key, suffix);
+        }
+
+        let mut fields: Vec<(&str, String)> = vec![("title", format!("{{{}}}", bibtex_escape(&paper.title)))];
+        let authors = authors(paper);
+        if !authors.is_empty() {
+            fields.push(("author", bibtex_escape(&authors.join(" and "))));
+        }
+        if let Some((year, month, _)) = &date {
+            fields.push(("year", year.clone()));
+            fields.push(("month", month.clone()));
+        }
+        if let Some(venue) = &paper.venue {
+            fields.push(("journal", bibtex_escape(venue)));
+        }
+        if paper.source == "arxiv" {
+            fields.push(("eprint", paper.source_id.clone()));
+            fields.push(("archivePrefix", "arXiv".to_string()));
+        }
+        let url = landing_url(paper);
+        if !url.trim().is_empty() {
+            fields.push(("url", url));
+        }
+        if let Some(abstract_text) = &paper.abstract_text {
+            fields.push(("abstract", bibtex_escape(&single_line(abstract_text))));
+        }
+        if let Some(phrases) = paper.id.and_then(|id| keywords.get(&id)).filter(|k| !k.is_empty()) {
+            fields.push(("keywords", bibtex_escape(&phrases.join(", "))));
+        }
+        if let Some(title_zh) = &paper.title_zh {
+            fields.push(("note", bibtex_escape(title_zh)));
+        }
+
+        let entry_type = if paper.venue.is_some() { "article" } else { "misc" };
+        out.push_str(&format!("@{}{{{},\n", entry_type, key));
+        for (name, value) in fields {
+            out.push_str(&format!("  {} = {{{}}},\n", name, value));
+        }
+        out.push_str("}\n\n");
+    }
+    out
+}
+
+/// `authors` 列以逗号分隔
+pub fn authors(paper: &Paper) -> Vec<String> {
+    paper
+        .authors
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect()
+}
+
+/// 发布日期（如 `2024-01-15T18:00:00Z`）拆为年、月、日
+fn publish_date(paper: &Paper) -> Option<(String, String, String)> {
+    let date = paper.publish_date.as_deref()?.get(..10)?;
+    let mut parts = date.split('-');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if year.len() != 4 || !year.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((year.to_string(), month.to_string(), day.to_string()))
+}
+
+/// 论文主页：arXiv 论文为摘要页，其他来源为 PDF 地址
+pub fn landing_url(paper: &Paper) -> String {
+    source_url(&paper.source, &paper.source_id, paper.pdf_url.as_deref())
+}
+
+/// 论文在某个来源中的主页，规则同 [`landing_url`]
+pub fn source_url(source: &str, source_id: &str, pdf_url: Option<&str>) -> String {
+    match source {
+        "arxiv" => format!("https://arxiv.org/abs/{}", source_id),
+        _ => pdf_url.unwrap_or_default().to_string(),
+    }
+}
+
+fn citation_key(paper: &Paper, year: Option<&str>) -> String {
+    let surname = authors(paper)
+        .first()
+        .and_then(|a| a.split_whitespace().last().map(str::to_string))
+        .unwrap_or_default();
+    let word = paper
+        .title
+        .split(|c: char| !c.is_alphanumeric())
+        .find(|w| w.len() > 3)
+        .unwrap_or_default();
+    let key: String = format!("{}{}{}", surname, year.unwrap_or_default(), word)
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase();
+    if key.is_empty() {
+        paper.source_id.chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+    } else {
+        key
+    }
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn bibtex_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' => {
+                out.push('\\');
+                out.push(c);
+            }
+            // 花括号不成对会破坏条目，直接去掉
+            '{' | '}' => {}
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paper(source: &str, source_id: &str, pdf_url: Option<&str>) -> Paper {
+        Paper {
+            id: Some(1),
+            title: "Attention Is All You Need".to_string(),
+            title_zh: None,
+            authors: Some("Ashish Vaswani, Noam Shazeer".to_string()),
+            abstract_text: None,
+            abstract_zh: None,
+            publish_date: Some("2017-06-12T17:57:34Z".to_string()),
+            source: source.to_string(),
+            source_id: source_id.to_string(),
+            pdf_url: pdf_url.map(str::to_string),
+            pdf_path: None,
+            processed: false,
+            created_at: None,
+            translation_status: String::new(),
+            code_urls: None,
+            venue: None,
+            funding: None,
+        }
+    }
+
+    #[test]
+    fn bibtex_includes_arxiv_url() {
+        let bibtex = to_bibtex(&[paper("arxiv", "1706.03762", None)], &HashMap::new());
+        assert!(bibtex.starts_with("@misc{vaswani2017attention,\n"));
+        assert!(bibtex.contains("  url = {https://arxiv.org/abs/1706.03762},\n"));
+        assert!(bibtex.contains("  eprint = {1706.03762},\n"));
+    }
+
+    #[test]
+    fn bibtex_omits_missing_url() {
+        let bibtex = to_bibtex(&[paper("local", "paper.pdf", None)], &HashMap::new());
+        assert!(!bibtex.contains("url ="), "{}", bibtex);
+        assert!(bibtex.contains("  title = {{Attention Is All You Need}},\n"));
+
+        let bibtex = to_bibtex(&[paper("local", "paper.pdf", Some("https://example.com/a.pdf"))], &HashMap::new());
+        assert!(bibtex.contains("  url = {https://example.com/a.pdf},\n"));
+    }
+
+    #[test]
+    fn ris_omits_missing_url() {
+        let ris = to_ris(&[paper("local", "paper.pdf", None)], &HashMap::new());
+        assert!(!ris.contains("UR  -"));
+        assert!(ris.contains("TI  - Attention Is All You Need\r\n"));
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::{bail, Context, Result};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, MultiPart, SinglePart};
+use lettre::{AsyncTransport, Message};
+use std::path::Path;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::config::EmailDigestConfig;
+use crate::utils::smtp;
+
+/// 通过 SMTP（STARTTLS 或隐式 TLS）把报告发送给收件人
+pub struct DigestMailer {
+    config: EmailDigestConfig,
+}
+
+impl DigestMailer {
+    pub fn new(config: EmailDigestConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        self.config.smtp.is_configured() && !self.config.to.is_empty()
+    }
+
+    /// 发送报告，失败时按 max_retries 重试，等待时间逐次增加
+    pub async fn send(&self, date: &str, report_path: &Path, paper_count: usize) -> Result<()> {
+        if !self.is_configured() {
+            bail!("未配置 [notify.email] 的 smtp_host 和 to");
+        }
+        let attempts = self.config.max_retries + 1;
+        let mut attempt = 1;
+        loop {
+            match self.try_send(date, report_path, paper_count).await {
+                Ok(()) => {
+                    info!("报告已发送给 {} 位收件人", self.config.to.len());
+                    return Ok(());
+                }
+                Err(e) if attempt < attempts => {
+                    let backoff = Duration::from_secs(10 * attempt as u64);
+                    warn!("发送报告邮件失败 (第 {}/{} 次): {:#}，{}s 后重试", attempt, attempts, e, backoff.as_secs());
+                    tokio::time::sleep(backoff).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(e.context(format!("发送报告邮件失败（已尝试 {} 次）", attempts))),
+            }
+        }
+    }
+
+    async fn try_send(&self, date: &str, report_path: &Path, paper_count: usize) -> Result<()> {
+        let message = self.build_message(date, report_path, paper_count)?;
+        smtp::build_transport(&self.config.smtp)?.send(message).await?;
+        Ok(())
+    }
+
+    fn build_message(&self, date: &str, report_path: &Path, paper_count: usize) -> Result<Message> {
+        let builder = smtp::message_builder(
+            &self.config.smtp,
+            &self.config.from,
+            &self.config.to,
+            format!("科研论文报告 {}（{} 篇）", date, paper_count),
+        )?;
+
+        let content = std::fs::read(report_path)
+            .with_context(|| format!("读取报告失败: {}", report_path.display()))?;
+        let filename = report_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "report.html".to_string());
+        let is_html = filename.ends_with(".html");
+        let text = format!("{} 的科研论文报告，共 {} 篇论文，详见附件 {}。", date, paper_count, filename);
+
+        // PPT 只能作为附件发送
+        let message = if is_html && !self.config.attach {
+            let html = String::from_utf8_lossy(&content).into_owned();
+            builder.multipart(MultiPart::alternative_plain_html(
+                format!("{} 的科研论文报告，共 {} 篇论文。", date, paper_count),
+                html,
+            ))?
+        } else {
+            let content_type = if is_html {
+                ContentType::TEXT_HTML
+            } else {
+                ContentType::parse("application/vnd.openxmlformats-officedocument.presentationml.presentation")?
+            };
+            builder.multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(text))
+                    .singlepart(Attachment::new(filename).body(content, content_type)),
+            )?
+        };
+        Ok(message)
+    }
+}
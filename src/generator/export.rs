@@ -0,0 +1,175 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::citation::{self, authors, landing_url};
+use crate::storage::models::Paper;
+use crate::storage::tables::escape_field;
+use crate::storage::Database;
+
+/// 论文的导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// BibTeX 题录
+    Bibtex,
+    /// RIS 题录（Zotero / EndNote / Mendeley）
+    Ris,
+    /// 完整字段的 JSON 数组
+    Json,
+    /// 每篇论文一行的 CSV，可用 Excel 打开
+    Csv,
+}
+
+impl ExportFormat {
+    /// 输出文件的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Bibtex => "bib",
+            Self::Ris => "ris",
+            Self::Json => "json",
+            Self::Csv => "csv",
+        }
+    }
+}
+
+/// 导出时附带的标签和关键短语，按论文ID索引
+#[derive(Debug, Default)]
+pub struct PaperExtras {
+    pub tags: HashMap<i64, Vec<String>>,
+    pub keyphrases: HashMap<i64, Vec<String>>,
+}
+
+impl PaperExtras {
+    pub async fn load(db: &Database) -> Result<Self> {
+        Ok(Self {
+            tags: db.get_tag_map().await?,
+            keyphrases: db.get_keyphrase_map().await?,
+        })
+    }
+
+    /// 题录的关键词：标签在前，关键短语在后，不区分大小写去重
+    pub fn keywords(&self) -> HashMap<i64, Vec<String>> {
+        let mut keywords = self.tags.clone();
+        for (paper_id, phrases) in &self.keyphrases {
+            let entry = keywords.entry(*paper_id).or_default();
+            for phrase in phrases {
+                if !entry.iter().any(|k| k.eq_ignore_ascii_case(phrase)) {
+                    entry.push(phrase.clone());
+                }
+            }
+        }
+        keywords
+    }
+
+    fn tags(&self, paper: &Paper) -> &[String] {
+        paper.id.and_then(|id| self.tags.get(&id)).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn keyphrases(&self, paper: &Paper) -> &[String] {
+        paper.id.and_then(|id| self.keyphrases.get(&id)).map(Vec::as_slice).unwrap_or_default()
+    }
+}
+
+/// JSON 导出的一篇论文
+#[derive(Serialize)]
+struct ExportedPaper<'a> {
+    id: Option<i64>,
+    source: &'a str,
+    source_id: &'a str,
+    title: &'a str,
+    title_zh: Option<&'a str>,
+    authors: Vec<String>,
+    publish_date: Option<&'a str>,
+    venue: Option<&'a str>,
+    #[serde(rename = "abstract")]
+    abstract_text: Option<&'a str>,
+    abstract_zh: Option<&'a str>,
+    url: String,
+    pdf_url: Option<&'a str>,
+    code_urls: Vec<String>,
+    tags: &'a [String],
+    keyphrases: &'a [String],
+}
+
+/// 把论文导出为指定格式的文本
+pub fn render(format: ExportFormat, papers: &[Paper], extras: &PaperExtras) -> Result<String> {
+    let text = match format {
+        ExportFormat::Bibtex => citation::to_bibtex(papers, &extras.keywords()),
+        ExportFormat::Ris => citation::to_ris(papers, &extras.keywords()),
+        ExportFormat::Json => to_json(papers, extras)?,
+        ExportFormat::Csv => to_csv(papers, extras),
+    };
+    Ok(text)
+}
+
+fn to_json(papers: &[Paper], extras: &PaperExtras) -> Result<String> {
+    let exported: Vec<ExportedPaper> = papers
+        .iter()
+        .map(|paper| ExportedPaper {
+            id: paper.id,
+            source: &paper.source,
+            source_id: &paper.source_id,
+            title: &paper.title,
+            title_zh: paper.title_zh.as_deref(),
+            authors: authors(paper),
+            publish_date: paper.publish_date.as_deref(),
+            venue: paper.venue.as_deref(),
+            abstract_text: paper.abstract_text.as_deref(),
+            abstract_zh: paper.abstract_zh.as_deref(),
+            url: landing_url(paper),
+            pdf_url: paper.pdf_url.as_deref(),
+            code_urls: paper.code_urls(),
+            tags: extras.tags(paper),
+            keyphrases: extras.keyphrases(paper),
+        })
+        .collect();
+    Ok(serde_json::to_string_pretty(&exported)? + "\n")
+}
+
+/// 与表格导出相同，以 UTF-8 BOM 开头；作者、标签等多值字段以 `; ` 连接
+fn to_csv(papers: &[Paper], extras: &PaperExtras) -> String {
+    const HEADERS: [&str; 15] = [
+        "id",
+        "source",
+        "source_id",
+        "title",
+        "title_zh",
+        "authors",
+        "publish_date",
+        "venue",
+        "abstract",
+        "abstract_zh",
+        "url",
+        "pdf_url",
+        "code_urls",
+        "tags",
+        "keyphrases",
+    ];
+
+    let mut csv = String::from('\u{feff}');
+    csv.push_str(&HEADERS.join(","));
+    csv.push_str("\r\n");
+    for paper in papers {
+        let row = [
+            paper.id.map(|id| id.to_string()).unwrap_or_default(),
+            paper.source.clone(),
+            paper.source_id.clone(),
+            paper.title.clone(),
+            paper.title_zh.clone().unwrap_or_default(),
+            authors(paper).join("; "),
+            paper.publish_date.clone().unwrap_or_default(),
+            paper.venue.clone().unwrap_or_default(),
+            paper.abstract_text.clone().unwrap_or_default(),
+            paper.abstract_zh.clone().unwrap_or_default(),
+            landing_url(paper),
+            paper.pdf_url.clone().unwrap_or_default(),
+            paper.code_urls().join("; "),
+            extras.tags(paper).join("; "),
+            extras.keyphrases(paper).join("; "),
+        ];
+        let cells: Vec<String> = row.iter().map(|cell| escape_field(cell)).collect();
+        csv.push_str(&cells.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
@@ -0,0 +1,91 @@
+use super::citation::{authors, landing_url};
+use crate::storage::models::Paper;
+
+/// 生成最新论文的 Atom 订阅源，标题和摘要中英对照
+///
+/// `papers` 按入库时间从新到旧排列，`updated` 为 RFC 3339 时间。
+pub fn to_atom(papers: &[Paper], updated: &str) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xml:lang="zh-CN">
+  <title>bsxbot 最新论文</title>
+  <id>urn:bsxbot:feed</id>
+  <updated>{}</updated>
+  <generator>bsxbot</generator>
+"#,
+        xml_escape(updated)
+    );
+
+    for paper in papers {
+        let title = match paper.title_zh.as_deref().filter(|t| !t.is_empty()) {
+            Some(title_zh) => format!("{} | {}", paper.title, title_zh),
+            None => paper.title.clone(),
+        };
+        let entry_updated = paper.created_at.as_deref().map(sqlite_to_rfc3339).unwrap_or_else(|| updated.to_string());
+
+        xml.push_str("  <entry>\n");
+        xml.push_str(&format!("    <title>{}</title>\n", xml_escape(&title)));
+        xml.push_str(&format!("    <id>urn:bsxbot:{}:{}</id>\n", xml_escape(&paper.source), xml_escape(&paper.source_id)));
+        xml.push_str(&format!("    <updated>{}</updated>\n", xml_escape(&entry_updated)));
+        if let Some(published) = paper.publish_date.as_deref().filter(|d| d.contains('T')) {
+            xml.push_str(&format!("    <published>{}</published>\n", xml_escape(published)));
+        }
+        let url = landing_url(paper);
+        if !url.is_empty() {
+            xml.push_str(&format!("    <link rel=\"alternate\" href=\"{}\"/>\n", xml_escape(&url)));
+        }
+        if let Some(pdf_url) = &paper.pdf_url {
+            xml.push_str(&format!(
+                "    <link rel=\"related\" type=\"application/pdf\" title=\"PDF\" href=\"{}\"/>\n",
+                xml_escape(pdf_url)
+            ));
+        }
+        for author in authors(paper) {
+            xml.push_str(&format!("    <author><name>{}</name></author>\n", xml_escape(&author)));
+        }
+        if let Some(venue) = &paper.venue {
+            xml.push_str(&format!("    <category term=\"{}\"/>\n", xml_escape(venue)));
+        }
+        let summary = summary_html(paper);
+        if !summary.is_empty() {
+            xml.push_str(&format!("    <summary type=\"html\">{}</summary>\n", xml_escape(&summary)));
+        }
+        xml.push_str("  </entry>\n");
+    }
+
+    xml.push_str("</feed>\n");
+    xml
+}
+
+/// 条目摘要：中文标题、中文摘要在前，英文摘要在后
+fn summary_html(paper: &Paper) -> String {
+    let mut html = String::new();
+    for (label, text) in [
+        ("中文标题", paper.title_zh.as_deref()),
+        ("中文摘要", paper.abstract_zh.as_deref()),
+        ("Abstract", paper.abstract_text.as_deref()),
+    ] {
+        if let Some(text) = text.filter(|t| !t.trim().is_empty()) {
+            html.push_str(&format!("<p><b>{}</b>: {}</p>", label, xml_escape(text.trim())));
+        }
+    }
+    html
+}
+
+/// SQLite 的 `CURRENT_TIMESTAMP`（UTC，`YYYY-MM-DD HH:MM:SS`）转为 RFC 3339
+fn sqlite_to_rfc3339(timestamp: &str) -> String {
+    match chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S") {
+        Ok(time) => time.and_utc().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
+        Err(_) => timestamp.to_string(),
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
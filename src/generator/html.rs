@@ -0,0 +1,580 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::Path;
+use tera::Tera;
+use tracing::{info, warn};
+
+use crate::config::dirs::AppDirs;
+use crate::config::GeneratorConfig;
+use crate::parser::{self, PaperContent};
+use crate::storage::models::CrawlRun;
+
+/// 报告中渲染公式使用的 KaTeX 资源地址；无法加载时公式显示为原始文本
+const KATEX_CDN: &str = "https://cdn.jsdelivr.net/npm/katex@0.16.11/dist";
+
+pub const REPORT_TEMPLATE: &str = "report.html.tera";
+pub const PAPER_TEMPLATE: &str = "paper.html.tera";
+pub const DARK_THEME: &str = "dark.css";
+
+/// 旧版占位符模板的文件名，已不再读取
+const LEGACY_TEMPLATES: [&str; 2] = ["report.html", "paper.html"];
+
+/// 内置模板，模板目录中没有对应文件时使用
+const DEFAULT_REPORT_TEMPLATE: &str = include_str!("../../config/templates/report.html.tera");
+const DEFAULT_PAPER_TEMPLATE: &str = include_str!("../../config/templates/paper.html.tera");
+const DEFAULT_DARK_THEME: &str = include_str!("../../config/templates/dark.css");
+
+/// 每篇论文最多显示的图片数和每个表格最多显示的行数
+const MAX_IMAGES: usize = 20;
+const MAX_TABLE_ROWS: usize = 20;
+
+/// 按 Tera 模板生成 HTML 报告
+///
+/// 模板目录（`<配置目录>/templates/`）中的 `report.html.tera` 决定页面框架和样式，
+/// `paper.html.tera` 决定每篇论文的版式；缺少的文件使用内置模板。
+/// 模板拿到的是结构化数据（论文、章节、公式、要点等），文本由 Tera 自动转义。
+pub struct HtmlGenerator {
+    tera: Tera,
+    /// 两个模板共用的变量：配色、样式和配置中的自定义模板变量
+    base: tera::Context,
+    /// 英文报告：界面文字为英文，不显示译文
+    english: bool,
+}
+
+/// 一组论文，`title` 为空时不分组
+#[derive(Serialize)]
+struct GroupView<'a> {
+    title: Option<&'a str>,
+    papers: Vec<PaperView<'a>>,
+}
+
+#[derive(Serialize)]
+struct NoticeView<'a> {
+    subscription: &'a str,
+    source: &'a str,
+    not_retrieved: i64,
+    total: i64,
+    fetched: i64,
+}
+
+#[derive(Serialize)]
+struct PaperView<'a> {
+    id: &'a str,
+    title: Option<&'a str>,
+    title_zh: Option<&'a str>,
+    authors: String,
+    venue: Option<&'a str>,
+    relevance: Option<String>,
+    relevance_reasons: &'a [String],
+    read: bool,
+    starred: bool,
+    #[serde(rename = "abstract")]
+    abstract_text: Option<&'a str>,
+    abstract_zh: Option<&'a str>,
+    code_links: Vec<CodeLinkView<'a>>,
+    keyphrases: &'a [parser::Keyphrase],
+    key_points: &'a [String],
+    sections: Vec<SectionView<'a>>,
+    formulas: Vec<FormulaView<'a>>,
+    hidden_formulas: usize,
+    images: Vec<ImageView<'a>>,
+    hidden_images: usize,
+    tables: Vec<TableView<'a>>,
+    funding: &'a [String],
+    links: &'a [parser::Link],
+    section_count: usize,
+    formula_count: usize,
+    image_count: usize,
+    table_count: usize,
+    link_count: usize,
+    search_text: String,
+    empty: bool,
+}
+
+#[derive(Serialize)]
+struct CodeLinkView<'a> {
+    url: &'a str,
+    host: &'static str,
+}
+
+#[derive(Serialize)]
+struct SectionView<'a> {
+    heading: &'a str,
+    page: Option<usize>,
+    body: Vec<TextPart<'a>>,
+    citations: Vec<CitedView<'a>>,
+}
+
+/// 章节正文片段；公式引用（如「Eq. (3)」）带有指向对应公式的锚点
+#[derive(Serialize)]
+struct TextPart<'a> {
+    text: &'a str,
+    anchor: Option<String>,
+}
+
+#[derive(Serialize)]
+struct CitedView<'a> {
+    label: Option<&'a str>,
+    author: Option<&'a str>,
+    year: Option<i32>,
+    title: &'a str,
+}
+
+#[derive(Serialize)]
+struct FormulaView<'a> {
+    raw: String,
+    number: Option<&'a str>,
+    latex: Option<&'a str>,
+    anchor: Option<String>,
+    page: Option<usize>,
+    context: &'a str,
+}
+
+#[derive(Serialize)]
+struct ImageView<'a> {
+    src: String,
+    page: usize,
+    caption: Option<&'a str>,
+    width: u32,
+    height: u32,
+    format: &'a str,
+}
+
+#[derive(Serialize)]
+struct TableView<'a> {
+    caption: Option<&'a str>,
+    page: Option<usize>,
+    well_formed: bool,
+    headers: &'a [String],
+    rows: &'a [Vec<String>],
+}
+
+impl HtmlGenerator {
+    /// 加载模板目录中的模板和主题，配置中的自定义模板变量在两个模板中都可以使用
+    pub fn new(dirs: &AppDirs, config: &GeneratorConfig) -> Result<Self> {
+        let templates_dir = dirs.templates_dir();
+        for legacy in LEGACY_TEMPLATES {
+            let path = templates_dir.join(legacy);
+            if path.exists() {
+                warn!("模板 {} 已不再使用，请改为 Tera 语法的 {}.tera", path.display(), legacy);
+            }
+        }
+
+        let mut tera = Tera::default();
+        tera.autoescape_on(vec![".html.tera"]);
+        tera.add_raw_templates(vec![
+            (REPORT_TEMPLATE, read_or_default(&templates_dir, REPORT_TEMPLATE, DEFAULT_REPORT_TEMPLATE)?),
+            (PAPER_TEMPLATE, read_or_default(&templates_dir, PAPER_TEMPLATE, DEFAULT_PAPER_TEMPLATE)?),
+        ])
+        .map_err(|e| anyhow::anyhow!("模板语法错误 ({}): {}", templates_dir.display(), tera_error_chain(&e)))?;
+
+        let mut base = tera::Context::new();
+        for (key, value) in &config.template_vars {
+            base.insert(key.as_str(), value);
+        }
+        // 深色样式覆盖在默认（浅色）样式之上
+        let dark_css = || read_or_default(&templates_dir, DARK_THEME, DEFAULT_DARK_THEME);
+        let (color_scheme, theme_css) = match config.theme.trim().to_lowercase().as_str() {
+            "light" => ("light", String::new()),
+            "dark" => ("dark", dark_css()?),
+            other => {
+                if !matches!(other, "auto" | "") {
+                    warn!("未知的 theme '{}'，使用 auto", other);
+                }
+                ("light dark", format!("@media (prefers-color-scheme: dark) {{\n{}}}\n", dark_css()?))
+            }
+        };
+        base.insert("color_scheme", color_scheme);
+        base.insert("theme_css", &theme_css);
+        if !config.custom_css.trim().is_empty() {
+            let path = dirs.config_dir.join(config.custom_css.trim());
+            let css = std::fs::read_to_string(&path)
+                .with_context(|| format!("读取自定义样式失败: {}", path.display()))?;
+            base.insert("custom_css", &css);
+        }
+
+        Ok(Self { tera, base, english: false })
+    }
+
+    /// 生成英文报告，模板中的 `english` 变量为 true
+    pub fn english(mut self, english: bool) -> Self {
+        self.english = english;
+        self
+    }
+
+    /// `groups` 为每篇论文的分组标题（与 `papers` 一一对应，同组论文相邻），为空时不分组
+    pub fn generate(
+        &self,
+        date: &str,
+        papers: &[(String, PaperContent)],
+        groups: Option<&[String]>,
+        crawl_runs: &[CrawlRun],
+        translation_label: &str,
+    ) -> Result<String> {
+        let mut group_views: Vec<GroupView> = Vec::new();
+        for (index, (paper_id, content)) in papers.iter().enumerate() {
+            let title = groups.and_then(|g| g.get(index)).map(String::as_str);
+            let paper = self.paper_view(paper_id, content);
+            match group_views.last_mut() {
+                Some(last) if title.is_some() && last.title == title => last.papers.push(paper),
+                _ => group_views.push(GroupView { title, papers: vec![paper] }),
+            }
+        }
+
+        let notices: Vec<NoticeView> = crawl_runs
+            .iter()
+            .map(|run| NoticeView {
+                subscription: &run.subscription,
+                source: &run.source,
+                not_retrieved: run.not_retrieved(),
+                total: run.total_matched.unwrap_or_default(),
+                fetched: run.fetched,
+            })
+            .collect();
+
+        let mut context = self.base.clone();
+        context.insert("english", &self.english);
+        context.insert("date", date);
+        context.insert("count", &papers.len());
+        context.insert("katex", KATEX_CDN);
+        context.insert("translation_label", translation_label);
+        context.insert("notices", &notices);
+        context.insert("groups", &group_views);
+        self.tera
+            .render(REPORT_TEMPLATE, &context)
+            .map_err(|e| anyhow::anyhow!("渲染报告模板失败: {}", tera_error_chain(&e)))
+    }
+
+    fn paper_view<'a>(&self, paper_id: &'a str, content: &'a PaperContent) -> PaperView<'a> {
+        let meta = &content.metadata;
+        let formula_total = content.formula_total.max(content.formulas.len());
+        // 英文报告不显示译文；要点和评分依据按中文生成，同样省略
+        let translated = |text: &'a Option<String>| if self.english { None } else { text.as_deref() };
+        let chinese_only = |items: &'a [String]| if self.english { &[][..] } else { items };
+
+        PaperView {
+            id: paper_id,
+            title: meta.title.as_deref(),
+            title_zh: translated(&meta.title_zh),
+            authors: meta.authors.join(", "),
+            venue: meta.venue.as_deref(),
+            relevance: meta.relevance.map(|score| format!("{:.2}", score)),
+            relevance_reasons: chinese_only(&meta.relevance_reasons),
+            read: meta.read,
+            starred: meta.starred,
+            abstract_text: meta.abstract_text.as_deref(),
+            abstract_zh: translated(&meta.abstract_zh),
+            code_links: meta.code_urls.iter().map(|url| CodeLinkView { url, host: code_host(url) }).collect(),
+            keyphrases: &content.keyphrases,
+            key_points: chinese_only(&meta.key_points),
+            sections: section_views(paper_id, content),
+            formulas: formula_views(paper_id, content),
+            hidden_formulas: if content.formulas.is_empty() { 0 } else { formula_total - content.formulas.len() },
+            images: content.images.iter().take(MAX_IMAGES).map(image_view).collect(),
+            hidden_images: content.images.len().saturating_sub(MAX_IMAGES),
+            tables: content
+                .tables
+                .iter()
+                .map(|table| TableView {
+                    caption: table.caption.as_deref(),
+                    page: table.page,
+                    // 结构不可信的表格折叠显示，避免错位的行列误导阅读
+                    well_formed: table.is_well_formed(),
+                    headers: &table.headers,
+                    rows: &table.rows[..table.rows.len().min(MAX_TABLE_ROWS)],
+                })
+                .collect(),
+            funding: &meta.funding,
+            links: &content.links,
+            section_count: content.sections.len(),
+            formula_count: formula_total,
+            image_count: content.images.len(),
+            table_count: content.tables.len(),
+            link_count: content.links.len(),
+            search_text: search_text(paper_id, content),
+            empty: content.sections.is_empty()
+                && content.formulas.is_empty()
+                && content.images.is_empty()
+                && content.tables.is_empty(),
+        }
+    }
+}
+
+/// Tera 错误的完整原因链（最外层通常只有“渲染失败”）
+fn tera_error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
+}
+
+/// 读取模板目录中的文件，不存在时使用内置内容
+fn read_or_default(dir: &Path, name: &str, default: &str) -> Result<String> {
+    let path = dir.join(name);
+    if !path.exists() {
+        return Ok(default.to_string());
+    }
+    info!("使用自定义模板: {}", path.display());
+    std::fs::read_to_string(&path).with_context(|| format!("读取模板失败: {}", path.display()))
+}
+
+/// 把内置模板写入模板目录，已存在的文件保留不动
+pub fn write_default_templates(dir: &Path) -> Result<Vec<std::path::PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    let mut written = Vec::new();
+    for (name, source) in [
+        (REPORT_TEMPLATE, DEFAULT_REPORT_TEMPLATE),
+        (PAPER_TEMPLATE, DEFAULT_PAPER_TEMPLATE),
+        (DARK_THEME, DEFAULT_DARK_THEME),
+    ] {
+        let path = dir.join(name);
+        if !path.exists() {
+            std::fs::write(&path, source)?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+/// 报告内搜索匹配的文本：标题、中文标题、作者、关键短语、会议和论文 ID
+fn search_text(paper_id: &str, content: &PaperContent) -> String {
+    let meta = &content.metadata;
+    let mut parts: Vec<&str> = vec![paper_id];
+    parts.extend(meta.title.as_deref());
+    parts.extend(meta.title_zh.as_deref());
+    parts.extend(meta.venue.as_deref());
+    parts.extend(meta.authors.iter().map(String::as_str));
+    parts.extend(content.keyphrases.iter().map(|k| k.phrase.as_str()));
+    parts.join(" ").split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// 代码仓库所在平台，显示在徽章上
+fn code_host(url: &str) -> &'static str {
+    if url.contains("huggingface.co") {
+        "Hugging Face"
+    } else if url.contains("gitlab.com") {
+        "GitLab"
+    } else {
+        "GitHub"
+    }
+}
+
+/// 超过 `max` 字节的文本截断并加省略号
+fn truncate(text: &str, max: usize) -> String {
+    if text.len() > max {
+        format!("{}...", &text[..text.floor_char_boundary(max)])
+    } else {
+        text.to_string()
+    }
+}
+
+fn section_views<'a>(paper_id: &str, content: &'a PaperContent) -> Vec<SectionView<'a>> {
+    let numbers: HashSet<&str> = content.formulas.iter().filter_map(|f| f.number.as_deref()).collect();
+    content
+        .sections
+        .iter()
+        .map(|section| {
+            let preview = &section.body[..section.body.floor_char_boundary(800)];
+            let mut body = link_equation_refs(preview, paper_id, &numbers);
+            if preview.len() < section.body.len() {
+                body.push(TextPart { text: "...", anchor: None });
+            }
+            SectionView {
+                heading: &section.heading,
+                page: section.page,
+                body,
+                citations: section_citations(content, &section.heading),
+            }
+        })
+        .collect()
+}
+
+fn formula_views<'a>(paper_id: &str, content: &'a PaperContent) -> Vec<FormulaView<'a>> {
+    content
+        .formulas
+        .iter()
+        .map(|formula| FormulaView {
+            raw: truncate(&formula.raw, 200),
+            number: formula.number.as_deref(),
+            latex: formula.latex.as_deref(),
+            anchor: formula.number.as_deref().map(|n| equation_anchor(paper_id, n)),
+            page: formula.page,
+            context: &formula.context[..formula.context.floor_char_boundary(120)],
+        })
+        .collect()
+}
+
+fn image_view(image: &parser::ExtractedImage) -> ImageView<'_> {
+    // Report is at <data>/reports/, images at <data>/images/
+    let img_path = image.filename.replace('\\', "/");
+    let src = match img_path.rsplit('/').next() {
+        Some(name) => format!("../images/{}", name),
+        None => img_path.clone(),
+    };
+    ImageView {
+        src,
+        page: image.page,
+        caption: image.caption.as_deref(),
+        width: image.width,
+        height: image.height,
+        format: &image.format,
+    }
+}
+
+/// 编号公式在报告中的锚点
+fn equation_anchor(paper_id: &str, number: &str) -> String {
+    format!("eq-{}-{}", paper_id, number)
+}
+
+/// 把正文切成片段，「Eq. (3)」指向报告中对应编号的公式；未保留的公式不加链接
+fn link_equation_refs<'a>(text: &'a str, paper_id: &str, numbers: &HashSet<&str>) -> Vec<TextPart<'a>> {
+    let mut parts = Vec::new();
+    let mut last = 0;
+    if !numbers.is_empty() {
+        let pattern = Regex::new(parser::EQUATION_REFERENCE_PATTERN).unwrap();
+        for caps in pattern.captures_iter(text) {
+            if !numbers.contains(&caps[1]) {
+                continue;
+            }
+            let whole = caps.get(0).unwrap();
+            if whole.start() > last {
+                parts.push(TextPart { text: &text[last..whole.start()], anchor: None });
+            }
+            parts.push(TextPart { text: whole.as_str(), anchor: Some(equation_anchor(paper_id, &caps[1])) });
+            last = whole.end();
+        }
+    }
+    if last < text.len() {
+        parts.push(TextPart { text: &text[last..], anchor: None });
+    }
+    parts
+}
+
+/// 章节引用的参考文献（“本节引用”）
+fn section_citations<'a>(content: &'a PaperContent, heading: &str) -> Vec<CitedView<'a>> {
+    let Some(citations) = content.citations.iter().find(|c| c.heading == heading) else {
+        return Vec::new();
+    };
+    citations
+        .references
+        .iter()
+        .filter_map(|cited| content.references.get(cited.position))
+        .map(|reference| CitedView {
+            label: reference.label.as_deref(),
+            author: reference.authors.first().map(String::as_str),
+            year: reference.year,
+            title: reference.title.as_deref().unwrap_or(&reference.raw),
+        })
+        .collect()
+}
+
+/// 按报告语言选择界面文字
+pub(crate) fn tr<'a>(english: bool, zh: &'a str, en: &'a str) -> &'a str {
+    if english {
+        en
+    } else {
+        zh
+    }
+}
+
+pub fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+     .replace('<', "&lt;")
+     .replace('>', "&gt;")
+     .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn generator(english: bool) -> HtmlGenerator {
+        // 不存在的配置目录：全部使用内置模板
+        let root = std::env::temp_dir().join("bsxbot-html-test-missing");
+        let dirs = AppDirs { config_dir: root.join("config"), data_dir: root.join("data") };
+        HtmlGenerator::new(&dirs, &AppConfig::default().generator).unwrap().english(english)
+    }
+
+    fn paper(title: &str) -> PaperContent {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "title": title,
+                "title_zh": "注意力机制",
+                "authors": ["Ada Lovelace"],
+                "abstract_text": "We use a < b & c.",
+                "abstract_zh": "摘要译文",
+                "key_points": ["要点一"],
+            },
+            "sections": [{
+                "heading": "Method",
+                "level": 1,
+                "body": "As shown in Eq. (1), the loss converges. See Eq. (9).",
+                "page": 3,
+                "confidence": 1.0,
+            }],
+            "formulas": [{
+                "raw": "L = x^2",
+                "context": "the loss",
+                "kind": "display",
+                "number": "1",
+                "occurrences": 1,
+                "referenced": true,
+                "page": 3,
+                "latex": "L = x^2",
+                "confidence": 1.0,
+            }],
+            "images": [],
+            "tables": [],
+            "full_text": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn renders_structured_papers_with_escaping() {
+        let papers = vec![("2401.00001".to_string(), paper("<script>alert(1)</script>"))];
+        let html = generator(false).generate("2026-10-17", &papers, None, &[], "中文翻译").unwrap();
+
+        assert!(!html.contains("<script>alert(1)</script>"));
+        assert!(html.contains("&lt;script&gt;alert(1)&lt;"));
+        assert!(html.contains("We use a &lt; b &amp; c."));
+        assert!(html.contains("注意力机制"));
+        assert!(html.contains("<li>要点一</li>"));
+        assert!(html.contains(r##"<a class="eq-ref" href="#eq-2401.00001-1">Eq. (1)</a>"##));
+        // 没有对应公式的引用不加链接
+        assert!(html.contains("See Eq. (9)."));
+        assert!(html.contains(r#"id="eq-2401.00001-1""#));
+    }
+
+    #[test]
+    fn english_report_omits_translations() {
+        let papers = vec![("2401.00001".to_string(), paper("Attention"))];
+        let html = generator(true).generate("2026-10-17", &papers, None, &[], "中文翻译").unwrap();
+
+        assert!(html.contains(r#"<html lang="en">"#));
+        assert!(!html.contains(r#"<div class="paper-title-zh">"#));
+        assert!(!html.contains("摘要译文"));
+        assert!(!html.contains("要点一"));
+    }
+
+    #[test]
+    fn groups_adjacent_papers_under_one_heading() {
+        let papers = vec![
+            ("a".to_string(), paper("First")),
+            ("b".to_string(), paper("Second")),
+            ("c".to_string(), paper("Third")),
+        ];
+        let groups = vec!["llm".to_string(), "llm".to_string(), "vision".to_string()];
+        let html = generator(false).generate("2026-10-17", &papers, Some(&groups), &[], "中文翻译").unwrap();
+
+        assert_eq!(html.matches(r#"<section class="paper-group">"#).count(), 2);
+        assert!(html.contains(r#"llm <span class="group-count">2 篇</span>"#));
+        assert!(html.contains(r#"vision <span class="group-count">1 篇</span>"#));
+    }
+}
@@ -1,3 +1,18 @@
-// 报告生成模块占位
-// TODO: 实现PPT生成功能
+pub mod citation;
+pub mod email;
+pub mod export;
+pub mod feed;
+pub mod html;
+pub mod notion;
+pub mod ppt;
+pub mod publisher;
+pub mod section_filter;
+pub mod site;
 
+pub use email::DigestMailer;
+pub use html::HtmlGenerator;
+pub use notion::NotionExporter;
+pub use ppt::PptGenerator;
+pub use publisher::Publisher;
+pub use section_filter::SectionFilter;
+pub use site::SiteBuilder;
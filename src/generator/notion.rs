@@ -0,0 +1,180 @@
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::citation::landing_url;
+use crate::config::NotionConfig;
+use crate::storage::models::Paper;
+
+const NOTION_API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Notion 单个文本对象最多 2000 字符
+const MAX_TEXT_LEN: usize = 2000;
+/// 官方限速约为每秒 3 次请求
+const REQUEST_INTERVAL: Duration = Duration::from_millis(350);
+const MAX_RETRIES: u32 = 3;
+
+/// 导出结果统计
+#[derive(Debug, Default)]
+pub struct NotionStats {
+    pub created: usize,
+    pub updated: usize,
+    pub failed: usize,
+}
+
+/// 把论文写入 Notion 数据库，每篇论文一个页面
+///
+/// 以 `Source ID` 属性识别已导出的页面：已存在时更新属性，否则新建，重复导出不会产生重复页面。
+/// 数据库需要包含以下属性：Name（标题）、中文标题（文本）、Abstract（文本）、Tags（多选）、
+/// PDF（URL）、Source ID（文本）、Link（URL）。
+pub struct NotionExporter {
+    client: reqwest::Client,
+    config: NotionConfig,
+}
+
+impl NotionExporter {
+    pub fn new(config: NotionConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(60))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client, config }
+    }
+
+    pub fn is_configured(&self) -> bool {
+        !self.config.token.trim().is_empty() && !self.config.database_id.trim().is_empty()
+    }
+
+    /// `tags` 与 `papers` 一一对应
+    pub async fn export(&self, papers: &[Paper], tags: &[Vec<String>]) -> Result<NotionStats> {
+        if !self.is_configured() {
+            bail!("未配置 [notion] 的 token 和 database_id");
+        }
+        let mut stats = NotionStats::default();
+        for (paper, tags) in papers.iter().zip(tags) {
+            match self.upsert(paper, tags).await {
+                Ok(true) => stats.created += 1,
+                Ok(false) => stats.updated += 1,
+                Err(e) => {
+                    warn!("导出到 Notion 失败 {}: {:#}", paper.source_id, e);
+                    stats.failed += 1;
+                }
+            }
+        }
+        info!(
+            "Notion 导出完成: 新建 {} 篇，更新 {} 篇，失败 {} 篇",
+            stats.created, stats.updated, stats.failed
+        );
+        Ok(stats)
+    }
+
+    /// 新建或更新一篇论文的页面，新建时返回 true
+    async fn upsert(&self, paper: &Paper, tags: &[String]) -> Result<bool> {
+        let properties = page_properties(paper, tags);
+        match self.find_page(&paper.source_id).await? {
+            Some(page_id) => {
+                self.request(reqwest::Method::PATCH, &format!("pages/{}", page_id), json!({ "properties": properties }))
+                    .await?;
+                info!("已更新 Notion 页面: {}", paper.source_id);
+                Ok(false)
+            }
+            None => {
+                let body = json!({
+                    "parent": { "database_id": self.config.database_id.trim() },
+                    "properties": properties,
+                });
+                self.request(reqwest::Method::POST, "pages", body).await?;
+                info!("已新建 Notion 页面: {}", paper.source_id);
+                Ok(true)
+            }
+        }
+    }
+
+    async fn find_page(&self, source_id: &str) -> Result<Option<String>> {
+        let body = json!({
+            "filter": { "property": "Source ID", "rich_text": { "equals": source_id } },
+            "page_size": 1,
+        });
+        let response = self
+            .request(
+                reqwest::Method::POST,
+                &format!("databases/{}/query", self.config.database_id.trim()),
+                body,
+            )
+            .await?;
+        Ok(response["results"]
+            .as_array()
+            .and_then(|results| results.first())
+            .and_then(|page| page["id"].as_str())
+            .map(str::to_string))
+    }
+
+    /// 发送请求，遇到限速（429）或服务端错误时按 Retry-After 等待后重试
+    async fn request(&self, method: reqwest::Method, path: &str, body: Value) -> Result<Value> {
+        let url = format!("{}/{}", NOTION_API, path);
+        let mut attempt = 0;
+        loop {
+            tokio::time::sleep(REQUEST_INTERVAL).await;
+            let response = self
+                .client
+                .request(method.clone(), &url)
+                .bearer_auth(self.config.token.trim())
+                .header("Notion-Version", NOTION_VERSION)
+                .json(&body)
+                .send()
+                .await
+                .with_context(|| format!("请求 Notion 失败: {}", url))?;
+
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response.json().await?);
+            }
+            let retryable = status.as_u16() == 429 || status.is_server_error();
+            if retryable && attempt < MAX_RETRIES {
+                attempt += 1;
+                let wait = response
+                    .headers()
+                    .get("retry-after")
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<u64>().ok())
+                    .unwrap_or(attempt as u64 * 2);
+                warn!("Notion 返回 {}，{}s 后重试 ({}/{})", status, wait, attempt, MAX_RETRIES);
+                tokio::time::sleep(Duration::from_secs(wait)).await;
+                continue;
+            }
+            let text = response.text().await.unwrap_or_default();
+            bail!("Notion 返回错误 {}: {}", status, text);
+        }
+    }
+}
+
+fn page_properties(paper: &Paper, tags: &[String]) -> Value {
+    let url_or_null = |url: String| if url.is_empty() { Value::Null } else { Value::String(url) };
+    // 多选项不能包含逗号，且最长 100 字符
+    let tags: Vec<Value> = tags
+        .iter()
+        .map(|t| t.replace(',', " ").trim().chars().take(100).collect::<String>())
+        .filter(|t| !t.is_empty())
+        .map(|t| json!({ "name": t }))
+        .collect();
+    json!({
+        "Name": { "title": rich_text(&paper.title) },
+        "中文标题": { "rich_text": rich_text(paper.title_zh.as_deref().unwrap_or_default()) },
+        "Abstract": { "rich_text": rich_text(paper.abstract_text.as_deref().unwrap_or_default()) },
+        "Tags": { "multi_select": tags },
+        "PDF": { "url": url_or_null(paper.pdf_url.clone().unwrap_or_default()) },
+        "Source ID": { "rich_text": rich_text(&paper.source_id) },
+        "Link": { "url": url_or_null(landing_url(paper)) },
+    })
+}
+
+/// 长文本按 2000 字符拆成多个文本对象
+fn rich_text(text: &str) -> Value {
+    let chars: Vec<char> = text.trim().chars().collect();
+    let parts: Vec<Value> = chars
+        .chunks(MAX_TEXT_LEN)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect();
+    Value::Array(parts)
+}
@@ -0,0 +1,638 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::io::{Seek, Write};
+use std::path::Path;
+use tracing::{info, warn};
+use zip::write::SimpleFileOptions;
+use zip::CompressionMethod;
+
+use super::html::tr;
+use crate::config::GeneratorConfig;
+use crate::parser::{ExtractedImage, PaperContent};
+use crate::storage::images::ImageStore;
+
+/// 1 英寸对应的 EMU（OOXML 的长度单位）
+const EMU_PER_INCH: i64 = 914_400;
+/// 16:9 幻灯片，13.333 x 7.5 英寸
+const SLIDE_WIDTH: i64 = 12_192_000;
+const SLIDE_HEIGHT: i64 = 6_858_000;
+
+/// 每页最多显示的要点和公式数
+const MAX_KEY_POINTS: usize = 5;
+const MAX_FORMULAS: usize = 2;
+/// 没有要点时用摘要代替，截断到该长度
+const MAX_ABSTRACT_CHARS: usize = 600;
+
+const LATIN_FONT: &str = "Calibri";
+const EA_FONT: &str = "Microsoft YaHei";
+
+const NS: &str = r#"xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main""#;
+const REL_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+const PKG_REL_NS: &str = "http://schemas.openxmlformats.org/package/2006/relationships";
+
+/// 幻灯片配色，对应配置中的 `ppt_template`
+#[derive(Debug, Clone, Copy)]
+struct Palette {
+    background: &'static str,
+    title: &'static str,
+    text: &'static str,
+    muted: &'static str,
+    accent: &'static str,
+}
+
+impl Palette {
+    fn from_template(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "academic" | "" => Self::ACADEMIC,
+            "dark" => Self {
+                background: "263238",
+                title: "FFFFFF",
+                text: "ECEFF1",
+                muted: "90A4AE",
+                accent: "80CBC4",
+            },
+            "minimal" => Self {
+                background: "FFFFFF",
+                title: "212121",
+                text: "424242",
+                muted: "9E9E9E",
+                accent: "757575",
+            },
+            other => {
+                warn!("未知的 ppt_template '{}'，使用 academic", other);
+                Self::ACADEMIC
+            }
+        }
+    }
+
+    const ACADEMIC: Self = Self {
+        background: "FFFFFF",
+        title: "1A237E",
+        text: "333333",
+        muted: "888888",
+        accent: "5C6BC0",
+    };
+}
+
+/// 生成 PowerPoint 报告（.pptx）：封面一页，之后每篇论文一页，
+/// 包含标题、中文标题、要点和一张配图
+pub struct PptGenerator {
+    palette: Palette,
+    include_images: bool,
+    include_formulas: bool,
+    /// 英文报告：界面文字为英文，不显示译文和要点
+    english: bool,
+}
+
+impl PptGenerator {
+    pub fn new(config: &GeneratorConfig) -> Self {
+        Self {
+            palette: Palette::from_template(&config.ppt_template),
+            include_images: config.include_images,
+            include_formulas: config.include_formulas,
+            english: false,
+        }
+    }
+
+    pub fn english(mut self, english: bool) -> Self {
+        self.english = english;
+        self
+    }
+
+    pub fn generate(
+        &self,
+        date: &str,
+        papers: &[(String, PaperContent)],
+        images: &dyn ImageStore,
+        output: &Path,
+    ) -> Result<()> {
+        let mut media: Vec<(String, Vec<u8>)> = Vec::new();
+        let mut media_by_key: HashMap<String, String> = HashMap::new();
+        let mut slides = vec![(self.cover_slide(date, papers.len()), None)];
+
+        for (paper_id, content) in papers {
+            let figure = if self.include_images {
+                pick_figure(&content.images, images)
+            } else {
+                None
+            };
+            let figure = figure.and_then(|img| {
+                let name = match media_by_key.get(&img.filename) {
+                    Some(name) => name.clone(),
+                    None => {
+                        let data = match std::fs::read(images.path(&img.filename)) {
+                            Ok(data) => data,
+                            Err(e) => {
+                                warn!("读取图片失败 {}: {}", img.filename, e);
+                                return None;
+                            }
+                        };
+                        let name = format!("image{}.{}", media.len() + 1, media_extension(&img.filename)?);
+                        media.push((name.clone(), data));
+                        media_by_key.insert(img.filename.clone(), name.clone());
+                        name
+                    }
+                };
+                Some((img, name))
+            });
+            let slide = self.paper_slide(paper_id, content, figure.as_ref().map(|(img, _)| *img));
+            slides.push((slide, figure.map(|(_, name)| name)));
+        }
+
+        let file = std::fs::File::create(output).with_context(|| format!("创建PPT失败: {}", output.display()))?;
+        let mut zip = PackageWriter::new(std::io::BufWriter::new(file));
+        zip.add("[Content_Types].xml", content_types(slides.len()).as_bytes())?;
+        zip.add("_rels/.rels", package_rels().as_bytes())?;
+        zip.add("ppt/presentation.xml", presentation(slides.len()).as_bytes())?;
+        zip.add("ppt/_rels/presentation.xml.rels", presentation_rels(slides.len()).as_bytes())?;
+        zip.add("ppt/slideMasters/slideMaster1.xml", slide_master().as_bytes())?;
+        zip.add(
+            "ppt/slideMasters/_rels/slideMaster1.xml.rels",
+            rels(&[("rId1", "slideLayout", "../slideLayouts/slideLayout1.xml"), ("rId2", "theme", "../theme/theme1.xml")])
+                .as_bytes(),
+        )?;
+        zip.add("ppt/slideLayouts/slideLayout1.xml", slide_layout().as_bytes())?;
+        zip.add(
+            "ppt/slideLayouts/_rels/slideLayout1.xml.rels",
+            rels(&[("rId1", "slideMaster", "../slideMasters/slideMaster1.xml")]).as_bytes(),
+        )?;
+        zip.add("ppt/theme/theme1.xml", theme(&self.palette).as_bytes())?;
+        for (i, (slide, figure)) in slides.iter().enumerate() {
+            let n = i + 1;
+            zip.add(&format!("ppt/slides/slide{}.xml", n), slide.as_bytes())?;
+            let target = figure.as_ref().map(|name| format!("../media/{}", name));
+            let mut entries = vec![("rId1", "slideLayout", "../slideLayouts/slideLayout1.xml")];
+            if let Some(target) = target.as_deref() {
+                entries.push(("rId2", "image", target));
+            }
+            zip.add(&format!("ppt/slides/_rels/slide{}.xml.rels", n), rels(&entries).as_bytes())?;
+        }
+        for (name, data) in &media {
+            zip.add(&format!("ppt/media/{}", name), data)?;
+        }
+        zip.finish().with_context(|| format!("写入PPT失败: {}", output.display()))?;
+        info!("PPT共 {} 页，{} 张配图", slides.len(), media.len());
+        Ok(())
+    }
+
+    fn cover_slide(&self, date: &str, count: usize) -> String {
+        let p = &self.palette;
+        let mut shapes = ShapeTree::default();
+        shapes.rect(0, 0, SLIDE_WIDTH, inches(0.25), p.accent);
+        shapes.text_box(
+            "Title",
+            (inches(0.8), inches(2.4), inches(11.7), inches(1.3)),
+            &[Paragraph::new(tr(self.english, "科研论文提取报告", "Research Paper Digest"), 4000, p.title).bold()],
+        );
+        shapes.text_box(
+            "Subtitle",
+            (inches(0.8), inches(3.8), inches(11.7), inches(0.8)),
+            &[Paragraph::new(
+                &if self.english {
+                    format!("Date: {}  |  Papers: {}", date, count)
+                } else {
+                    format!("日期: {}  |  论文数: {}", date, count)
+                },
+                2000,
+                p.muted,
+            )],
+        );
+        slide_xml(p, &shapes)
+    }
+
+    fn paper_slide(&self, paper_id: &str, content: &PaperContent, figure: Option<&ExtractedImage>) -> String {
+        let p = &self.palette;
+        let meta = &content.metadata;
+        let mut shapes = ShapeTree::default();
+        shapes.rect(0, 0, SLIDE_WIDTH, inches(0.12), p.accent);
+
+        let title = meta.title.as_deref().unwrap_or(tr(self.english, "(未提取到标题)", "(title not extracted)"));
+        let title = if meta.starred { format!("★ {}", title) } else { title.to_string() };
+        shapes.text_box(
+            "Title",
+            (inches(0.5), inches(0.3), inches(12.33), inches(1.0)),
+            &[Paragraph::new(&title, 2400, p.title).bold()],
+        );
+        if let Some(title_zh) = meta.title_zh.as_deref().filter(|t| !t.is_empty() && !self.english) {
+            shapes.text_box(
+                "Title (zh)",
+                (inches(0.5), inches(1.3), inches(12.33), inches(0.6)),
+                &[Paragraph::new(title_zh, 1800, p.text)],
+            );
+        }
+
+        let formulas: Vec<String> = if self.include_formulas {
+            content
+                .formulas
+                .iter()
+                .filter(|f| f.number.is_some())
+                .take(MAX_FORMULAS)
+                .map(|f| {
+                    let body = f.latex.as_deref().unwrap_or(&f.raw);
+                    let body = truncate(body, 120);
+                    match f.number.as_deref() {
+                        Some(n) => format!("({}) {}", n, body),
+                        None => body,
+                    }
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let body_width = if figure.is_some() { inches(6.6) } else { inches(12.33) };
+        let body_height = if formulas.is_empty() { inches(4.5) } else { inches(3.6) };
+        let mut body = Vec::new();
+        if !meta.key_points.is_empty() && !self.english {
+            body.push(Paragraph::new("要点", 1800, p.accent).bold());
+            for point in meta.key_points.iter().take(MAX_KEY_POINTS) {
+                body.push(Paragraph::new(point, 1600, p.text).bullet());
+            }
+        } else if let Some(abstract_text) = meta.abstract_text.as_deref().filter(|a| !a.is_empty()) {
+            body.push(Paragraph::new(tr(self.english, "摘要", "Abstract"), 1800, p.accent).bold());
+            let text = meta
+                .abstract_zh
+                .as_deref()
+                .filter(|a| !a.is_empty() && !self.english)
+                .unwrap_or(abstract_text);
+            body.push(Paragraph::new(&truncate(text, MAX_ABSTRACT_CHARS), 1400, p.text));
+        }
+        if !body.is_empty() {
+            shapes.text_box("Key points", (inches(0.5), inches(2.1), body_width, body_height), &body);
+        }
+
+        if !formulas.is_empty() {
+            let mut lines = vec![Paragraph::new(tr(self.english, "公式", "Formulas"), 1400, p.accent).bold()];
+            lines.extend(formulas.iter().map(|f| Paragraph::new(f, 1200, p.text)));
+            shapes.text_box("Formulas", (inches(0.5), inches(5.8), body_width, inches(1.1)), &lines);
+        }
+
+        if let Some(img) = figure {
+            // 按原始宽高比放入右侧区域
+            let (box_w, box_h) = (inches(5.4), inches(4.1));
+            let (w, h) = (img.width.max(1) as i64, img.height.max(1) as i64);
+            let (cx, cy) = if w * box_h > h * box_w {
+                (box_w, box_w * h / w)
+            } else {
+                (box_h * w / h, box_h)
+            };
+            let x = inches(7.4) + (box_w - cx) / 2;
+            shapes.picture("Figure", "rId2", (x, inches(2.1), cx, cy));
+            let mut caption = format!("p. {}", img.page);
+            if let Some(text) = img.caption.as_deref() {
+                caption = format!("{}  {}", truncate(text, 160), caption);
+            }
+            shapes.text_box(
+                "Caption",
+                (inches(7.4), inches(2.1) + cy + inches(0.1), box_w, inches(0.8)),
+                &[Paragraph::new(&caption, 1100, p.muted)],
+            );
+        }
+
+        let mut footer = format!("[{}]", paper_id);
+        if let Some(venue) = meta.venue.as_deref() {
+            footer.push_str(&format!("  {}", venue));
+        }
+        shapes.text_box(
+            "Footer",
+            (inches(0.5), inches(7.0), inches(12.33), inches(0.4)),
+            &[Paragraph::new(&footer, 1000, p.muted)],
+        );
+        slide_xml(p, &shapes)
+    }
+}
+
+/// 选择配图：优先有图注的图片，其次面积最大的；只考虑 PPT 支持的格式
+fn pick_figure<'a>(images: &'a [ExtractedImage], store: &dyn ImageStore) -> Option<&'a ExtractedImage> {
+    images
+        .iter()
+        .filter(|img| media_extension(&img.filename).is_some() && store.exists(&img.filename))
+        .max_by_key(|img| (img.caption.is_some(), img.width as u64 * img.height as u64))
+}
+
+fn media_extension(filename: &str) -> Option<&'static str> {
+    match filename.rsplit('.').next()?.to_lowercase().as_str() {
+        "png" => Some("png"),
+        "jpg" | "jpeg" => Some("jpeg"),
+        _ => None,
+    }
+}
+
+fn inches(value: f64) -> i64 {
+    (value * EMU_PER_INCH as f64) as i64
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.chars()
+        .filter(|c| !c.is_control() || matches!(c, '\t' | '\n'))
+        .collect::<String>()
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 文本框中的一段
+struct Paragraph {
+    text: String,
+    size: u32,
+    color: &'static str,
+    bold: bool,
+    bullet: bool,
+}
+
+impl Paragraph {
+    fn new(text: &str, size: u32, color: &'static str) -> Self {
+        Self {
+            text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+            size,
+            color,
+            bold: false,
+            bullet: false,
+        }
+    }
+
+    fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    fn bullet(mut self) -> Self {
+        self.bullet = true;
+        self
+    }
+
+    fn to_xml(&self) -> String {
+        let ppr = if self.bullet {
+            r#"<a:pPr marL="285750" indent="-285750"><a:spcBef><a:spcPts val="600"/></a:spcBef><a:buFont typeface="Arial"/><a:buChar char="&#8226;"/></a:pPr>"#
+        } else {
+            r#"<a:pPr><a:buNone/></a:pPr>"#
+        };
+        format!(
+            r#"<a:p>{ppr}<a:r><a:rPr lang="zh-CN" sz="{size}"{bold} dirty="0"><a:solidFill><a:srgbClr val="{color}"/></a:solidFill><a:latin typeface="{latin}"/><a:ea typeface="{ea}"/></a:rPr><a:t>{text}</a:t></a:r></a:p>"#,
+            ppr = ppr,
+            size = self.size,
+            bold = if self.bold { r#" b="1""# } else { "" },
+            color = self.color,
+            latin = LATIN_FONT,
+            ea = EA_FONT,
+            text = xml_escape(&self.text),
+        )
+    }
+}
+
+/// 幻灯片中的形状，按添加顺序编号
+#[derive(Default)]
+struct ShapeTree {
+    xml: String,
+    next_id: u32,
+}
+
+impl ShapeTree {
+    fn id(&mut self) -> u32 {
+        self.next_id += 1;
+        // id 1 留给形状树本身
+        self.next_id + 1
+    }
+
+    fn rect(&mut self, x: i64, y: i64, cx: i64, cy: i64, color: &str) {
+        let id = self.id();
+        self.xml.push_str(&format!(
+            r#"<p:sp><p:nvSpPr><p:cNvPr id="{id}" name="Bar {id}"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:spPr>{xfrm}<a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:solidFill><a:srgbClr val="{color}"/></a:solidFill><a:ln><a:noFill/></a:ln></p:spPr></p:sp>"#,
+            id = id,
+            xfrm = xfrm((x, y, cx, cy)),
+            color = color,
+        ));
+    }
+
+    fn text_box(&mut self, name: &str, rect: (i64, i64, i64, i64), paragraphs: &[Paragraph]) {
+        let id = self.id();
+        let body: String = paragraphs.iter().map(Paragraph::to_xml).collect();
+        self.xml.push_str(&format!(
+            r#"<p:sp><p:nvSpPr><p:cNvPr id="{id}" name="{name}"/><p:cNvSpPr txBox="1"/><p:nvPr/></p:nvSpPr><p:spPr>{xfrm}<a:prstGeom prst="rect"><a:avLst/></a:prstGeom><a:noFill/></p:spPr><p:txBody><a:bodyPr wrap="square" lIns="0" rIns="0" anchor="t"><a:normAutofit/></a:bodyPr><a:lstStyle/>{body}</p:txBody></p:sp>"#,
+            id = id,
+            name = xml_escape(name),
+            xfrm = xfrm(rect),
+            body = body,
+        ));
+    }
+
+    fn picture(&mut self, name: &str, rel_id: &str, rect: (i64, i64, i64, i64)) {
+        let id = self.id();
+        self.xml.push_str(&format!(
+            r#"<p:pic><p:nvPicPr><p:cNvPr id="{id}" name="{name}"/><p:cNvPicPr><a:picLocks noChangeAspect="1"/></p:cNvPicPr><p:nvPr/></p:nvPicPr><p:blipFill><a:blip r:embed="{rel_id}"/><a:stretch><a:fillRect/></a:stretch></p:blipFill><p:spPr>{xfrm}<a:prstGeom prst="rect"><a:avLst/></a:prstGeom></p:spPr></p:pic>"#,
+            id = id,
+            name = xml_escape(name),
+            rel_id = rel_id,
+            xfrm = xfrm(rect),
+        ));
+    }
+}
+
+fn xfrm((x, y, cx, cy): (i64, i64, i64, i64)) -> String {
+    format!(r#"<a:xfrm><a:off x="{}" y="{}"/><a:ext cx="{}" cy="{}"/></a:xfrm>"#, x, y, cx, cy)
+}
+
+const GROUP_HEADER: &str = r#"<p:nvGrpSpPr><p:cNvPr id="1" name=""/><p:cNvGrpSpPr/><p:nvPr/></p:nvGrpSpPr><p:grpSpPr><a:xfrm><a:off x="0" y="0"/><a:ext cx="0" cy="0"/><a:chOff x="0" y="0"/><a:chExt cx="0" cy="0"/></a:xfrm></p:grpSpPr>"#;
+
+fn slide_xml(palette: &Palette, shapes: &ShapeTree) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sld {ns}><p:cSld><p:bg><p:bgPr><a:solidFill><a:srgbClr val="{bg}"/></a:solidFill><a:effectLst/></p:bgPr></p:bg><p:spTree>{header}{shapes}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sld>"#,
+        ns = NS,
+        bg = palette.background,
+        header = GROUP_HEADER,
+        shapes = shapes.xml,
+    )
+}
+
+fn content_types(slide_count: usize) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Default Extension="png" ContentType="image/png"/><Default Extension="jpeg" ContentType="image/jpeg"/><Override PartName="/ppt/presentation.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml"/><Override PartName="/ppt/slideMasters/slideMaster1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideMaster+xml"/><Override PartName="/ppt/slideLayouts/slideLayout1.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slideLayout+xml"/><Override PartName="/ppt/theme/theme1.xml" ContentType="application/vnd.openxmlformats-officedocument.theme+xml"/>"#,
+    );
+    for n in 1..=slide_count {
+        xml.push_str(&format!(
+            r#"<Override PartName="/ppt/slides/slide{}.xml" ContentType="application/vnd.openxmlformats-officedocument.presentationml.slide+xml"/>"#,
+            n
+        ));
+    }
+    xml.push_str("</Types>");
+    xml
+}
+
+fn package_rels() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="{}"><Relationship Id="rId1" Type="{}/officeDocument" Target="ppt/presentation.xml"/></Relationships>"#,
+        PKG_REL_NS, REL_NS
+    )
+}
+
+/// 部件关系：(Id, 类型, 目标)
+fn rels(entries: &[(&str, &str, &str)]) -> String {
+    let mut xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="{}">"#,
+        PKG_REL_NS
+    );
+    for (id, kind, target) in entries {
+        xml.push_str(&format!(
+            r#"<Relationship Id="{}" Type="{}/{}" Target="{}"/>"#,
+            id, REL_NS, kind, target
+        ));
+    }
+    xml.push_str("</Relationships>");
+    xml
+}
+
+/// rId1 为母版，rId2 为主题，幻灯片从 rId3 开始
+fn presentation(slide_count: usize) -> String {
+    let slide_ids: String = (0..slide_count)
+        .map(|i| format!(r#"<p:sldId id="{}" r:id="rId{}"/>"#, 256 + i, i + 3))
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:presentation {ns}><p:sldMasterIdLst><p:sldMasterId id="2147483648" r:id="rId1"/></p:sldMasterIdLst><p:sldIdLst>{slides}</p:sldIdLst><p:sldSz cx="{cx}" cy="{cy}"/><p:notesSz cx="6858000" cy="9144000"/></p:presentation>"#,
+        ns = NS,
+        slides = slide_ids,
+        cx = SLIDE_WIDTH,
+        cy = SLIDE_HEIGHT,
+    )
+}
+
+fn presentation_rels(slide_count: usize) -> String {
+    let targets: Vec<String> = (1..=slide_count).map(|n| format!("slides/slide{}.xml", n)).collect();
+    let ids: Vec<String> = (0..slide_count).map(|i| format!("rId{}", i + 3)).collect();
+    let mut entries = vec![
+        ("rId1", "slideMaster", "slideMasters/slideMaster1.xml"),
+        ("rId2", "theme", "theme/theme1.xml"),
+    ];
+    for (id, target) in ids.iter().zip(&targets) {
+        entries.push((id, "slide", target));
+    }
+    rels(&entries)
+}
+
+fn slide_master() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldMaster {ns}><p:cSld><p:bg><p:bgRef idx="1001"><a:schemeClr val="bg1"/></p:bgRef></p:bg><p:spTree>{header}</p:spTree></p:cSld><p:clrMap bg1="lt1" tx1="dk1" bg2="lt2" tx2="dk2" accent1="accent1" accent2="accent2" accent3="accent3" accent4="accent4" accent5="accent5" accent6="accent6" hlink="hlink" folHlink="folHlink"/><p:sldLayoutIdLst><p:sldLayoutId id="2147483649" r:id="rId1"/></p:sldLayoutIdLst><p:txStyles><p:titleStyle/><p:bodyStyle/><p:otherStyle/></p:txStyles></p:sldMaster>"#,
+        ns = NS,
+        header = GROUP_HEADER,
+    )
+}
+
+fn slide_layout() -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:sldLayout {ns} type="blank" preserve="1"><p:cSld name="Blank"><p:spTree>{header}</p:spTree></p:cSld><p:clrMapOvr><a:masterClrMapping/></p:clrMapOvr></p:sldLayout>"#,
+        ns = NS,
+        header = GROUP_HEADER,
+    )
+}
+
+fn theme(palette: &Palette) -> String {
+    let fill = r#"<a:solidFill><a:schemeClr val="phClr"/></a:solidFill>"#;
+    let line = r#"<a:ln w="9525"><a:solidFill><a:schemeClr val="phClr"/></a:solidFill></a:ln>"#;
+    let effect = "<a:effectStyle><a:effectLst/></a:effectStyle>";
+    let font = format!(
+        r#"<a:latin typeface="{}"/><a:ea typeface="{}"/><a:cs typeface=""/>"#,
+        LATIN_FONT, EA_FONT
+    );
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<a:theme xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" name="bsxbot"><a:themeElements><a:clrScheme name="bsxbot"><a:dk1><a:srgbClr val="{text}"/></a:dk1><a:lt1><a:srgbClr val="{bg}"/></a:lt1><a:dk2><a:srgbClr val="{title}"/></a:dk2><a:lt2><a:srgbClr val="EEEEEE"/></a:lt2><a:accent1><a:srgbClr val="{accent}"/></a:accent1><a:accent2><a:srgbClr val="FFC107"/></a:accent2><a:accent3><a:srgbClr val="4CAF50"/></a:accent3><a:accent4><a:srgbClr val="1E88E5"/></a:accent4><a:accent5><a:srgbClr val="E65100"/></a:accent5><a:accent6><a:srgbClr val="78909C"/></a:accent6><a:hlink><a:srgbClr val="1E88E5"/></a:hlink><a:folHlink><a:srgbClr val="5C6BC0"/></a:folHlink></a:clrScheme><a:fontScheme name="bsxbot"><a:majorFont>{font}</a:majorFont><a:minorFont>{font}</a:minorFont></a:fontScheme><a:fmtScheme name="bsxbot"><a:fillStyleLst>{fill}{fill}{fill}</a:fillStyleLst><a:lnStyleLst>{line}{line}{line}</a:lnStyleLst><a:effectStyleLst>{effect}{effect}{effect}</a:effectStyleLst><a:bgFillStyleLst>{fill}{fill}{fill}</a:bgFillStyleLst></a:fmtScheme></a:themeElements></a:theme>"#,
+        text = palette.text,
+        bg = palette.background,
+        title = palette.title,
+        accent = palette.accent,
+        font = font,
+        fill = fill,
+        line = line,
+        effect = effect,
+    )
+}
+
+/// PPTX 包写入器：全部条目使用 deflate 压缩；单个条目超过 4 GiB 时报错（不写 ZIP64）
+struct PackageWriter<W: Write + Seek> {
+    zip: zip::ZipWriter<W>,
+}
+
+impl<W: Write + Seek> PackageWriter<W> {
+    fn new(writer: W) -> Self {
+        Self { zip: zip::ZipWriter::new(writer) }
+    }
+
+    fn add(&mut self, name: &str, content: &[u8]) -> Result<()> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        self.zip.start_file(name, options).with_context(|| format!("添加PPT条目失败: {}", name))?;
+        self.zip.write_all(content).with_context(|| format!("写入PPT条目失败: {}", name))?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<()> {
+        self.zip.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::storage::images::LocalImageStore;
+    use std::io::Read;
+
+    fn paper(title: &str) -> PaperContent {
+        serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "title": title,
+                "authors": ["Ada Lovelace"],
+                "abstract_text": "A short abstract.",
+                "key_points": ["要点一"],
+            },
+            "sections": [],
+            "formulas": [],
+            "images": [],
+            "tables": [],
+            "full_text": "",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn generated_pptx_reopens_as_zip() {
+        let dir = std::env::temp_dir().join(format!("bsxbot_ppt_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let output = dir.join("report.pptx");
+        let papers = vec![("a".to_string(), paper("First")), ("b".to_string(), paper("Second & <more>"))];
+        let store = LocalImageStore::new(dir.join("images"));
+        PptGenerator::new(&AppConfig::default().generator)
+            .generate("2026-10-17", &papers, &store, &output)
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(&output).unwrap()).unwrap();
+        let mut content_types = String::new();
+        archive.by_name("[Content_Types].xml").unwrap().read_to_string(&mut content_types).unwrap();
+        // 封面加每篇论文一页
+        assert_eq!(content_types.matches("/ppt/slides/slide").count(), 3);
+        let slides = archive
+            .file_names()
+            .filter(|name| name.starts_with("ppt/slides/slide") && name.ends_with(".xml"))
+            .count();
+        assert_eq!(slides, 3);
+
+        let mut slide = String::new();
+        archive.by_name("ppt/slides/slide3.xml").unwrap().read_to_string(&mut slide).unwrap();
+        assert!(slide.contains("Second &amp; &lt;more&gt;"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::config::PublisherConfig;
+
+/// 将生成的报告上传到配置的 HTTP 端点
+pub struct Publisher {
+    client: reqwest::Client,
+    config: PublisherConfig,
+}
+
+impl Publisher {
+    pub fn new(config: PublisherConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(120))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client, config }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.endpoint.trim().is_empty()
+    }
+
+    /// 上传报告；tarball 模式下一并打包报告引用的图片
+    pub async fn publish(&self, date: &str, report_path: &Path, images: &[PathBuf]) -> Result<()> {
+        let (body, content_type) = match self.config.format.to_lowercase().as_str() {
+            "tarball" | "tar" | "tar.gz" => (build_bundle(report_path, images)?, "application/gzip"),
+            _ if report_path.extension().is_some_and(|e| e == "pptx") => (
+                std::fs::read(report_path)?,
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+            ),
+            _ => (std::fs::read(report_path)?, "text/html; charset=utf-8"),
+        };
+
+        let url = self.config.endpoint.replace("{date}", date);
+        let mut request = match self.config.method.to_uppercase().as_str() {
+            "POST" => self.client.post(&url),
+            _ => self.client.put(&url),
+        };
+        request = request.header("Content-Type", content_type);
+        if !self.config.auth_token.is_empty() {
+            request = request.bearer_auth(&self.config.auth_token);
+        }
+
+        info!("发布报告到 {} ({} 字节)", url, body.len());
+        let response = request
+            .body(body)
+            .send()
+            .await
+            .with_context(|| format!("发布报告失败: {}", url))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("发布端点返回错误 {}: {}", status, text);
+        }
+
+        info!("报告发布成功: {}", status);
+        Ok(())
+    }
+}
+
+/// 打包报告和图片，保持 reports/ 与 images/ 的相对位置，报告中的 ../images/ 链接依然有效
+fn build_bundle(report_path: &Path, images: &[PathBuf]) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+
+    let report_name = report_path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "report.html".to_string());
+    builder.append_path_with_name(report_path, format!("reports/{}", report_name))?;
+
+    for image in images {
+        let Some(name) = image.file_name() else { continue };
+        if let Err(e) = builder.append_path_with_name(image, Path::new("images").join(name)) {
+            warn!("打包图片失败 {}: {}", image.display(), e);
+        }
+    }
+
+    Ok(builder.into_inner()?.finish()?)
+}
@@ -0,0 +1,74 @@
+use crate::parser::Section;
+
+/// 报告中章节的取舍规则
+///
+/// - `hidden` 中匹配的章节不显示
+/// - `pinned` 非空时只显示匹配的章节；单篇论文的置顶优先于全局隐藏
+///
+/// 匹配忽略大小写和章节编号，按子串判断，如 "method" 可匹配 "3.1 Methods"。
+#[derive(Debug, Clone, Default)]
+pub struct SectionFilter {
+    pinned: Vec<String>,
+    hidden: Vec<String>,
+    paper_pinned: Vec<String>,
+}
+
+impl SectionFilter {
+    pub fn new(pinned: &[String], hidden: &[String]) -> Self {
+        Self {
+            pinned: normalize_all(pinned),
+            hidden: normalize_all(hidden),
+            paper_pinned: Vec::new(),
+        }
+    }
+
+    /// 叠加单篇论文的偏好：论文置顶替代全局置顶，论文隐藏追加到全局隐藏
+    pub fn with_paper_prefs(&self, pinned: &[String], hidden: &[String]) -> Self {
+        let mut filter = self.clone();
+        if !pinned.is_empty() {
+            filter.paper_pinned = normalize_all(pinned);
+            filter.pinned = filter.paper_pinned.clone();
+        }
+        filter.hidden.extend(normalize_all(hidden));
+        filter
+    }
+
+    pub fn is_visible(&self, heading: &str) -> bool {
+        let heading = normalize_heading(heading);
+        if matches_any(&heading, &self.paper_pinned) {
+            return true;
+        }
+        if matches_any(&heading, &self.hidden) {
+            return false;
+        }
+        self.pinned.is_empty() || matches_any(&heading, &self.pinned)
+    }
+
+    /// 按规则筛选章节，保持原有顺序
+    pub fn apply<'a>(&self, sections: &'a [Section]) -> Vec<&'a Section> {
+        sections.iter().filter(|s| self.is_visible(&s.heading)).collect()
+    }
+}
+
+fn matches_any(heading: &str, patterns: &[String]) -> bool {
+    patterns.iter().any(|p| heading.contains(p.as_str()))
+}
+
+fn normalize_all(patterns: &[String]) -> Vec<String> {
+    patterns
+        .iter()
+        .map(|p| normalize_heading(p))
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// 去掉 "1." / "2.3" 之类的编号并转小写
+fn normalize_heading(heading: &str) -> String {
+    heading
+        .trim()
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c.is_whitespace())
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
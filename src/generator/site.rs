@@ -0,0 +1,326 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tracing::info;
+
+use super::citation::{authors, landing_url, source_url};
+use super::html::html_escape;
+use crate::storage::models::{Paper, PaperSource};
+
+/// 每篇论文除标签外最多作为标签使用的关键短语数
+const MAX_TAGS_PER_PAPER: usize = 5;
+
+const STYLE: &str = r#"* { margin: 0; padding: 0; box-sizing: border-box; }
+body { font-family: -apple-system, "Segoe UI", Roboto, "Noto Sans SC", sans-serif; background: #f5f5f5; color: #333; line-height: 1.6; }
+.container { max-width: 960px; margin: 0 auto; padding: 20px; }
+header { background: linear-gradient(135deg, #1a237e 0%, #283593 100%); color: white; padding: 24px 30px; border-radius: 12px; margin-bottom: 24px; }
+header a { color: white; text-decoration: none; margin-right: 16px; font-size: 14px; opacity: 0.9; }
+header h1 { font-size: 24px; margin-bottom: 8px; }
+main { background: white; border-radius: 12px; padding: 30px; box-shadow: 0 2px 8px rgba(0,0,0,0.08); }
+h2 { font-size: 20px; color: #1a237e; margin-bottom: 12px; }
+h3 { font-size: 16px; color: #283593; margin: 20px 0 8px 0; }
+a { color: #283593; }
+ul.papers { list-style: none; }
+ul.papers li { padding: 10px 0; border-bottom: 1px solid #eee; }
+ul.papers .zh { display: block; font-size: 14px; color: #555; }
+ul.papers .meta, .meta { font-size: 13px; color: #888; }
+.tag { display: inline-block; background: #ede7f6; color: #4527a0; font-size: 13px; padding: 1px 10px; border-radius: 12px; margin: 2px 4px 2px 0; text-decoration: none; }
+.abstract { white-space: pre-wrap; font-size: 15px; }
+.translation { background: #e8f5e9; border-left: 3px solid #4caf50; padding: 12px 16px; margin-top: 8px; font-size: 15px; color: #2e7d32; }
+.key-points { background: #e3f2fd; padding: 12px 16px 12px 36px; color: #0d47a1; }
+@media (prefers-color-scheme: dark) {
+  body { background: #121212; color: #e0e0e0; }
+  main { background: #1e1e1e; }
+  h2, h3, a { color: #9fa8da; }
+  ul.papers li { border-bottom-color: #333; }
+  ul.papers .zh { color: #bdbdbd; }
+  .tag { background: #2f2640; color: #d1c4e9; }
+  .translation { background: #1b2e1c; color: #a5d6a7; }
+  .key-points { background: #162a3f; color: #90caf9; }
+}
+"#;
+
+/// 静态站点中的一篇论文
+pub struct SitePaper {
+    pub paper: Paper,
+    /// 爬取该论文的订阅
+    pub subscription: Option<String>,
+    /// 手动添加的标签（与订阅同名的自动标签不重复列出）
+    pub tags: Vec<String>,
+    /// 关键短语，前几个作为标签
+    pub keyphrases: Vec<String>,
+    pub key_points: Vec<String>,
+    /// 合并进来的其他来源（跨来源去重后的重复论文）
+    pub sources: Vec<PaperSource>,
+}
+
+impl SitePaper {
+    fn tags(&self) -> Vec<&String> {
+        let mut tags: Vec<&String> = self.tags.iter().filter(|t| Some(*t) != self.subscription.as_ref()).collect();
+        for phrase in self.keyphrases.iter().take(MAX_TAGS_PER_PAPER) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(phrase)) {
+                tags.push(phrase);
+            }
+        }
+        tags
+    }
+
+    /// 入库日期（YYYY-MM-DD）
+    fn date(&self) -> String {
+        self.paper
+            .created_at
+            .as_deref()
+            .and_then(|t| t.get(..10))
+            .unwrap_or("unknown")
+            .to_string()
+    }
+
+    fn page(&self) -> String {
+        format!("papers/{}.html", slug(&self.paper.source_id))
+    }
+}
+
+/// 生成结果统计
+#[derive(Debug, Default)]
+pub struct SiteStats {
+    pub written: usize,
+    pub unchanged: usize,
+}
+
+/// 多页面静态站点：按日期的首页、论文页、订阅页和标签页
+///
+/// 内容没有变化的页面不会重写，重复构建只更新新增或变化的页面。
+pub struct SiteBuilder {
+    root: PathBuf,
+}
+
+impl SiteBuilder {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn build(&self, papers: &[SitePaper]) -> Result<SiteStats> {
+        let mut stats = SiteStats::default();
+
+        let mut by_date: BTreeMap<String, Vec<&SitePaper>> = BTreeMap::new();
+        let mut by_subscription: BTreeMap<String, Vec<&SitePaper>> = BTreeMap::new();
+        let mut by_tag: BTreeMap<String, Vec<&SitePaper>> = BTreeMap::new();
+        for paper in papers {
+            by_date.entry(paper.date()).or_default().push(paper);
+            if let Some(sub) = &paper.subscription {
+                by_subscription.entry(sub.clone()).or_default().push(paper);
+            }
+            for tag in paper.tags() {
+                by_tag.entry(tag.to_lowercase()).or_default().push(paper);
+            }
+        }
+
+        self.write("style.css", STYLE, &mut stats)?;
+
+        for paper in papers {
+            self.write(&paper.page(), &paper_page(paper), &mut stats)?;
+        }
+
+        let mut index = String::from("<h2>按日期</h2>\n<ul class=\"papers\">");
+        for (date, list) in by_date.iter().rev() {
+            index.push_str(&format!(
+                r#"<li><a href="dates/{date}.html">{date}</a> <span class="meta">{n} 篇</span></li>"#,
+                date = html_escape(date),
+                n = list.len()
+            ));
+            let body = format!("<h2>{}</h2>\n{}", html_escape(date), paper_list(list, "../"));
+            self.write(&format!("dates/{}.html", slug(date)), &layout(date, "../", &body), &mut stats)?;
+        }
+        index.push_str("</ul>\n");
+        self.write("index.html", &layout("论文库", "", &index), &mut stats)?;
+
+        let mut subscriptions = String::from("<h2>订阅</h2>\n<ul class=\"papers\">");
+        for (name, list) in &by_subscription {
+            subscriptions.push_str(&format!(
+                r#"<li><a href="{slug}.html">{name}</a> <span class="meta">{n} 篇</span></li>"#,
+                slug = slug(name),
+                name = html_escape(name),
+                n = list.len()
+            ));
+            let body = format!("<h2>订阅: {}</h2>\n{}", html_escape(name), paper_list(list, "../"));
+            self.write(&format!("subscriptions/{}.html", slug(name)), &layout(name, "../", &body), &mut stats)?;
+        }
+        subscriptions.push_str("</ul>\n");
+        self.write("subscriptions/index.html", &layout("订阅", "../", &subscriptions), &mut stats)?;
+
+        // 标签按论文数从多到少排列
+        let mut tags: Vec<(&String, &Vec<&SitePaper>)> = by_tag.iter().collect();
+        tags.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(b.0)));
+        let mut tag_index = String::from("<h2>标签</h2>\n<p>");
+        for (tag, list) in tags {
+            tag_index.push_str(&format!(
+                r#"<a class="tag" href="{slug}.html">{tag} ({n})</a>"#,
+                slug = slug(tag),
+                tag = html_escape(tag),
+                n = list.len()
+            ));
+            let body = format!("<h2>标签: {}</h2>\n{}", html_escape(tag), paper_list(list, "../"));
+            self.write(&format!("tags/{}.html", slug(tag)), &layout(tag, "../", &body), &mut stats)?;
+        }
+        tag_index.push_str("</p>\n");
+        self.write("tags/index.html", &layout("标签", "../", &tag_index), &mut stats)?;
+
+        info!(
+            "静态站点已生成: {}（更新 {} 个文件，{} 个未变化）",
+            self.root.display(),
+            stats.written,
+            stats.unchanged
+        );
+        Ok(stats)
+    }
+
+    /// 内容变化时才写入
+    fn write(&self, relative: &str, content: &str, stats: &mut SiteStats) -> Result<()> {
+        let path = self.root.join(relative);
+        if std::fs::read(&path).is_ok_and(|existing| existing == content.as_bytes()) {
+            stats.unchanged += 1;
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, content).with_context(|| format!("写入页面失败: {}", path.display()))?;
+        stats.written += 1;
+        Ok(())
+    }
+}
+
+/// `base` 为从当前页面回到站点根目录的相对前缀
+fn layout(title: &str, base: &str, body: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="zh-CN">
+<head>
+<meta charset="UTF-8">
+<meta name="viewport" content="width=device-width, initial-scale=1.0">
+<meta name="color-scheme" content="light dark">
+<title>{title} - bsxbot</title>
+<link rel="stylesheet" href="{base}style.css">
+</head>
+<body>
+<div class="container">
+<header>
+  <h1>{title}</h1>
+  <nav><a href="{base}index.html">按日期</a><a href="{base}subscriptions/index.html">订阅</a><a href="{base}tags/index.html">标签</a></nav>
+</header>
+<main>
+{body}</main>
+</div>
+</body>
+</html>
+"#,
+        title = html_escape(title),
+        base = base,
+        body = body,
+    )
+}
+
+fn paper_list(papers: &[&SitePaper], base: &str) -> String {
+    let mut html = String::from("<ul class=\"papers\">");
+    for paper in papers {
+        let zh = paper
+            .paper
+            .title_zh
+            .as_deref()
+            .filter(|t| !t.is_empty())
+            .map(|t| format!(r#"<span class="zh">{}</span>"#, html_escape(t)))
+            .unwrap_or_default();
+        html.push_str(&format!(
+            r#"<li><a href="{base}{page}">{title}</a>{zh}<span class="meta">{authors}</span></li>"#,
+            base = base,
+            page = paper.page(),
+            title = html_escape(&paper.paper.title),
+            zh = zh,
+            authors = html_escape(&authors(&paper.paper).join(", ")),
+        ));
+    }
+    html.push_str("</ul>\n");
+    html
+}
+
+fn paper_page(paper: &SitePaper) -> String {
+    let p = &paper.paper;
+    let mut body = format!("<h2>{}</h2>\n", html_escape(&p.title));
+    if let Some(title_zh) = p.title_zh.as_deref().filter(|t| !t.is_empty()) {
+        body.push_str(&format!("<p><b>{}</b></p>\n", html_escape(title_zh)));
+    }
+
+    let mut meta = vec![html_escape(&authors(p).join(", "))];
+    if let Some(date) = p.publish_date.as_deref().and_then(|d| d.get(..10)) {
+        meta.push(html_escape(date));
+    }
+    if let Some(venue) = &p.venue {
+        meta.push(html_escape(venue));
+    }
+    let url = landing_url(p);
+    if !url.is_empty() {
+        meta.push(format!(r#"<a href="{}">{}</a>"#, html_escape(&url), html_escape(&p.source_id)));
+    }
+    if let Some(pdf_url) = &p.pdf_url {
+        meta.push(format!(r#"<a href="{}">PDF</a>"#, html_escape(pdf_url)));
+    }
+    for source in &paper.sources {
+        let url = source_url(&source.source, &source.source_id, source.pdf_url.as_deref());
+        let label = html_escape(&format!("{} {}", source.source, source.source_id));
+        if url.is_empty() {
+            meta.push(label);
+        } else {
+            meta.push(format!(r#"<a href="{}">{}</a>"#, html_escape(&url), label));
+        }
+    }
+    if let Some(sub) = &paper.subscription {
+        meta.push(format!(r#"订阅 <a href="../subscriptions/{}.html">{}</a>"#, slug(sub), html_escape(sub)));
+    }
+    body.push_str(&format!("<p class=\"meta\">{}</p>\n", meta.join(" · ")));
+
+    let tags = paper.tags();
+    if !tags.is_empty() {
+        body.push_str("<p>");
+        for tag in tags {
+            body.push_str(&format!(
+                r#"<a class="tag" href="../tags/{}.html">{}</a>"#,
+                slug(&tag.to_lowercase()),
+                html_escape(tag)
+            ));
+        }
+        body.push_str("</p>\n");
+    }
+
+    if !paper.key_points.is_empty() {
+        body.push_str("<h3>要点</h3>\n<ul class=\"key-points\">");
+        for point in &paper.key_points {
+            body.push_str(&format!("<li>{}</li>", html_escape(point)));
+        }
+        body.push_str("</ul>\n");
+    }
+    if let Some(abstract_text) = p.abstract_text.as_deref().filter(|a| !a.is_empty()) {
+        body.push_str(&format!("<h3>摘要</h3>\n<div class=\"abstract\">{}</div>\n", html_escape(abstract_text)));
+    }
+    if let Some(abstract_zh) = p.abstract_zh.as_deref().filter(|a| !a.is_empty()) {
+        body.push_str(&format!("<div class=\"translation\">{}</div>\n", html_escape(abstract_zh)));
+    }
+    layout(&p.title, "../", &body)
+}
+
+/// 页面文件名：字母数字（含中文）保留，其余替换为 `-`
+fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '.' {
+            slug.extend(c.to_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches(|c| c == '-' || c == '.').to_string();
+    if slug.is_empty() {
+        "_".to_string()
+    } else {
+        slug
+    }
+}
@@ -7,10 +7,14 @@ mod storage;
 mod utils;
 
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use tracing::info;
+use clap::{Parser, Subcommand, ValueEnum};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{info, warn};
 
 use config::{AppConfig, KeywordConfig};
+use storage::batch::{ContentWrite, NewPaper, PaperTarget, PaperWrite, SubscriptionMatch, TranslationWrite};
+use storage::images::ImageStore;
+use storage::models::{PaperFilter, PaperSort, TranslationStatus};
 use storage::Database;
 use translator::Translator;
 use utils::logger;
@@ -19,6 +23,18 @@ use utils::logger;
 #[command(name = "bsxbot")]
 #[command(about = "科研信息自动提取与分析系统", long_about = None)]
 struct Cli {
+    /// 数据根目录（其下包含 config/ 和 data/），默认使用平台标准位置
+    #[arg(long, global = true, env = "BSXBOT_DATA_DIR")]
+    data_dir: Option<std::path::PathBuf>,
+
+    /// 配置目录（settings.toml、keywords.toml 所在目录），优先于 --data-dir 和配置档案
+    #[arg(long = "config", global = true, env = "BSXBOT_CONFIG_DIR")]
+    config_dir: Option<std::path::PathBuf>,
+
+    /// 配置档案名称，如 lab-a：使用独立的配置、数据目录和数据库
+    #[arg(long, global = true, env = "BSXBOT_PROFILE", value_parser = config::dirs::parse_profile_name)]
+    profile: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -32,48 +48,705 @@ enum Commands {
         /// 订阅名称
         #[arg(short, long)]
         subscription: Option<String>,
+        /// 回溯爬取该日期（YYYY-MM-DD）以来的论文，按月分段并可断点续跑
+        #[arg(long)]
+        since: Option<String>,
+    },
+    /// 依次执行爬取、翻译、解析和生成报告（按订阅分组），最后汇总各阶段的结果；定时任务执行的就是这一流程
+    Run {
+        /// 只爬取该订阅
+        #[arg(short, long)]
+        subscription: Option<String>,
+        /// 跳过爬取
+        #[arg(long)]
+        no_crawl: bool,
+        /// 跳过补充翻译（爬取时仍会翻译新论文）
+        #[arg(long)]
+        no_translate: bool,
+        /// 跳过补充解析（爬取时仍会解析新论文的PDF）
+        #[arg(long)]
+        no_parse: bool,
+        /// 不生成报告
+        #[arg(long)]
+        no_report: bool,
     },
     /// 启动定时任务
     Schedule,
-    /// 生成报告
+    /// 生成报告，或查看、重新生成历史报告
+    #[command(args_conflicts_with_subcommands = true)]
     Report {
+        #[command(subcommand)]
+        action: Option<ReportAction>,
         /// 报告日期 (YYYY-MM-DD)
         #[arg(short, long)]
         date: Option<String>,
+        /// 隐藏可信度低于该值（0~1）的章节、公式和表格，默认使用配置中的 min_confidence
+        #[arg(long)]
+        min_confidence: Option<f32>,
+        /// 输出格式：html 或 pptx（每篇论文一页幻灯片）
+        #[arg(long, value_enum, default_value_t = ReportFormat::Html)]
+        format: ReportFormat,
+        /// 生成后通过邮件发送（使用 [notify.email] 配置），不论是否开启自动发送
+        #[arg(long)]
+        send: bool,
+        /// 按订阅、来源或发布日期分组
+        #[arg(long, value_enum)]
+        group_by: Option<ReportGroupBy>,
+        /// 论文排序方式（分组时在组内排序）
+        #[arg(long, value_enum, default_value_t = ReportSortBy::Relevance)]
+        sort_by: ReportSortBy,
+        /// 报告语言：bilingual 中英对照，en 只含英文原文（界面文字为英文）
+        #[arg(long, value_enum, default_value_t = ReportLang::Bilingual)]
+        lang: ReportLang,
+        /// 只收录带有任一指定标签的论文（可重复）
+        #[arg(long)]
+        tag: Vec<String>,
     },
     /// 翻译未翻译的论文
     Translate {
         /// 指定论文ID翻译
         #[arg(long)]
         id: Option<i64>,
+        /// 只重试上次翻译失败的论文
+        #[arg(long)]
+        retry_failed: bool,
+        /// 重新翻译已有译文的论文（如更换模型后），忽略翻译缓存
+        #[arg(long, conflicts_with_all = ["retry_failed", "retranslate_failed"])]
+        force: bool,
+        /// 重新翻译标题带有“(翻译失败，请重试)”标记的论文
+        #[arg(long, conflicts_with = "retry_failed")]
+        retranslate_failed: bool,
+    },
+    /// 以表格列出论文（按入库时间从新到旧），可按翻译、解析状态、来源、标签和发表日期筛选
+    List {
+        /// 只列出待翻译的论文
+        #[arg(long)]
+        untranslated: bool,
+        /// 只列出PDF未解析的论文
+        #[arg(long)]
+        unprocessed: bool,
+        /// 只列出该来源的论文，如 arxiv
+        #[arg(long)]
+        source: Option<String>,
+        /// 只列出带有任一指定标签的论文（可重复）
+        #[arg(long)]
+        tag: Vec<String>,
+        /// 只列出该日期（YYYY-MM-DD）及之后发表的论文
+        #[arg(long)]
+        since: Option<String>,
+        /// 排序方式
+        #[arg(long, value_enum, default_value_t = ListSort::Newest)]
+        sort: ListSort,
+        /// 最多列出的论文数，0 表示全部
+        #[arg(long, default_value_t = 50)]
+        limit: i64,
+        /// 以 JSON 输出，便于脚本处理
+        #[arg(long)]
+        json: bool,
+    },
+    /// 查看、修改和检查 settings.toml
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// 查看一篇论文的全部信息：元数据、译文、提取统计、要点、文件、标签、分拣和各步骤记录
+    Show {
+        /// 论文ID
+        id: i64,
+        /// 以 JSON 输出，便于脚本处理
+        #[arg(long)]
+        json: bool,
+    },
+    /// 搜索本地论文库，管理已保存的搜索
+    Search {
+        /// 搜索关键词（空格分隔，需全部命中）
+        query: Option<String>,
+        /// 保存本次搜索以便之后重新运行
+        #[arg(long)]
+        save: bool,
+        /// 列出已保存的搜索
+        #[arg(long)]
+        list: bool,
+        /// 重新运行指定ID的已保存搜索
+        #[arg(long)]
+        run: Option<i64>,
+        /// 将指定ID的已保存搜索提升为爬取订阅
+        #[arg(long)]
+        promote: Option<i64>,
+        /// 删除指定ID的已保存搜索
+        #[arg(long)]
+        delete: Option<i64>,
+        /// 最多显示的结果数
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+    /// 设置单篇论文在报告中显示/隐藏的章节
+    Sections {
+        /// 论文ID
+        id: i64,
+        /// 只显示匹配的章节（可多次指定）
+        #[arg(long)]
+        pin: Vec<String>,
+        /// 隐藏匹配的章节（可多次指定）
+        #[arg(long)]
+        hide: Vec<String>,
+        /// 清除该论文的章节设置
+        #[arg(long)]
+        clear: bool,
+    },
+    /// 为论文添加或移除标签；只给论文ID时列出其标签，不带参数时列出全部标签
+    Tag {
+        /// 论文ID
+        id: Option<i64>,
+        /// 标签（可多个）
+        tags: Vec<String>,
+        /// 移除给出的标签
+        #[arg(long)]
+        remove: bool,
+    },
+    /// 把论文标记为已读，报告中已读论文会淡化显示
+    MarkRead {
+        /// 论文ID（可多个）
+        #[arg(required_unless_present = "tag")]
+        ids: Vec<i64>,
+        /// 同时标记带有任一指定标签的论文（可重复）
+        #[arg(long)]
+        tag: Vec<String>,
+        /// 改为未读
+        #[arg(long)]
+        unread: bool,
+    },
+    /// 为论文加星标，报告中星标论文排在最前
+    Star {
+        /// 论文ID（可多个）
+        #[arg(required = true)]
+        ids: Vec<i64>,
+        /// 取消星标
+        #[arg(long)]
+        remove: bool,
+    },
+    /// 删除论文（移入回收站），提取内容等数据保留，可用 restore 恢复，直到被 prune 清理
+    Delete {
+        /// 论文ID（可多个）
+        #[arg(required = true)]
+        ids: Vec<i64>,
+    },
+    /// 从回收站恢复论文，不带ID时列出回收站中的论文
+    Restore {
+        /// 论文ID（可多个）
+        ids: Vec<i64>,
+    },
+    /// 逐篇分拣新论文：k 保留、s 星标、a 归档、n 跳过、o 打开PDF、q 退出
+    Triage {
+        /// 本次最多分拣的论文数
+        #[arg(long, default_value = "50")]
+        limit: i64,
+    },
+    /// 查看论文中提取的公式（按重要性排序）
+    Formulas {
+        /// 论文ID
+        id: i64,
+        /// 显示全部公式，而不只是报告中保留的部分
+        #[arg(long)]
+        all: bool,
+        /// 只显示可信度不低于该值（0~1）的公式
+        #[arg(long, default_value_t = 0.0)]
+        min_confidence: f32,
+    },
+    /// 对单个PDF运行提取管道并打印结果，对应数据库中的论文时同时更新提取内容
+    Parse {
+        /// PDF文件路径或论文ID
+        target: String,
+        /// 以 JSON 输出完整提取结果（不含全文）
+        #[arg(long)]
+        json: bool,
+        /// 只打印结果，不写入数据库
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 导入本地PDF：复制到数据目录、创建来源为 local 的论文并运行提取管道，已导入的文件跳过
+    Import {
+        /// PDF文件或包含PDF的目录
+        #[arg(required = true)]
+        paths: Vec<std::path::PathBuf>,
+        /// 按PDF首页的 arXiv 编号、DOI 或提取的标题，在 arXiv / Crossref 查询标题、作者、摘要和发表日期
+        #[arg(long)]
+        lookup: bool,
+    },
+    /// 把已提取的表格导出为 CSV 文件（<论文ID>_table_<n>.csv）
+    ExportTables {
+        /// 只导出指定论文ID的表格
+        #[arg(long)]
+        id: Option<i64>,
+        /// 输出目录，默认为数据目录下的 tables/
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+        /// 跳过可信度低于该值（0~1）的表格
+        #[arg(long, default_value_t = 0.0)]
+        min_confidence: f32,
+    },
+    /// 按标题和摘要的向量查找相似论文（需配置 translator.embedding_model），先为缺少向量的论文生成向量
+    Similar {
+        /// 论文ID，省略时只生成缺少的向量
+        id: Option<i64>,
+        /// 列出的相似论文数
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// 查看库中论文之间的引用关系：指定论文ID时列出它引用和被引用的库中论文，
+    /// 否则列出最近入库的论文引用了库中哪些论文
+    Citations {
+        /// 论文ID
+        id: Option<i64>,
+        /// 最近多少天入库的论文
+        #[arg(long, default_value = "7", conflicts_with = "id")]
+        days: u32,
+        /// 同时列出论文提取到的全部参考文献
+        #[arg(long, requires = "id")]
+        references: bool,
+    },
+    /// 旧命令，等同于 export --format ris|bibtex -o <报告目录>/papers.ris|bib
+    #[command(hide = true)]
+    ExportCitations {
+        #[arg(long, value_enum, default_value_t = generator::export::ExportFormat::Ris)]
+        format: generator::export::ExportFormat,
+        #[arg(long)]
+        id: Vec<i64>,
+        #[arg(long)]
+        tag: Vec<String>,
+        #[arg(short, long)]
+        output: Option<std::path::PathBuf>,
+    },
+    /// 生成多页面静态站点（data/site/）
+    Site {
+        #[command(subcommand)]
+        action: SiteAction,
+    },
+    /// 导出论文为 BibTeX、RIS、JSON 或 CSV，或写入外部服务（Notion）
+    Export {
+        /// 导出格式
+        #[arg(long, value_enum, conflicts_with = "notion")]
+        format: Option<generator::export::ExportFormat>,
+        /// 写入 [notion] 配置的 Notion 数据库，按 source_id 更新已导出的页面
+        #[arg(long)]
+        notion: bool,
+        /// 只导出指定论文ID（可重复，或以逗号分隔）
+        #[arg(long, visible_alias = "ids", value_delimiter = ',')]
+        id: Vec<i64>,
+        /// 只导出带有任一指定标签的论文（可重复）
+        #[arg(long)]
+        tag: Vec<String>,
+        /// 只导出该日期（YYYY-MM-DD）及之后发表的论文
+        #[arg(long)]
+        since: Option<String>,
+        /// 输出文件，默认写到标准输出
+        #[arg(short, long, requires = "format")]
+        output: Option<std::path::PathBuf>,
+    },
+    /// 论文库统计：每日入库、来源、订阅、翻译覆盖率、解析失败率和占用空间
+    Stats {
+        /// 按天统计的天数
+        #[arg(long, default_value = "14")]
+        days: i64,
+        /// 以 JSON 输出
+        #[arg(long)]
+        json: bool,
+    },
+    /// 清理入库超过保留期的论文（星标除外）、在回收站中超过保留期的论文及其PDF、图片、表格，以及旧的爬取、调用记录和缓存
+    Prune {
+        /// 保留期，如 90d、12w、6m，默认取 [storage] cache_ttl_days
+        #[arg(long)]
+        older_than: Option<String>,
+        /// 只列出将要删除的内容和可释放的空间，不做修改
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 按论文记录的PDF链接重新下载缺失或损坏的PDF并重新提取，同一数据源的并发数和请求间隔与爬取时相同
+    Redownload {
+        /// 只重新下载指定论文（可重复，或以逗号分隔），不论本地PDF是否完好
+        #[arg(long, visible_alias = "ids", value_delimiter = ',')]
+        id: Vec<i64>,
+        /// 只列出将要重新下载的论文，不做修改
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 检查运行环境（配置、数据库、目录权限、API key、arXiv 连通性）；加 --files 时检查论文库文件，可自动修复
+    Doctor {
+        /// 核对数据库中记录的PDF和图片文件，找出缺失、为空或不完整的文件
+        #[arg(long)]
+        files: bool,
+        /// 检查运行环境时不调用翻译服务、不访问 arXiv
+        #[arg(long, conflicts_with = "files")]
+        offline: bool,
+        /// 重新下载有问题的PDF并重新提取，PDF完好但图片有问题的论文直接重新提取
+        #[arg(long, requires = "files")]
+        repair: bool,
+        /// 以 JSON 输出检查结果
+        #[arg(long, conflicts_with = "repair")]
+        json: bool,
+    },
+    /// 对已下载的PDF重新运行提取，覆盖已有的提取内容，不重新爬取或下载
+    #[command(group(clap::ArgGroup::new("target").required(true)))]
+    Reparse {
+        /// 所有有本地PDF的论文
+        #[arg(long, group = "target")]
+        all: bool,
+        /// 指定论文ID（可重复，或以逗号分隔）
+        #[arg(long, visible_alias = "ids", value_delimiter = ',', group = "target")]
+        id: Vec<i64>,
+        /// PDF无法打开或尚未提取出内容的论文
+        #[arg(long, group = "target")]
+        failed: bool,
+        /// 只列出将要重新提取的论文，不做修改
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 重新生成旧版本程序产生的结果，如重新提取旧版解析器处理的论文
+    Reprocess {
+        /// 重新提取由早于该版本（如 v0.3）的解析器产生的内容，版本未知的旧结果同样重新提取
+        #[arg(long, required = true)]
+        parser_older_than: String,
+        /// 只列出将要重新处理的论文，不做修改
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// 导出或导入完整应用状态
+    State {
+        #[command(subcommand)]
+        action: StateAction,
+    },
+    /// 查看数据库中的订阅，删除已从 keywords.toml 移除的订阅
+    Subscriptions {
+        #[command(subcommand)]
+        action: SubscriptionAction,
+    },
+    /// 数据库维护：备份，以 JSON 导出或导入论文、提取内容、标签和订阅
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// 数据目录移动后重写数据库中的图片和PDF引用
+    RelocateData {
+        /// 原数据目录，用于映射旧的绝对路径
+        #[arg(long)]
+        from: Option<std::path::PathBuf>,
     },
     /// 清理所有缓存数据
     Clean,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    Html,
+    Pptx,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportLang {
+    Bilingual,
+    En,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportGroupBy {
+    Subscription,
+    Source,
+    Date,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportSortBy {
+    /// 发布日期，新的在前
+    Date,
+    /// 相关度，高的在前
+    Relevance,
+    /// 标题字母顺序
+    Title,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ListSort {
+    /// 入库时间，新的在前
+    Newest,
+    /// 发表日期，新的在前
+    Published,
+    /// 标题字母顺序
+    Title,
+}
+
+impl ListSort {
+    fn paper_sort(self) -> PaperSort {
+        match self {
+            ListSort::Newest => PaperSort::Newest,
+            ListSort::Published => PaperSort::Published,
+            ListSort::Title => PaperSort::Title,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum ReportAction {
+    /// 列出生成过的报告
+    List {
+        /// 显示的条数
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+    /// 按记录中的日期、格式、分组和排序方式重新生成报告
+    Regenerate {
+        /// 报告记录ID（见 report list）
+        id: i64,
+        /// 生成后通过邮件发送
+        #[arg(long)]
+        send: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SiteAction {
+    /// 生成或增量更新站点，只重写内容变化的页面
+    Build {
+        /// 清空站点目录后完整重建
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum StateAction {
+    /// 导出配置、关键词和数据库到单个归档
+    Export {
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+        /// 同时打包 data/papers 中的PDF
+        #[arg(long)]
+        include_pdfs: bool,
+    },
+    /// 从归档恢复状态
+    Import {
+        /// 归档文件路径
+        archive: String,
+        /// 覆盖已存在的配置、数据库和数据文件
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// 打印配置项的生效值（未设置时为默认值），如 translator.model；不带参数时打印完整配置
+    Get {
+        key: Option<String>,
+    },
+    /// 修改配置项并保留文件中的注释；值按该项的类型解析，数组写为 '["a", "b"]'
+    Set {
+        key: String,
+        value: String,
+    },
+    /// 检查未知的配置项、缺少的 API key、无效的取值和无法连接的服务地址，有错误时以非零状态退出
+    Validate {
+        /// 不测试服务地址能否连接
+        #[arg(long)]
+        offline: bool,
+        /// 以 JSON 输出
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SubscriptionAction {
+    /// 列出所有订阅，包括已从 keywords.toml 移除而停用的
+    List,
+    /// 查看一个订阅的关键词、来源和分类
+    Show {
+        /// 订阅名称
+        name: String,
+    },
+    /// 删除已从 keywords.toml 移除的订阅，论文和爬取记录保留订阅名称
+    Remove {
+        /// 订阅名称
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum DbAction {
+    /// 导出为 JSON 文件，不依赖数据库结构版本
+    Export {
+        /// 输出文件路径
+        output: String,
+    },
+    /// 从 JSON 文件导入，来源ID已存在的论文会跳过
+    Import {
+        /// 导出文件路径
+        input: String,
+    },
+    /// 备份数据库为带时间戳的压缩文件，并删除超出保留数的旧备份
+    Backup {
+        /// 保留最新的备份数（0 表示全部保留），默认取 [storage.backup] keep
+        #[arg(long)]
+        keep: Option<usize>,
+    },
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    // 初始化日志
-    logger::init_logger();
-    info!("bsxbot 启动");
-
     let cli = Cli::parse();
+    let dirs = config::dirs::init(cli.data_dir.as_deref(), cli.config_dir.as_deref(), cli.profile.as_deref());
+
+    // 初始化日志；定时任务同时写入日志文件，告警中引用对应的日志片段
+    let log_file = matches!(cli.command, Commands::Schedule).then(|| dirs.logs_dir().join("bsxbot.log"));
+    let json_output = matches!(
+        cli.command,
+        Commands::Parse { json: true, .. } | Commands::Stats { json: true, .. } | Commands::List { json: true, .. }
+            | Commands::Show { json: true, .. }
+            | Commands::Doctor { json: true, .. }
+            | Commands::Config { .. }
+            | Commands::Export { format: Some(_), output: None, .. }
+    );
+    logger::init_logger(log_file.as_deref(), json_output);
+    info!("bsxbot 启动");
+    if let Some(ref profile) = cli.profile {
+        info!("配置档案: {}", profile);
+    }
+    info!("配置目录: {}", dirs.config_dir.display());
+    info!("数据目录: {}", dirs.data_dir.display());
 
     match cli.command {
         Commands::Init => {
             init_command().await?;
         }
-        Commands::Crawl { subscription } => {
-            crawl_command(subscription).await?;
+        Commands::Crawl { subscription, since } => {
+            crawl_command(subscription, since).await?;
+        }
+        Commands::Run { subscription, no_crawl, no_translate, no_parse, no_report } => {
+            let stages = PipelineStages {
+                crawl: !no_crawl,
+                translate: !no_translate,
+                parse: !no_parse,
+                report: !no_report,
+            };
+            run_pipeline(subscription, stages).await?;
         }
         Commands::Schedule => {
             schedule_command().await?;
         }
-        Commands::Report { date } => {
-            report_command(date).await?;
+        Commands::Report { action: Some(action), .. } => {
+            report_action_command(action).await?;
+        }
+        Commands::Report { action: None, date, min_confidence, format, send, group_by, sort_by, lang, tag } => {
+            report_command(ReportOptions { date, min_confidence, format, send, group_by, sort_by, lang, tags: tag }).await?;
+        }
+        Commands::Translate { id, retry_failed, force, retranslate_failed } => {
+            translate_command(id, retry_failed, force, retranslate_failed).await?;
+        }
+        Commands::List { untranslated, unprocessed, source, tag, since, sort, limit, json } => {
+            let filter = PaperFilter {
+                source,
+                since,
+                processed: unprocessed.then_some(false),
+                translated: untranslated.then_some(false),
+                tags: tag,
+                ..Default::default()
+            };
+            list_command(filter, sort.paper_sort(), limit, json).await?;
+        }
+        Commands::Config { action } => {
+            config_command(action).await?;
+        }
+        Commands::Show { id, json } => {
+            show_command(id, json).await?;
+        }
+        Commands::Search { query, save, list, run, promote, delete, limit } => {
+            search_command(query, save, list, run, promote, delete, limit).await?;
+        }
+        Commands::Sections { id, pin, hide, clear } => {
+            sections_command(id, pin, hide, clear).await?;
+        }
+        Commands::Tag { id, tags, remove } => {
+            tag_command(id, tags, remove).await?;
+        }
+        Commands::MarkRead { ids, tag, unread } => {
+            mark_read_command(ids, tag, unread).await?;
         }
-        Commands::Translate { id } => {
-            translate_command(id).await?;
+        Commands::Star { ids, remove } => {
+            star_command(ids, remove).await?;
+        }
+        Commands::Delete { ids } => {
+            delete_command(ids).await?;
+        }
+        Commands::Restore { ids } => {
+            restore_command(ids).await?;
+        }
+        Commands::Triage { limit } => {
+            triage_command(limit).await?;
+        }
+        Commands::Formulas { id, all, min_confidence } => {
+            formulas_command(id, all, min_confidence).await?;
+        }
+        Commands::Parse { target, json, dry_run } => {
+            parse_command(&target, json, dry_run).await?;
+        }
+        Commands::Import { paths, lookup } => {
+            import_command(paths, lookup).await?;
+        }
+        Commands::ExportTables { id, output, min_confidence } => {
+            export_tables_command(id, output, min_confidence).await?;
+        }
+        Commands::Similar { id, limit } => {
+            similar_command(id, limit).await?;
+        }
+        Commands::Citations { id, days, references } => {
+            citations_command(id, days, references).await?;
+        }
+        Commands::ExportCitations { format, id, tag, output } => {
+            let output = match output {
+                Some(path) => path,
+                None => {
+                    let dir = config::dirs::get().reports_dir();
+                    tokio::fs::create_dir_all(&dir).await?;
+                    dir.join(format!("papers.{}", format.extension()))
+                }
+            };
+            export_command(Some(format), false, id, tag, None, Some(output)).await?;
+        }
+        Commands::Export { format, notion, id, tag, since, output } => {
+            export_command(format, notion, id, tag, since, output).await?;
+        }
+        Commands::Site { action } => {
+            site_command(action).await?;
+        }
+        Commands::Stats { days, json } => {
+            stats_command(days, json).await?;
+        }
+        Commands::Prune { older_than, dry_run } => {
+            prune_command(older_than, dry_run).await?;
+        }
+        Commands::Doctor { files, offline, repair, json } => {
+            doctor_command(files, offline, repair, json).await?;
+        }
+        Commands::Redownload { id, dry_run } => {
+            redownload_command(&id, dry_run).await?;
+        }
+        Commands::Reparse { all: _, id, failed, dry_run } => {
+            reparse_command(&id, failed, dry_run).await?;
+        }
+        Commands::Reprocess { parser_older_than, dry_run } => {
+            reprocess_command(&parser_older_than, dry_run).await?;
+        }
+        Commands::State { action } => {
+            state_command(action).await?;
+        }
+        Commands::Subscriptions { action } => {
+            subscriptions_command(action).await?;
+        }
+        Commands::Db { action } => {
+            db_command(action).await?;
+        }
+        Commands::RelocateData { from } => {
+            relocate_data_command(from).await?;
         }
         Commands::Clean => {
             clean_command().await?;
@@ -86,257 +759,2778 @@ async fn main() -> Result<()> {
 async fn init_command() -> Result<()> {
     info!("初始化系统...");
 
+    let dirs = config::dirs::get();
+
     // 创建必要的目录
-    tokio::fs::create_dir_all("data/papers").await?;
-    tokio::fs::create_dir_all("data/images").await?;
-    tokio::fs::create_dir_all("data/reports").await?;
-    tokio::fs::create_dir_all("config").await?;
+    tokio::fs::create_dir_all(dirs.papers_dir()).await?;
+    tokio::fs::create_dir_all(dirs.images_dir()).await?;
+    tokio::fs::create_dir_all(dirs.tables_dir()).await?;
+    tokio::fs::create_dir_all(dirs.reports_dir()).await?;
+    tokio::fs::create_dir_all(&dirs.config_dir).await?;
 
     // 生成默认配置文件
     let app_config = AppConfig::default();
-    app_config.save("config/settings.toml")?;
-    info!("已生成配置文件: config/settings.toml");
+    app_config.save(dirs.settings_file())?;
+    info!("已生成配置文件: {}", dirs.settings_file().display());
 
     let keyword_config = KeywordConfig::default();
-    let keyword_toml = toml::to_string_pretty(&keyword_config)?;
-    tokio::fs::write("config/keywords.toml", keyword_toml).await?;
-    info!("已生成关键词配置: config/keywords.toml");
+    keyword_config.save(dirs.keywords_file())?;
+    info!("已生成关键词配置: {}", dirs.keywords_file().display());
+
+    for path in generator::html::write_default_templates(&dirs.templates_dir())? {
+        info!("已生成报告模板: {}", path.display());
+    }
 
     // 初始化数据库（确保data目录已创建）
-    let db_path = "sqlite:./data/papers.db";
-    info!("正在初始化数据库: {}", db_path);
-    let db = Database::new(db_path).await?;
-    db.init_schema().await?;
-    info!("数据库初始化完成");
+    info!("正在初始化数据库: {}", app_config.storage.database_url());
+    let db = Database::open(&app_config.storage).await?;
+    info!("数据库初始化完成（结构版本 {}）", db.schema_version().await?);
 
     info!("✅ 系统初始化完成！");
     info!("下一步:");
-    info!("  1. 编辑 config/settings.toml 配置API密钥");
-    info!("  2. 编辑 config/keywords.toml 配置研究方向");
+    info!("  1. 编辑 {} 配置API密钥", dirs.settings_file().display());
+    info!("  2. 编辑 {} 配置研究方向", dirs.keywords_file().display());
     info!("  3. 运行 'bsxbot crawl' 开始爬取");
 
     Ok(())
 }
 
-async fn crawl_command(subscription: Option<String>) -> Result<()> {
-    info!("开始爬取任务...");
-
-    let app_config = AppConfig::load()?;
-    let keyword_config = KeywordConfig::load()?;
-    let db = Database::new(&format!("sqlite:{}", app_config.storage.database_path)).await?;
+async fn crawl_command(subscription: Option<String>, since: Option<String>) -> Result<()> {
+    warn_config_issues().await;
+    run_crawl(subscription, since).await?;
+    Ok(())
+}
+
+/// 爬取前只检查配置本身，服务地址的连通性由 config validate 检查
+async fn warn_config_issues() {
+    for issue in config::validate::validate(false).await {
+        warn!("配置问题 {}: {}（运行 config validate 查看详情）", issue.key, issue.message);
+    }
+}
+
+/// 把 keywords.toml 中的订阅同步到数据库，相关度和爬取记录据此关联订阅ID
+async fn sync_subscriptions(db: &Database, keyword_config: &KeywordConfig) -> Result<()> {
+    let (added, disabled) = db.sync_subscriptions(&keyword_config.subscriptions).await?;
+    if added > 0 || disabled > 0 {
+        info!("订阅已同步到数据库: 新增 {} 个，停用 {} 个（已从 keywords.toml 删除）", added, disabled);
+    }
+    Ok(())
+}
+
+async fn run_crawl(subscription: Option<String>, since: Option<String>) -> Result<CrawlSummary> {
+    info!("开始爬取任务...");
+
+    let since = match since {
+        Some(value) => Some(
+            chrono::NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("无效的日期 '{}'，格式应为 YYYY-MM-DD", value))?,
+        ),
+        None => None,
+    };
+
+    let dirs = config::dirs::get();
+    let app_config = AppConfig::load()?;
+    let keyword_config = KeywordConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    tokio::fs::create_dir_all(dirs.papers_dir()).await?;
+    let image_store = storage::images::LocalImageStore::new(dirs.images_dir());
+
+    // 爬取前自动备份，失败不影响爬取
+    if app_config.storage.backup.before_crawl {
+        let dir = app_config.storage.backup_dir();
+        match storage::backup::create(&db, &dir).await {
+            Ok(path) => {
+                info!("爬取前已备份数据库: {}", path.display());
+                if let Err(e) = storage::backup::rotate(&dir, app_config.storage.backup.keep) {
+                    info!("⚠️ 清理旧备份失败: {}", e);
+                }
+            }
+            Err(e) => info!("⚠️ 爬取前备份数据库失败: {:#}", e),
+        }
+    }
+    sync_subscriptions(&db, &keyword_config).await?;
+
+    // 初始化翻译器
+    let translator = Translator::new(app_config.translator.clone())
+        .with_cache(db.clone())
+        .with_usage_log(db.clone())
+        .with_glossary(config::Glossary::load()?);
+    let translation_enabled = translator.is_configured();
+    if !translation_enabled {
+        info!("⚠️ API key 未配置，跳过翻译。请在 {} 中设置 api_key", dirs.settings_file().display());
+    }
+
+    let triage_enabled = app_config.crawler.llm_triage && translation_enabled;
+    if app_config.crawler.llm_triage && !translation_enabled {
+        info!("⚠️ API key 未配置，跳过 LLM 初筛");
+    }
+
+    let subscriptions = keyword_config.get_active_subscriptions();
+
+    if subscriptions.is_empty() {
+        info!("没有启用的订阅，请检查 {}", dirs.keywords_file().display());
+        return Ok(CrawlSummary::default());
+    }
+
+    let cache = storage::cache::Cache::new(db.clone(), app_config.storage.cache_ttl_days);
+    match cache.clear_expired().await {
+        Ok(0) => {}
+        Ok(n) => info!("已清理 {} 条过期缓存", n),
+        Err(e) => info!("⚠️ 清理过期缓存失败: {}", e),
+    }
+    let crawler = crawler::ArxivCrawler::new(crawler::Fetcher::new(&app_config.crawler)).with_cache(cache);
+    let starred = db.get_starred_abstracts().await?;
+    if !starred.is_empty() {
+        info!("相关度评分参考 {} 篇星标论文", starred.len());
+    }
+    let ctx = CrawlContext {
+        app_config: &app_config,
+        db: &db,
+        translator: &translator,
+        crawler: &crawler,
+        image_store: &image_store,
+        translation_enabled,
+        triage_enabled,
+        translation_attempts: AtomicUsize::new(0),
+        translation_failures: AtomicUsize::new(0),
+        errors: AtomicUsize::new(0),
+        starred: crawler::relevance::StarredProfile::new(starred),
+        new_papers: std::sync::Mutex::new(Vec::new()),
+    };
+
+    let mut not_retrieved_notes: Vec<String> = Vec::new();
+    let mut saved = 0;
+
+    for sub in subscriptions {
+        if let Some(ref name) = subscription {
+            if &sub.name != name {
+                continue;
+            }
+        }
+
+        info!("处理订阅: {}", sub.name);
+        info!("关键词: {:?}", sub.keywords);
+
+        let search_terms = if app_config.crawler.query_expansion && translation_enabled {
+            expand_search_terms(&db, &translator, sub).await
+        } else {
+            sub.keywords.clone()
+        };
+
+        if !sub.sources.contains(&"arxiv".to_string()) {
+            continue;
+        }
+
+        let subscription_context = sub.llm_context();
+        if triage_enabled && !sub.has_description() {
+            info!("订阅 '{}' 未配置 description，LLM 功能仅依据关键词判断", sub.name);
+        }
+
+        // 每个订阅的爬取结果、错误数和耗时记录到 crawl_runs，出错中断时同样记录
+        let started = std::time::Instant::now();
+        let errors_before = ctx.errors.load(Ordering::Relaxed);
+        let mut run = storage::models::CrawlRun {
+            subscription: sub.name.clone(),
+            source: "arxiv".to_string(),
+            ..Default::default()
+        };
+        // 指定 --since 时按月回溯爬取，进度持久化以便中断后续跑
+        let outcome = match since {
+            Some(since) => backfill_arxiv(&ctx, sub, &subscription_context, &search_terms, since, &mut run).await,
+            None => crawl_arxiv(&ctx, sub, &subscription_context, &search_terms, &mut run).await,
+        };
+        if let Err(e) = &outcome {
+            run.error = Some(format!("{:#}", e));
+        }
+        run.errors = (ctx.errors.load(Ordering::Relaxed) - errors_before) as i64;
+        run.duration_ms = Some(started.elapsed().as_millis() as i64);
+        db.record_crawl_run(&run).await?;
+        outcome?;
+        saved += run.saved as usize;
+
+        let not_retrieved = run.not_retrieved();
+        if not_retrieved > 0 {
+            not_retrieved_notes.push(format!("{} (arxiv): {}", sub.name, not_retrieved));
+        }
+    }
+
+    for note in &not_retrieved_notes {
+        info!("⚠️ {} 篇论文匹配但未获取，可调大 max_papers_per_day", note);
+    }
+    log_translation_statuses(&db).await?;
+    match storage::citations::resolve(&db).await {
+        Ok(0) => {}
+        Ok(n) => info!("新关联了 {} 条对库中论文的引用", n),
+        Err(e) => info!("⚠️ 关联引用失败: {}", e),
+    }
+    let new_papers = std::mem::take(&mut *ctx.new_papers.lock().unwrap());
+    notify_new_papers(&app_config, &new_papers).await;
+    if let Err(e) = write_feed(&db, &app_config).await {
+        info!("⚠️ 更新订阅源失败: {}", e);
+    }
+    // 站点已生成过时才随爬取增量更新
+    if config::dirs::get().site_dir().exists() {
+        if let Err(e) = build_site(&db).await {
+            info!("⚠️ 更新静态站点失败: {}", e);
+        }
+    }
+
+    info!("✅ 爬取任务完成");
+    Ok(CrawlSummary {
+        saved,
+        translation_attempts: ctx.translation_attempts.load(Ordering::Relaxed),
+        translation_failures: ctx.translation_failures.load(Ordering::Relaxed),
+    })
+}
+
+/// 开启 `notifier.notify_new_papers` 时，把新论文及相关度评分依据推送到通知渠道，失败只记录日志
+async fn notify_new_papers(app_config: &AppConfig, papers: &[String]) {
+    if !app_config.notifier.notify_new_papers || papers.is_empty() {
+        return;
+    }
+    let notifier = utils::notifier::Notifier::new(app_config.notifier.clone());
+    if !notifier.is_enabled() {
+        info!("⚠️ 未配置通知渠道 [notifier]，无法推送新论文");
+        return;
+    }
+    let subject = format!("[bsxbot] 新论文 {} 篇", papers.len());
+    if let Err(e) = notifier.send(&subject, &papers.join("\n\n")).await {
+        info!("⚠️ 新论文推送失败: {:#}", e);
+    }
+}
+
+/// 把最新的论文写入 reports/feed.xml（Atom），供 RSS 阅读器订阅
+async fn write_feed(db: &Database, app_config: &AppConfig) -> Result<()> {
+    let size = app_config.generator.feed_size;
+    if size == 0 {
+        return Ok(());
+    }
+    let papers = db.get_recent_papers(size as i64).await?;
+    let updated = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    let dir = config::dirs::get().reports_dir();
+    tokio::fs::create_dir_all(&dir).await?;
+    let path = dir.join("feed.xml");
+    tokio::fs::write(&path, generator::feed::to_atom(&papers, &updated)).await?;
+    info!("订阅源已更新: {} ({} 篇)", path.display(), papers.len());
+    Ok(())
+}
+
+/// 从数据库生成静态站点
+async fn build_site(db: &Database) -> Result<generator::site::SiteStats> {
+    let mut subscriptions = db.get_paper_subscriptions().await?;
+    let mut tags = db.get_tag_map().await?;
+    let mut keyphrases = db.get_keyphrase_map().await?;
+    let mut key_points = db.get_key_points().await?;
+    let mut sources = db.get_alternate_sources().await?;
+    let papers = db
+        .get_all_papers()
+        .await?
+        .into_iter()
+        .map(|paper| {
+            let id = paper.id.unwrap_or_default();
+            generator::site::SitePaper {
+                subscription: subscriptions.remove(&id),
+                tags: tags.remove(&id).unwrap_or_default(),
+                keyphrases: keyphrases.remove(&id).unwrap_or_default(),
+                key_points: key_points.remove(&paper.source_id).unwrap_or_default(),
+                sources: sources.remove(&id).unwrap_or_default(),
+                paper,
+            }
+        })
+        .collect::<Vec<_>>();
+    generator::SiteBuilder::new(config::dirs::get().site_dir()).build(&papers)
+}
+
+/// 爬取过程中各论文共享的依赖和开关
+struct CrawlContext<'a> {
+    app_config: &'a AppConfig,
+    db: &'a Database,
+    translator: &'a Translator,
+    crawler: &'a crawler::ArxivCrawler,
+    image_store: &'a dyn storage::images::ImageStore,
+    translation_enabled: bool,
+    triage_enabled: bool,
+    /// 本次爬取中的翻译尝试次数和失败次数
+    translation_attempts: AtomicUsize,
+    translation_failures: AtomicUsize,
+    /// 翻译、下载和解析失败的累计次数
+    errors: AtomicUsize,
+    /// 星标论文，新论文与其相似时提高相关度
+    starred: crawler::relevance::StarredProfile,
+    /// 本次新入库的论文及相关度说明，爬取结束后推送
+    new_papers: std::sync::Mutex<Vec<String>>,
+}
+
+/// 一次爬取的结果统计
+#[derive(Default)]
+struct CrawlSummary {
+    /// 新入库的论文数
+    saved: usize,
+    translation_attempts: usize,
+    translation_failures: usize,
+}
+
+/// 单篇论文的处理结果
+enum PaperOutcome {
+    /// 数据库中已存在
+    Existing,
+    /// 被 LLM 初筛剔除
+    Rejected,
+    /// 已入库（翻译、下载或解析失败不影响入库）
+    Saved,
+    /// 已存在但PDF缺失或未解析，本次重新下载/解析
+    Repaired,
+}
+
+/// 爬取订阅当天的 arXiv 论文，结果计入 `run`
+async fn crawl_arxiv(
+    ctx: &CrawlContext<'_>,
+    sub: &config::keywords::Subscription,
+    subscription_context: &str,
+    search_terms: &[String],
+    run: &mut storage::models::CrawlRun,
+) -> Result<()> {
+    let result = match ctx.crawler.search(search_terms, ctx.app_config.crawler.max_papers_per_day).await {
+        Ok(result) => result,
+        Err(e) => {
+            info!("arXiv 搜索失败: {}", e);
+            run.error = Some(format!("arXiv 搜索失败: {}", e));
+            return Ok(());
+        }
+    };
+
+    run.total_matched = result.total_results.map(|n| n as i64);
+    run.fetched = result.papers.len() as i64;
+    // 重试后仍然失败时返回空结果，没有匹配总数
+    if result.total_results.is_none() {
+        run.error = Some("arXiv 请求失败".to_string());
+    }
+    let papers = result.papers;
+
+    if papers.is_empty() {
+        info!("未找到匹配的论文，跳过该订阅");
+        return Ok(());
+    }
+
+    info!("找到 {} 篇论文", papers.len());
+
+    for paper in papers.iter().take(3) {
+        let mut pending = Vec::new();
+        let outcome = process_arxiv_paper(ctx, sub, subscription_context, paper, &mut pending).await?;
+        storage::batch::commit(ctx.db, &pending, None).await?;
+        count_outcome(run, &outcome);
+    }
+
+    if ctx.triage_enabled {
+        info!("LLM 初筛剔除 {} 篇不相关论文", run.rejected);
+    }
+    Ok(())
+}
+
+/// 把单篇论文的处理结果计入爬取记录
+fn count_outcome(run: &mut storage::models::CrawlRun, outcome: &PaperOutcome) {
+    match outcome {
+        PaperOutcome::Saved => run.saved += 1,
+        PaperOutcome::Rejected => run.rejected += 1,
+        PaperOutcome::Existing | PaperOutcome::Repaired => run.existing += 1,
+    }
+}
+
+/// 回溯爬取每页条数
+const BACKFILL_PAGE_SIZE: usize = 100;
+/// arXiv 单个查询最多可翻到的结果数，超出部分会被截断
+const ARXIV_MAX_RESULTS_PER_QUERY: usize = 10000;
+
+/// 从 `since` 到今天按月回溯爬取，每页的论文和进度一起提交
+///
+/// 已完成的月份直接跳过，未完成的月份从上次的偏移继续。
+/// 当前月份不会标记为完成，下次运行时会继续获取新提交的论文。
+async fn backfill_arxiv(
+    ctx: &CrawlContext<'_>,
+    sub: &config::keywords::Subscription,
+    subscription_context: &str,
+    search_terms: &[String],
+    since: chrono::NaiveDate,
+    run: &mut storage::models::CrawlRun,
+) -> Result<()> {
+    let today = chrono::Local::now().date_naive();
+    let windows = crawler::backfill::month_windows(since, today);
+    info!("回溯爬取 {} 至 {}，共 {} 个月", since, today, windows.len());
+
+    for window in &windows {
+        let month = window.label();
+        let progress = ctx.db.get_backfill_progress(&sub.name, "arxiv", &month).await?;
+        if progress.as_ref().map(|p| p.completed).unwrap_or(false) {
+            info!("[{}] 已完成，跳过", month);
+            continue;
+        }
+
+        let mut offset = progress.map(|p| p.fetched.max(0) as usize).unwrap_or(0);
+        let total = loop {
+            let result = ctx
+                .crawler
+                .search_window(search_terms, window, offset, BACKFILL_PAGE_SIZE)
+                .await?;
+
+            // 请求最终失败时 total_results 为空，保留进度留待下次续跑
+            let Some(total_results) = result.total_results else {
+                info!("[{}] arXiv 请求失败，已保存进度，稍后重新运行可继续", month);
+                run.error = Some(format!("[{}] arXiv 请求失败", month));
+                return Ok(());
+            };
+
+            if offset == 0 {
+                info!("[{}] 匹配 {} 篇论文", month, total_results);
+                if total_results > ARXIV_MAX_RESULTS_PER_QUERY {
+                    info!(
+                        "⚠️ [{}] 匹配数超过 arXiv 单次查询上限 {}，超出部分无法获取，可细化关键词",
+                        month, ARXIV_MAX_RESULTS_PER_QUERY
+                    );
+                }
+            }
+            if result.papers.is_empty() {
+                break total_results;
+            }
+
+            run.fetched += result.papers.len() as i64;
+            let mut pending = Vec::new();
+            let mut outcomes = Vec::new();
+            for paper in &result.papers {
+                outcomes.push(process_arxiv_paper(ctx, sub, subscription_context, paper, &mut pending).await?);
+            }
+
+            // 整页论文和进度在一个事务中提交，中断后从该页重新开始
+            offset += result.papers.len();
+            let progress = storage::models::BackfillProgress {
+                subscription: sub.name.clone(),
+                source: "arxiv".to_string(),
+                month: month.clone(),
+                fetched: offset as i64,
+                total_matched: Some(total_results as i64),
+                completed: false,
+                updated_at: None,
+            };
+            storage::batch::commit(ctx.db, &pending, Some(&progress)).await?;
+            for outcome in &outcomes {
+                count_outcome(run, outcome);
+            }
+            info!("[{}] 进度 {}/{}", month, offset, total_results);
+
+            if offset >= total_results.min(ARXIV_MAX_RESULTS_PER_QUERY) {
+                break total_results;
+            }
+        };
+
+        let completed = window.end < today;
+        ctx.db
+            .save_backfill_progress(&sub.name, "arxiv", &month, offset as i64, Some(total as i64), completed)
+            .await?;
+    }
+
+    info!("回溯爬取完成: 新增 {} 篇论文", run.saved);
+    if ctx.triage_enabled {
+        info!("LLM 初筛剔除 {} 篇不相关论文", run.rejected);
+    }
+    Ok(())
+}
+
+/// 处理单篇 arXiv 论文：去重、初筛、翻译、下载并解析PDF
+///
+/// 入库所需的写入追加到 `pending`，由调用方在一个事务中提交（见 [`storage::batch::commit`]）。
+async fn process_arxiv_paper(
+    ctx: &CrawlContext<'_>,
+    sub: &config::keywords::Subscription,
+    subscription_context: &str,
+    paper: &crawler::arxiv::ArxivPaper,
+    pending: &mut Vec<PaperWrite>,
+) -> Result<PaperOutcome> {
+    let dirs = config::dirs::get();
+    let db = ctx.db;
+
+    info!("---");
+    info!("标题: {}", paper.title);
+    info!("作者: {}", paper.authors.join(", "));
+    info!("发布日期: {}", paper.published);
+    info!("PDF: {}", paper.pdf_url);
+
+    // 提取arXiv ID
+    let arxiv_id = paper.id.replace("http://arxiv.org/abs/", "");
+
+    if db.is_paper_deleted("arxiv", &arxiv_id).await? {
+        info!("论文已删除（在回收站中），跳过");
+        return Ok(PaperOutcome::Existing);
+    }
+
+    // 检查是否已存在：记录完整则跳过，PDF缺失或未解析完成的记录在本次修复
+    let (target, needs_translation) = match db.get_paper_by_source("arxiv", &arxiv_id).await? {
+        Some(row) => {
+            let paper_id = row.id.ok_or_else(|| anyhow::anyhow!("论文记录缺少ID: {}", arxiv_id))?;
+            if !db.has_relevance(paper_id, &sub.name).await? {
+                let (relevance, label) = score_relevance(ctx, sub, subscription_context, paper, &arxiv_id).await?;
+                db.save_relevance(
+                    paper_id,
+                    &sub.name,
+                    relevance.score,
+                    &relevance.reasons,
+                    label.map(|l| l.as_str()),
+                )
+                .await?;
+                info!("论文已属于其他订阅，已记录与订阅 {} 的相关度", sub.name);
+            }
+            if paper_is_complete(&row) {
+                info!("论文已存在，跳过");
+                return Ok(PaperOutcome::Existing);
+            }
+            info!("论文已存在但PDF缺失或未解析完成，尝试修复");
+            let status = TranslationStatus::parse(&row.translation_status);
+            let needs_translation = matches!(status, Some(TranslationStatus::Pending | TranslationStatus::Failed));
+            (PaperTarget::Existing(paper_id), needs_translation)
+        }
+        None => match prepare_new_arxiv_paper(ctx, sub, subscription_context, paper, &arxiv_id).await? {
+            Some(new_paper) => (PaperTarget::New(Box::new(new_paper)), true),
+            None => return Ok(PaperOutcome::Rejected),
+        },
+    };
+    let outcome = match target {
+        PaperTarget::New(_) => PaperOutcome::Saved,
+        PaperTarget::Existing(_) => PaperOutcome::Repaired,
+    };
+    let mut write = PaperWrite {
+        source: "arxiv".to_string(),
+        source_id: arxiv_id.clone(),
+        target,
+        translation: None,
+        pdf_path: None,
+        pdf_error: None,
+        content: None,
+    };
+
+    // 翻译标题和摘要（修复已有记录时只补翻待翻译/失败的）
+    if needs_translation && paper.summary.trim().is_empty() {
+        info!("论文没有摘要，跳过翻译");
+        write.translation = Some(TranslationWrite::Status(TranslationStatus::SkippedNoAbstract));
+    } else if needs_translation && ctx.translator.is_target_language(&paper.title, &paper.summary) {
+        info!("标题和摘要已是目标语言，跳过翻译");
+        write.translation = Some(TranslationWrite::SameLanguage);
+    } else if needs_translation && ctx.translation_enabled {
+        info!("正在翻译论文...");
+        ctx.translation_attempts.fetch_add(1, Ordering::Relaxed);
+        match ctx.translator.translate_paper(&paper.title, &paper.summary).await {
+            Ok(translated) => {
+                info!("翻译完成 [{}]: {}", translated.provider, translated.title);
+                write.translation = Some(TranslationWrite::Done {
+                    title: translated.title,
+                    abstract_text: translated.abstract_text,
+                    provider: translated.provider,
+                });
+            }
+            Err(e) => {
+                info!("翻译失败: {}，继续处理", e);
+                ctx.translation_failures.fetch_add(1, Ordering::Relaxed);
+                ctx.errors.fetch_add(1, Ordering::Relaxed);
+                write.translation = Some(TranslationWrite::Status(TranslationStatus::Failed));
+            }
+        }
+    }
+
+    // 下载PDF
+    let file_stem = utils::paths::safe_filename(&arxiv_id);
+    let pdf_relative = format!("papers/{}.pdf", file_stem);
+    let pdf_filename = dirs.resolve_data_path(&pdf_relative).to_string_lossy().to_string();
+    let downloaded = if crawler::arxiv::is_complete_pdf(&pdf_filename) {
+        info!("PDF已存在，跳过下载: {}", pdf_filename);
+        Ok(())
+    } else {
+        ctx.crawler.download_pdf(&paper.pdf_url, &pdf_filename).await
+    };
+    match downloaded {
+        Ok(_) => {
+            // PDF路径相对数据目录保存
+            write.pdf_path = Some(pdf_relative);
+
+            // 使用提取管道解析PDF
+            let pipeline = parser::ExtractionPipeline::new(&ctx.app_config.parser);
+            match process_pdf_cached(db, &pipeline, &pdf_filename, ctx.image_store).await {
+                Ok(mut content) => {
+                    content.metadata.cross_check_title(&paper.title);
+                    info!("PDF解析完成:");
+                    if let Some(ref title) = content.metadata.title {
+                        info!("  标题: {}", title);
+                    }
+                    if let Some(ref abs) = content.metadata.abstract_text {
+                        let preview = if abs.len() > 100 { &abs[..100] } else { abs };
+                        info!("  摘要: {}...", preview);
+                    }
+                    info!("  章节数: {}", content.sections.len());
+                    info!("  公式数: {}", content.formulas.len());
+                    info!("  图片数: {}", content.images.len());
+                    info!("  表格数: {}", content.tables.len());
+                    info!("  参考文献: {}", content.references.len());
+                    info!("  链接数: {}", content.links.len());
+                    if let Some(ref venue) = content.metadata.venue {
+                        info!("  出处: {}", venue);
+                    }
+
+                    let key_points = if ctx.app_config.crawler.llm_key_points && ctx.translation_enabled {
+                        generate_key_points(ctx, subscription_context, paper, &content).await
+                    } else {
+                        None
+                    };
+                    write.content = Some(ContentWrite {
+                        content,
+                        summary: Some(paper.summary.clone()),
+                        key_points,
+                    });
+                }
+                Err(e) => match e.downcast_ref::<utils::BsxError>() {
+                    Some(utils::BsxError::PdfError(reason)) => {
+                        ctx.errors.fetch_add(1, Ordering::Relaxed);
+                        info!("⚠️ PDF无法打开，已记录到论文: {}", reason);
+                        write.pdf_error = Some(reason.clone());
+                    }
+                    _ => {
+                        ctx.errors.fetch_add(1, Ordering::Relaxed);
+                        info!("PDF解析失败: {}", e);
+                    }
+                },
+            }
+        }
+        Err(e) => {
+            ctx.errors.fetch_add(1, Ordering::Relaxed);
+            info!("PDF下载失败: {}", e);
+        }
+    }
+
+    pending.push(write);
+    Ok(outcome)
+}
+
+/// 数据库记录完整：PDF已下载到记录的位置且已解析
+fn paper_is_complete(row: &storage::models::Paper) -> bool {
+    if !row.processed {
+        return false;
+    }
+    match row.pdf_path.as_deref() {
+        Some(path) if utils::paths::is_safe_relative_path(path) => {
+            crawler::arxiv::is_complete_pdf(config::dirs::get().resolve_data_path(path))
+        }
+        _ => false,
+    }
+}
+
+/// 论文与订阅的相关度：关键词评分、星标论文相似度和引用热度，启用时附带 LLM 相关度标签
+async fn score_relevance(
+    ctx: &CrawlContext<'_>,
+    sub: &config::keywords::Subscription,
+    subscription_context: &str,
+    paper: &crawler::arxiv::ArxivPaper,
+    arxiv_id: &str,
+) -> Result<(crawler::relevance::RelevanceScore, Option<crawler::relevance::RelevanceLabel>)> {
+    let mut relevance = crawler::relevance::score_paper(sub, &paper.title, &paper.summary, &paper.categories);
+    if let Some((similarity, title)) = ctx.starred.most_similar(&paper.title, &paper.summary) {
+        relevance.add_starred_similarity(similarity, title);
+    }
+    if let Some(cited) = storage::citations::cited_id(Some(arxiv_id), None, None) {
+        let days = crawler::relevance::CITATION_WINDOW_DAYS;
+        let citing = storage::citations::count_citing(ctx.db, &cited, days).await?;
+        relevance.add_citation_velocity(citing as usize, days);
+    }
+    info!("相关度: {:.2} ({})", relevance.score, relevance.explanation());
+
+    // LLM 相关度标签：不剔除论文，生成报告时按 min_relevance 过滤
+    let mut label = None;
+    if ctx.app_config.crawler.llm_relevance && ctx.translation_enabled {
+        match ctx
+            .translator
+            .classify_relevance(subscription_context, &sub.keywords, &paper.title, &paper.summary)
+            .await
+        {
+            Ok(Some(l)) => {
+                info!("LLM 相关度: {}", l.label());
+                label = Some(l);
+            }
+            Ok(None) => info!("LLM 相关度判定格式不符，未打标签"),
+            Err(e) => info!("LLM 相关度判定失败: {}", e),
+        }
+    }
+
+    Ok((relevance, label))
+}
+
+/// 新论文：LLM 初筛、相关度评分后入库，被初筛剔除时返回 `None`
+async fn prepare_new_arxiv_paper(
+    ctx: &CrawlContext<'_>,
+    sub: &config::keywords::Subscription,
+    subscription_context: &str,
+    paper: &crawler::arxiv::ArxivPaper,
+    arxiv_id: &str,
+) -> Result<Option<NewPaper>> {
+    // LLM 初筛：下载PDF前剔除明显不相关的论文
+    if ctx.triage_enabled {
+        match ctx
+            .translator
+            .triage_paper(subscription_context, &paper.title, &paper.summary, Some(&ctx.app_config.crawler.triage_model))
+            .await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                info!("LLM 初筛判定不相关，跳过");
+                return Ok(None);
+            }
+            Err(e) => {
+                info!("LLM 初筛失败: {}，保留该论文", e);
+            }
+        }
+    }
+
+    let (relevance, label) = score_relevance(ctx, sub, subscription_context, paper, arxiv_id).await?;
+
+    let db_paper = storage::models::Paper {
+        id: None,
+        title: paper.title.clone(),
+        title_zh: None,
+        authors: Some(paper.authors.join(", ")),
+        abstract_text: Some(paper.summary.clone()),
+        abstract_zh: None,
+        publish_date: Some(paper.published.clone()),
+        source: "arxiv".to_string(),
+        source_id: arxiv_id.to_string(),
+        pdf_url: Some(paper.pdf_url.clone()),
+        pdf_path: None,
+        processed: false,
+        created_at: None,
+        translation_status: TranslationStatus::Pending.as_str().to_string(),
+        code_urls: None,
+        venue: None,
+        funding: None,
+    };
+
+    ctx.new_papers.lock().unwrap().push(format!(
+        "[{}] {}\nhttps://arxiv.org/abs/{}\n相关度 {:.2}{}：{}",
+        sub.name,
+        paper.title,
+        arxiv_id,
+        relevance.score,
+        label.map(|l| format!("（{}）", l.label())).unwrap_or_default(),
+        relevance.explanation(),
+    ));
+
+    Ok(Some(NewPaper {
+        paper: db_paper,
+        subscription: Some(SubscriptionMatch {
+            name: sub.name.clone(),
+            relevance_score: relevance.score,
+            relevance_reasons: relevance.reasons,
+            relevance_label: label.map(|l| l.as_str().to_string()),
+        }),
+    }))
+}
+
+/// 要点生成时附带的正文节选上限（字节）
+const KEY_POINTS_EXCERPT_BYTES: usize = 4000;
+
+/// 结合订阅的研究方向用 LLM 总结论文要点，失败只记录日志
+async fn generate_key_points(
+    ctx: &CrawlContext<'_>,
+    subscription_context: &str,
+    paper: &crawler::arxiv::ArxivPaper,
+    content: &parser::PaperContent,
+) -> Option<translator::KeyPoints> {
+    info!("正在生成论文要点...");
+    let excerpt = key_points_excerpt(&content.sections);
+    match ctx.translator.summarize_key_points(Some(subscription_context), &paper.title, &paper.summary, &excerpt).await {
+        Ok(key_points) if !key_points.points.is_empty() => {
+            info!("已生成 {} 条要点 [{}]", key_points.points.len(), key_points.provider);
+            Some(key_points)
+        }
+        Ok(_) => {
+            info!("未能生成论文要点");
+            None
+        }
+        Err(e) => {
+            info!("生成论文要点失败: {}", e);
+            None
+        }
+    }
+}
+
+/// 选取结论、结果、讨论等章节作为要点生成的正文节选
+fn key_points_excerpt(sections: &[parser::Section]) -> String {
+    const PRIORITY: [&str; 5] = ["conclusion", "result", "discussion", "experiment", "introduction"];
+
+    let mut excerpt = String::new();
+    for keyword in PRIORITY {
+        for section in sections {
+            if !section.heading.to_lowercase().contains(keyword) {
+                continue;
+            }
+            let remaining = KEY_POINTS_EXCERPT_BYTES.saturating_sub(excerpt.len());
+            if remaining == 0 {
+                return excerpt;
+            }
+            let body = &section.body[..section.body.floor_char_boundary(remaining.min(section.body.len()))];
+            excerpt.push_str(&format!("## {}\n{}\n\n", section.heading, body));
+        }
+    }
+    excerpt
+}
+
+/// 订阅关键词加上 LLM 建议的同义词/缩写，扩展结果按订阅和关键词缓存
+async fn expand_search_terms(
+    db: &Database,
+    translator: &Translator,
+    sub: &config::keywords::Subscription,
+) -> Vec<String> {
+    let keywords_hash = utils::hash::sha256_hex(sub.keywords.join("\n").as_bytes());
+    let expansions = match db.get_query_expansion(&sub.name, &keywords_hash).await {
+        Ok(Some(terms)) => terms,
+        _ => match translator.expand_keywords(&sub.llm_context(), &sub.keywords).await {
+            Ok(terms) => {
+                if let Err(e) = db.save_query_expansion(&sub.name, &keywords_hash, &terms).await {
+                    info!("保存扩展检索词失败: {}", e);
+                }
+                terms
+            }
+            Err(e) => {
+                info!("关键词扩展失败: {}，使用原关键词", e);
+                Vec::new()
+            }
+        },
+    };
+
+    if !expansions.is_empty() {
+        info!("扩展检索词: {:?}", expansions);
+    }
+    sub.keywords.iter().cloned().chain(expansions).collect()
+}
+
+/// 运行提取管道，PDF全文优先从 pdf_texts 缓存读取
+async fn process_pdf_cached(
+    db: &Database,
+    pipeline: &parser::ExtractionPipeline,
+    pdf_path: &str,
+    image_store: &dyn storage::images::ImageStore,
+) -> Result<parser::PaperContent> {
+    let pdf_hash = match utils::hash::sha256_file(pdf_path) {
+        Ok(hash) => hash,
+        Err(e) => {
+            info!("计算PDF哈希失败 {}: {}，跳过文本缓存", pdf_path, e);
+            return pipeline.process(pdf_path, image_store);
+        }
+    };
+
+    let full_text = cached_pdf_text(db, pipeline, pdf_path, &pdf_hash).await?;
+    pipeline.process_text(full_text, pdf_path, image_store)
+}
+
+/// 在阻塞线程池中并行解析多个 PDF，同时解析的数量不超过 `parallelism`，结果按输入顺序返回
+///
+/// 文本缓存的读写仍在异步任务中进行，只有哈希计算、文本提取和内容分析放到 `spawn_blocking` 中。
+async fn process_pdfs_parallel(
+    db: &Database,
+    pipeline: std::sync::Arc<parser::ExtractionPipeline>,
+    pdf_paths: &[String],
+    image_store: std::sync::Arc<dyn storage::images::ImageStore>,
+    parallelism: usize,
+) -> Vec<(String, Result<parser::PaperContent>)> {
+    info!("并行解析 {} 个PDF，并行度 {}", pdf_paths.len(), parallelism);
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(parallelism.max(1)));
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for (index, pdf_path) in pdf_paths.iter().enumerate() {
+        let db = db.clone();
+        let pipeline = pipeline.clone();
+        let image_store = image_store.clone();
+        let semaphore = semaphore.clone();
+        let pdf_path = pdf_path.clone();
+        tasks.spawn(async move {
+            let result = match semaphore.acquire_owned().await {
+                Ok(_permit) => {
+                    info!("处理: {}", pdf_path);
+                    process_pdf_blocking(&db, pipeline, &pdf_path, image_store).await
+                }
+                Err(e) => Err(e.into()),
+            };
+            (index, pdf_path, result)
+        });
+    }
+
+    let mut results = Vec::with_capacity(pdf_paths.len());
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => info!("PDF解析任务异常退出: {}", e),
+        }
+    }
+    results.sort_by_key(|(index, _, _)| *index);
+    results.into_iter().map(|(_, pdf_path, result)| (pdf_path, result)).collect()
+}
+
+/// 与 `process_pdf_cached` 相同，但耗时的解析步骤在阻塞线程池中执行
+async fn process_pdf_blocking(
+    db: &Database,
+    pipeline: std::sync::Arc<parser::ExtractionPipeline>,
+    pdf_path: &str,
+    image_store: std::sync::Arc<dyn storage::images::ImageStore>,
+) -> Result<parser::PaperContent> {
+    let path = pdf_path.to_string();
+    let pdf_hash = match tokio::task::spawn_blocking(move || utils::hash::sha256_file(&path)).await? {
+        Ok(hash) => hash,
+        Err(e) => {
+            info!("计算PDF哈希失败 {}: {}，跳过文本缓存", pdf_path, e);
+            let path = pdf_path.to_string();
+            return tokio::task::spawn_blocking(move || pipeline.process(&path, image_store.as_ref())).await?;
+        }
+    };
+
+    let cache_key = pipeline.text_cache_key(&pdf_hash);
+    let full_text = match db.get_pdf_text(&cache_key).await {
+        Ok(Some(text)) => {
+            info!("使用缓存的PDF文本: {}", pdf_path);
+            text
+        }
+        Ok(None) | Err(_) => {
+            let (pipeline, path) = (pipeline.clone(), pdf_path.to_string());
+            let text = tokio::task::spawn_blocking(move || pipeline.extract_text(&path)).await??;
+            if let Err(e) = db.save_pdf_text(&cache_key, &text).await {
+                info!("缓存PDF文本失败: {}", e);
+            }
+            text
+        }
+    };
+
+    let path = pdf_path.to_string();
+    tokio::task::spawn_blocking(move || pipeline.process_text(full_text, &path, image_store.as_ref())).await?
+}
+
+/// 读取 pdf_texts 中缓存的全文，未命中时提取并写入缓存
+async fn cached_pdf_text(
+    db: &Database,
+    pipeline: &parser::ExtractionPipeline,
+    pdf_path: &str,
+    pdf_hash: &str,
+) -> Result<String> {
+    let cache_key = pipeline.text_cache_key(pdf_hash);
+    match db.get_pdf_text(&cache_key).await {
+        Ok(Some(text)) => {
+            info!("使用缓存的PDF文本: {}", pdf_path);
+            Ok(text)
+        }
+        Ok(None) | Err(_) => {
+            let text = pipeline.extract_text(pdf_path)?;
+            if let Err(e) = db.save_pdf_text(&cache_key, &text).await {
+                info!("缓存PDF文本失败: {}", e);
+            }
+            Ok(text)
+        }
+    }
+}
+
+async fn translate_command(
+    paper_id: Option<i64>,
+    retry_failed: bool,
+    force: bool,
+    retranslate_failed: bool,
+) -> Result<TranslateSummary> {
+    info!("开始翻译任务...");
+
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let mut translator = Translator::new(app_config.translator.clone())
+        .with_cache(db.clone())
+        .with_usage_log(db.clone())
+        .with_glossary(config::Glossary::load()?);
+    // 重新翻译时缓存中是旧模型或无法解析的响应，必须重新请求
+    if force || retranslate_failed {
+        translator = translator.refresh_cache();
+    }
+
+    if !translator.is_configured() {
+        info!(
+            "❌ API key 未配置。请在 {} 中设置 [translator] api_key",
+            config::dirs::get().settings_file().display()
+        );
+        log_translation_statuses(&db).await?;
+        return Ok(TranslateSummary::default());
+    }
+
+    let papers = if paper_id.is_none() && !force && retranslate_failed {
+        db.get_papers_with_title_zh_marker(translator::FALLBACK_MARKER).await?
+    } else {
+        let filter = if let Some(id) = paper_id {
+            PaperFilter { ids: vec![id], ..Default::default() }
+        } else if force {
+            PaperFilter { has_abstract: Some(true), ..Default::default() }
+        } else if retry_failed {
+            PaperFilter { translation_status: Some(TranslationStatus::Failed), ..Default::default() }
+        } else {
+            PaperFilter { translated: Some(false), ..Default::default() }
+        };
+        db.query_papers(&filter, PaperSort::Id, None, 0).await?
+    };
+
+    if papers.is_empty() {
+        info!("没有需要翻译的论文");
+        log_translation_statuses(&db).await?;
+        return Ok(TranslateSummary::default());
+    }
+
+    info!("找到 {} 篇待翻译论文", papers.len());
+
+    let mut success_count = 0;
+    let mut fail_count = 0;
+
+    // 有界并发：信号量控制同时进行的请求数，Translator 内部令牌桶控制速率
+    let translator = std::sync::Arc::new(translator);
+    let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(translator.max_concurrent()));
+    let mut tasks = tokio::task::JoinSet::new();
+
+    for paper in papers {
+        let abstract_text = paper.abstract_text.clone().unwrap_or_default();
+        if abstract_text.trim().is_empty() {
+            info!("论文 [{}] {} 没有摘要，跳过", paper.source_id, paper.title);
+            db.set_translation_status(&paper.source, &paper.source_id, TranslationStatus::SkippedNoAbstract)
+                .await?;
+            continue;
+        }
+        if translator.is_target_language(&paper.title, &abstract_text) {
+            info!("论文 [{}] {} 已是目标语言，跳过", paper.source_id, paper.title);
+            db.mark_same_language(&paper.source, &paper.source_id).await?;
+            continue;
+        }
+
+        let translator = translator.clone();
+        let semaphore = semaphore.clone();
+        tasks.spawn(async move {
+            let result = match semaphore.acquire_owned().await {
+                Ok(_permit) => {
+                    info!("翻译: {}", paper.title);
+                    translator.translate_paper(&paper.title, &abstract_text).await
+                }
+                Err(e) => Err(e.into()),
+            };
+            (paper, result)
+        });
+    }
+
+    // 单个任务出错只计入失败，继续等待其余任务，不中断整批翻译
+    while let Some(joined) = tasks.join_next().await {
+        let (paper, result) = match joined {
+            Ok(output) => output,
+            Err(e) => {
+                // 任务异常退出时无法得知是哪篇论文，状态保持待翻译，下次重试
+                info!("  ❌ 翻译任务异常退出: {}", e);
+                fail_count += 1;
+                continue;
+            }
+        };
+        match result {
+            Ok(translated) => {
+                db.update_translation(
+                    &paper.source,
+                    &paper.source_id,
+                    &translated.title,
+                    &translated.abstract_text,
+                    &translated.provider,
+                )
+                .await?;
+                info!("  ✅ [{}] {}", translated.provider, translated.title);
+                success_count += 1;
+            }
+            Err(e) => {
+                info!("  ❌ 翻译失败 [{}]: {}", paper.source_id, e);
+                db.set_translation_status(&paper.source, &paper.source_id, TranslationStatus::Failed)
+                    .await?;
+                fail_count += 1;
+            }
+        }
+    }
+
+    info!("✅ 翻译完成: {} 成功, {} 失败", success_count, fail_count);
+    log_translation_statuses(&db).await?;
+    Ok(TranslateSummary { succeeded: success_count, failed: fail_count })
+}
+
+/// 一次翻译任务的结果统计
+#[derive(Default)]
+struct TranslateSummary {
+    succeeded: usize,
+    failed: usize,
+}
+
+/// 输出各翻译状态的论文数，便于发现被跳过或失败的论文
+async fn log_translation_statuses(db: &Database) -> Result<()> {
+    let counts = db.count_translation_statuses().await?;
+    let summary: Vec<String> = counts
+        .iter()
+        .map(|(status, count)| {
+            let label = TranslationStatus::parse(status).map(|s| s.label()).unwrap_or(status.as_str());
+            format!("{} {}", label, count)
+        })
+        .collect();
+    if !summary.is_empty() {
+        info!("翻译状态: {}", summary.join(", "));
+    }
+    Ok(())
+}
+
+/// `list --json` 输出的论文条目
+#[derive(serde::Serialize)]
+struct ListedPaper {
+    id: i64,
+    publish_date: Option<String>,
+    source: String,
+    source_id: String,
+    title: String,
+    title_zh: Option<String>,
+    translation_status: String,
+    processed: bool,
+    starred: bool,
+    read: bool,
+    tags: Vec<String>,
+}
+
+async fn list_command(filter: PaperFilter, sort: PaperSort, limit: i64, json: bool) -> Result<()> {
+    if let Some(ref since) = filter.since {
+        validate_date(since)?;
+    }
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    let total = db.count_papers(&filter).await?;
+    let papers = db
+        .query_papers(&filter, sort, (limit > 0).then_some(limit), 0)
+        .await?;
+    let starred = db.get_starred_source_ids().await?;
+    let read = db.get_read_source_ids().await?;
+
+    if json {
+        let mut listed = Vec::with_capacity(papers.len());
+        for paper in papers {
+            let id = paper.id.unwrap_or_default();
+            listed.push(ListedPaper {
+                id,
+                starred: starred.contains(&paper.source_id),
+                read: read.contains(&paper.source_id),
+                tags: db.get_paper_tags(id).await?,
+                publish_date: paper.publish_date,
+                source: paper.source,
+                source_id: paper.source_id,
+                title: paper.title,
+                title_zh: paper.title_zh,
+                translation_status: paper.translation_status,
+                processed: paper.processed,
+            });
+        }
+        println!("{}", serde_json::to_string_pretty(&listed)?);
+        return Ok(());
+    }
+
+    if papers.is_empty() {
+        println!("没有符合条件的论文");
+        return Ok(());
+    }
+    use utils::text::fit_width;
+    println!(
+        "{:>6}  {}  {}  {}  {}  标记",
+        "ID",
+        fit_width("日期", 10),
+        fit_width("来源", 8),
+        fit_width("标题", 48),
+        fit_width("中文标题", 30)
+    );
+    for paper in &papers {
+        let date = paper.publish_date.as_deref().unwrap_or("-");
+        let mut flags = String::new();
+        for (set, flag) in [
+            (paper.title_zh.is_some(), 'T'),
+            (paper.processed, 'P'),
+            (starred.contains(&paper.source_id), 'S'),
+            (read.contains(&paper.source_id), 'R'),
+        ] {
+            flags.push(if set { flag } else { '-' });
+        }
+        println!(
+            "{:>6}  {}  {}  {}  {}  {}",
+            paper.id.unwrap_or_default(),
+            fit_width(date.get(..10).unwrap_or(date), 10),
+            fit_width(&paper.source, 8),
+            fit_width(&paper.title, 48),
+            fit_width(paper.title_zh.as_deref().unwrap_or("-"), 30),
+            flags
+        );
+    }
+    println!("共 {} 篇，显示 {} 篇；标记: T 已翻译  P 已解析  S 星标  R 已读", total, papers.len());
+    Ok(())
+}
+
+async fn config_command(action: ConfigAction) -> Result<()> {
+    match action {
+        ConfigAction::Get { key } => print_config_value(&config::settings::get(key.as_deref())?)?,
+        ConfigAction::Set { key, value } => {
+            let saved = config::settings::set(&key, &value)?;
+            print!("{} = ", key);
+            print_config_value(&saved)?;
+            info!("已写入 {}", config::dirs::get().settings_file().display());
+        }
+        ConfigAction::Validate { offline, json } => {
+            let issues = config::validate::validate(!offline).await;
+            let errors = issues
+                .iter()
+                .filter(|issue| issue.severity == config::validate::Severity::Error)
+                .count();
+            if json {
+                println!("{}", serde_json::to_string_pretty(&issues)?);
+            } else {
+                for issue in &issues {
+                    let mark = match issue.severity {
+                        config::validate::Severity::Error => "✗",
+                        config::validate::Severity::Warning => "⚠️",
+                    };
+                    println!("{} {}: {}", mark, issue.key, issue.message);
+                }
+                if issues.is_empty() {
+                    println!("✅ 配置检查通过");
+                }
+            }
+            if errors > 0 {
+                anyhow::bail!("配置检查发现 {} 个错误", errors);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 字符串直接打印，成组的配置按 TOML 格式打印
+fn print_config_value(value: &toml::Value) -> Result<()> {
+    match value {
+        toml::Value::String(s) => println!("{}", s),
+        toml::Value::Table(table) => print!("{}", toml::to_string_pretty(table)?),
+        other => println!("{}", other),
+    }
+    Ok(())
+}
+
+async fn show_command(paper_id: i64, json: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let Some(details) = storage::details::load(&db, paper_id).await? else {
+        anyhow::bail!("论文不存在: {}", paper_id);
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&details)?);
+        return Ok(());
+    }
+
+    let paper = &details.paper;
+    let or_dash = |value: Option<&str>| value.unwrap_or("-").to_string();
+    println!("#{} {}", paper_id, paper.title);
+    if let Some(ref deleted_at) = details.deleted_at {
+        println!("（已移入回收站: {}，可用 restore {} 恢复）", deleted_at, paper_id);
+    }
+    println!("作者: {}", or_dash(paper.authors.as_deref()));
+    println!("发表日期: {}", or_dash(paper.publish_date.as_deref()));
+    println!("入库时间: {}", or_dash(paper.created_at.as_deref()));
+    if let Some(ref venue) = paper.venue {
+        println!("发表于: {}", venue);
+    }
+    for source in &details.sources {
+        println!(
+            "来源: {} {}  PDF: {}",
+            source.source,
+            source.source_id,
+            or_dash(source.pdf_url.as_deref())
+        );
+    }
+    let code_urls = paper.code_urls();
+    if !code_urls.is_empty() {
+        println!("代码: {}", code_urls.join(", "));
+    }
+    let mut state = vec![if details.starred { "星标" } else { "未星标" }];
+    state.push(if details.read_at.is_some() { "已读" } else { "未读" });
+    println!("状态: {}", state.join("，"));
+    if let Some(ref triage) = details.triage {
+        let decision = storage::models::TriageDecision::parse(&triage.decision)
+            .map(|d| d.label())
+            .unwrap_or(&triage.decision);
+        println!("分拣: {} ({})", decision, or_dash(triage.decided_at.as_deref()));
+    }
+    for relevance in &details.relevance {
+        println!(
+            "相关度: {:.2}（订阅 {}{}）",
+            relevance.score,
+            relevance.subscription,
+            relevance.label.as_deref().map(|l| format!("，{}", l)).unwrap_or_default()
+        );
+    }
+    println!("标签: {}", if details.tags.is_empty() { "-".to_string() } else { details.tags.join(", ") });
+
+    println!();
+    println!("翻译状态: {}", paper.translation_status);
+    println!("中文标题: {}", or_dash(paper.title_zh.as_deref()));
+    if let Some(ref abstract_zh) = paper.abstract_zh {
+        println!("中文摘要: {}", abstract_zh);
+    }
+    if let Some(ref abstract_text) = paper.abstract_text {
+        println!("摘要: {}", abstract_text);
+    }
+
+    println!();
+    let counts = &details.counts;
+    if let Some(ref extracted_at) = details.extracted_at {
+        println!(
+            "提取（{}）: 章节 {}，公式 {}，图片 {}，表格 {}，链接 {}，参考文献 {}，关键短语 {}",
+            extracted_at,
+            counts.sections, counts.formulas, counts.images, counts.tables, counts.links, counts.references, counts.keyphrases
+        );
+    } else {
+        println!("提取: PDF尚未解析");
+    }
+    if let Some(ref error) = details.pdf_error {
+        println!("PDF错误: {}", error);
+    }
+    println!("引用: 引用库中论文 {} 篇，被库中论文引用 {} 次", counts.cites, counts.cited_by);
+    if !details.keyphrases.is_empty() {
+        println!("关键短语: {}", details.keyphrases.join(", "));
+    }
+    if !details.key_points.is_empty() {
+        println!("要点:");
+        for point in &details.key_points {
+            println!("  - {}", point);
+        }
+    }
+
+    println!();
+    match details.files.pdf {
+        Some(ref pdf) => match pdf.size {
+            Some(size) => println!("PDF: {} ({} 字节)", pdf.path.display(), size),
+            None => println!("PDF: {}（文件不存在）", pdf.path.display()),
+        },
+        None => println!("PDF: 未下载"),
+    }
+    if details.files.images > 0 {
+        println!("图片: {} 张，缺失 {} 张", details.files.images, details.files.images_missing);
+    }
+
+    if !details.events.is_empty() {
+        println!();
+        println!("处理记录:");
+        for event in &details.events {
+            println!(
+                "  {}  {:<12} {} ({})",
+                or_dash(event.produced_at.as_deref()),
+                event.field,
+                event.producer,
+                event.version.as_deref().unwrap_or("版本未知")
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn search_command(
+    query: Option<String>,
+    save: bool,
+    list: bool,
+    run: Option<i64>,
+    promote: Option<i64>,
+    delete: Option<i64>,
+    limit: i64,
+) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    if list {
+        let searches = db.get_saved_searches().await?;
+        if searches.is_empty() {
+            info!("没有已保存的搜索");
+        }
+        for search in &searches {
+            info!(
+                "[{}] {}{}  (上次运行: {})",
+                search.id.unwrap_or_default(),
+                search.query,
+                if search.promoted { "  [已订阅]" } else { "" },
+                search.last_run_at.as_deref().unwrap_or("从未"),
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(id) = delete {
+        if db.delete_saved_search(id).await? {
+            info!("已删除保存的搜索 [{}]", id);
+        } else {
+            info!("未找到保存的搜索 [{}]", id);
+        }
+        return Ok(());
+    }
+
+    if let Some(id) = promote {
+        let Some(search) = db.get_saved_search(id).await? else {
+            info!("未找到保存的搜索 [{}]", id);
+            return Ok(());
+        };
+
+        let mut keyword_config = KeywordConfig::load()?;
+        if keyword_config.subscriptions.iter().any(|s| s.name == search.query) {
+            info!("订阅 '{}' 已存在", search.query);
+        } else {
+            keyword_config
+                .subscriptions
+                .push(config::keywords::Subscription::from_search(&search.query));
+            let keywords_file = config::dirs::get().keywords_file();
+            keyword_config.save(&keywords_file)?;
+            info!("✅ 已将 '{}' 添加为订阅，可在 {} 中调整", search.query, keywords_file.display());
+        }
+        db.mark_search_promoted(id).await?;
+        return Ok(());
+    }
+
+    let (query, saved_id) = if let Some(id) = run {
+        let Some(search) = db.get_saved_search(id).await? else {
+            info!("未找到保存的搜索 [{}]", id);
+            return Ok(());
+        };
+        (search.query, Some(id))
+    } else if let Some(query) = query {
+        let saved_id = if save {
+            let id = db.save_search(&query).await?;
+            info!("已保存搜索 [{}]: {}", id, query);
+            Some(id)
+        } else {
+            None
+        };
+        (query, saved_id)
+    } else {
+        info!("请提供搜索关键词，或使用 --list / --run 管理已保存的搜索");
+        return Ok(());
+    };
+
+    let papers = db.search_papers(&query, limit).await?;
+    if let Some(id) = saved_id {
+        db.touch_saved_search(id).await?;
+    }
+
+    info!("搜索 '{}' 找到 {} 篇论文", query, papers.len());
+    for paper in &papers {
+        info!(
+            "[{}] {} ({})",
+            paper.id.unwrap_or_default(),
+            paper.title,
+            paper.publish_date.as_deref().unwrap_or("-"),
+        );
+        if let Some(ref title_zh) = paper.title_zh {
+            info!("     {}", title_zh);
+        }
+    }
+
+    Ok(())
+}
+
+async fn sections_command(paper_id: i64, pin: Vec<String>, hide: Vec<String>, clear: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    if clear {
+        db.clear_section_prefs(paper_id).await?;
+        info!("已清除论文 [{}] 的章节设置", paper_id);
+    }
+    for pattern in &pin {
+        db.set_section_pref(paper_id, pattern, "pin").await?;
+    }
+    for pattern in &hide {
+        db.set_section_pref(paper_id, pattern, "hide").await?;
+    }
+
+    let prefs: Vec<_> = db
+        .get_section_prefs()
+        .await?
+        .into_iter()
+        .filter(|p| p.paper_id == paper_id)
+        .collect();
+    if prefs.is_empty() {
+        info!("论文 [{}] 使用全局章节设置", paper_id);
+    }
+    for pref in &prefs {
+        let label = if pref.action == "pin" { "显示" } else { "隐藏" };
+        info!("论文 [{}] {}: {}", paper_id, label, pref.pattern);
+    }
+    Ok(())
+}
+
+async fn triage_command(limit: i64) -> Result<()> {
+    use storage::models::TriageDecision;
+
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    let papers = db.get_untriaged_papers(limit).await?;
+    if papers.is_empty() {
+        info!("没有待分拣的论文");
+        return Ok(());
+    }
+
+    let total = papers.len();
+    let mut counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+    'papers: for (index, paper) in papers.iter().enumerate() {
+        let Some(paper_id) = paper.id else { continue };
+        print_triage_card(paper, index + 1, total);
+
+        loop {
+            println!("[k] 保留  [s] 星标  [a] 归档  [n] 跳过  [o] 打开PDF  [q] 退出");
+            let decision = match read_triage_key().await? {
+                'k' => TriageDecision::Keep,
+                's' => TriageDecision::Star,
+                'a' => TriageDecision::Archive,
+                'n' | ' ' => {
+                    *counts.entry("跳过").or_default() += 1;
+                    continue 'papers;
+                }
+                'o' => {
+                    open_paper_pdf(paper);
+                    continue;
+                }
+                'q' => break 'papers,
+                _ => continue,
+            };
+            db.set_triage_decision(paper_id, decision).await?;
+            *counts.entry(decision.label()).or_default() += 1;
+            println!("→ {}", decision.label());
+            continue 'papers;
+        }
+    }
+
+    let summary: Vec<String> = ["保留", "星标", "归档", "跳过"]
+        .iter()
+        .filter_map(|label| counts.get(label).map(|n| format!("{} {}", label, n)))
+        .collect();
+    info!("✅ 分拣结束: {}", if summary.is_empty() { "无操作".to_string() } else { summary.join(", ") });
+    Ok(())
+}
+
+/// 在终端显示一篇待分拣论文，优先显示中文标题和摘要
+fn print_triage_card(paper: &storage::models::Paper, position: usize, total: usize) {
+    println!();
+    println!("──────── [{}/{}] {} {} ────────", position, total, paper.source, paper.source_id);
+    match paper.title_zh.as_deref().filter(|t| !t.trim().is_empty()) {
+        Some(title_zh) => {
+            println!("{}", title_zh);
+            println!("{}", paper.title);
+        }
+        None => println!("{}", paper.title),
+    }
+    if let Some(ref authors) = paper.authors {
+        println!("作者: {}", authors);
+    }
+    if let Some(ref date) = paper.publish_date {
+        println!("日期: {}", date);
+    }
+    let abstract_text = paper
+        .abstract_zh
+        .as_deref()
+        .filter(|a| !a.trim().is_empty())
+        .or(paper.abstract_text.as_deref())
+        .filter(|a| !a.trim().is_empty());
+    if let Some(abstract_text) = abstract_text {
+        println!();
+        println!("{}", abstract_text.trim());
+    }
+    println!();
+}
+
+/// 读取单个按键（不需要回车），Esc 和 Ctrl+C 视为退出
+async fn read_triage_key() -> Result<char> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal;
+
+    tokio::task::spawn_blocking(|| -> Result<char> {
+        terminal::enable_raw_mode()?;
+        let key = loop {
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break Ok('q'),
+                    KeyCode::Char(c) => break Ok(c.to_ascii_lowercase()),
+                    KeyCode::Esc => break Ok('q'),
+                    _ => {}
+                },
+                Ok(_) => {}
+                Err(e) => break Err(e.into()),
+            }
+        };
+        terminal::disable_raw_mode()?;
+        key
+    })
+    .await?
+}
+
+/// 用系统默认程序打开本地PDF，未下载时打开PDF链接
+fn open_paper_pdf(paper: &storage::models::Paper) {
+    let local = paper
+        .pdf_path
+        .as_deref()
+        .map(|p| config::dirs::get().resolve_data_path(p))
+        .filter(|p| p.exists());
+    let target = match (local, paper.pdf_url.as_deref()) {
+        (Some(path), _) => path.to_string_lossy().to_string(),
+        (None, Some(url)) => url.to_string(),
+        (None, None) => {
+            println!("该论文没有PDF");
+            return;
+        }
+    };
+    if let Err(e) = open::that(&target) {
+        println!("无法打开 {}: {}", target, e);
+    }
+}
+
+async fn formulas_command(paper_id: i64, all: bool, min_confidence: f32) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    let paper = db
+        .get_paper_by_id(paper_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("论文 [{}] 不存在", paper_id))?;
+    let pdf_relative = paper
+        .pdf_path
+        .ok_or_else(|| anyhow::anyhow!("论文 [{}] 尚未下载PDF", paper_id))?;
+    let pdf_path = config::dirs::get().resolve_data_path(&pdf_relative).to_string_lossy().to_string();
+
+    let pipeline = parser::ExtractionPipeline::new(&app_config.parser);
+    let pdf_hash = utils::hash::sha256_file(&pdf_path)?;
+    let full_text = cached_pdf_text(&db, &pipeline, &pdf_path, &pdf_hash).await?;
+    let mut formulas = pipeline.extract_formulas(&full_text);
+    formulas.retain(|f| f.confidence >= min_confidence);
+
+    let shown = if all { formulas.len() } else { formulas.len().min(parser::MAX_STORED_FORMULAS) };
+    info!("论文 [{}] {}: 共 {} 个公式，显示 {} 个", paper_id, paper.title, formulas.len(), shown);
+    for (i, formula) in formulas.iter().take(shown).enumerate() {
+        let number = formula.number.as_deref().map(|n| format!(" ({})", n)).unwrap_or_default();
+        let referenced = if formula.referenced { " [被引用]" } else { "" };
+        let page = formula.page.map(|p| format!(" p.{}", p)).unwrap_or_default();
+        info!(
+            "{:>3}.{}{} [{} ×{}{} 可信度 {:.2}] {}",
+            i + 1, number, referenced, formula.kind, formula.occurrences, page, formula.confidence, formula.raw
+        );
+    }
+    if shown < formulas.len() {
+        info!("还有 {} 个公式未显示，使用 --all 查看全部", formulas.len() - shown);
+    }
+    Ok(())
+}
+
+async fn parse_command(target: &str, json: bool, dry_run: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let dirs = config::dirs::get();
+
+    // 已存在的文件按路径处理，否则视为论文ID
+    let (pdf_path, paper) = if std::path::Path::new(target).is_file() {
+        let path = std::fs::canonicalize(target)?;
+        let mut matched = None;
+        let downloaded = PaperFilter { downloaded: Some(true), ..Default::default() };
+        for paper in db.query_papers(&downloaded, PaperSort::Id, None, 0).await? {
+            let same_file = paper
+                .pdf_path
+                .as_deref()
+                .and_then(|p| std::fs::canonicalize(dirs.resolve_data_path(p)).ok())
+                .map(|p| p == path)
+                .unwrap_or(false);
+            if same_file {
+                matched = Some(paper);
+                break;
+            }
+        }
+        (path.to_string_lossy().to_string(), matched)
+    } else {
+        let paper_id: i64 = target
+            .parse()
+            .map_err(|_| anyhow::anyhow!("{} 既不是PDF文件也不是论文ID", target))?;
+        let paper = db
+            .get_paper_by_id(paper_id)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("论文 [{}] 不存在", paper_id))?;
+        let pdf_relative = paper
+            .pdf_path
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("论文 [{}] 尚未下载PDF", paper_id))?;
+        (dirs.resolve_data_path(&pdf_relative).to_string_lossy().to_string(), Some(paper))
+    };
+
+    let pipeline = parser::ExtractionPipeline::new(&app_config.parser);
+    let image_store = storage::images::LocalImageStore::new(dirs.images_dir());
+    let mut content = match process_pdf_cached(&db, &pipeline, &pdf_path, &image_store).await {
+        Ok(content) => content,
+        Err(e) => {
+            if let (Some(utils::BsxError::PdfError(reason)), Some(paper_id), false) =
+                (e.downcast_ref::<utils::BsxError>(), paper.as_ref().and_then(|p| p.id), dry_run)
+            {
+                db.set_pdf_error(paper_id, Some(reason)).await?;
+            }
+            return Err(e);
+        }
+    };
+    if let Some(ref paper) = paper {
+        content.metadata.cross_check_title(&paper.title);
+    }
+
+    if json {
+        let mut value = serde_json::to_value(&content)?;
+        if let Some(object) = value.as_object_mut() {
+            object.remove("full_text");
+        }
+        println!("{}", serde_json::to_string_pretty(&value)?);
+    } else {
+        print_content_summary(&pdf_path, &content);
+    }
+
+    match (paper, dry_run) {
+        (Some(paper), false) => {
+            let paper_id = paper.id.ok_or_else(|| anyhow::anyhow!("论文缺少ID"))?;
+            storage::batch::save_content(&db, paper_id, &content, paper.abstract_text.as_deref()).await?;
+            info!("✅ 已更新论文 [{}] 的提取内容", paper_id);
+        }
+        (Some(_), true) => info!("--dry-run：未写入数据库"),
+        (None, _) => info!("数据库中没有对应该PDF的论文，结果未写入数据库"),
+    }
+    Ok(())
+}
+
+async fn import_command(paths: Vec<std::path::PathBuf>, lookup: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let dirs = config::dirs::get();
+
+    let mut pdf_files = Vec::new();
+    for path in &paths {
+        if path.is_dir() {
+            let mut found: Vec<std::path::PathBuf> = std::fs::read_dir(path)?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|p| p.is_file() && p.extension().is_some_and(|e| e.eq_ignore_ascii_case("pdf")))
+                .collect();
+            found.sort();
+            pdf_files.extend(found);
+        } else if path.is_file() {
+            pdf_files.push(path.clone());
+        } else {
+            info!("⚠️ 文件不存在，跳过: {}", path.display());
+        }
+    }
+    if pdf_files.is_empty() {
+        anyhow::bail!("没有找到要导入的PDF文件");
+    }
+    info!("准备导入 {} 个PDF", pdf_files.len());
+
+    std::fs::create_dir_all(dirs.papers_dir())?;
+    let pipeline = parser::ExtractionPipeline::new(&app_config.parser);
+    let image_store = storage::images::LocalImageStore::new(dirs.images_dir());
+    let metadata_lookup = lookup.then(|| crawler::MetadataLookup::new(&app_config.crawler));
+
+    let (mut imported, mut skipped, mut failed) = (0, 0, 0);
+    for file in &pdf_files {
+        info!("导入: {}", file.display());
+        match import_pdf(&db, &pipeline, &image_store, metadata_lookup.as_ref(), file).await {
+            Ok(Some(paper_id)) => {
+                info!("✅ 已导入为论文 [{}]", paper_id);
+                imported += 1;
+            }
+            Ok(None) => skipped += 1,
+            Err(e) => {
+                info!("导入 {} 失败: {}", file.display(), e);
+                failed += 1;
+            }
+        }
+    }
+
+    println!("导入完成: 新增 {} 篇，跳过 {} 篇，失败 {} 篇", imported, skipped, failed);
+    if imported > 0 {
+        println!("运行 translate 翻译新导入论文的标题和摘要");
+    }
+    Ok(())
+}
+
+/// 导入一个PDF，返回新论文的ID；已导入过（或查到的 arXiv 论文已在库中）时返回 None
+///
+/// 来源ID取文件内容哈希的前 16 位，同一文件重复导入会被跳过。
+async fn import_pdf(
+    db: &Database,
+    pipeline: &parser::ExtractionPipeline,
+    image_store: &dyn storage::images::ImageStore,
+    metadata_lookup: Option<&crawler::MetadataLookup>,
+    file: &std::path::Path,
+) -> Result<Option<i64>> {
+    const SOURCE: &str = "local";
+    if !crawler::arxiv::is_complete_pdf(file) {
+        anyhow::bail!("不是PDF文件");
+    }
+    let source_id = utils::hash::sha256_file(file)?[..16].to_string();
+    if db.paper_exists(SOURCE, &source_id).await? {
+        info!("该文件已导入过，跳过");
+        return Ok(None);
+    }
+
+    let dirs = config::dirs::get();
+    let pdf_relative = format!("papers/{}.pdf", utils::paths::safe_filename(&source_id));
+    let pdf_path = dirs.data_dir.join(&pdf_relative);
+    std::fs::copy(file, &pdf_path)?;
+    let pdf_path_str = pdf_path.to_string_lossy().to_string();
+
+    let discard = || {
+        let _ = std::fs::remove_file(&pdf_path);
+    };
+    let (mut content, pdf_error) = match process_pdf_cached(db, pipeline, &pdf_path_str, image_store).await {
+        Ok(content) => (Some(content), None),
+        // 加密或损坏的PDF仍然导入，记录原因
+        Err(e) => match e.downcast_ref::<utils::BsxError>() {
+            Some(utils::BsxError::PdfError(reason)) => (None, Some(reason.clone())),
+            _ => {
+                discard();
+                return Err(e);
+            }
+        },
+    };
+
+    let found = match (metadata_lookup, content.as_ref()) {
+        (Some(metadata_lookup), Some(content)) => metadata_lookup.find(&content.metadata).await,
+        _ => None,
+    };
+    if let Some(arxiv_id) = found.as_ref().and_then(|f| f.arxiv_id.as_deref()) {
+        if db.paper_exists("arxiv", arxiv_id).await? {
+            discard();
+            info!("arXiv 论文 {} 已在库中，跳过", arxiv_id);
+            return Ok(None);
+        }
+    }
+
+    let file_stem = file.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+    let title = found
+        .as_ref()
+        .map(|f| f.title.clone())
+        .or_else(|| content.as_ref().and_then(|c| c.metadata.title.clone()))
+        .unwrap_or(file_stem);
+    if let (Some(found), Some(content)) = (found.as_ref(), content.as_mut()) {
+        content.metadata.cross_check_title(&found.title);
+        if content.metadata.venue.is_none() {
+            content.metadata.venue = found.venue.clone();
+        }
+    }
+
+    let paper = storage::models::Paper {
+        id: None,
+        title,
+        title_zh: None,
+        authors: found.as_ref().filter(|f| !f.authors.is_empty()).map(|f| f.authors.join(", ")),
+        abstract_text: found.as_ref().and_then(|f| f.abstract_text.clone()),
+        abstract_zh: None,
+        publish_date: found.as_ref().and_then(|f| f.publish_date.clone()),
+        source: SOURCE.to_string(),
+        source_id: source_id.clone(),
+        pdf_url: found.as_ref().and_then(|f| f.pdf_url.clone()),
+        pdf_path: None,
+        processed: false,
+        created_at: None,
+        translation_status: TranslationStatus::Pending.as_str().to_string(),
+        code_urls: None,
+        venue: None,
+        funding: None,
+    };
+    let summary = paper.abstract_text.clone();
+    let write = PaperWrite {
+        source: SOURCE.to_string(),
+        source_id,
+        target: PaperTarget::New(Box::new(NewPaper { paper, subscription: None })),
+        translation: None,
+        pdf_path: Some(pdf_relative),
+        pdf_error,
+        content: content.map(|content| ContentWrite { content, summary, key_points: None }),
+    };
+    let ids = storage::batch::commit(db, &[write], None).await?;
+    Ok(ids.first().copied())
+}
+
+/// 按类别打印提取结果的摘要
+fn print_content_summary(pdf_path: &str, content: &parser::PaperContent) {
+    let metadata = &content.metadata;
+    println!("文件: {}", pdf_path);
+    println!("标题: {}", metadata.title.as_deref().unwrap_or("(未识别)"));
+    if let Some(ref venue) = metadata.venue {
+        println!("出处: {}", venue);
+    }
+    if let Some(ref abstract_text) = metadata.abstract_text {
+        let preview: String = abstract_text.chars().take(200).collect();
+        println!("摘要: {}{}", preview, if preview.len() < abstract_text.len() { "..." } else { "" });
+    }
+
+    println!("\n章节 ({}):", content.sections.len());
+    for section in &content.sections {
+        let indent = "  ".repeat(section.level.max(1) as usize);
+        let page = section.page.map(|p| format!(" p.{}", p)).unwrap_or_default();
+        println!(
+            "{}{}{} ({} 字符, 可信度 {:.2})",
+            indent, section.heading, page, section.body.chars().count(), section.confidence
+        );
+    }
+
+    println!("\n公式: 保留 {} 个，共 {} 个", content.formulas.len(), content.formula_total);
+    for formula in content.formulas.iter().take(5) {
+        let number = formula.number.as_deref().map(|n| format!("({}) ", n)).unwrap_or_default();
+        println!("  {}{} [{:.2}]", number, formula.raw, formula.confidence);
+    }
+    println!("图片: {}", content.images.len());
+    println!("表格: {}", content.tables.len());
+    for table in &content.tables {
+        let caption = table.caption.as_deref().unwrap_or("(无标题)");
+        println!("  {} ({} 行, 可信度 {:.2})", caption, table.rows.len(), table.confidence);
+    }
+    println!("参考文献: {}", content.references.len());
+    let cited: usize = content.citations.iter().map(|c| c.references.len()).sum();
+    println!("文内引用: {}", cited);
+    println!("链接: {}", content.links.len());
+    for link in &content.links {
+        println!("  [{}] {}", link.kind, link.url);
+    }
+    if !metadata.code_urls.is_empty() {
+        println!("代码: {}", metadata.code_urls.join(", "));
+    }
+    if !content.keyphrases.is_empty() {
+        let phrases: Vec<&str> = content.keyphrases.iter().map(|k| k.phrase.as_str()).collect();
+        println!("关键短语: {}", phrases.join(", "));
+    }
+    for statement in &metadata.funding {
+        println!("资助: {}", statement);
+    }
+}
+
+async fn export_tables_command(
+    paper_id: Option<i64>,
+    output: Option<std::path::PathBuf>,
+    min_confidence: f32,
+) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    let dir = output.unwrap_or_else(|| config::dirs::get().tables_dir());
+    let count = storage::tables::export_tables(&db, &dir, paper_id, min_confidence).await?;
+    info!("已导出 {} 个表格到 {}", count, dir.display());
+    Ok(())
+}
+
+/// 每次嵌入请求包含的论文数
+const EMBEDDING_BATCH_SIZE: usize = 32;
+
+async fn similar_command(paper_id: Option<i64>, limit: usize) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let translator = Translator::new(app_config.translator.clone());
+    let Some(model) = translator.embedding_model().map(str::to_string) else {
+        anyhow::bail!("请先在 settings.toml 的 [translator] 中配置 embedding_model 和 API key");
+    };
+
+    let missing = storage::embeddings::missing_paper_ids(&db, &model).await?;
+    if !missing.is_empty() {
+        info!("为 {} 篇论文生成向量 ({})", missing.len(), model);
+    }
+    let mut embedded = 0;
+    for batch in missing.chunks(EMBEDDING_BATCH_SIZE) {
+        let mut ids = Vec::with_capacity(batch.len());
+        let mut texts = Vec::with_capacity(batch.len());
+        for &id in batch {
+            if let Some(paper) = db.get_paper_by_id(id).await? {
+                ids.push(id);
+                texts.push(format!("{}\n\n{}", paper.title, paper.abstract_text.unwrap_or_default()));
+            }
+        }
+        match translator.embed(&texts).await {
+            Ok(vectors) => {
+                for (id, vector) in ids.into_iter().zip(vectors) {
+                    storage::embeddings::upsert(&db, id, &model, &vector).await?;
+                    embedded += 1;
+                }
+            }
+            Err(e) => {
+                warn!("生成向量失败: {}", e);
+                break;
+            }
+        }
+    }
+    if embedded > 0 {
+        info!("✅ 已生成 {} 篇论文的向量", embedded);
+    }
+
+    let Some(paper_id) = paper_id else {
+        return Ok(());
+    };
+    let paper = db
+        .get_paper_by_id(paper_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("论文 [{}] 不存在", paper_id))?;
+    let neighbors = storage::embeddings::nearest_to_paper(&db, paper_id, &model, limit).await?;
+    println!("[{}] {}", paper_id, paper.title);
+    if neighbors.is_empty() {
+        println!("没有找到相似论文");
+    }
+    for neighbor in neighbors {
+        let title = db.get_paper_by_id(neighbor.paper_id).await?.map(|p| p.title).unwrap_or_default();
+        println!("  {:.3}  [{}] {}", neighbor.similarity, neighbor.paper_id, title);
+    }
+    Ok(())
+}
+
+async fn citations_command(paper_id: Option<i64>, days: u32, show_references: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    storage::citations::resolve(&db).await?;
+
+    let Some(paper_id) = paper_id else {
+        let cited = storage::citations::cited_by_recent(&db, days).await?;
+        if cited.is_empty() {
+            println!("最近 {} 天入库的论文没有引用库中的论文", days);
+        }
+        for paper in cited {
+            println!("[{}] {}  （被引用 {} 次）", paper.id, paper.title, paper.citations);
+        }
+        return Ok(());
+    };
+    let paper = db
+        .get_paper_by_id(paper_id)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("论文 [{}] 不存在", paper_id))?;
+    println!("[{}] {}", paper_id, paper.title);
+    for (label, papers) in [
+        ("引用了库中的论文", storage::citations::cited_papers(&db, paper_id).await?),
+        ("被库中的论文引用", storage::citations::citing_papers(&db, paper_id).await?),
+    ] {
+        println!("\n{}（{} 篇）:", label, papers.len());
+        for (id, title) in papers {
+            println!("  [{}] {}", id, title);
+        }
+    }
+    if show_references {
+        let references = db.get_references(paper_id).await?;
+        println!("\n参考文献（{} 条）:", references.len());
+        for reference in references {
+            let label = reference.label.unwrap_or_else(|| (reference.position + 1).to_string());
+            println!("  [{}] {}", label, reference.title.unwrap_or(reference.raw));
+        }
+    }
+    Ok(())
+}
+
+/// 按论文ID、标签和发表日期筛选论文，条件都为空时返回全部
+async fn select_papers(
+    db: &Database,
+    ids: &[i64],
+    tags: &[String],
+    since: Option<String>,
+) -> Result<Vec<storage::models::Paper>> {
+    let filter = PaperFilter { ids: ids.to_vec(), tags: tags.to_vec(), since, ..Default::default() };
+    db.query_papers(&filter, PaperSort::Id, None, 0).await
+}
+
+/// 校验 `--since` 等日期参数的格式
+fn validate_date(date: &str) -> Result<()> {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("无效的日期 '{}'，格式应为 YYYY-MM-DD", date))?;
+    Ok(())
+}
+
+async fn tag_command(id: Option<i64>, tags: Vec<String>, remove: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    let Some(paper_id) = id else {
+        let counts = db.get_tag_counts().await?;
+        if counts.is_empty() {
+            println!("还没有标签");
+        }
+        for (name, count) in counts {
+            println!("{}  ({} 篇)", name, count);
+        }
+        return Ok(());
+    };
+    if db.get_paper_by_id(paper_id).await?.is_none() {
+        anyhow::bail!("没有ID为 {} 的论文", paper_id);
+    }
+
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if remove {
+            if !db.remove_tag(paper_id, tag).await? {
+                println!("论文 {} 没有标签 {}", paper_id, tag);
+            }
+        } else {
+            db.add_tag(paper_id, tag, "manual").await?;
+        }
+    }
+    let current = db.get_paper_tags(paper_id).await?;
+    println!("论文 {} 的标签: {}", paper_id, if current.is_empty() { "（无）".to_string() } else { current.join(", ") });
+    Ok(())
+}
+
+async fn mark_read_command(mut ids: Vec<i64>, tags: Vec<String>, unread: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    if !tags.is_empty() {
+        let mut tagged: Vec<i64> = db.get_paper_ids_with_tags(&tags).await?.into_iter().collect();
+        if tagged.is_empty() {
+            println!("没有带标签 {} 的论文", tags.join(", "));
+        }
+        tagged.sort_unstable();
+        ids.extend(tagged.into_iter().filter(|id| !ids.contains(id)).collect::<Vec<_>>());
+    }
+    for id in ids {
+        if db.set_read(id, !unread).await? {
+            println!("论文 {} 已标记为{}", id, if unread { "未读" } else { "已读" });
+        } else {
+            println!("没有ID为 {} 的论文", id);
+        }
+    }
+    Ok(())
+}
+
+async fn star_command(ids: Vec<i64>, remove: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    for id in ids {
+        if db.set_starred(id, !remove).await? {
+            println!("论文 {} {}", id, if remove { "已取消星标" } else { "已加星标" });
+        } else {
+            println!("没有ID为 {} 的论文", id);
+        }
+    }
+    Ok(())
+}
+
+async fn delete_command(ids: Vec<i64>) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    for id in ids {
+        if db.soft_delete_paper(id).await? {
+            println!("论文 {} 已移入回收站，可用 restore {} 恢复", id, id);
+        } else {
+            println!("没有ID为 {} 的论文（或已在回收站中）", id);
+        }
+    }
+    Ok(())
+}
+
+async fn restore_command(ids: Vec<i64>) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    if ids.is_empty() {
+        let deleted = db.get_deleted_papers().await?;
+        if deleted.is_empty() {
+            println!("回收站为空");
+        }
+        for (id, title, deleted_at) in deleted {
+            println!("[{}] {}  （删除于 {}）", id, title, deleted_at);
+        }
+        return Ok(());
+    }
+    for id in ids {
+        if db.restore_paper(id).await? {
+            println!("论文 {} 已恢复", id);
+        } else {
+            println!("回收站中没有ID为 {} 的论文", id);
+        }
+    }
+    Ok(())
+}
+
+async fn export_command(
+    format: Option<generator::export::ExportFormat>,
+    notion: bool,
+    ids: Vec<i64>,
+    tags: Vec<String>,
+    since: Option<String>,
+    output: Option<std::path::PathBuf>,
+) -> Result<()> {
+    if format.is_none() && !notion {
+        anyhow::bail!("请指定导出格式（--format bibtex|ris|json|csv）或导出目标（--notion）");
+    }
+    if let Some(ref since) = since {
+        validate_date(since)?;
+    }
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    let papers = select_papers(&db, &ids, &tags, since).await?;
+    if let Some(format) = format {
+        let extras = generator::export::PaperExtras::load(&db).await?;
+        let text = generator::export::render(format, &papers, &extras)?;
+        match output {
+            Some(path) => {
+                tokio::fs::write(&path, text).await?;
+                info!("已导出 {} 篇论文到 {}", papers.len(), path.display());
+            }
+            None => {
+                use std::io::Write;
+                std::io::stdout().write_all(text.as_bytes())?;
+                info!("已导出 {} 篇论文", papers.len());
+            }
+        }
+        return Ok(());
+    }
+
+    if papers.is_empty() {
+        println!("没有可导出的论文");
+        return Ok(());
+    }
+    // 论文的标签加上前几个关键短语
+    let tag_map = db.get_tag_map().await?;
+    let keyphrases = db.get_keyphrase_map().await?;
+    let tags: Vec<Vec<String>> = papers
+        .iter()
+        .map(|p| {
+            let id = p.id.unwrap_or_default();
+            let mut tags = tag_map.get(&id).cloned().unwrap_or_default();
+            tags.extend(keyphrases.get(&id).into_iter().flatten().take(5).cloned());
+            tags
+        })
+        .collect();
+
+    let exporter = generator::NotionExporter::new(app_config.notion.clone());
+    let stats = exporter.export(&papers, &tags).await?;
+    println!(
+        "已导出到 Notion: 新建 {} 篇，更新 {} 篇，失败 {} 篇",
+        stats.created, stats.updated, stats.failed
+    );
+    Ok(())
+}
+
+async fn site_command(action: SiteAction) -> Result<()> {
+    match action {
+        SiteAction::Build { force } => {
+            let site_dir = config::dirs::get().site_dir();
+            if force && site_dir.exists() {
+                std::fs::remove_dir_all(&site_dir)?;
+            }
+            let app_config = AppConfig::load()?;
+            let db = Database::open(&app_config.storage).await?;
+            let stats = build_site(&db).await?;
+            println!(
+                "站点已生成: {}（更新 {} 个文件，{} 个未变化）",
+                site_dir.join("index.html").display(),
+                stats.written,
+                stats.unchanged
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn stats_command(days: i64, json: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    sync_subscriptions(&db, &KeywordConfig::load()?).await?;
+
+    let stats = storage::stats::collect(
+        &db,
+        &app_config.storage.database_file(),
+        &app_config.storage.backup_dir(),
+        days,
+    )
+    .await?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+
+    let percent = |ratio: f64| format!("{:.1}%", ratio * 100.0);
+    let size = storage::stats::format_size;
+    println!("══════════ 论文库统计 ══════════");
+    println!("论文总数      {}", stats.total_papers);
+    println!("星标 / 已读   {} / {}", stats.starred, stats.read);
+    println!("标签数        {}", stats.tags);
+    println!("回收站        {}", stats.deleted);
+
+    println!("\n── 每日入库（最近 {} 天）──", stats.papers_per_day.len());
+    let max = stats.papers_per_day.iter().map(|c| c.count).max().unwrap_or(0).max(1);
+    for day in &stats.papers_per_day {
+        // 条形图最长 30 格
+        let bar = "█".repeat(((day.count * 30 + max - 1) / max) as usize);
+        println!("{}  {:>5}  {}", day.name, day.count, bar);
+    }
+
+    // 名称可能含中文，数字列在前以便对齐
+    println!("\n── 来源 ──");
+    for source in &stats.papers_per_source {
+        println!("{:>6}  {}", source.count, source.name);
+    }
+    println!("\n── 订阅 ──");
+    if stats.papers_per_subscription.is_empty() {
+        println!("（无）");
+    }
+    for sub in &stats.papers_per_subscription {
+        println!("{:>6}  {}", sub.count, sub.name);
+    }
+
+    println!("\n── 翻译 ──（覆盖率 {}）", percent(stats.translation.coverage));
+    for status in &stats.translation.statuses {
+        let label = TranslationStatus::parse(&status.name).map(|s| s.label()).unwrap_or(status.name.as_str());
+        println!("{:>6}  {}", status.count, label);
+    }
+
+    let parsing = &stats.parsing;
+    println!("\n── PDF ──");
+    println!("已下载        {}", parsing.downloaded);
+    println!("已解析        {}", parsing.parsed);
+    println!("无法打开      {}（失败率 {}）", parsing.failed, percent(parsing.failure_rate));
+
+    let crawls = &stats.crawls;
+    println!("\n── 爬取（最近 {} 天）──", stats.papers_per_day.len());
+    println!("爬取次数      {}（中断 {}）", crawls.runs, crawls.failed_runs);
+    println!("获取 / 新增   {} / {}", crawls.fetched, crawls.saved);
+    println!("处理失败      {}", crawls.errors);
+    println!("总耗时        {:.1} 分钟", crawls.duration_ms as f64 / 60000.0);
+
+    let api = &stats.api;
+    println!("\n── LLM 调用（最近 {} 天）──", stats.papers_per_day.len());
+    println!("请求次数      {}（失败 {}）", api.calls, api.failed);
+    println!("平均耗时      {:.0} ms", api.avg_duration_ms);
+    println!("输入 token    {}", api.prompt_tokens);
+    println!("输出 token    {}", api.completion_tokens);
+    for purpose in &api.per_purpose {
+        println!("{:>6}  {}", purpose.count, purpose.name);
+    }
+
+    let storage = &stats.storage;
+    println!("\n── 占用空间 ──");
+    println!("数据库        {}", size(storage.database));
+    println!("PDF           {}", size(storage.papers));
+    println!("图片          {}", size(storage.images));
+    println!("报告          {}", size(storage.reports));
+    println!("备份          {}", size(storage.backups));
+    Ok(())
+}
+
+async fn prune_command(older_than: Option<String>, dry_run: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let days = match older_than {
+        Some(value) => storage::prune::parse_age(&value)?,
+        None if app_config.storage.cache_ttl_days > 0 => app_config.storage.cache_ttl_days,
+        None => anyhow::bail!("cache_ttl_days 为 0，请用 --older-than 指定保留期"),
+    };
+    let db = Database::open(&app_config.storage).await?;
+    let plan = storage::prune::plan(&db, days).await?;
+
+    let size = storage::stats::format_size(plan.file_bytes);
+    if dry_run {
+        println!("入库超过 {} 天、未加星标，或在回收站中超过 {} 天的论文 {} 篇:", days, days, plan.papers.len());
+        for paper in &plan.papers {
+            let date = paper.created_at.as_deref().unwrap_or_default().get(..10).unwrap_or_default();
+            println!("  [{}] {}  {}", paper.id, date, paper.title);
+        }
+        println!("文件 {} 个，共 {}", plan.files.len(), size);
+        println!(
+            "爬取记录 {} 条，API 调用记录 {} 条，缓存 {} 条",
+            plan.crawl_runs, plan.api_calls, plan.cache_entries
+        );
+        println!("（--dry-run：未做任何修改）");
+        return Ok(());
+    }
+
+    let reclaimed = storage::prune::execute(&db, &plan, &app_config.storage.database_file()).await?;
+    info!(
+        "✅ 已删除 {} 篇论文、{} 个文件（{}），爬取记录 {} 条，API 调用记录 {} 条，缓存 {} 条；数据库缩小 {}",
+        plan.papers.len(),
+        plan.files.len(),
+        size,
+        plan.crawl_runs,
+        plan.api_calls,
+        plan.cache_entries,
+        storage::stats::format_size(reclaimed)
+    );
+    Ok(())
+}
+
+async fn doctor_command(files: bool, offline: bool, repair: bool, json: bool) -> Result<()> {
+    if !files {
+        return environment_doctor(!offline, json).await;
+    }
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let report = storage::integrity::check_files(&db).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    println!("已检查 {} 个PDF、{} 张图片", report.pdfs_checked, report.images_checked);
+    if report.issues.is_empty() {
+        info!("✅ 未发现问题");
+        return Ok(());
+    }
+    for issue in &report.issues {
+        let kind = match issue.kind {
+            storage::integrity::FileKind::Pdf => "PDF",
+            storage::integrity::FileKind::Image => "图片",
+        };
+        println!(
+            "  [{}] {}{}: {}  {}",
+            issue.paper_id,
+            kind,
+            issue.problem.label(),
+            issue.path.display(),
+            issue.title
+        );
+    }
+    let redownload = report.redownload_ids();
+    let reextract = report.reextract_ids();
+    println!("需要重新下载PDF的论文 {} 篇，需要重新提取的论文 {} 篇", redownload.len(), reextract.len());
+    if !repair {
+        println!("（加 --repair 修复）");
+        return Ok(());
+    }
+
+    let repaired = repair_paper_files(&app_config, &db, &redownload, &reextract).await?;
+    info!("✅ 已修复 {}/{} 篇论文", repaired, redownload.len() + reextract.len());
+    Ok(())
+}
+
+async fn environment_doctor(check_network: bool, json: bool) -> Result<()> {
+    use utils::diagnostics::CheckStatus;
 
-    // 初始化翻译器
-    let translator = Translator::new(app_config.translator.clone());
-    let translation_enabled = translator.is_configured();
-    if !translation_enabled {
-        info!("⚠️ API key 未配置，跳过翻译。请在 config/settings.toml 中设置 api_key");
+    let checks = utils::diagnostics::run(check_network).await;
+    let failed = checks.iter().filter(|check| check.status == CheckStatus::Fail).count();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&checks)?);
+    } else {
+        for check in &checks {
+            let mark = match check.status {
+                CheckStatus::Pass => "✓",
+                CheckStatus::Warn => "⚠️",
+                CheckStatus::Fail => "✗",
+            };
+            println!("{} {}: {}", mark, check.name, check.detail);
+            if let Some(ref hint) = check.hint {
+                println!("    → {}", hint);
+            }
+        }
+        if failed == 0 {
+            println!("✅ 环境检查通过");
+        }
+    }
+    if failed > 0 {
+        anyhow::bail!("{} 项检查未通过", failed);
     }
+    Ok(())
+}
 
-    let subscriptions = keyword_config.get_active_subscriptions();
+/// 重新下载 `redownload` 中论文的PDF，与 `reextract` 中的论文一起重新提取；单篇失败只记录日志，返回修复成功的篇数
+async fn repair_paper_files(app_config: &AppConfig, db: &Database, redownload: &[i64], reextract: &[i64]) -> Result<usize> {
+    let dirs = config::dirs::get();
+    std::fs::create_dir_all(dirs.papers_dir())?;
+    let pipeline = parser::ExtractionPipeline::new(&app_config.parser);
+    let image_store = storage::images::LocalImageStore::new(dirs.images_dir());
 
-    if subscriptions.is_empty() {
-        info!("没有启用的订阅，请检查 config/keywords.toml");
-        return Ok(());
+    // 先并发下载，同一数据源的并发数和请求间隔由 Fetcher 的礼貌策略限制
+    let fetcher = std::sync::Arc::new(crawler::Fetcher::new(&app_config.crawler));
+    let mut downloads = tokio::task::JoinSet::new();
+    for &paper_id in redownload {
+        let Some(paper) = db.get_paper_by_id(paper_id).await? else {
+            continue;
+        };
+        let Some(url) = paper.pdf_url.filter(|url| !url.is_empty()) else {
+            info!("[{}] 没有PDF链接，无法重新下载", paper_id);
+            continue;
+        };
+        let fetcher = fetcher.clone();
+        downloads.spawn(async move {
+            let pdf_relative = format!("papers/{}.pdf", utils::paths::safe_filename(&paper.source_id));
+            let pdf_filename = dirs.data_dir.join(&pdf_relative).to_string_lossy().to_string();
+            let result = fetcher.download_pdf(&paper.source, &url, &pdf_filename).await;
+            (paper_id, result.map(|_| pdf_relative))
+        });
     }
-
-    for sub in subscriptions {
-        if let Some(ref name) = subscription {
-            if &sub.name != name {
-                continue;
+    let mut downloaded = std::collections::HashMap::new();
+    while let Some(joined) = downloads.join_next().await {
+        match joined? {
+            (paper_id, Ok(pdf_relative)) => {
+                downloaded.insert(paper_id, pdf_relative);
             }
+            (paper_id, Err(e)) => info!("[{}] PDF下载失败: {}", paper_id, e),
         }
+    }
 
-        info!("处理订阅: {}", sub.name);
-        info!("关键词: {:?}", sub.keywords);
-
-        // 使用 arXiv 爬虫
-        if sub.sources.contains(&"arxiv".to_string()) {
-            let crawler = crawler::ArxivCrawler::new();
+    let mut repaired = 0;
+    for &paper_id in redownload.iter().chain(reextract) {
+        let Some(paper) = db.get_paper_by_id(paper_id).await? else {
+            continue;
+        };
+        let mut write = PaperWrite {
+            source: paper.source.clone(),
+            source_id: paper.source_id.clone(),
+            target: PaperTarget::Existing(paper_id),
+            translation: None,
+            pdf_path: None,
+            pdf_error: None,
+            content: None,
+        };
 
-            let papers = match crawler.search(&sub.keywords, app_config.crawler.max_papers_per_day).await {
-                Ok(papers) => papers,
-                Err(e) => {
-                    info!("arXiv 搜索失败: {}", e);
-                    continue;
-                }
+        let stored = if redownload.contains(&paper_id) {
+            let Some(pdf_relative) = downloaded.remove(&paper_id) else {
+                continue;
             };
+            write.pdf_path = Some(pdf_relative.clone());
+            pdf_relative
+        } else {
+            paper.pdf_path.clone().unwrap_or_default()
+        };
 
-            if papers.is_empty() {
-                info!("未找到匹配的论文，跳过该订阅");
-                continue;
+        let pdf_path = dirs.resolve_data_path(&stored).to_string_lossy().to_string();
+        match process_pdf_cached(db, &pipeline, &pdf_path, &image_store).await {
+            Ok(mut content) => {
+                content.metadata.cross_check_title(&paper.title);
+                write.content = Some(ContentWrite {
+                    content,
+                    summary: paper.abstract_text.clone(),
+                    key_points: None,
+                });
+                repaired += 1;
             }
-
-            info!("找到 {} 篇论文", papers.len());
-
-            for paper in papers.iter().take(3) {
-                info!("---");
-                info!("标题: {}", paper.title);
-                info!("作者: {}", paper.authors.join(", "));
-                info!("发布日期: {}", paper.published);
-                info!("PDF: {}", paper.pdf_url);
-
-                // 提取arXiv ID
-                let arxiv_id = paper.id.replace("http://arxiv.org/abs/", "");
-
-                // 检查是否已存在
-                if db.paper_exists("arxiv", &arxiv_id).await? {
-                    info!("论文已存在，跳过");
-                    continue;
+            Err(e) => match e.downcast_ref::<utils::BsxError>() {
+                Some(utils::BsxError::PdfError(reason)) => {
+                    info!("[{}] ⚠️ PDF无法打开，已记录到论文: {}", paper_id, reason);
+                    write.pdf_error = Some(reason.clone());
                 }
+                _ => info!("[{}] PDF解析失败: {}", paper_id, e),
+            },
+        }
+        storage::batch::commit(db, &[write], None).await?;
+    }
+    Ok(repaired)
+}
 
-                // 保存到数据库
-                let db_paper = storage::models::Paper {
-                    id: None,
-                    title: paper.title.clone(),
-                    title_zh: None,
-                    authors: Some(paper.authors.join(", ")),
-                    abstract_text: Some(paper.summary.clone()),
-                    abstract_zh: None,
-                    publish_date: Some(paper.published.clone()),
-                    source: "arxiv".to_string(),
-                    source_id: arxiv_id.clone(),
-                    pdf_url: Some(paper.pdf_url.clone()),
-                    pdf_path: None,
-                    processed: false,
-                    created_at: None,
-                };
-
-                let paper_id = db.save_paper(&db_paper).await?;
-                info!("论文已保存到数据库，ID: {}", paper_id);
-
-                // 翻译标题和摘要
-                if translation_enabled {
-                    info!("正在翻译论文...");
-                    match translator.translate_paper(&paper.title, &paper.summary).await {
-                        Ok((title_zh, abstract_zh)) => {
-                            db.update_translation("arxiv", &arxiv_id, &title_zh, &abstract_zh).await?;
-                            info!("翻译完成: {}", title_zh);
-                        }
-                        Err(e) => {
-                            info!("翻译失败: {}，继续处理", e);
-                        }
-                    }
-                }
+/// `ids` 为空时选择没有本地PDF，或本地PDF缺失、为空、不完整的论文
+async fn redownload_command(ids: &[i64], dry_run: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
 
-                // 下载PDF
-                let pdf_filename = format!("data/papers/{}.pdf", arxiv_id.replace("/", "_"));
-                match crawler.download_pdf(&paper.pdf_url, &pdf_filename).await {
-                    Ok(_) => {
-                        // 更新PDF路径
-                        db.update_pdf_path("arxiv", &arxiv_id, &pdf_filename).await?;
-
-                        // 使用提取管道解析PDF
-                        let arxiv_id_safe = arxiv_id.replace("/", "_");
-                        let pipeline = parser::ExtractionPipeline::new();
-                        match pipeline.process(&pdf_filename, &arxiv_id_safe, "data/images") {
-                            Ok(content) => {
-                                info!("PDF解析完成:");
-                                if let Some(ref title) = content.metadata.title {
-                                    info!("  标题: {}", title);
-                                }
-                                if let Some(ref abs) = content.metadata.abstract_text {
-                                    let preview = if abs.len() > 100 { &abs[..100] } else { abs };
-                                    info!("  摘要: {}...", preview);
-                                }
-                                info!("  章节数: {}", content.sections.len());
-                                info!("  公式数: {}", content.formulas.len());
-                                info!("  图片数: {}", content.images.len());
-                                info!("  表格数: {}", content.tables.len());
-
-                                // 序列化存入数据库
-                                let formulas_json = serde_json::to_string(&content.formulas).unwrap_or_default();
-                                let images_json = serde_json::to_string(&content.images).unwrap_or_default();
-                                let tables_json = serde_json::to_string(&content.tables).unwrap_or_default();
-                                let sections_json = serde_json::to_string(&content.sections).unwrap_or_default();
-
-                                if let Err(e) = db.save_extracted_content(
-                                    paper_id,
-                                    &formulas_json,
-                                    &images_json,
-                                    &tables_json,
-                                    &sections_json,
-                                ).await {
-                                    info!("保存提取内容失败: {}", e);
-                                }
-
-                                // 标记论文已处理
-                                db.mark_paper_processed("arxiv", &arxiv_id).await?;
-                            }
-                            Err(e) => {
-                                info!("PDF解析失败: {}", e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        info!("PDF下载失败: {}", e);
-                    }
+    let papers = if ids.is_empty() {
+        let mut papers = db.get_papers_without_pdf().await?;
+        let report = storage::integrity::check_files(&db).await?;
+        for paper_id in report.redownload_ids() {
+            if let Some(issue) = report.issues.iter().find(|issue| issue.paper_id == paper_id) {
+                papers.push((paper_id, issue.title.clone()));
+            }
+        }
+        papers.sort_unstable_by_key(|(paper_id, _)| *paper_id);
+        papers
+    } else {
+        let mut papers = Vec::new();
+        for &paper_id in ids {
+            match db.get_paper_by_id(paper_id).await? {
+                Some(paper) if paper.pdf_url.as_deref().is_some_and(|url| !url.is_empty()) => {
+                    papers.push((paper_id, paper.title))
                 }
-
-                // 延迟避免请求过快
-                tokio::time::sleep(tokio::time::Duration::from_millis(
-                    app_config.crawler.request_delay_ms,
-                ))
-                .await;
+                Some(_) => info!("[{}] 没有PDF链接，跳过", paper_id),
+                None => info!("[{}] 论文不存在", paper_id),
             }
         }
+        papers
+    };
+    if papers.is_empty() {
+        info!("✅ 没有需要重新下载的PDF");
+        return Ok(());
     }
 
-    info!("✅ 爬取任务完成");
+    if dry_run {
+        println!("将重新下载 {} 篇论文的PDF:", papers.len());
+        for (paper_id, title) in &papers {
+            println!("  [{}] {}", paper_id, title);
+        }
+        println!("（--dry-run：未做任何修改）");
+        return Ok(());
+    }
+
+    info!("重新下载 {} 篇论文的PDF", papers.len());
+    let ids: Vec<i64> = papers.iter().map(|(paper_id, _)| *paper_id).collect();
+    let repaired = repair_paper_files(&app_config, &db, &ids, &[]).await?;
+    info!("✅ 已重新下载并提取 {}/{} 篇论文", repaired, ids.len());
     Ok(())
 }
 
-async fn translate_command(paper_id: Option<i64>) -> Result<()> {
-    info!("开始翻译任务...");
-
+/// `ids` 为空时按 `failed` 选择全部或提取失败的论文
+async fn reparse_command(ids: &[i64], failed: bool, dry_run: bool) -> Result<()> {
     let app_config = AppConfig::load()?;
-    let db = Database::new(&format!("sqlite:{}", app_config.storage.database_path)).await?;
-    let translator = Translator::new(app_config.translator.clone());
-
-    if !translator.is_configured() {
-        info!("❌ API key 未配置。请在 config/settings.toml 中设置 [translator] api_key");
-        return Ok(());
-    }
+    let db = Database::open(&app_config.storage).await?;
 
-    let papers = if let Some(_id) = paper_id {
-        // 获取所有论文，过滤指定ID
-        let all = db.get_all_papers().await?;
-        all.into_iter().filter(|p| p.id == Some(_id)).collect::<Vec<_>>()
+    let papers = if ids.is_empty() {
+        db.get_papers_with_pdf(failed).await?
     } else {
-        db.get_untranslated_papers().await?
+        let mut papers = Vec::new();
+        for &paper_id in ids {
+            match db.get_paper_by_id(paper_id).await? {
+                Some(paper) if paper.pdf_path.is_some() => papers.push((paper_id, paper.title)),
+                Some(_) => info!("[{}] 没有本地PDF，跳过（可用 doctor --files --repair 重新下载）", paper_id),
+                None => info!("[{}] 论文不存在", paper_id),
+            }
+        }
+        papers
     };
-
     if papers.is_empty() {
-        info!("没有需要翻译的论文");
+        info!("没有需要重新提取的论文");
         return Ok(());
     }
 
-    info!("找到 {} 篇待翻译论文", papers.len());
+    if dry_run {
+        println!("将重新提取 {} 篇论文:", papers.len());
+        for (paper_id, title) in &papers {
+            println!("  [{}] {}", paper_id, title);
+        }
+        println!("（--dry-run：未做任何修改）");
+        return Ok(());
+    }
 
-    let mut success_count = 0;
-    let mut fail_count = 0;
+    info!("重新提取 {} 篇论文（解析器版本 {}）", papers.len(), storage::provenance::VERSION);
+    let ids: Vec<i64> = papers.iter().map(|(paper_id, _)| *paper_id).collect();
+    let reparsed = repair_paper_files(&app_config, &db, &[], &ids).await?;
+    info!("✅ 已重新提取 {}/{} 篇论文", reparsed, ids.len());
+    Ok(())
+}
 
-    for paper in &papers {
-        let abstract_text = paper.abstract_text.as_deref().unwrap_or("");
-        if abstract_text.is_empty() {
-            info!("论文 [{}] {} 没有摘要，跳过", paper.source_id, paper.title);
-            continue;
+async fn reprocess_command(parser_older_than: &str, dry_run: bool) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let stale = storage::provenance::content_older_than(&db, parser_older_than).await?;
+    if stale.is_empty() {
+        info!("没有早于 {} 的解析结果", parser_older_than);
+        return Ok(());
+    }
+
+    if dry_run {
+        println!("解析结果早于 {} 的论文 {} 篇:", parser_older_than, stale.len());
+        for paper in &stale {
+            println!("  [{}] {}  {}", paper.id, paper.version.as_deref().unwrap_or("版本未知"), paper.title);
         }
+        println!("（--dry-run：未做任何修改）");
+        return Ok(());
+    }
 
-        info!("翻译: {}", paper.title);
-        match translator.translate_paper(&paper.title, abstract_text).await {
-            Ok((title_zh, abstract_zh)) => {
-                db.update_translation(&paper.source, &paper.source_id, &title_zh, &abstract_zh).await?;
-                info!("  ✅ {}", title_zh);
-                success_count += 1;
+    info!("重新提取 {} 篇论文（当前版本 {}）", stale.len(), storage::provenance::VERSION);
+    let ids: Vec<i64> = stale.iter().map(|paper| paper.id).collect();
+    let reprocessed = repair_paper_files(&app_config, &db, &[], &ids).await?;
+    info!("✅ 已重新提取 {}/{} 篇论文", reprocessed, ids.len());
+    Ok(())
+}
+
+async fn subscriptions_command(action: SubscriptionAction) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let keyword_config = KeywordConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    sync_subscriptions(&db, &keyword_config).await?;
+
+    match action {
+        SubscriptionAction::List => {
+            let subs = db.get_subscriptions().await?;
+            if subs.is_empty() {
+                info!("数据库中没有订阅");
             }
-            Err(e) => {
-                info!("  ❌ 翻译失败: {}", e);
-                fail_count += 1;
+            for sub in &subs {
+                let state = if sub.enabled { "启用" } else { "停用" };
+                println!("[{}] {} ({}): {}", sub.id, sub.name, state, sub.keywords().join(", "));
+            }
+        }
+        SubscriptionAction::Show { name } => {
+            let sub = db
+                .get_subscription_by_name(&name)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("订阅不存在: {}", name))?;
+            let list = |json: Option<&str>| -> String {
+                let values: Vec<String> = json.and_then(|s| serde_json::from_str(s).ok()).unwrap_or_default();
+                if values.is_empty() { "-".to_string() } else { values.join(", ") }
+            };
+            println!("[{}] {}", sub.id, sub.name);
+            println!("状态: {}", if sub.enabled { "启用" } else { "停用（已从 keywords.toml 删除）" });
+            if !sub.description.is_empty() {
+                println!("描述: {}", sub.description);
+            }
+            println!("关键词: {}", sub.keywords().join(", "));
+            println!("来源: {}", list(Some(&sub.sources)));
+            println!("分类: {}", list(sub.categories.as_deref()));
+            println!("更新时间: {}", sub.updated_at.as_deref().unwrap_or("-"));
+        }
+        SubscriptionAction::Remove { name } => {
+            if keyword_config.subscriptions.iter().any(|s| s.name == name) {
+                anyhow::bail!("订阅 {} 仍在 keywords.toml 中，请先从配置中删除", name);
+            }
+            if !db.delete_subscription(&name).await? {
+                anyhow::bail!("订阅不存在: {}", name);
+            }
+            info!("✅ 已删除订阅: {}", name);
+        }
+    }
+    Ok(())
+}
+
+async fn db_command(action: DbAction) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    match action {
+        DbAction::Export { output } => {
+            let dump = storage::dump::export(&db).await?;
+            storage::dump::write(&dump, &output)?;
+            info!(
+                "✅ 已导出 {} 篇论文、{} 个订阅: {}",
+                dump.papers.len(),
+                dump.subscriptions.len(),
+                output
+            );
+        }
+        DbAction::Import { input } => {
+            let dump = storage::dump::read(&input)?;
+            info!("导入 {}（导出于 {}，共 {} 篇论文）", input, dump.exported_at, dump.papers.len());
+            storage::dump::import(&db, &dump).await?;
+        }
+        DbAction::Backup { keep } => {
+            let dir = app_config.storage.backup_dir();
+            let path = storage::backup::create(&db, &dir).await?;
+            info!("✅ 数据库已备份: {}", path.display());
+            let removed = storage::backup::rotate(&dir, keep.unwrap_or(app_config.storage.backup.keep))?;
+            if !removed.is_empty() {
+                info!("已删除 {} 个旧备份", removed.len());
             }
         }
     }
+    Ok(())
+}
 
-    info!("✅ 翻译完成: {} 成功, {} 失败", success_count, fail_count);
+async fn state_command(action: StateAction) -> Result<()> {
+    match action {
+        StateAction::Export { output, include_pdfs } => {
+            let output = output.unwrap_or_else(|| {
+                format!("bsxbot_state_{}.tar.gz", chrono::Local::now().format("%Y%m%d_%H%M%S"))
+            });
+            info!("导出应用状态: {}", output);
+            let manifest = storage::state::export_state(&output, include_pdfs).await?;
+            info!("✅ 状态已导出: {} (PDF: {} 个)", output, manifest.pdf_count);
+        }
+        StateAction::Import { archive, force } => {
+            info!("导入应用状态: {}", archive);
+            let manifest = storage::state::import_state(&archive, force)?;
+            info!(
+                "✅ 状态已导入 (导出于 {}, 版本 {}, PDF: {} 个)",
+                manifest.created_at, manifest.version, manifest.pdf_count
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn relocate_data_command(from: Option<std::path::PathBuf>) -> Result<()> {
+    info!("重写数据目录引用: {}", config::dirs::get().data_dir.display());
+    let summary = storage::images::relocate_data(from.as_deref()).await?;
+    if summary.images_missing > 0 || summary.pdfs_missing > 0 {
+        info!("⚠️ 部分文件未找到，可通过 --from 指定原数据目录后重试");
+    }
+    info!("✅ 引用重写完成");
     Ok(())
 }
 
@@ -345,9 +3539,11 @@ async fn clean_command() -> Result<()> {
 
     let mut total_files = 0u64;
 
-    // 清理 data/ 下的三个子目录
-    for dir in &["data/papers", "data/images", "data/reports"] {
-        match tokio::fs::read_dir(dir).await {
+    let dirs = config::dirs::get();
+
+    // 清理数据目录下的各子目录
+    for dir in [dirs.papers_dir(), dirs.images_dir(), dirs.tables_dir(), dirs.reports_dir()] {
+        match tokio::fs::read_dir(&dir).await {
             Ok(mut entries) => {
                 let mut count = 0u64;
                 while let Some(entry) = entries.next_entry().await? {
@@ -360,11 +3556,11 @@ async fn clean_command() -> Result<()> {
                         }
                     }
                 }
-                info!("已清理 {}: {} 个文件", dir, count);
+                info!("已清理 {}: {} 个文件", dir.display(), count);
                 total_files += count;
             }
             Err(_) => {
-                info!("目录不存在，跳过: {}", dir);
+                info!("目录不存在，跳过: {}", dir.display());
             }
         }
     }
@@ -373,8 +3569,7 @@ async fn clean_command() -> Result<()> {
     let app_config = AppConfig::load();
     match app_config {
         Ok(config) => {
-            let db_url = format!("sqlite:{}", config.storage.database_path);
-            match Database::new(&db_url).await {
+            match Database::open(&config.storage).await {
                 Ok(db) => {
                     db.clear_all_tables().await?;
                 }
@@ -395,16 +3590,16 @@ async fn clean_command() -> Result<()> {
 async fn schedule_command() -> Result<()> {
     info!("启动定时任务调度器...");
 
-    let scheduler = utils::scheduler::TaskScheduler::new().await?;
+    let app_config = AppConfig::load()?;
+    let notifier = utils::notifier::Notifier::new(app_config.notifier.clone());
+    if !notifier.is_enabled() {
+        info!("未配置通知渠道 [notifier]，定时任务失败时不会发送告警");
+    }
+    let scheduler = utils::scheduler::TaskScheduler::new(notifier).await?;
 
     // 添加每日任务（每天早上8点执行）
-    let job_fn = std::sync::Arc::new(|| {
-        info!("执行每日爬取任务");
-        // TODO: 调用爬取逻辑
-    });
-
     scheduler
-        .add_daily_job("0 0 8 * * *", job_fn)
+        .add_daily_job("每日流程", "0 0 8 * * *", || run_pipeline(None, PipelineStages::ALL))
         .await?;
 
     scheduler.start().await?;
@@ -419,7 +3614,121 @@ async fn schedule_command() -> Result<()> {
     Ok(())
 }
 
-async fn report_command(date: Option<String>) -> Result<()> {
+/// `run` 要执行的阶段
+#[derive(Clone, Copy)]
+struct PipelineStages {
+    crawl: bool,
+    translate: bool,
+    parse: bool,
+    report: bool,
+}
+
+impl PipelineStages {
+    const ALL: Self = Self { crawl: true, translate: true, parse: true, report: true };
+}
+
+/// 爬取 → 补充翻译 → 补充解析 → 生成报告
+///
+/// 某一阶段失败时继续执行后续阶段，最后汇总各阶段的结果；有阶段失败，或翻译全部失败（多为 API key 失效）时返回错误，
+/// 定时任务据此告警。
+async fn run_pipeline(subscription: Option<String>, stages: PipelineStages) -> Result<()> {
+    info!("开始执行流程...");
+    let mut summary: Vec<(&str, Result<String>)> = Vec::new();
+    let mut all_translations_failed = false;
+
+    if stages.crawl {
+        warn_config_issues().await;
+        let result = run_crawl(subscription, None).await.map(|crawl| {
+            all_translations_failed |=
+                crawl.translation_attempts > 0 && crawl.translation_failures == crawl.translation_attempts;
+            format!(
+                "新入库 {} 篇，翻译 {} 篇（失败 {} 篇）",
+                crawl.saved, crawl.translation_attempts, crawl.translation_failures
+            )
+        });
+        summary.push(("爬取", result));
+    }
+    if stages.translate {
+        let result = translate_command(None, false, false, false).await.map(|translated| {
+            all_translations_failed |= translated.failed > 0 && translated.succeeded == 0;
+            format!("补充翻译 {} 篇，失败 {} 篇", translated.succeeded, translated.failed)
+        });
+        summary.push(("翻译", result));
+    }
+    if stages.parse {
+        summary.push(("解析", parse_pending().await));
+    }
+    if stages.report {
+        let options = ReportOptions {
+            date: None,
+            min_confidence: None,
+            format: ReportFormat::Html,
+            send: false,
+            group_by: Some(ReportGroupBy::Subscription),
+            sort_by: ReportSortBy::Relevance,
+            lang: ReportLang::Bilingual,
+            tags: Vec::new(),
+        };
+        let result = report_command(options).await.map(|output| match output {
+            Some(output) => format!("{}（{} 篇）", output.path, output.papers),
+            None => "没有可收录的论文，未生成".to_string(),
+        });
+        summary.push(("报告", result));
+    }
+
+    info!("流程结束:");
+    let mut failed = Vec::new();
+    for (stage, result) in &summary {
+        match result {
+            Ok(detail) => info!("  ✅ {}: {}", stage, detail),
+            Err(e) => {
+                info!("  ❌ {}: {:#}", stage, e);
+                failed.push(*stage);
+            }
+        }
+    }
+    if !failed.is_empty() {
+        anyhow::bail!("{} 阶段失败", failed.join("、"));
+    }
+    if all_translations_failed {
+        anyhow::bail!("本次翻译全部失败，请检查翻译服务配置和 API key");
+    }
+    Ok(())
+}
+
+/// 重新提取有本地PDF但没有提取内容或PDF无法打开的论文
+async fn parse_pending() -> Result<String> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+    let ids: Vec<i64> = db.get_papers_with_pdf(true).await?.into_iter().map(|(paper_id, _)| paper_id).collect();
+    if ids.is_empty() {
+        return Ok("没有待解析的论文".to_string());
+    }
+    let parsed = repair_paper_files(&app_config, &db, &[], &ids).await?;
+    Ok(format!("补充解析 {}/{} 篇", parsed, ids.len()))
+}
+
+/// `report` 命令的参数
+struct ReportOptions {
+    date: Option<String>,
+    min_confidence: Option<f32>,
+    format: ReportFormat,
+    send: bool,
+    group_by: Option<ReportGroupBy>,
+    sort_by: ReportSortBy,
+    lang: ReportLang,
+    tags: Vec<String>,
+}
+
+/// 生成的报告文件和收录的论文数
+struct ReportOutput {
+    path: String,
+    papers: usize,
+}
+
+/// 没有可收录的PDF时不生成报告，返回 None
+async fn report_command(options: ReportOptions) -> Result<Option<ReportOutput>> {
+    let ReportOptions { date, min_confidence, format, send, group_by, sort_by, lang, tags } = options;
     let report_date = date.unwrap_or_else(|| {
         chrono::Local::now().format("%Y-%m-%d").to_string()
     });
@@ -427,14 +3736,154 @@ async fn report_command(date: Option<String>) -> Result<()> {
     info!("生成报告: {}", report_date);
 
     let app_config = AppConfig::load()?;
-    let db = Database::new(&format!("sqlite:{}", app_config.storage.database_path)).await?;
+    let db = Database::open(&app_config.storage).await?;
+    sync_subscriptions(&db, &KeywordConfig::load()?).await?;
+
+    // 最近一次爬取中匹配但未获取的论文数
+    let crawl_runs: Vec<storage::models::CrawlRun> = db
+        .get_latest_crawl_runs()
+        .await?
+        .into_iter()
+        .filter(|run| run.not_retrieved() > 0)
+        .collect();
+
+    // 从数据库获取已下载论文的翻译等信息；按标签筛选时只取带有任一标签的论文
+    let db_papers = db
+        .query_papers(
+            &PaperFilter { downloaded: Some(true), tags: tags.clone(), ..Default::default() },
+            PaperSort::Id,
+            None,
+            0,
+        )
+        .await?;
+
+    // 章节取舍：全局配置叠加单篇论文的设置
+    let section_filter = generator::SectionFilter::new(
+        &app_config.generator.pinned_sections,
+        &app_config.generator.hidden_sections,
+    );
+    let mut paper_prefs: std::collections::HashMap<i64, (Vec<String>, Vec<String>)> =
+        std::collections::HashMap::new();
+    for pref in db.get_section_prefs().await? {
+        let entry = paper_prefs.entry(pref.paper_id).or_default();
+        if pref.action == "pin" {
+            entry.0.push(pref.pattern);
+        } else {
+            entry.1.push(pref.pattern);
+        }
+    }
+    let paper_filters: std::collections::HashMap<String, generator::SectionFilter> = db_papers
+        .iter()
+        .filter_map(|p| {
+            let (pinned, hidden) = paper_prefs.get(&p.id?)?;
+            Some((
+                utils::paths::safe_filename(&p.source_id),
+                section_filter.with_paper_prefs(pinned, hidden),
+            ))
+        })
+        .collect();
+
+    // 按 LLM 相关度标签过滤，未打标签的论文始终收录
+    let min_relevance = match app_config.generator.min_relevance.trim() {
+        "" => None,
+        value => match crawler::relevance::RelevanceLabel::parse(value) {
+            Some(label) => Some(label),
+            None => {
+                info!("⚠️ 无效的 min_relevance '{}'，不按相关度过滤", value);
+                None
+            }
+        },
+    };
+    let excluded: std::collections::HashSet<String> = match min_relevance {
+        Some(min) => db
+            .get_relevance_labels()
+            .await?
+            .into_iter()
+            .filter(|(_, label)| {
+                crawler::relevance::RelevanceLabel::parse(label)
+                    .map(|l| l < min)
+                    .unwrap_or(false)
+            })
+            .map(|(source_id, _)| utils::paths::safe_filename(&source_id))
+            .collect(),
+        None => std::collections::HashSet::new(),
+    };
+
+    let relevance_scores: std::collections::HashMap<String, f32> = db
+        .get_relevance_scores()
+        .await?
+        .into_iter()
+        .map(|(source_id, score)| (utils::paths::safe_filename(&source_id), score))
+        .collect();
+    let mut relevance_reasons: std::collections::HashMap<String, Vec<String>> = db
+        .get_relevance_reasons()
+        .await?
+        .into_iter()
+        .map(|(source_id, reasons)| (utils::paths::safe_filename(&source_id), reasons))
+        .collect();
+    let min_relevance_score = app_config.generator.min_relevance_score;
+    let read_ids: std::collections::HashSet<String> =
+        db.get_read_source_ids().await?.iter().map(|id| utils::paths::safe_filename(id)).collect();
+    let starred_ids: std::collections::HashSet<String> =
+        db.get_starred_source_ids().await?.iter().map(|id| utils::paths::safe_filename(id)).collect();
+
+    // 按标签筛选时只保留带有任一标签的论文
+    let tagged: Option<std::collections::HashSet<String>> = if tags.is_empty() {
+        None
+    } else {
+        Some(db_papers.iter().map(|p| utils::paths::safe_filename(&p.source_id)).collect())
+    };
+
+    let key_points: std::collections::HashMap<String, Vec<String>> = db
+        .get_key_points()
+        .await?
+        .into_iter()
+        .map(|(source_id, points)| (utils::paths::safe_filename(&source_id), points))
+        .collect();
+
+    let code_urls: std::collections::HashMap<String, Vec<String>> = db_papers
+        .iter()
+        .map(|p| (utils::paths::safe_filename(&p.source_id), p.code_urls()))
+        .filter(|(_, urls)| !urls.is_empty())
+        .collect();
+
+    // 爬取时的标题，用于核对PDF中提取的标题
+    let crawled_titles: std::collections::HashMap<String, String> = db_papers
+        .iter()
+        .map(|p| (utils::paths::safe_filename(&p.source_id), p.title.clone()))
+        .collect();
+
+    let crawled_authors: std::collections::HashMap<String, Vec<String>> = db_papers
+        .iter()
+        .filter_map(|p| {
+            let authors: Vec<String> = p
+                .authors
+                .as_deref()?
+                .split(',')
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect();
+            (!authors.is_empty()).then(|| (utils::paths::safe_filename(&p.source_id), authors))
+        })
+        .collect();
+
+    // 分组和排序用到的来源、发布日期和订阅
+    let mut subscriptions = db.get_paper_subscriptions().await?;
+    let paper_info: std::collections::HashMap<String, (String, Option<String>, Option<String>)> = db_papers
+        .iter()
+        .map(|p| {
+            let subscription = p.id.and_then(|id| subscriptions.remove(&id));
+            (
+                utils::paths::safe_filename(&p.source_id),
+                (p.source.clone(), p.publish_date.clone(), subscription),
+            )
+        })
+        .collect();
 
-    // 从数据库获取论文翻译信息
-    let db_papers = db.get_all_papers().await?;
     let translations: std::collections::HashMap<String, (Option<String>, Option<String>)> = db_papers
         .into_iter()
         .filter_map(|p| {
-            let key = p.source_id.replace("/", "_");
+            let key = utils::paths::safe_filename(&p.source_id);
             if p.title_zh.is_some() || p.abstract_zh.is_some() {
                 Some((key, (p.title_zh, p.abstract_zh)))
             } else {
@@ -443,9 +3892,13 @@ async fn report_command(date: Option<String>) -> Result<()> {
         })
         .collect();
 
-    // Scan all PDFs in data/papers/
+    // Scan all PDFs in the papers directory
+    let dirs = config::dirs::get();
+    let papers_dir = dirs.papers_dir();
+    let image_store = std::sync::Arc::new(storage::images::LocalImageStore::new(dirs.images_dir()));
     let mut pdf_files: Vec<String> = Vec::new();
-    let mut entries = tokio::fs::read_dir("data/papers").await?;
+    tokio::fs::create_dir_all(&papers_dir).await?;
+    let mut entries = tokio::fs::read_dir(&papers_dir).await?;
     while let Some(entry) = entries.next_entry().await? {
         let path = entry.path();
         if path.extension().map(|e| e == "pdf").unwrap_or(false) {
@@ -454,30 +3907,90 @@ async fn report_command(date: Option<String>) -> Result<()> {
     }
 
     if pdf_files.is_empty() {
-        info!("data/papers/ 中没有PDF文件，请先运行 crawl");
-        return Ok(());
+        info!("{} 中没有PDF文件，请先运行 crawl", papers_dir.display());
+        return Ok(None);
     }
 
     pdf_files.sort();
     info!("找到 {} 个PDF文件", pdf_files.len());
 
-    let pipeline = parser::ExtractionPipeline::new();
-    let mut all_contents: Vec<(String, parser::PaperContent)> = Vec::new();
+    let pdf_files: Vec<String> = pdf_files
+        .into_iter()
+        .filter(|pdf_path| {
+            let paper_id = paper_id_from_path(pdf_path);
+            if excluded.contains(&paper_id) {
+                info!("跳过相关度低于 {} 的论文: {}", app_config.generator.min_relevance, paper_id);
+                return false;
+            }
+            if tagged.as_ref().is_some_and(|tagged| !tagged.contains(&paper_id)) {
+                return false;
+            }
+            if let Some(score) = relevance_scores.get(&paper_id).filter(|s| **s < min_relevance_score) {
+                info!("跳过相关度 {:.2} 低于 {} 的论文: {}", score, min_relevance_score, paper_id);
+                return false;
+            }
+            true
+        })
+        .collect();
 
-    for pdf_path in &pdf_files {
-        let paper_id = std::path::Path::new(pdf_path)
-            .file_stem()
-            .map(|s| s.to_string_lossy().to_string())
-            .unwrap_or_default();
+    let min_confidence = min_confidence.unwrap_or(app_config.generator.min_confidence);
+    if min_confidence > 0.0 {
+        info!("隐藏可信度低于 {} 的章节、公式和表格", min_confidence);
+    }
+    // 先加载模板，模板有语法错误时不必等待PDF处理完成
+    let english = lang == ReportLang::En;
+    let html_generator = generator::HtmlGenerator::new(dirs, &app_config.generator)?.english(english);
+    let pipeline = std::sync::Arc::new(parser::ExtractionPipeline::new(&app_config.parser));
+    let results = process_pdfs_parallel(
+        &db,
+        pipeline,
+        &pdf_files,
+        image_store.clone(),
+        app_config.parser.parallelism(),
+    )
+    .await;
+    let mut all_contents: Vec<(String, parser::PaperContent)> = Vec::new();
 
-        info!("处理: {}", paper_id);
-        match pipeline.process(pdf_path, &paper_id, "data/images") {
+    for (pdf_path, result) in results {
+        let paper_id = paper_id_from_path(&pdf_path);
+        match result {
             Ok(mut content) => {
+                if let Some(title) = crawled_titles.get(&paper_id) {
+                    content.metadata.cross_check_title(title);
+                }
+                if content.metadata.authors.is_empty() {
+                    if let Some(authors) = crawled_authors.get(&paper_id) {
+                        content.metadata.authors = authors.clone();
+                    }
+                }
                 // 注入数据库中的翻译
                 if let Some((title_zh, abstract_zh)) = translations.get(&paper_id) {
                     content.metadata.title_zh = title_zh.clone();
                     content.metadata.abstract_zh = abstract_zh.clone();
                 }
+                if let Some(points) = key_points.get(&paper_id) {
+                    content.metadata.key_points = points.clone();
+                }
+                content.metadata.relevance = relevance_scores.get(&paper_id).copied();
+                content.metadata.relevance_reasons = relevance_reasons.remove(&paper_id).unwrap_or_default();
+                content.metadata.read = read_ids.contains(&paper_id);
+                content.metadata.starred = starred_ids.contains(&paper_id);
+                if let Some((source, publish_date, subscription)) = paper_info.get(&paper_id) {
+                    content.metadata.source = Some(source.clone());
+                    content.metadata.publish_date = publish_date.clone();
+                    content.metadata.subscription = subscription.clone();
+                }
+                // 数据库中的链接包含 arXiv 摘要里的代码仓库
+                if let Some(urls) = code_urls.get(&paper_id) {
+                    for url in urls {
+                        if !content.metadata.code_urls.iter().any(|u| u.eq_ignore_ascii_case(url)) {
+                            content.metadata.code_urls.push(url.clone());
+                        }
+                    }
+                }
+                content.retain_confident(min_confidence);
+                let filter = paper_filters.get(&paper_id).unwrap_or(&section_filter);
+                content.sections = filter.apply(&content.sections).into_iter().cloned().collect();
                 all_contents.push((paper_id, content));
             }
             Err(e) => {
@@ -486,238 +3999,212 @@ async fn report_command(date: Option<String>) -> Result<()> {
         }
     }
 
-    // Generate HTML
-    let html = generate_html_report(&report_date, &all_contents);
-    let output_path = format!("data/reports/report_{}.html", report_date);
-    tokio::fs::create_dir_all("data/reports").await?;
-    tokio::fs::write(&output_path, html).await?;
+    sort_report_papers(&mut all_contents, sort_by);
+    // 分组时先按组排列，组内保持上面的顺序
+    // 日期分组新的在前，其余按名称排列，缺少信息的论文归入最后的「未知」组
+    let group_labels: Option<Vec<String>> = group_by.map(|group_by| {
+        all_contents.sort_by(|(_, a), (_, b)| match (report_group(group_by, a), report_group(group_by, b)) {
+            (Some(x), Some(y)) if group_by == ReportGroupBy::Date => y.cmp(&x),
+            (Some(x), Some(y)) => x.to_lowercase().cmp(&y.to_lowercase()),
+            (x, y) => x.is_none().cmp(&y.is_none()),
+        });
+        all_contents
+            .iter()
+            .map(|(_, content)| report_group(group_by, content).unwrap_or_else(|| "未知".to_string()))
+            .collect()
+    });
+
+    let reports_dir = dirs.reports_dir();
+    tokio::fs::create_dir_all(&reports_dir).await?;
+    let extension = match format {
+        ReportFormat::Html => "html",
+        ReportFormat::Pptx => "pptx",
+    };
+    // 英文报告另存一份，不覆盖中英对照的报告
+    let suffix = if english { "_en" } else { "" };
+    let output_path = reports_dir
+        .join(format!("report_{}{}.{}", utils::paths::safe_filename(&report_date), suffix, extension))
+        .to_string_lossy()
+        .to_string();
+    match format {
+        ReportFormat::Html => {
+            let language = translator::TargetLanguage::new(&app_config.translator.target_language);
+            let translation_label = format!("{}翻译", language.name());
+            let html = html_generator.generate(
+                &report_date,
+                &all_contents,
+                group_labels.as_deref(),
+                &crawl_runs,
+                &translation_label,
+            )?;
+            tokio::fs::write(&output_path, html).await?;
+        }
+        ReportFormat::Pptx => {
+            let ppt = generator::PptGenerator::new(&app_config.generator).english(english);
+            ppt.generate(&report_date, &all_contents, image_store.as_ref(), std::path::Path::new(&output_path))?;
+        }
+    }
 
     info!("✅ 报告已生成: {}", output_path);
-    Ok(())
-}
 
-fn generate_html_report(date: &str, papers: &[(String, parser::PaperContent)]) -> String {
-    let mut html = format!(r#"<!DOCTYPE html>
-<html lang="zh-CN">
-<head>
-<meta charset="UTF-8">
-<meta name="viewport" content="width=device-width, initial-scale=1.0">
-<title>科研论文提取报告 - {date}</title>
-<style>
-* {{ margin: 0; padding: 0; box-sizing: border-box; }}
-body {{ font-family: -apple-system, "Segoe UI", Roboto, "Noto Sans SC", sans-serif; background: #f5f5f5; color: #333; line-height: 1.6; }}
-.container {{ max-width: 1100px; margin: 0 auto; padding: 20px; }}
-header {{ background: linear-gradient(135deg, #1a237e 0%, #283593 100%); color: white; padding: 40px 30px; border-radius: 12px; margin-bottom: 30px; }}
-header h1 {{ font-size: 28px; margin-bottom: 8px; }}
-header .meta {{ opacity: 0.85; font-size: 14px; }}
-.paper {{ background: white; border-radius: 12px; padding: 30px; margin-bottom: 24px; box-shadow: 0 2px 8px rgba(0,0,0,0.08); }}
-.paper-title {{ font-size: 22px; color: #1a237e; margin-bottom: 8px; padding-bottom: 12px; border-bottom: 2px solid #e8eaf6; }}
-.paper-title-zh {{ font-size: 18px; color: #37474f; margin-bottom: 16px; }}
-.paper-id {{ font-size: 13px; color: #888; font-weight: normal; }}
-.stats {{ display: flex; gap: 16px; margin-bottom: 20px; flex-wrap: wrap; }}
-.stat {{ background: #f5f5f5; padding: 8px 16px; border-radius: 8px; font-size: 14px; }}
-.stat b {{ color: #1a237e; }}
-h3 {{ font-size: 17px; color: #283593; margin: 24px 0 12px 0; padding-left: 12px; border-left: 4px solid #5c6bc0; }}
-.section {{ background: #fafafa; border-radius: 8px; padding: 16px; margin-bottom: 12px; }}
-.section-heading {{ font-weight: 600; color: #37474f; margin-bottom: 6px; }}
-.section-body {{ font-size: 14px; color: #555; white-space: pre-wrap; word-break: break-word; max-height: 300px; overflow-y: auto; }}
-.translation {{ background: #e8f5e9; border-left: 3px solid #4caf50; padding: 12px 16px; margin-top: 8px; border-radius: 0 8px 8px 0; font-size: 14px; color: #2e7d32; }}
-.translation-label {{ font-size: 12px; color: #66bb6a; margin-bottom: 4px; font-weight: 600; }}
-.formula-list {{ list-style: none; }}
-.formula-item {{ background: #fff8e1; border-left: 3px solid #ffc107; padding: 10px 14px; margin-bottom: 8px; border-radius: 0 6px 6px 0; font-family: "Cambria Math", "Latin Modern Math", Georgia, serif; font-size: 15px; word-break: break-all; }}
-.formula-context {{ font-size: 12px; color: #888; margin-top: 4px; font-family: sans-serif; }}
-.images-grid {{ display: grid; grid-template-columns: repeat(auto-fill, minmax(280px, 1fr)); gap: 16px; }}
-.image-card {{ background: #f5f5f5; border-radius: 8px; overflow: hidden; }}
-.image-card img {{ width: 100%; height: auto; display: block; }}
-.image-card .caption {{ padding: 8px 12px; font-size: 12px; color: #666; }}
-table.data-table {{ width: 100%; border-collapse: collapse; margin-bottom: 12px; font-size: 14px; }}
-table.data-table th {{ background: #e8eaf6; padding: 8px 12px; text-align: left; border: 1px solid #c5cae9; }}
-table.data-table td {{ padding: 8px 12px; border: 1px solid #e0e0e0; }}
-table.data-table tr:nth-child(even) {{ background: #fafafa; }}
-.table-caption {{ font-size: 13px; color: #666; margin-bottom: 6px; font-style: italic; }}
-.empty {{ color: #999; font-style: italic; padding: 12px; }}
-</style>
-</head>
-<body>
-<div class="container">
-<header>
-  <h1>科研论文提取报告</h1>
-  <div class="meta">日期: {date} &nbsp;|&nbsp; 论文数: {count}</div>
-</header>
-"#, date = date, count = papers.len());
-
-    for (paper_id, content) in papers {
-        let title = content.metadata.title.as_deref().unwrap_or("(未提取到标题)");
-
-        html.push_str(&format!(r#"<div class="paper">
-<div class="paper-title">{title} <span class="paper-id">[{paper_id}]</span></div>
-"#,
-            title = html_escape(title),
-            paper_id = html_escape(paper_id),
-        ));
-
-        // 中文标题
-        if let Some(ref title_zh) = content.metadata.title_zh {
-            if !title_zh.is_empty() {
-                html.push_str(&format!(
-                    r#"<div class="paper-title-zh">{}</div>"#,
-                    html_escape(title_zh)
-                ));
-                html.push('\n');
-            }
-        }
-
-        html.push_str(&format!(r#"<div class="stats">
-  <div class="stat"><b>{sections}</b> 章节</div>
-  <div class="stat"><b>{formulas}</b> 公式</div>
-  <div class="stat"><b>{images}</b> 图片</div>
-  <div class="stat"><b>{tables}</b> 表格</div>
-</div>
-"#,
-            sections = content.sections.len(),
-            formulas = content.formulas.len(),
-            images = content.images.len(),
-            tables = content.tables.len(),
-        ));
-
-        // Abstract
-        if let Some(ref abs) = content.metadata.abstract_text {
-            if !abs.is_empty() {
-                html.push_str("<h3>摘要</h3>\n");
-                html.push_str(&format!(r#"<div class="section"><div class="section-body">{}</div></div>"#,
-                    html_escape(abs)));
-                html.push('\n');
-
-                // 中文摘要
-                if let Some(ref abs_zh) = content.metadata.abstract_zh {
-                    if !abs_zh.is_empty() {
-                        html.push_str(&format!(
-                            r#"<div class="translation"><div class="translation-label">中文翻译</div>{}</div>"#,
-                            html_escape(abs_zh)
-                        ));
-                        html.push('\n');
-                    }
-                }
+    let mut subscriptions: Vec<&str> =
+        all_contents.iter().filter_map(|(_, c)| c.metadata.subscription.as_deref()).collect();
+    subscriptions.sort_unstable();
+    subscriptions.dedup();
+    let value_name = |value: Option<clap::builder::PossibleValue>| value.map(|v| v.get_name().to_string());
+    let report_id = db
+        .record_report(&storage::models::ReportRecord {
+            id: None,
+            report_date: report_date.clone(),
+            paper_count: Some(all_contents.len() as i64),
+            output_path: Some(output_path.clone()),
+            format: Some(extension.to_string()),
+            subscriptions: (!subscriptions.is_empty()).then(|| subscriptions.join(", ")),
+            group_by: value_name(group_by.and_then(|g| g.to_possible_value())),
+            sort_by: value_name(sort_by.to_possible_value()),
+            lang: value_name(lang.to_possible_value()),
+            tags: (!tags.is_empty()).then(|| tags.join(", ")),
+            status: Some("generated".to_string()),
+            created_at: None,
+        })
+        .await?;
+
+    let publisher = generator::Publisher::new(app_config.publisher.clone());
+    if publisher.is_enabled() {
+        // PPT 已内嵌配图，无需另外打包
+        let images: Vec<std::path::PathBuf> = match format {
+            ReportFormat::Html => all_contents
+                .iter()
+                .flat_map(|(_, content)| content.images.iter().map(|img| image_store.path(&img.filename)))
+                .collect(),
+            ReportFormat::Pptx => Vec::new(),
+        };
+        if let Err(e) = publisher.publish(&report_date, std::path::Path::new(&output_path), &images).await {
+            info!("❌ 报告发布失败: {}", e);
+            db.update_report_status(report_id, "publish_failed").await?;
+        }
+    }
+
+    let email = &app_config.notify.email;
+    if send || email.enabled {
+        let mailer = generator::DigestMailer::new(email.with_fallback(&app_config.notifier));
+        match mailer.send(&report_date, std::path::Path::new(&output_path), all_contents.len()).await {
+            Ok(()) => db.update_report_status(report_id, "sent").await?,
+            Err(e) => {
+                info!("❌ 报告邮件发送失败: {:#}", e);
+                db.update_report_status(report_id, "send_failed").await?;
             }
         }
+    }
 
-        // Sections
-        if !content.sections.is_empty() {
-            html.push_str("<h3>章节内容</h3>\n");
-            for section in &content.sections {
-                let body_preview = if section.body.len() > 800 {
-                    format!("{}...", &section.body[..section.body.floor_char_boundary(800)])
-                } else {
-                    section.body.clone()
-                };
-                html.push_str(&format!(
-                    r#"<div class="section"><div class="section-heading">{heading}</div><div class="section-body">{body}</div></div>"#,
-                    heading = html_escape(&section.heading),
-                    body = html_escape(&body_preview),
-                ));
-                html.push('\n');
-            }
-        }
-
-        // Formulas
-        if !content.formulas.is_empty() {
-            html.push_str(&format!("<h3>公式 ({})</h3>\n", content.formulas.len()));
-            html.push_str(r#"<ul class="formula-list">"#);
-            let max_show = 30;
-            for (i, formula) in content.formulas.iter().enumerate() {
-                if i >= max_show {
-                    html.push_str(&format!(
-                        r#"<li class="formula-item" style="background:#f5f5f5">... 还有 {} 个公式未显示</li>"#,
-                        content.formulas.len() - max_show));
-                    break;
-                }
-                let raw_display = if formula.raw.len() > 200 {
-                    format!("{}...", &formula.raw[..formula.raw.floor_char_boundary(200)])
-                } else {
-                    formula.raw.clone()
-                };
-                html.push_str(&format!(
-                    r#"<li class="formula-item">{raw}<div class="formula-context">...{ctx}...</div></li>"#,
-                    raw = html_escape(&raw_display),
-                    ctx = html_escape(&formula.context[..formula.context.len().min(120)]),
-                ));
-                html.push('\n');
-            }
-            html.push_str("</ul>\n");
-        }
-
-        // Images
-        if !content.images.is_empty() {
-            html.push_str(&format!("<h3>图片 ({})</h3>\n", content.images.len()));
-            html.push_str(r#"<div class="images-grid">"#);
-            let max_images = 20;
-            for (i, img) in content.images.iter().enumerate() {
-                if i >= max_images {
-                    html.push_str(&format!(
-                        r#"<div class="image-card"><div class="caption">... 还有 {} 张图片未显示</div></div>"#,
-                        content.images.len() - max_images));
-                    break;
-                }
-                // Convert path to relative from report location
-                let img_path = img.filename.replace('\\', "/");
-                // Report is at data/reports/, images at data/images/
-                let relative_path = if img_path.starts_with("data/") {
-                    format!("../{}", &img_path[5..])
-                } else {
-                    img_path.clone()
-                };
-                html.push_str(&format!(
-                    r#"<div class="image-card"><img src="{src}" alt="page {page}" loading="lazy"><div class="caption">Page {page} &nbsp; {w}x{h} &nbsp; {fmt}</div></div>"#,
-                    src = html_escape(&relative_path),
-                    page = img.page,
-                    w = img.width,
-                    h = img.height,
-                    fmt = img.format,
-                ));
-                html.push('\n');
-            }
-            html.push_str("</div>\n");
-        }
-
-        // Tables
-        if !content.tables.is_empty() {
-            html.push_str(&format!("<h3>表格 ({})</h3>\n", content.tables.len()));
-            for table in &content.tables {
-                if let Some(ref caption) = table.caption {
-                    html.push_str(&format!(r#"<div class="table-caption">{}</div>"#, html_escape(caption)));
-                }
-                html.push_str(r#"<table class="data-table"><thead><tr>"#);
-                for h in &table.headers {
-                    html.push_str(&format!("<th>{}</th>", html_escape(h)));
-                }
-                html.push_str("</tr></thead><tbody>");
-                for row in table.rows.iter().take(20) {
-                    html.push_str("<tr>");
-                    for cell in row {
-                        html.push_str(&format!("<td>{}</td>", html_escape(cell)));
-                    }
-                    html.push_str("</tr>");
+    Ok(Some(ReportOutput { path: output_path, papers: all_contents.len() }))
+}
+
+async fn report_action_command(action: ReportAction) -> Result<()> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    match action {
+        ReportAction::List { limit } => {
+            let reports = db.get_reports(limit).await?;
+            if reports.is_empty() {
+                println!("还没有生成过报告");
+            }
+            for report in &reports {
+                let options: Vec<String> = [("分组", &report.group_by), ("排序", &report.sort_by), ("语言", &report.lang), ("标签", &report.tags)]
+                    .into_iter()
+                    .filter_map(|(label, value)| value.as_ref().map(|v| format!("{} {}", label, v)))
+                    .collect();
+                println!(
+                    "[{}] {}  {}  {} 篇  {}  {}{}",
+                    report.id.unwrap_or_default(),
+                    report.report_date,
+                    report.format.as_deref().unwrap_or("html"),
+                    report.paper_count.unwrap_or_default(),
+                    report.status.as_deref().unwrap_or("pending"),
+                    report.subscriptions.as_deref().unwrap_or("全部订阅"),
+                    if options.is_empty() { String::new() } else { format!("  ({})", options.join(", ")) },
+                );
+                if let Some(path) = &report.output_path {
+                    let missing = if std::path::Path::new(path).exists() { "" } else { "（文件已删除）" };
+                    println!("    {}{}", path, missing);
                 }
-                html.push_str("</tbody></table>\n");
             }
         }
-
-        // No content fallback
-        if content.sections.is_empty() && content.formulas.is_empty()
-            && content.images.is_empty() && content.tables.is_empty() {
-            html.push_str(r#"<div class="empty">未提取到内容</div>"#);
+        ReportAction::Regenerate { id, send } => {
+            let Some(report) = db.get_report(id).await? else {
+                anyhow::bail!("没有ID为 {} 的报告记录", id);
+            };
+            let format = match report.format.as_deref() {
+                Some(value) => ReportFormat::from_str(value, true).map_err(anyhow::Error::msg)?,
+                None => ReportFormat::Html,
+            };
+            let group_by = report
+                .group_by
+                .as_deref()
+                .map(|value| ReportGroupBy::from_str(value, true).map_err(anyhow::Error::msg))
+                .transpose()?;
+            let sort_by = match report.sort_by.as_deref() {
+                Some(value) => ReportSortBy::from_str(value, true).map_err(anyhow::Error::msg)?,
+                None => ReportSortBy::Relevance,
+            };
+            let lang = match report.lang.as_deref() {
+                Some(value) => ReportLang::from_str(value, true).map_err(anyhow::Error::msg)?,
+                None => ReportLang::Bilingual,
+            };
+            info!("重新生成报告 #{}: {}", id, report.report_date);
+            report_command(ReportOptions {
+                date: Some(report.report_date),
+                min_confidence: None,
+                format,
+                send,
+                group_by,
+                sort_by,
+                lang,
+                tags: report
+                    .tags
+                    .as_deref()
+                    .map(|tags| tags.split(',').map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect())
+                    .unwrap_or_default(),
+            })
+            .await?;
         }
+    }
+    Ok(())
+}
 
-        html.push_str("</div>\n"); // close .paper
+/// 报告中的论文排序；星标论文始终在前，缺少排序依据（相关度、发布日期）的论文排在最后
+fn sort_report_papers(papers: &mut [(String, parser::PaperContent)], sort_by: ReportSortBy) {
+    match sort_by {
+        ReportSortBy::Relevance => papers.sort_by(|(_, a), (_, b)| {
+            let score = |c: &parser::PaperContent| c.metadata.relevance.unwrap_or(-1.0);
+            score(b).total_cmp(&score(a))
+        }),
+        ReportSortBy::Date => papers.sort_by(|(_, a), (_, b)| {
+            b.metadata.publish_date.as_deref().unwrap_or_default().cmp(a.metadata.publish_date.as_deref().unwrap_or_default())
+        }),
+        ReportSortBy::Title => papers.sort_by_cached_key(|(paper_id, content)| {
+            content.metadata.title.as_deref().unwrap_or(paper_id).to_lowercase()
+        }),
     }
+    papers.sort_by_key(|(_, content)| !content.metadata.starred);
+}
 
-    html.push_str("</div>\n</body>\n</html>");
-    html
+/// 论文所在的分组，缺少对应信息时为 None
+fn report_group(group_by: ReportGroupBy, content: &parser::PaperContent) -> Option<String> {
+    let meta = &content.metadata;
+    match group_by {
+        ReportGroupBy::Subscription => meta.subscription.clone(),
+        ReportGroupBy::Source => meta.source.clone(),
+        ReportGroupBy::Date => meta.publish_date.as_deref().and_then(|d| d.get(..10)).map(str::to_string),
+    }
+    .filter(|g| !g.trim().is_empty())
 }
 
-fn html_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-     .replace('<', "&lt;")
-     .replace('>', "&gt;")
-     .replace('"', "&quot;")
+/// PDF 文件名（不含扩展名）即论文 ID
+fn paper_id_from_path(pdf_path: &str) -> String {
+    std::path::Path::new(pdf_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
 }
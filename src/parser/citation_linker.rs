@@ -0,0 +1,220 @@
+use regex::Regex;
+use std::collections::HashMap;
+use tracing::info;
+
+use super::{CitedReference, Reference, Section, SectionCitations};
+
+/// 展开编号范围时的上限，避免 `[1-2000]` 之类的误匹配
+const MAX_RANGE: usize = 50;
+
+/// 从章节正文中识别文内引用，并关联到解析出的参考文献
+pub struct CitationLinker {
+    /// `[12]`、`[3, 5-7]`
+    numeric: Regex,
+    /// 括号内的作者-年份引用：`(Smith et al., 2023; Doe and Roe, 2020)`
+    parenthetical: Regex,
+    /// 叙述式引用：`Smith et al. (2023)`、`Smith and Doe (2020)`
+    narrative: Regex,
+    /// 括号内单条引用的作者和年份
+    author_year: Regex,
+}
+
+impl CitationLinker {
+    pub fn new() -> Self {
+        Self {
+            numeric: Regex::new(r"\[(\d{1,4}(?:\s*[,–-]\s*\d{1,4})*)\]").unwrap(),
+            parenthetical: Regex::new(r"\(([^()]*?(?:19|20)\d{2}[a-z]?[^()]*)\)").unwrap(),
+            narrative: Regex::new(
+                r"\b([A-Z][A-Za-z'\-]+)(?:\s+et\s+al\.?|\s+(?:and|&)\s+[A-Z][A-Za-z'\-]+)?\s+\(((?:19|20)\d{2})[a-z]?\)",
+            )
+            .unwrap(),
+            author_year: Regex::new(r"([A-Z][A-Za-z'\-]+)[^;]*?\b((?:19|20)\d{2})[a-z]?\b").unwrap(),
+        }
+    }
+
+    /// 为每个章节列出引用的参考文献（按首次引用的顺序），参考文献章节本身跳过
+    pub fn link(&self, sections: &[Section], references: &[Reference]) -> Vec<SectionCitations> {
+        if references.is_empty() {
+            return Vec::new();
+        }
+
+        let by_label: HashMap<&str, usize> = references
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| r.label.as_deref().map(|label| (label, i)))
+            .collect();
+        let by_author_year: HashMap<(String, i32), usize> = references
+            .iter()
+            .enumerate()
+            .filter_map(|(i, r)| {
+                let surname = first_author_surname(r)?;
+                Some(((surname.to_lowercase(), r.year?), i))
+            })
+            .collect();
+
+        let mut result = Vec::new();
+        for section in sections {
+            let heading = section.heading.to_lowercase();
+            if heading.contains("reference") || heading.contains("bibliography") {
+                continue;
+            }
+
+            let mut cited = CitationList::default();
+            if !by_label.is_empty() {
+                for caps in self.numeric.captures_iter(&section.body) {
+                    for label in expand_labels(&caps[1]) {
+                        match by_label.get(label.as_str()) {
+                            Some(&position) => cited.add(position),
+                            None => cited.unresolved += 1,
+                        }
+                    }
+                }
+            }
+            if !by_author_year.is_empty() {
+                let lookup = |surname: &str, year: &str| -> Option<usize> {
+                    let year: i32 = year.parse().ok()?;
+                    by_author_year.get(&(surname.to_lowercase(), year)).copied()
+                };
+                for caps in self.parenthetical.captures_iter(&section.body) {
+                    for part in caps[1].split(';') {
+                        let Some(inner) = self.author_year.captures(part) else { continue };
+                        match lookup(&inner[1], &inner[2]) {
+                            Some(position) => cited.add(position),
+                            None => cited.unresolved += 1,
+                        }
+                    }
+                }
+                for caps in self.narrative.captures_iter(&section.body) {
+                    match lookup(&caps[1], &caps[2]) {
+                        Some(position) => cited.add(position),
+                        None => cited.unresolved += 1,
+                    }
+                }
+            }
+
+            if !cited.references.is_empty() || cited.unresolved > 0 {
+                result.push(SectionCitations {
+                    heading: section.heading.clone(),
+                    references: cited.references,
+                    unresolved: cited.unresolved,
+                });
+            }
+        }
+
+        let linked: usize = result.iter().map(|s| s.references.len()).sum();
+        info!("文内引用关联完成: {} 个章节, {} 处关联", result.len(), linked);
+        result
+    }
+}
+
+/// 单个章节的引用累计
+#[derive(Default)]
+struct CitationList {
+    references: Vec<CitedReference>,
+    unresolved: usize,
+}
+
+impl CitationList {
+    fn add(&mut self, position: usize) {
+        match self.references.iter_mut().find(|r| r.position == position) {
+            Some(existing) => existing.mentions += 1,
+            None => self.references.push(CitedReference { position, mentions: 1 }),
+        }
+    }
+}
+
+/// 展开 `3, 5-7` 为 ["3", "5", "6", "7"]
+fn expand_labels(text: &str) -> Vec<String> {
+    let mut labels = Vec::new();
+    for part in text.split(',') {
+        let part = part.trim();
+        let range = part
+            .split_once(['-', '–'])
+            .and_then(|(a, b)| Some((a.trim().parse::<usize>().ok()?, b.trim().parse::<usize>().ok()?)));
+        match range {
+            Some((start, end)) if start <= end && end - start <= MAX_RANGE => {
+                labels.extend((start..=end).map(|n| n.to_string()));
+            }
+            _ => labels.push(part.to_string()),
+        }
+    }
+    labels
+}
+
+/// 第一作者的姓：`Smith, J.` 取逗号前，`J. Smith` 取最后一个词
+fn first_author_surname(reference: &Reference) -> Option<String> {
+    let author = reference.authors.first()?;
+    let surname = match author.split_once(',') {
+        Some((surname, _)) => surname,
+        None => author.split_whitespace().last()?,
+    };
+    Some(surname.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference(label: Option<&str>, author: &str, year: i32) -> Reference {
+        Reference {
+            label: label.map(str::to_string),
+            raw: format!("{} ({}). Some title.", author, year),
+            authors: vec![author.to_string()],
+            year: Some(year),
+            title: Some("Some title".to_string()),
+            venue: None,
+            arxiv_id: None,
+            doi: None,
+        }
+    }
+
+    fn section(heading: &str, body: &str) -> Section {
+        Section { heading: heading.to_string(), level: 1, body: body.to_string(), page: None, confidence: 1.0 }
+    }
+
+    fn positions(citations: &SectionCitations) -> Vec<(usize, usize)> {
+        citations.references.iter().map(|r| (r.position, r.mentions)).collect()
+    }
+
+    #[test]
+    fn expands_numeric_ranges_and_lists() {
+        assert_eq!(expand_labels("3-5"), vec!["3", "4", "5"]);
+        assert_eq!(expand_labels("1, 3–4"), vec!["1", "3", "4"]);
+        // 超过上限的范围不展开
+        assert_eq!(expand_labels("1-2000"), vec!["1-2000"]);
+    }
+
+    #[test]
+    fn links_numeric_citations() {
+        let references: Vec<Reference> =
+            (1..=6).map(|n| reference(Some(&n.to_string()), "A. Author", 2020)).collect();
+        let sections = vec![
+            section("Introduction", "Prior work [1] and [3-5] studied this; see also [1, 9]."),
+            section("References", "[1] A. Author (2020). Some title."),
+        ];
+        let linked = CitationLinker::new().link(&sections, &references);
+
+        assert_eq!(linked.len(), 1);
+        assert_eq!(linked[0].heading, "Introduction");
+        assert_eq!(positions(&linked[0]), vec![(0, 2), (2, 1), (3, 1), (4, 1)]);
+        assert_eq!(linked[0].unresolved, 1);
+    }
+
+    #[test]
+    fn links_author_year_citations() {
+        let references = vec![
+            reference(None, "Smith, J.", 2023),
+            reference(None, "Doe, A.", 2020),
+            reference(None, "K. Lee", 2019),
+        ];
+        let sections = vec![section(
+            "Related Work",
+            "Retrieval helps (Smith et al., 2023; Doe and Roe, 2020). Lee (2019) disagrees, as does (Brown, 2018).",
+        )];
+        let linked = CitationLinker::new().link(&sections, &references);
+
+        assert_eq!(linked.len(), 1);
+        assert_eq!(positions(&linked[0]), vec![(0, 1), (1, 1), (2, 1)]);
+        assert_eq!(linked[0].unresolved, 1);
+    }
+}
@@ -1,10 +1,21 @@
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use tracing::{info, debug};
 
-use super::Formula;
+use super::{latex, Formula, PageIndex};
+
+/// 每篇论文保存的公式上限，其余可用 `formulas --all` 查看
+pub const MAX_STORED_FORMULAS: usize = 30;
+
+/// 正文中对编号公式的引用，如 `Eq. (3)`、`Equation 2`；第一个捕获组为编号
+pub const EQUATION_REFERENCE_PATTERN: &str = r"(?i)\b(?:eqs?|equations?)\.?\s*\(?(\d+(?:\.\d+)?[a-z]?)\)?";
 
 pub struct FormulaExtractor {
     patterns: Vec<(Regex, &'static str)>,
+    /// 公式末尾的编号，如 `(3)`
+    number_pattern: Regex,
+    /// 正文中对编号公式的引用，如 `Eq. (3)`、`Equation 2`
+    reference_pattern: Regex,
 }
 
 impl FormulaExtractor {
@@ -36,24 +47,36 @@ impl FormulaExtractor {
             (Regex::new(r"\$[^\$]{2,}?\$").unwrap(), "inline_latex"),
             (Regex::new(r"\$\$[\s\S]+?\$\$").unwrap(), "display_latex"),
         ];
-        Self { patterns }
+        Self {
+            patterns,
+            number_pattern: Regex::new(r"\((\d+(?:\.\d+)?[a-z]?)\)\s*$").unwrap(),
+            reference_pattern: Regex::new(EQUATION_REFERENCE_PATTERN).unwrap(),
+        }
     }
 
-    /// 从全文中提取公式
+    /// 从全文中提取公式，归一化去重后按重要性排序
+    ///
+    /// 被正文引用的编号公式最靠前，其次是独立成行的公式，再按出现次数排序；
+    /// 互相包含的匹配（同一公式被多个模式命中）合并为最长的一条。
     pub fn extract(&self, full_text: &str) -> Vec<Formula> {
-        let mut formulas: Vec<Formula> = Vec::new();
-        let mut seen = std::collections::HashSet::new();
+        let mut candidates: Vec<Candidate> = Vec::new();
+        let mut by_key: HashMap<String, usize> = HashMap::new();
 
         for (pattern, kind) in &self.patterns {
             for mat in pattern.find_iter(full_text) {
                 let raw = mat.as_str().trim().to_string();
 
-                if seen.contains(&raw) {
+                // Skip very short or very long matches
+                if raw.len() < 4 || raw.len() > 500 {
                     continue;
                 }
 
-                // Skip very short or very long matches
-                if raw.len() < 4 || raw.len() > 500 {
+                let key = normalize(&raw);
+                if key.is_empty() {
+                    continue;
+                }
+                if let Some(&index) = by_key.get(&key) {
+                    candidates[index].formula.occurrences += 1;
                     continue;
                 }
 
@@ -65,14 +88,157 @@ impl FormulaExtractor {
                 let end = full_text.ceil_char_boundary(end);
                 let context = full_text[start..end].trim().to_string();
 
-                debug!("公式匹配 [{}]: {}", kind, &raw[..raw.len().min(80)]);
+                debug!("公式匹配 [{}]: {}", kind, &raw[..raw.floor_char_boundary(80)]);
+
+                let number = self
+                    .number_pattern
+                    .captures(&raw)
+                    .map(|caps| caps[1].to_string());
+                by_key.insert(key.clone(), candidates.len());
+                candidates.push(Candidate {
+                    key,
+                    position: mat.start(),
+                    formula: Formula {
+                        latex: Some(latex::to_latex(without_number(&raw))),
+                        raw,
+                        context,
+                        kind: kind.to_string(),
+                        number,
+                        occurrences: 1,
+                        referenced: false,
+                        page: None,
+                        confidence: 0.0,
+                    },
+                });
+            }
+        }
 
-                seen.insert(raw.clone());
-                formulas.push(Formula { raw, context });
+        let total_matches: usize = candidates.iter().map(|c| c.formula.occurrences).sum();
+        let mut formulas = merge_contained(candidates);
+
+        let referenced: HashSet<String> = self
+            .reference_pattern
+            .captures_iter(full_text)
+            .map(|caps| caps[1].to_string())
+            .collect();
+        let pages = PageIndex::new(full_text);
+        for candidate in &mut formulas {
+            candidate.formula.page = Some(pages.page_at(candidate.position));
+            if let Some(ref number) = candidate.formula.number {
+                candidate.formula.referenced = referenced.contains(number);
             }
+            candidate.formula.confidence = confidence(&candidate.formula);
         }
 
-        info!("公式提取完成，共 {} 个", formulas.len());
-        formulas
+        formulas.sort_by(|a, b| {
+            importance(&b.formula)
+                .cmp(&importance(&a.formula))
+                .then(a.position.cmp(&b.position))
+        });
+
+        info!("公式提取完成，{} 处匹配去重后共 {} 个", total_matches, formulas.len());
+        formulas.into_iter().map(|c| c.formula).collect()
+    }
+}
+
+/// 去重过程中的候选公式
+struct Candidate {
+    key: String,
+    position: usize,
+    formula: Formula,
+}
+
+/// 归一化用于去重：去掉末尾的公式编号、`$` 和所有空白
+fn normalize(raw: &str) -> String {
+    without_number(raw)
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '$')
+        .collect()
+}
+
+/// 去掉末尾的公式编号
+fn without_number(raw: &str) -> &str {
+    match raw.rfind('(') {
+        Some(i) if is_equation_number(&raw[i..]) => &raw[..i],
+        _ => raw,
+    }
+}
+
+/// 形如 `(3)`、`(2.1)`、`(4a)` 的公式编号
+fn is_equation_number(text: &str) -> bool {
+    let text = text.trim_end();
+    match text.strip_prefix('(').and_then(|t| t.strip_suffix(')')) {
+        Some(inner) => {
+            !inner.is_empty()
+                && inner.starts_with(|c: char| c.is_ascii_digit())
+                && inner.chars().all(|c| c.is_ascii_alphanumeric() || c == '.')
+        }
+        None => false,
+    }
+}
+
+/// 归一化后被其他候选包含、或编号相同的公式并入较长的一条，累加出现次数
+///
+/// 同一编号的公式常被不同模式截取成不同片段（如只匹配到等号右侧），按编号合并后每个编号只保留一条。
+fn merge_contained(mut candidates: Vec<Candidate>) -> Vec<Candidate> {
+    candidates.sort_by(|a, b| b.key.len().cmp(&a.key.len()).then(a.position.cmp(&b.position)));
+
+    let mut kept: Vec<Candidate> = Vec::new();
+    for candidate in candidates {
+        let same_number = |k: &Candidate| k.formula.number.is_some() && k.formula.number == candidate.formula.number;
+        match kept.iter_mut().find(|k| k.key.contains(&candidate.key) || same_number(k)) {
+            Some(container) => {
+                container.formula.occurrences += candidate.formula.occurrences;
+                container.position = container.position.min(candidate.position);
+                if container.formula.number.is_none() {
+                    container.formula.number = candidate.formula.number;
+                }
+            }
+            None => kept.push(candidate),
+        }
+    }
+    kept
+}
+
+/// 可信度：LaTeX 源码和含等号的整行最可能是公式，其余符号模式容易误中正文；
+/// 有编号、被正文引用或多次出现时提高，夹杂大量英文单词（多半是正文句子）时降低
+fn confidence(formula: &Formula) -> f32 {
+    let base = match formula.kind.as_str() {
+        "latex_cmd" | "inline_latex" | "display_latex" => 0.8,
+        "equation" => 0.6,
+        "math_symbol" | "greek_expr" | "math_func" => 0.45,
+        _ => 0.35,
+    };
+    let mut score = base;
+    if formula.number.is_some() {
+        score += 0.2;
+    }
+    if formula.referenced {
+        score += 0.1;
+    }
+    score += 0.05 * formula.occurrences.saturating_sub(1).min(2) as f32;
+    let words = formula
+        .raw
+        .split_whitespace()
+        .filter(|w| w.len() >= 4 && w.chars().all(|c| c.is_ascii_lowercase()))
+        .count();
+    if words > 6 {
+        score -= 0.3;
+    }
+    score.clamp(0.0, 1.0)
+}
+
+/// 重要性评分：被引用 > 编号/独立成行 > 出现次数
+fn importance(formula: &Formula) -> usize {
+    let mut score = formula.occurrences.min(10);
+    if formula.is_display() {
+        score += 20;
+    }
+    if formula.number.is_some() {
+        score += 20;
+    }
+    if formula.referenced {
+        score += 100;
     }
+    score
 }
@@ -1,31 +1,55 @@
 use anyhow::Result;
-use lopdf::{Document, Object, ObjectId, Dictionary};
+use pdf_extract::{Document, Object, ObjectId, Dictionary};
 use tracing::{info, warn, debug};
+use std::collections::HashSet;
 use std::path::Path;
 use std::io::Read as IoRead;
 
-use super::ExtractedImage;
+use super::{pdf_loader, ExtractedImage};
+use crate::storage::images::ImageStore;
 
-pub struct ImageAnalyzer;
+/// 宽高比超过该值的图片视为分隔线、背景条等装饰
+const MAX_ASPECT_RATIO: f64 = 12.0;
+/// 编码后数据少于该字节数的图片（纯色块压缩后极小）视为装饰
+const MIN_IMAGE_BYTES: usize = 256;
+/// 最亮与最暗像素的亮度差小于该值时视为纯色（全白、全黑背景）
+const MIN_LUMA_RANGE: u8 = 16;
+
+pub struct ImageAnalyzer {
+    /// 像素面积（宽×高）小于该值的图片视为图标、徽标
+    min_area: u32,
+}
 
 impl ImageAnalyzer {
-    pub fn new() -> Self {
-        Self
+    pub fn with_min_area(min_area: u32) -> Self {
+        Self { min_area }
     }
 
-    /// 从PDF中提取嵌入的图片
-    pub fn extract_images(&self, pdf_path: &str, paper_id: &str, images_dir: &str) -> Result<Vec<ExtractedImage>> {
+    /// 按尺寸和数据量判断的装饰图片，返回原因
+    fn decorative_reason(&self, width: u32, height: u32, encoded_len: usize) -> Option<&'static str> {
+        let (long, short) = (width.max(height) as f64, width.min(height).max(1) as f64);
+        if width.saturating_mul(height) < self.min_area {
+            Some("面积过小")
+        } else if long / short > MAX_ASPECT_RATIO {
+            Some("宽高比极端")
+        } else if encoded_len < MIN_IMAGE_BYTES {
+            Some("数据量过小")
+        } else {
+            None
+        }
+    }
+
+    /// 从PDF中提取嵌入的图片，按内容哈希保存到图片存储
+    pub fn extract_images(&self, pdf_path: &str, store: &dyn ImageStore) -> Result<Vec<ExtractedImage>> {
         info!("提取PDF图片: {}", pdf_path);
 
         if !Path::new(pdf_path).exists() {
             return Err(anyhow::anyhow!("PDF文件不存在: {}", pdf_path));
         }
 
-        std::fs::create_dir_all(images_dir)?;
-
-        let doc = Document::load(pdf_path)?;
+        let doc = pdf_loader::load_document(pdf_path)?;
         let mut images: Vec<ExtractedImage> = Vec::new();
-        let mut img_index = 0;
+        let mut decorative = 0;
 
         // Collect all image stream ObjectIds from the entire document
         // by scanning every object, rather than navigating the page tree
@@ -58,10 +82,19 @@ impl ImageAnalyzer {
                 continue;
             }
 
+            if let Some(reason) = self.decorative_reason(width, height, stream.content.len()) {
+                debug!("跳过装饰图片（{}）: {}x{} (obj {:?})", reason, width, height, obj_id);
+                decorative += 1;
+                continue;
+            }
+
             let filter_name = self.get_filter_name(&stream.dict);
             debug!("Image obj {:?}: {}x{}, filter={:?}, page~{}",
                 obj_id, width, height, filter_name, page_hint);
 
+            // 软蒙版（透明度），有则合成为 alpha 通道后保存为 PNG
+            let soft_mask = self.decode_soft_mask(&stream.dict, &doc, width, height);
+
             match filter_name.as_deref() {
                 Some("DCTDecode") => {
                     // JPEG data
@@ -71,19 +104,45 @@ impl ImageAnalyzer {
                         warn!("JPEG数据为空 (obj {:?})", obj_id);
                         continue;
                     }
-                    let filename = format!("{}/{}_img_{}.jpg", images_dir, paper_id, img_index);
-                    if let Err(e) = std::fs::write(&filename, &data) {
-                        warn!("写入JPEG失败: {}", e);
+                    let decoded = image::load_from_memory_with_format(&data, image::ImageFormat::Jpeg).ok();
+                    if decoded.as_ref().is_some_and(is_uniform) {
+                        debug!("跳过纯色图片 (obj {:?})", obj_id);
+                        decorative += 1;
                         continue;
                     }
+                    // CMYK JPEG 在浏览器中常显示为反色，解码转为 RGB 后重新编码
+                    let cmyk = self.get_color_channels(&stream.dict, &doc) == 4;
+                    let stored = if cmyk || soft_mask.is_some() {
+                        decoded
+                            .ok_or_else(|| anyhow::anyhow!("JPEG解码失败"))
+                            .and_then(|img| {
+                                let mut img = image::DynamicImage::ImageRgb8(img.to_rgb8());
+                                if cmyk && has_inverted_decode(&stream.dict) {
+                                    img.invert();
+                                }
+                                match soft_mask {
+                                    Some(ref mask) => self.store_png(store, &apply_soft_mask(img, mask)).map(|key| (key, "png")),
+                                    None => self.store_jpeg(store, &img).map(|key| (key, "jpeg")),
+                                }
+                            })
+                    } else {
+                        store.put(&data, "jpg").map(|key| (key, "jpeg"))
+                    };
+                    let (filename, format) = match stored {
+                        Ok(stored) => stored,
+                        Err(e) => {
+                            warn!("写入JPEG失败: {}", e);
+                            continue;
+                        }
+                    };
                     images.push(ExtractedImage {
                         filename,
                         page: *page_hint,
                         width,
                         height,
-                        format: "jpeg".to_string(),
+                        format: format.to_string(),
+                        caption: None,
                     });
-                    img_index += 1;
                 }
                 Some("FlateDecode") => {
                     // Try lopdf's decompressed_content first, fall back to manual flate2
@@ -108,22 +167,31 @@ impl ImageAnalyzer {
 
                     // Check if this is an Indexed (palette) color space
                     if let Some(rgb_data) = self.try_decode_indexed(&stream.dict, &doc, &data, width, height, bits) {
-                        let filename = format!("{}/{}_img_{}.png", images_dir, paper_id, img_index);
                         let expected = (width * height * 3) as usize;
                         if rgb_data.len() >= expected {
                             if let Some(img) = image::RgbImage::from_raw(width, height, rgb_data[..expected].to_vec()) {
-                                if let Err(e) = image::DynamicImage::ImageRgb8(img).save(&filename) {
-                                    warn!("保存Indexed图片失败: {}", e);
+                                let img = image::DynamicImage::ImageRgb8(img);
+                                if is_uniform(&img) {
+                                    debug!("跳过纯色图片 (obj {:?})", obj_id);
+                                    decorative += 1;
                                     continue;
                                 }
+                                let img = with_optional_mask(img, soft_mask.as_ref());
+                                let filename = match self.store_png(store, &img) {
+                                    Ok(key) => key,
+                                    Err(e) => {
+                                        warn!("保存Indexed图片失败: {}", e);
+                                        continue;
+                                    }
+                                };
                                 images.push(ExtractedImage {
                                     filename,
                                     page: *page_hint,
                                     width,
                                     height,
                                     format: "png".to_string(),
+                                    caption: None,
                                 });
-                                img_index += 1;
                                 continue;
                             }
                         }
@@ -138,14 +206,13 @@ impl ImageAnalyzer {
                         continue;
                     }
 
-                    let filename = format!("{}/{}_img_{}.png", images_dir, paper_id, img_index);
                     let img_result = match channels {
                         1 => image::GrayImage::from_raw(width, height, data[..expected_size].to_vec())
                             .map(image::DynamicImage::ImageLuma8),
                         3 => image::RgbImage::from_raw(width, height, data[..expected_size].to_vec())
                             .map(image::DynamicImage::ImageRgb8),
-                        4 => image::RgbaImage::from_raw(width, height, data[..expected_size].to_vec())
-                            .map(image::DynamicImage::ImageRgba8),
+                        4 => image::RgbImage::from_raw(width, height, cmyk_to_rgb(&data[..expected_size], has_inverted_decode(&stream.dict)))
+                            .map(image::DynamicImage::ImageRgb8),
                         _ => {
                             warn!("不支持的通道数: {} (obj {:?})", channels, obj_id);
                             continue;
@@ -153,19 +220,27 @@ impl ImageAnalyzer {
                     };
 
                     match img_result {
+                        Some(img) if is_uniform(&img) => {
+                            debug!("跳过纯色图片 (obj {:?})", obj_id);
+                            decorative += 1;
+                        }
                         Some(img) => {
-                            if let Err(e) = img.save(&filename) {
-                                warn!("保存PNG失败: {}", e);
-                                continue;
-                            }
+                            let img = with_optional_mask(img, soft_mask.as_ref());
+                            let filename = match self.store_png(store, &img) {
+                                Ok(key) => key,
+                                Err(e) => {
+                                    warn!("保存PNG失败: {}", e);
+                                    continue;
+                                }
+                            };
                             images.push(ExtractedImage {
                                 filename,
                                 page: *page_hint,
                                 width,
                                 height,
                                 format: "png".to_string(),
+                                caption: None,
                             });
-                            img_index += 1;
                         }
                         None => {
                             warn!("无法创建图片 (obj {:?}, {}x{}, ch={})", obj_id, width, height, channels);
@@ -176,19 +251,21 @@ impl ImageAnalyzer {
                     let data = stream.decompressed_content()
                         .unwrap_or_else(|_| stream.content.clone());
                     if data.is_empty() { continue; }
-                    let filename = format!("{}/{}_img_{}.jp2", images_dir, paper_id, img_index);
-                    if let Err(e) = std::fs::write(&filename, &data) {
-                        warn!("写入JP2失败: {}", e);
-                        continue;
-                    }
+                    let filename = match store.put(&data, "jp2") {
+                        Ok(key) => key,
+                        Err(e) => {
+                            warn!("写入JP2失败: {}", e);
+                            continue;
+                        }
+                    };
                     images.push(ExtractedImage {
                         filename,
                         page: *page_hint,
                         width,
                         height,
                         format: "jp2".to_string(),
+                        caption: None,
                     });
-                    img_index += 1;
                 }
                 Some(other) => {
                     warn!("跳过不支持的编码: {} (obj {:?}, {}x{})", other, obj_id, width, height);
@@ -204,42 +281,114 @@ impl ImageAnalyzer {
                         .unwrap_or(8) as u32;
                     let expected_size = (width * height * channels * bits / 8) as usize;
                     if data.len() < expected_size { continue; }
-                    let filename = format!("{}/{}_img_{}.png", images_dir, paper_id, img_index);
                     let img_result = match channels {
                         1 => image::GrayImage::from_raw(width, height, data[..expected_size].to_vec())
                             .map(image::DynamicImage::ImageLuma8),
                         3 => image::RgbImage::from_raw(width, height, data[..expected_size].to_vec())
                             .map(image::DynamicImage::ImageRgb8),
+                        4 => image::RgbImage::from_raw(width, height, cmyk_to_rgb(&data[..expected_size], has_inverted_decode(&stream.dict)))
+                            .map(image::DynamicImage::ImageRgb8),
                         _ => continue,
                     };
                     if let Some(img) = img_result {
-                        if img.save(&filename).is_ok() {
+                        if is_uniform(&img) {
+                            debug!("跳过纯色图片 (obj {:?})", obj_id);
+                            decorative += 1;
+                            continue;
+                        }
+                        let img = with_optional_mask(img, soft_mask.as_ref());
+                        if let Ok(filename) = self.store_png(store, &img) {
                             images.push(ExtractedImage {
                                 filename,
                                 page: *page_hint,
                                 width,
                                 height,
                                 format: "png".to_string(),
+                                caption: None,
                             });
-                            img_index += 1;
                         }
                     }
                 }
             }
         }
 
-        info!("图片提取完成，共 {} 张", images.len());
+        info!("图片提取完成，共 {} 张，过滤装饰图片 {} 张", images.len(), decorative);
         Ok(images)
     }
 
+    /// 编码为 PNG 后保存到图片存储，返回存储键
+    fn store_png(&self, store: &dyn ImageStore, img: &image::DynamicImage) -> Result<String> {
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buffer, image::ImageFormat::Png)?;
+        store.put(buffer.get_ref(), "png")
+    }
+
+    /// 编码为 JPEG 后保存到图片存储，返回存储键
+    fn store_jpeg(&self, store: &dyn ImageStore, img: &image::DynamicImage) -> Result<String> {
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        img.write_to(&mut buffer, image::ImageFormat::Jpeg)?;
+        store.put(buffer.get_ref(), "jpg")
+    }
+
+    /// 解码图片的 /SMask 软蒙版为灰度图，尺寸与图片不同时缩放到图片大小
+    fn decode_soft_mask(&self, dict: &Dictionary, doc: &Document, width: u32, height: u32) -> Option<image::GrayImage> {
+        let mask_id = dict.get(b"SMask").and_then(Object::as_reference).ok()?;
+        let mask = doc.get_object(mask_id).and_then(Object::as_stream).ok()?;
+
+        let mask_width = mask.dict.get(b"Width").and_then(Object::as_i64).ok()? as u32;
+        let mask_height = mask.dict.get(b"Height").and_then(Object::as_i64).ok()? as u32;
+        let bits = mask.dict.get(b"BitsPerComponent").and_then(Object::as_i64).unwrap_or(8);
+        if bits != 8 {
+            debug!("跳过 {} 位软蒙版 (obj {:?})", bits, mask_id);
+            return None;
+        }
+
+        let data = match self.get_filter_name(&mask.dict).as_deref() {
+            Some("DCTDecode") => image::load_from_memory_with_format(&mask.content, image::ImageFormat::Jpeg)
+                .ok()?
+                .to_luma8()
+                .into_raw(),
+            Some("FlateDecode") => mask
+                .decompressed_content()
+                .or_else(|_| self.manual_inflate(&mask.content))
+                .ok()?,
+            Some(_) => return None,
+            None => mask.content.clone(),
+        };
+        let expected = (mask_width * mask_height) as usize;
+        if data.len() < expected {
+            debug!("软蒙版数据不足: {} < {} (obj {:?})", data.len(), expected, mask_id);
+            return None;
+        }
+
+        let gray = image::GrayImage::from_raw(mask_width, mask_height, data[..expected].to_vec())?;
+        if (mask_width, mask_height) == (width, height) {
+            Some(gray)
+        } else {
+            Some(image::imageops::resize(&gray, width, height, image::imageops::FilterType::Triangle))
+        }
+    }
+
     /// 遍历文档所有对象，找出 Subtype=Image 的 Stream 对象
     /// 这种方式不依赖页面树结构，能找到所有图片（包括嵌套在 Form XObject 中的）
     fn collect_all_image_ids(&self, doc: &Document) -> Vec<(ObjectId, usize)> {
         let mut image_ids: Vec<(ObjectId, usize)> = Vec::new();
 
+        // 被其他图片作为 SMask / Mask 引用的对象是透明度蒙版，不单独提取
+        let mask_ids: HashSet<ObjectId> = doc
+            .objects
+            .values()
+            .filter_map(|object| object.as_stream().ok())
+            .flat_map(|stream| [b"SMask".as_slice(), b"Mask".as_slice()].map(|key| stream.dict.get(key).and_then(Object::as_reference).ok()))
+            .flatten()
+            .collect();
+
         // Build a rough page mapping: which page references which objects
         // For simplicity, we just scan all objects directly
         for (&obj_id, object) in doc.objects.iter() {
+            if mask_ids.contains(&obj_id) {
+                continue;
+            }
             let stream = match object.as_stream() {
                 Ok(s) => s,
                 Err(_) => continue,
@@ -303,10 +452,10 @@ impl ImageAnalyzer {
                             .ok()
                             .and_then(|s| s.as_name().ok())
                             .and_then(|n| std::str::from_utf8(n).ok());
-                        if sub == Some("Form") {
-                            if self.page_references_object(doc, ref_id, target_id, depth + 1) {
-                                return true;
-                            }
+                        if sub == Some("Form")
+                            && self.page_references_object(doc, ref_id, target_id, depth + 1)
+                        {
+                            return true;
                         }
                     }
                 }
@@ -331,9 +480,8 @@ impl ImageAnalyzer {
         // Try zlib (with header) first
         let mut decoder = flate2::read::ZlibDecoder::new(compressed);
         let mut result = Vec::new();
-        match decoder.read_to_end(&mut result) {
-            Ok(_) => return Ok(result),
-            Err(_) => {}
+        if decoder.read_to_end(&mut result).is_ok() {
+            return Ok(result);
         }
 
         // Fall back to raw deflate (no header)
@@ -406,7 +554,7 @@ impl ImageAnalyzer {
 
         // Decode: each pixel byte is an index into the lookup table
         let pixel_count = (width * height) as usize;
-        let bytes_per_row = ((width * bits + 7) / 8) as usize;
+        let bytes_per_row = (width * bits).div_ceil(8) as usize;
         let expected_data = bytes_per_row * height as usize;
 
         if data.len() < expected_data {
@@ -416,15 +564,14 @@ impl ImageAnalyzer {
 
         let mut rgb_data = Vec::with_capacity(pixel_count * base_channels);
 
-        for i in 0..pixel_count {
-            let idx = data[i] as usize;
-            let idx = idx.min(hival);
+        for &pixel in data.iter().take(pixel_count) {
+            let idx = (pixel as usize).min(hival);
             let offset = idx * base_channels;
             if offset + base_channels <= lookup_data.len() {
                 rgb_data.extend_from_slice(&lookup_data[offset..offset + base_channels]);
             } else {
                 // Fallback: black pixel
-                rgb_data.extend(std::iter::repeat(0u8).take(base_channels));
+                rgb_data.extend(std::iter::repeat_n(0u8, base_channels));
             }
         }
 
@@ -529,3 +676,51 @@ impl ImageAnalyzer {
         }
     }
 }
+
+/// CMYK 像素数据转为 RGB；`inverted` 对应 `/Decode [1 0 1 0 1 0 1 0]`（Adobe 反相 CMYK）
+fn cmyk_to_rgb(data: &[u8], inverted: bool) -> Vec<u8> {
+    let mut rgb = Vec::with_capacity(data.len() / 4 * 3);
+    for pixel in data.chunks_exact(4) {
+        let [c, m, y, k] = [pixel[0], pixel[1], pixel[2], pixel[3]].map(|v| if inverted { 255 - v } else { v } as u32);
+        rgb.extend([c, m, y].map(|v| ((255 - v) * (255 - k) / 255) as u8));
+    }
+    rgb
+}
+
+/// 图片字典的 /Decode 数组是否把分量反相（首项为 1）
+fn has_inverted_decode(dict: &Dictionary) -> bool {
+    dict.get(b"Decode")
+        .and_then(Object::as_array)
+        .ok()
+        .and_then(|decode| decode.first())
+        .and_then(|first| first.as_float().ok().or_else(|| first.as_i64().ok().map(|v| v as f32)))
+        .map(|first| first > 0.5)
+        .unwrap_or(false)
+}
+
+/// 以软蒙版作为 alpha 通道合成 RGBA 图片
+fn apply_soft_mask(img: image::DynamicImage, mask: &image::GrayImage) -> image::DynamicImage {
+    let mut rgba = img.to_rgba8();
+    for (pixel, alpha) in rgba.pixels_mut().zip(mask.pixels()) {
+        pixel[3] = alpha[0];
+    }
+    image::DynamicImage::ImageRgba8(rgba)
+}
+
+fn with_optional_mask(img: image::DynamicImage, mask: Option<&image::GrayImage>) -> image::DynamicImage {
+    match mask {
+        Some(mask) => apply_soft_mask(img, mask),
+        None => img,
+    }
+}
+
+/// 亮度几乎没有变化的纯色图片（全白、全黑的背景或占位块）
+///
+/// 逐像素比较而不是先缩小，线稿中的细线在缩略图里会被平均掉。
+fn is_uniform(img: &image::DynamicImage) -> bool {
+    let luma = img.to_luma8();
+    let (min, max) = luma
+        .pixels()
+        .fold((u8::MAX, u8::MIN), |(min, max), p| (min.min(p[0]), max.max(p[0])));
+    max.saturating_sub(min) < MIN_LUMA_RANGE
+}
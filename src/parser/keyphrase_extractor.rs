@@ -0,0 +1,146 @@
+use regex::Regex;
+use std::collections::HashMap;
+use tracing::info;
+
+use super::Keyphrase;
+
+/// 关键短语最多包含的单词数
+const MAX_PHRASE_WORDS: usize = 3;
+
+/// 英文停用词和论文中常见的套话，候选短语在这些词处断开
+const STOPWORDS: &[&str] = &[
+    "a", "about", "above", "across", "after", "again", "against", "al", "all", "almost", "also", "although",
+    "always", "among", "an", "and", "another", "any", "appendix", "approach", "approaches", "are", "as", "at",
+    "based", "be", "because", "been", "before", "being", "below", "between", "both", "but", "by", "can",
+    "cannot", "compared", "consider", "could", "demonstrate", "different", "do", "does", "done", "due",
+    "during", "each", "either", "eq", "et", "etc", "even", "every", "example", "fig", "figure", "first",
+    "following", "for", "from", "further", "furthermore", "given", "has", "have", "having", "here", "how", "however", "i", "ie", "if", "in", "into", "is", "it", "its", "itself", "less",
+    "let", "like", "many", "may", "more", "moreover", "most", "much", "must", "new", "no", "not", "novel",
+    "number", "of", "on", "one", "only", "or", "other", "our", "ours", "out", "over", "paper", "particular",
+    "per", "perform", "performs", "propose", "proposed", "provide", "provides", "rather", "respectively",
+    "result", "results", "same", "second", "section", "see", "set", "several", "should", "show", "shown",
+    "shows", "significantly", "since", "so", "some", "such", "table", "than", "that", "the", "their", "them",
+    "then", "there", "therefore", "these", "they", "third", "this", "those", "three", "through", "thus", "to",
+    "two", "under", "until", "up", "upon", "us", "use", "used", "uses", "using", "various", "very", "via",
+    "was", "we", "well", "were", "what", "when", "where", "whether", "which", "while", "who", "whose", "why",
+    "will", "with", "within", "without", "work", "would", "yet",
+];
+
+/// 基于 RAKE 的关键短语提取：以停用词和标点切分候选短语，按词的共现度与频次打分
+///
+/// 参考文献章节不参与统计；只出现一次的短语除非出现在标题或摘要中，否则不入选。
+pub struct KeyphraseExtractor {
+    references_heading: Regex,
+    /// 句子、分句边界
+    delimiters: Regex,
+}
+
+impl KeyphraseExtractor {
+    pub fn new() -> Self {
+        Self {
+            references_heading: Regex::new(r"(?im)^(?:\d+\.?\s*)?(?:references|bibliography|literature\s+cited)\s*$").unwrap(),
+            delimiters: Regex::new(r"[.,;:!?()\[\]{}\x22“”|/=+<>\n\t]|\s[-–—]\s").unwrap(),
+        }
+    }
+
+    /// 返回得分最高的 `limit` 个关键短语（小写）
+    pub fn extract(&self, title: Option<&str>, abstract_text: Option<&str>, full_text: &str, limit: usize) -> Vec<Keyphrase> {
+        let body_end = self
+            .references_heading
+            .find_iter(full_text)
+            .last()
+            .map(|m| m.start())
+            .unwrap_or(full_text.len());
+        let body = &full_text[..body_end];
+
+        let phrases = self.candidates(body);
+        let mut frequency: HashMap<&str, usize> = HashMap::new();
+        let mut degree: HashMap<&str, usize> = HashMap::new();
+        let mut phrase_counts: HashMap<String, usize> = HashMap::new();
+        for phrase in &phrases {
+            for word in phrase {
+                *frequency.entry(word.as_str()).or_default() += 1;
+                *degree.entry(word.as_str()).or_default() += phrase.len();
+            }
+            *phrase_counts.entry(phrase.join(" ")).or_default() += 1;
+        }
+
+        let highlighted = format!("{} {}", title.unwrap_or_default(), abstract_text.unwrap_or_default()).to_lowercase();
+        let mut scored: Vec<Keyphrase> = phrase_counts
+            .into_iter()
+            .filter(|(phrase, count)| *count >= 2 || highlighted.contains(phrase.as_str()))
+            .map(|(phrase, count)| {
+                let word_score: f64 = phrase
+                    .split(' ')
+                    .map(|w| degree[w] as f64 / frequency[w] as f64)
+                    .sum();
+                // 标题、摘要中的短语加权，频次取对数避免通用词压过专有术语
+                let boost = if highlighted.contains(phrase.as_str()) { 1.5 } else { 1.0 };
+                let score = word_score * (1.0 + count as f64).ln() * boost;
+                Keyphrase { phrase, score, occurrences: count }
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.phrase.cmp(&b.phrase)));
+        let mut selected: Vec<Keyphrase> = Vec::new();
+        for candidate in scored {
+            // 已选短语的子串（"attention" 与 "self attention"）不再重复入选
+            if selected.iter().any(|k| overlaps(&k.phrase, &candidate.phrase)) {
+                continue;
+            }
+            selected.push(candidate);
+            if selected.len() >= limit {
+                break;
+            }
+        }
+
+        info!("关键短语提取完成，共 {} 个", selected.len());
+        selected
+    }
+
+    /// 切分出候选短语，每个短语为小写单词序列
+    fn candidates(&self, text: &str) -> Vec<Vec<String>> {
+        let mut phrases: Vec<Vec<String>> = Vec::new();
+        for fragment in self.delimiters.split(text) {
+            let mut current: Vec<String> = Vec::new();
+            for word in fragment.split_whitespace() {
+                let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '-');
+                if is_content_word(word) {
+                    current.push(word.to_lowercase());
+                } else {
+                    push_phrase(&mut phrases, &mut current);
+                }
+            }
+            push_phrase(&mut phrases, &mut current);
+        }
+        phrases
+    }
+}
+
+/// 结束当前候选短语；超过 MAX_PHRASE_WORDS 个词的长串拆成其中的二元、三元词组
+fn push_phrase(phrases: &mut Vec<Vec<String>>, current: &mut Vec<String>) {
+    if current.len() <= MAX_PHRASE_WORDS {
+        if !current.is_empty() {
+            phrases.push(std::mem::take(current));
+        }
+        return;
+    }
+    for n in 2..=MAX_PHRASE_WORDS {
+        phrases.extend(current.windows(n).map(<[String]>::to_vec));
+    }
+    current.clear();
+}
+
+/// 至少三个字符、以字母开头、不是停用词，且不全是数字
+fn is_content_word(word: &str) -> bool {
+    word.chars().count() >= 3
+        && word.starts_with(|c: char| c.is_alphabetic())
+        && word.chars().all(|c| c.is_alphanumeric() || c == '-')
+        && !STOPWORDS.contains(&word.to_lowercase().as_str())
+}
+
+/// 两个短语是否按整词互相包含
+fn overlaps(a: &str, b: &str) -> bool {
+    let (a, b) = (format!(" {} ", a), format!(" {} ", b));
+    a.contains(&b) || b.contains(&a)
+}
@@ -0,0 +1,196 @@
+/// 把 PDF 中提取的 Unicode 数学文本尽量转换为 LaTeX，供报告中的 KaTeX 渲染
+///
+/// 只做逐字符的映射：Unicode 运算符和希腊字母转为命令，上下标字符合并为 `^{}` / `_{}`，
+/// 数学字母（𝐱、ℝ 等）转为对应字体命令，常见函数名转为 `\log` 等。已含 LaTeX 命令的文本只去掉 `$`。
+pub fn to_latex(raw: &str) -> String {
+    let raw = raw.trim();
+    if is_latex(raw) {
+        return raw.replace('$', "").trim().to_string();
+    }
+
+    let chars: Vec<char> = raw.chars().collect();
+    let mut out = String::new();
+    // 上一个记号是否为正体单词，用于保留单词间的空格
+    let mut after_word = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c.is_ascii_alphabetic() {
+            let start = i;
+            while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            let token = word_to_latex(&word);
+            after_word = token.starts_with("\\mathrm");
+            push_token(&mut out, &token);
+            continue;
+        }
+        if !c.is_whitespace() {
+            after_word = false;
+        }
+
+        if let Some(script) = script_char(c) {
+            let marker = if script.superscript { '^' } else { '_' };
+            let mut group = String::new();
+            while let Some(s) = chars.get(i).and_then(|&c| script_char(c)).filter(|s| s.superscript == script.superscript) {
+                group.push_str(s.text);
+                i += 1;
+            }
+            out.push(marker);
+            out.push('{');
+            out.push_str(&group);
+            out.push('}');
+            continue;
+        }
+
+        if c.is_whitespace() {
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            // 正体单词前后保留一个显式空格，其余空白在数学模式下无意义
+            let next_word: String = chars[i..].iter().take_while(|c| c.is_ascii_alphabetic()).collect();
+            let before_word = word_to_latex(&next_word).starts_with("\\mathrm");
+            let between_words = (after_word && chars.get(i).map(|c| c.is_ascii_alphanumeric()).unwrap_or(false))
+                || (before_word && !out.is_empty());
+            out.push_str(if between_words { "\\ " } else { " " });
+            continue;
+        }
+
+        match symbol(c) {
+            Some(command) => push_token(&mut out, command),
+            None => match math_letter(c) {
+                Some(letter) => push_token(&mut out, &letter),
+                None => out.push(c),
+            },
+        }
+        i += 1;
+    }
+    out.trim().to_string()
+}
+
+/// 已是 LaTeX 源码（含 `\frac` 等命令）
+fn is_latex(raw: &str) -> bool {
+    raw.char_indices().any(|(i, c)| c == '\\' && raw[i + 1..].starts_with(|c: char| c.is_ascii_alphabetic()))
+}
+
+/// 追加一个记号；以字母结尾的命令后紧跟字母时补空格，避免 `\alphax` 这类粘连
+fn push_token(out: &mut String, token: &str) {
+    let command_before = out
+        .rsplit(|c: char| !c.is_ascii_alphabetic())
+        .next()
+        .map(|tail| !tail.is_empty() && out[..out.len() - tail.len()].ends_with('\\'))
+        .unwrap_or(false);
+    if command_before && token.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        out.push(' ');
+    }
+    out.push_str(token);
+}
+
+/// 数学函数名转为对应命令，其他多字母单词用正体，单个字母保持斜体变量
+fn word_to_latex(word: &str) -> String {
+    const FUNCTIONS: &[&str] = &[
+        "log", "ln", "exp", "sin", "cos", "tan", "tanh", "sinh", "cosh", "min", "max", "sup", "inf", "lim",
+        "det", "arg", "dim", "ker", "Pr", "gcd",
+    ];
+    match word {
+        "argmin" | "argmax" => format!("\\operatorname*{{{}}}", &word.replacen("arg", "arg\\,", 1)),
+        w if FUNCTIONS.contains(&w) => format!("\\{}", w),
+        w if w.len() >= 3 => format!("\\mathrm{{{}}}", w),
+        w => w.to_string(),
+    }
+}
+
+struct Script {
+    superscript: bool,
+    text: &'static str,
+}
+
+/// Unicode 上标和下标字符
+fn script_char(c: char) -> Option<Script> {
+    const SUPERSCRIPTS: &[(char, &str)] = &[
+        ('⁰', "0"), ('¹', "1"), ('²', "2"), ('³', "3"), ('⁴', "4"), ('⁵', "5"), ('⁶', "6"), ('⁷', "7"),
+        ('⁸', "8"), ('⁹', "9"), ('⁺', "+"), ('⁻', "-"), ('⁼', "="), ('⁽', "("), ('⁾', ")"), ('ⁿ', "n"),
+        ('ⁱ', "i"), ('ᵃ', "a"), ('ᵇ', "b"), ('ᶜ', "c"), ('ᵈ', "d"), ('ᵉ', "e"), ('ʲ', "j"), ('ᵏ', "k"),
+        ('ˡ', "l"), ('ᵐ', "m"), ('ʳ', "r"), ('ˢ', "s"), ('ᵗ', "t"), ('ᵘ', "u"), ('ᵛ', "v"), ('ʷ', "w"),
+        ('ˣ', "x"), ('ʸ', "y"), ('ᶻ', "z"), ('ᴰ', "D"), ('ᴴ', "H"), ('ᴺ', "N"), ('ᵀ', "T"),
+        ('′', "\\prime"), ('″', "\\prime\\prime"),
+    ];
+    const SUBSCRIPTS: &[(char, &str)] = &[
+        ('₀', "0"), ('₁', "1"), ('₂', "2"), ('₃', "3"), ('₄', "4"), ('₅', "5"), ('₆', "6"), ('₇', "7"),
+        ('₈', "8"), ('₉', "9"), ('₊', "+"), ('₋', "-"), ('₌', "="), ('₍', "("), ('₎', ")"), ('ₐ', "a"),
+        ('ₑ', "e"), ('ₒ', "o"), ('ₓ', "x"), ('ₕ', "h"), ('ₖ', "k"), ('ₗ', "l"), ('ₘ', "m"), ('ₙ', "n"),
+        ('ₚ', "p"), ('ₛ', "s"), ('ₜ', "t"), ('ᵢ', "i"), ('ⱼ', "j"), ('ᵣ', "r"), ('ᵤ', "u"), ('ᵥ', "v"),
+    ];
+    if let Some(&(_, text)) = SUPERSCRIPTS.iter().find(|(s, _)| *s == c) {
+        return Some(Script { superscript: true, text });
+    }
+    SUBSCRIPTS
+        .iter()
+        .find(|(s, _)| *s == c)
+        .map(|&(_, text)| Script { superscript: false, text })
+}
+
+/// Unicode 运算符、希腊字母和需要转义的 ASCII 字符
+fn symbol(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' => "\\alpha", 'β' => "\\beta", 'γ' => "\\gamma", 'δ' => "\\delta", 'ε' | 'ϵ' => "\\epsilon",
+        'ζ' => "\\zeta", 'η' => "\\eta", 'θ' => "\\theta", 'ϑ' => "\\vartheta", 'ι' => "\\iota",
+        'κ' => "\\kappa", 'λ' => "\\lambda", 'μ' => "\\mu", 'ν' => "\\nu", 'ξ' => "\\xi", 'π' => "\\pi",
+        'ρ' => "\\rho", 'σ' => "\\sigma", 'ς' => "\\varsigma", 'τ' => "\\tau", 'υ' => "\\upsilon",
+        'φ' => "\\phi", 'ϕ' => "\\phi", 'χ' => "\\chi", 'ψ' => "\\psi", 'ω' => "\\omega",
+        'Γ' => "\\Gamma", 'Δ' | '∆' => "\\Delta", 'Θ' => "\\Theta", 'Λ' => "\\Lambda", 'Ξ' => "\\Xi",
+        'Π' => "\\Pi", 'Σ' => "\\Sigma", 'Φ' => "\\Phi", 'Ψ' => "\\Psi", 'Ω' => "\\Omega",
+        '≤' | '⩽' => "\\leq", '≥' | '⩾' => "\\geq", '≠' => "\\neq", '≈' => "\\approx", '≡' => "\\equiv",
+        '∼' => "\\sim", '≃' => "\\simeq", '∝' => "\\propto", '≪' => "\\ll", '≫' => "\\gg",
+        '±' => "\\pm", '∓' => "\\mp", '×' => "\\times", '·' | '⋅' => "\\cdot", '÷' => "\\div",
+        '∗' => "*", '−' | '–' => "-", '∘' => "\\circ",
+        '∞' => "\\infty", '∑' => "\\sum", '∏' => "\\prod", '∫' => "\\int", '∮' => "\\oint",
+        '∂' => "\\partial", '∇' => "\\nabla", '√' => "\\sqrt", '∅' => "\\emptyset",
+        '∈' => "\\in", '∉' => "\\notin", '∋' => "\\ni", '⊂' => "\\subset", '⊃' => "\\supset",
+        '⊆' => "\\subseteq", '⊇' => "\\supseteq", '∪' => "\\cup", '∩' => "\\cap",
+        '∀' => "\\forall", '∃' => "\\exists", '∧' => "\\wedge", '∨' => "\\vee", '¬' => "\\neg",
+        '→' => "\\to", '←' => "\\leftarrow", '↔' => "\\leftrightarrow", '⇒' => "\\Rightarrow",
+        '⇐' => "\\Leftarrow", '⇔' => "\\Leftrightarrow", '↦' => "\\mapsto",
+        '⟨' | '〈' => "\\langle", '⟩' | '〉' => "\\rangle", '‖' | '∥' => "\\|",
+        '⌊' => "\\lfloor", '⌋' => "\\rfloor", '⌈' => "\\lceil", '⌉' => "\\rceil",
+        '⊗' => "\\otimes", '⊕' => "\\oplus", '⊙' => "\\odot", '⊤' => "\\top", '⊥' => "\\perp",
+        '…' => "\\ldots", '⋯' => "\\cdots", 'ℓ' => "\\ell", 'ℏ' => "\\hbar",
+        '#' => "\\#", '$' => "\\$", '%' => "\\%", '&' => "\\&", '_' => "\\_", '{' => "\\{", '}' => "\\}",
+        '~' => "\\sim", '^' => "\\hat{}", '\\' => "\\backslash",
+        _ => return None,
+    })
+}
+
+/// 数学字母数字符号（U+1D400 起的 𝐱、𝑥、𝔼 等，以及 ℝ、ℎ 等字母类符号）转为带字体命令的字母
+fn math_letter(c: char) -> Option<String> {
+    let letterlike = match c {
+        'ℝ' => Some(('R', "\\mathbb")),
+        'ℕ' => Some(('N', "\\mathbb")),
+        'ℤ' => Some(('Z', "\\mathbb")),
+        'ℚ' => Some(('Q', "\\mathbb")),
+        'ℂ' => Some(('C', "\\mathbb")),
+        'ℎ' => Some(('h', "")),
+        'ℒ' => Some(('L', "\\mathcal")),
+        'ℋ' => Some(('H', "\\mathcal")),
+        'ℱ' => Some(('F', "\\mathcal")),
+        'ℰ' => Some(('E', "\\mathcal")),
+        _ => None,
+    };
+    let (letter, font) = match letterlike {
+        Some(found) => found,
+        None => {
+            let offset = (c as u32).checked_sub(0x1D400).filter(|&o| o < 13 * 52)?;
+            let index = (offset % 52) as u8;
+            let letter = if index < 26 { (b'A' + index) as char } else { (b'a' + index - 26) as char };
+            // 依次为粗体、斜体、粗斜体、手写、粗手写、哥特、双线、粗哥特、无衬线四种、等宽
+            let font = [
+                "\\mathbf", "", "\\boldsymbol", "\\mathcal", "\\mathcal", "\\mathfrak", "\\mathbb",
+                "\\mathfrak", "\\mathsf", "\\mathsf", "\\mathsf", "\\mathsf", "\\mathtt",
+            ][(offset / 52) as usize];
+            (letter, font)
+        }
+    };
+    Some(if font.is_empty() { letter.to_string() } else { format!("{}{{{}}}", font, letter) })
+}
@@ -0,0 +1,427 @@
+use anyhow::Result;
+use pdf_extract::{MediaBox, OutputDev, OutputError, Transform};
+use regex::Regex;
+use tracing::debug;
+
+use super::pdf_loader;
+
+/// 栏间空白判定：落在分隔线上的文本片段不超过该比例
+const MAX_GUTTER_OVERLAP: f64 = 0.15;
+/// 每一栏至少包含的文本片段比例，避免把缩进或列表误判为分栏
+const MIN_COLUMN_SHARE: f64 = 0.2;
+
+/// 页面上一段连续的文本（同一基线、字符间无大间距）
+#[derive(Debug, Clone)]
+struct Fragment {
+    x0: f64,
+    x1: f64,
+    /// 基线位置，以页面顶部为 0 向下增长
+    y: f64,
+    size: f64,
+    text: String,
+}
+
+/// 收集每页的文本片段及坐标
+struct LayoutCollector {
+    pages: Vec<Page>,
+    current: Option<Page>,
+    /// 下一个字符是否开始新单词（由 begin_word 标记）
+    word_start: bool,
+}
+
+struct Page {
+    /// MediaBox 左下角，字符坐标换算为相对页面的位置
+    origin_x: f64,
+    top: f64,
+    width: f64,
+    height: f64,
+    fragments: Vec<Fragment>,
+}
+
+impl LayoutCollector {
+    fn new() -> Self {
+        Self { pages: Vec::new(), current: None, word_start: false }
+    }
+}
+
+impl OutputDev for LayoutCollector {
+    fn begin_page(&mut self, _page_num: u32, media_box: &MediaBox, _: Option<(f64, f64, f64, f64)>) -> Result<(), OutputError> {
+        self.current = Some(Page {
+            origin_x: media_box.llx,
+            top: media_box.ury,
+            width: media_box.urx - media_box.llx,
+            height: media_box.ury - media_box.lly,
+            fragments: Vec::new(),
+        });
+        Ok(())
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        if let Some(page) = self.current.take() {
+            self.pages.push(page);
+        }
+        Ok(())
+    }
+
+    fn output_character(&mut self, trm: &Transform, width: f64, _spacing: f64, font_size: f64, char: &str) -> Result<(), OutputError> {
+        let Some(page) = self.current.as_mut() else { return Ok(()) };
+        let scale = (trm.m11 * trm.m22 - trm.m12 * trm.m21).abs().sqrt();
+        let size = (font_size * scale).max(1.0);
+        let x = trm.m31 - page.origin_x;
+        let y = page.top - trm.m32;
+        let advance = width * size;
+
+        // 同一基线且紧接上一个字符时追加，否则开始新片段
+        if let Some(last) = page.fragments.last_mut() {
+            let same_line = (y - last.y).abs() <= last.size * 0.5;
+            let gap = x - last.x1;
+            if same_line && gap > -last.size && gap < last.size * 1.5 {
+                let spaced = self.word_start && gap > last.size * 0.1;
+                if spaced && !last.text.ends_with(' ') {
+                    last.text.push(' ');
+                }
+                last.text.push_str(char);
+                last.x1 = last.x1.max(x + advance);
+                self.word_start = false;
+                return Ok(());
+            }
+        }
+
+        if !char.trim().is_empty() {
+            page.fragments.push(Fragment { x0: x, x1: x + advance, y, size, text: char.to_string() });
+        }
+        self.word_start = false;
+        Ok(())
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        self.word_start = true;
+        Ok(())
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+}
+
+/// 图注上方留给图形的区域，坐标单位为 PDF 点，原点在页面左上角
+#[derive(Debug, Clone)]
+pub struct FigureRegion {
+    /// 页码（从 1 开始）
+    pub page: usize,
+    /// 图号，如 "3"
+    pub number: String,
+    pub caption: String,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+fn collect_pages(pdf_path: &str) -> Result<Vec<Page>> {
+    let doc = pdf_loader::load_document(pdf_path)?;
+    let mut collector = LayoutCollector::new();
+    pdf_extract::output_doc(&doc, &mut collector)?;
+    Ok(collector.pages)
+}
+
+/// 按版面提取每页文本：双栏页面先输出左栏再输出右栏，跨栏的标题、摘要等保持原位置
+pub fn extract_pages(pdf_path: &str) -> Result<Vec<String>> {
+    Ok(collect_pages(pdf_path)?
+        .into_iter()
+        .enumerate()
+        .map(|(i, page)| {
+            let gutter = find_gutter(&page);
+            if let Some(gutter) = gutter {
+                debug!("第 {} 页检测到双栏，分隔线 x={:.1}", i + 1, gutter);
+            }
+            render_page(page.fragments, gutter, page.width)
+        })
+        .collect())
+}
+
+/// 首页上半部分字号最大的文本，通常就是论文标题；跨多行的标题按行拼接
+///
+/// 竖排的 arXiv 水印逐字成为单独片段，要求片段至少含两个字母即可排除；
+/// 最大字号与正文相差不大时（如扫描件或纯文本排版）返回 None。
+pub fn title_by_font_size(pdf_path: &str) -> Result<Option<String>> {
+    let doc = pdf_loader::load_document(pdf_path)?;
+    let mut collector = LayoutCollector::new();
+    pdf_extract::output_doc_page(&doc, &mut collector, 1)?;
+    let Some(page) = collector.pages.first() else { return Ok(None) };
+
+    let watermark = Regex::new(r"(?i)^arxiv:\s*\d{4}\.\d{4,5}").unwrap();
+    let candidates: Vec<&Fragment> = page
+        .fragments
+        .iter()
+        .filter(|f| f.y <= page.height * 0.5)
+        .filter(|f| f.text.chars().filter(|c| c.is_alphabetic()).count() >= 2)
+        .filter(|f| !watermark.is_match(f.text.trim()))
+        .collect();
+    let Some(max_size) = candidates.iter().map(|f| f.size).max_by(f64::total_cmp) else { return Ok(None) };
+    if max_size < body_font_size(page) * 1.2 {
+        return Ok(None);
+    }
+
+    let mut title: Vec<&Fragment> = candidates.into_iter().filter(|f| f.size >= max_size - 0.5).collect();
+    title.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x0.total_cmp(&b.x0)));
+    // 只取第一段相邻的行，页面别处同样字号的文字（如期刊名）不并入标题
+    let mut lines = Vec::new();
+    let mut previous_y: Option<f64> = None;
+    for fragment in title {
+        if previous_y.is_some_and(|y| fragment.y - y > max_size * 2.5) {
+            break;
+        }
+        lines.push(fragment.text.trim());
+        previous_y = Some(fragment.y);
+    }
+    let title = lines.join(" ").split_whitespace().collect::<Vec<_>>().join(" ");
+    let length = title.chars().count();
+    debug!("按字号识别标题 ({:.1}pt): {}", max_size, title);
+    Ok((10..=300).contains(&length).then_some(title))
+}
+
+/// 定位每个「Figure N」图注上方的图形区域：向上直到同一栏中最近的正文行
+///
+/// 图形中的坐标轴刻度等小字不算正文行；区域高度过小（图注紧跟正文）时跳过。
+pub fn figure_regions(pdf_path: &str) -> Result<Vec<FigureRegion>> {
+    let caption_re = Regex::new(r"^(?:Figure|Fig\.)\s*(\d+)[.:]").unwrap();
+    let mut regions = Vec::new();
+
+    for (index, page) in collect_pages(pdf_path)?.iter().enumerate() {
+        let body_size = body_font_size(page);
+        let gutter = find_gutter(page);
+        let margin = page.width * 0.05;
+        let text_left = page.fragments.iter().map(|f| f.x0).fold(f64::MAX, f64::min);
+        let text_right = page.fragments.iter().map(|f| f.x1).fold(0.0, f64::max);
+        let text_top = page.fragments.iter().map(|f| f.y - f.size).fold(f64::MAX, f64::min);
+
+        for caption in &page.fragments {
+            let Some(caps) = caption_re.captures(caption.text.trim()) else { continue };
+
+            // 图注所在栏的水平范围
+            let (left, right) = match gutter {
+                Some(g) if caption.x1 <= g + margin => (text_left, g),
+                Some(g) if caption.x0 >= g - margin => (g, text_right),
+                _ => (text_left, text_right),
+            };
+            let top = page
+                .fragments
+                .iter()
+                .filter(|f| f.x1 > left && f.x0 < right && f.y < caption.y - caption.size)
+                .filter(|f| f.text.trim().chars().count() >= 30 && (f.size - body_size).abs() <= body_size * 0.15)
+                .map(|f| f.y + f.size * 0.3)
+                .fold(text_top, f64::max);
+            let bottom = caption.y - caption.size;
+            if bottom - top < body_size * 3.0 {
+                continue;
+            }
+
+            regions.push(FigureRegion {
+                page: index + 1,
+                number: caps[1].to_string(),
+                caption: caption.text.trim().to_string(),
+                x: left,
+                y: top,
+                width: right - left,
+                height: bottom - top,
+            });
+        }
+    }
+    Ok(regions)
+}
+
+/// 正文字号：较长片段字号的中位数
+fn body_font_size(page: &Page) -> f64 {
+    let mut sizes: Vec<f64> = page
+        .fragments
+        .iter()
+        .filter(|f| f.text.trim().chars().count() >= 30)
+        .map(|f| f.size)
+        .collect();
+    if sizes.is_empty() {
+        return 10.0;
+    }
+    sizes.sort_by(f64::total_cmp);
+    sizes[sizes.len() / 2]
+}
+
+/// 在页面中部寻找竖直空白带，找到时返回其 x 坐标
+fn find_gutter(page: &Page) -> Option<f64> {
+    let fragments: Vec<&Fragment> = page
+        .fragments
+        .iter()
+        .filter(|f| f.text.trim().chars().count() > 1)
+        .collect();
+    if fragments.len() < 10 || page.width <= 0.0 {
+        return None;
+    }
+
+    let total = fragments.len() as f64;
+    let mut best: Option<(usize, f64)> = None;
+    let step = page.width / 100.0;
+    for i in 35..=65 {
+        let x = step * i as f64;
+        let overlap = fragments.iter().filter(|f| f.x0 < x && f.x1 > x).count();
+        if best.map(|(count, _)| overlap < count).unwrap_or(true) {
+            best = Some((overlap, x));
+        }
+    }
+
+    let (overlap, x) = best?;
+    let left = fragments.iter().filter(|f| f.x1 <= x).count() as f64;
+    let right = fragments.iter().filter(|f| f.x0 >= x).count() as f64;
+    (overlap as f64 <= total * MAX_GUTTER_OVERLAP && left >= total * MIN_COLUMN_SHARE && right >= total * MIN_COLUMN_SHARE)
+        .then_some(x)
+}
+
+/// 输出一页文本：无分栏时按行从上到下；有分栏时以跨栏片段为界分段，每段内先左栏后右栏
+fn render_page(mut fragments: Vec<Fragment>, gutter: Option<f64>, page_width: f64) -> String {
+    fragments.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x0.total_cmp(&b.x0)));
+    let Some(gutter) = gutter else {
+        return render_lines(&fragments);
+    };
+
+    let mut blocks: Vec<String> = Vec::new();
+    let mut left: Vec<Fragment> = Vec::new();
+    let mut right: Vec<Fragment> = Vec::new();
+    let mut spanning: Vec<Fragment> = Vec::new();
+
+    let flush_columns = |left: &mut Vec<Fragment>, right: &mut Vec<Fragment>, blocks: &mut Vec<String>| {
+        for column in [left, right] {
+            if !column.is_empty() {
+                blocks.push(render_lines(column));
+                column.clear();
+            }
+        }
+    };
+
+    for fragment in fragments {
+        // 只略微越过分隔线的片段按中心归入某一栏
+        let margin = page_width * 0.05;
+        if fragment.x0 < gutter - margin && fragment.x1 > gutter + margin {
+            flush_columns(&mut left, &mut right, &mut blocks);
+            spanning.push(fragment);
+            continue;
+        }
+        if !spanning.is_empty() {
+            blocks.push(render_lines(&spanning));
+            spanning.clear();
+        }
+        if (fragment.x0 + fragment.x1) / 2.0 <= gutter {
+            left.push(fragment);
+        } else {
+            right.push(fragment);
+        }
+    }
+    if !spanning.is_empty() {
+        blocks.push(render_lines(&spanning));
+    }
+    flush_columns(&mut left, &mut right, &mut blocks);
+
+    blocks.join("\n\n")
+}
+
+/// 把同一基线的片段拼成一行；行距明显大于字号时插入空行
+fn render_lines(fragments: &[Fragment]) -> String {
+    let mut sorted = fragments.to_vec();
+    sorted.sort_by(|a, b| a.y.total_cmp(&b.y).then(a.x0.total_cmp(&b.x0)));
+
+    let mut lines: Vec<(f64, f64, Vec<Fragment>)> = Vec::new();
+    for fragment in sorted {
+        match lines.last_mut() {
+            Some((y, size, line)) if (fragment.y - *y).abs() <= size.min(fragment.size) * 0.5 => line.push(fragment),
+            _ => lines.push((fragment.y, fragment.size, vec![fragment])),
+        }
+    }
+
+    let mut text = String::new();
+    let mut previous: Option<(f64, f64)> = None;
+    for (y, size, mut line) in lines {
+        line.sort_by(|a, b| a.x0.total_cmp(&b.x0));
+        if let Some((previous_y, previous_size)) = previous {
+            text.push('\n');
+            if y - previous_y > previous_size.max(size) * 1.5 {
+                text.push('\n');
+            }
+        }
+        // 同一行的片段之间是大间距（表格列、公式编号），用两个空格分隔以便表格识别
+        let joined: Vec<&str> = line.iter().map(|f| f.text.trim()).collect();
+        text.push_str(&joined.join("  "));
+        previous = Some((y, size));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WIDTH: f64 = 612.0;
+
+    fn fragment(x0: f64, x1: f64, y: f64, text: &str) -> Fragment {
+        Fragment { x0, x1, y, size: 10.0, text: text.to_string() }
+    }
+
+    fn page(fragments: Vec<Fragment>) -> Page {
+        Page { origin_x: 0.0, top: 792.0, width: WIDTH, height: 792.0, fragments }
+    }
+
+    /// 两栏正文，每栏 `lines` 行，从 `top` 开始每行下移 12pt
+    fn two_columns(top: f64, lines: usize) -> Vec<Fragment> {
+        (0..lines)
+            .flat_map(|i| {
+                let y = top + 12.0 * i as f64;
+                [
+                    fragment(72.0, 290.0, y, &format!("left line {}", i)),
+                    fragment(322.0, 540.0, y, &format!("right line {}", i)),
+                ]
+            })
+            .collect()
+    }
+
+    fn render(page: Page) -> String {
+        let gutter = find_gutter(&page);
+        render_page(page.fragments, gutter, page.width)
+    }
+
+    #[test]
+    fn two_columns_read_left_then_right() {
+        let page = page(two_columns(100.0, 6));
+        let gutter = find_gutter(&page).expect("应检测到分栏");
+        assert!(gutter > 290.0 && gutter < 322.0);
+
+        let text = render(page);
+        let left_end = text.find("left line 5").unwrap();
+        let right_start = text.find("right line 0").unwrap();
+        assert!(left_end < right_start, "{}", text);
+        assert!(text.starts_with("left line 0\nleft line 1"));
+    }
+
+    #[test]
+    fn full_width_title_stays_above_columns() {
+        let mut fragments = vec![fragment(100.0, 510.0, 60.0, "A Spanning Paper Title")];
+        fragments.extend(two_columns(100.0, 6));
+        let text = render(page(fragments));
+
+        assert!(text.starts_with("A Spanning Paper Title\n\nleft line 0"), "{}", text);
+        assert!(text.find("left line 5").unwrap() < text.find("right line 0").unwrap());
+    }
+
+    #[test]
+    fn single_column_keeps_line_order() {
+        let fragments: Vec<Fragment> = (0..12)
+            .map(|i| fragment(72.0, 540.0, 100.0 + 12.0 * i as f64, &format!("body line {}", i)))
+            .collect();
+        let page = page(fragments);
+        assert!(find_gutter(&page).is_none());
+
+        let text = render(page);
+        let expected: Vec<String> = (0..12).map(|i| format!("body line {}", i)).collect();
+        assert_eq!(text, expected.join("\n"));
+    }
+}
@@ -0,0 +1,166 @@
+use regex::Regex;
+use std::collections::HashSet;
+use tracing::info;
+
+use super::{Link, PageIndex};
+
+/// 从正文中识别 DOI、arXiv ID 和网址（项目主页、数据集等）
+///
+/// 参考文献章节中的标识符已在 `references` 表中逐条记录，这里跳过，只保留正文、脚注中出现的链接。
+pub struct LinkExtractor {
+    references_heading: Regex,
+    doi: Regex,
+    arxiv: Regex,
+    url: Regex,
+    /// doi.org 或 arxiv.org 的网址，归入对应标识符
+    doi_url: Regex,
+    arxiv_url: Regex,
+    /// GitHub / GitLab / Hugging Face 仓库地址，捕获到仓库名为止
+    repository: Regex,
+    /// 网址前文表明是本文代码：「Code is available at」「We release our models at」等
+    code_context: Regex,
+    /// 脚注行：以编号或 *†‡ 开头、只含简短说明和网址
+    footnote: Regex,
+}
+
+impl LinkExtractor {
+    pub fn new() -> Self {
+        Self {
+            references_heading: Regex::new(r"(?im)^(?:\d+\.?\s*)?(?:references|bibliography|literature\s+cited)\s*$").unwrap(),
+            doi: Regex::new(r"\b(10\.\d{4,9}/[^\s,;<>]+)").unwrap(),
+            arxiv: Regex::new(r"(?i)\barxiv:\s*(\d{4}\.\d{4,5})(?:v\d+)?").unwrap(),
+            url: Regex::new(r"(?i)\b(?:https?://|www\.)[^\s<>\[\]{}]+").unwrap(),
+            doi_url: Regex::new(r"(?i)^https?://(?:dx\.)?doi\.org/(10\.\d{4,9}/.+)$").unwrap(),
+            arxiv_url: Regex::new(r"(?i)^https?://arxiv\.org/(?:abs|pdf)/(\d{4}\.\d{4,5})").unwrap(),
+            repository: Regex::new(
+                r"(?i)^(?:https?://)?(?:www\.)?((?:github\.com|gitlab\.com)/[\w.-]+/[\w.-]+|huggingface\.co/(?:(?:datasets|spaces)/)?[\w.-]+(?:/[\w.-]+)?)",
+            )
+            .unwrap(),
+            code_context: Regex::new(
+                r"(?i)\b(?:code|codes|implementation|source|models?|weights|checkpoints|datasets?)\b[^.]{0,60}\b(?:available|released?|found|hosted|open[- ]?sourced?|public)\b|\b(?:we|our)\b[^.]{0,40}\b(?:release|open[- ]?source|publish)",
+            )
+            .unwrap(),
+            footnote: Regex::new(r"^(?:\d{1,2}|[*†‡§¶])\s*(?:[^\s]+\s+){0,8}(?:https?://|www\.)\S+$").unwrap(),
+        }
+    }
+
+    /// 按首次出现的顺序返回去重后的链接
+    pub fn extract(&self, full_text: &str) -> Vec<Link> {
+        // 正文中也可能出现单独一行的 "References"，取最后一个
+        let body_end = self
+            .references_heading
+            .find_iter(full_text)
+            .last()
+            .map(|m| m.start())
+            .unwrap_or(full_text.len());
+        let body = &full_text[..body_end];
+        let pages = PageIndex::new(full_text);
+
+        let mut found: Vec<(usize, Link)> = Vec::new();
+        let mut url_spans: Vec<(usize, usize)> = Vec::new();
+
+        for m in self.url.find_iter(body) {
+            let raw = trim_trailing(m.as_str());
+            url_spans.push((m.start(), m.start() + raw.len()));
+            let url = if raw.to_lowercase().starts_with("www.") { format!("https://{}", raw) } else { raw.to_string() };
+            let link = if let Some(caps) = self.doi_url.captures(&url) {
+                doi_link(&caps[1])
+            } else if let Some(caps) = self.arxiv_url.captures(&url) {
+                arxiv_link(&caps[1])
+            } else {
+                Link { kind: "url".to_string(), value: raw.to_string(), url, page: None }
+            };
+            found.push((m.start(), link));
+        }
+
+        // 已作为网址一部分出现的 DOI 不再重复识别
+        let inside_url = |pos: usize| url_spans.iter().any(|&(start, end)| pos >= start && pos < end);
+        for caps in self.doi.captures_iter(body) {
+            let m = caps.get(1).unwrap();
+            if !inside_url(m.start()) {
+                found.push((m.start(), doi_link(trim_trailing(m.as_str()))));
+            }
+        }
+        for caps in self.arxiv.captures_iter(body) {
+            let m = caps.get(0).unwrap();
+            if !inside_url(m.start()) {
+                found.push((m.start(), arxiv_link(&caps[1])));
+            }
+        }
+
+        found.sort_by_key(|(position, _)| *position);
+        let mut seen: HashSet<String> = HashSet::new();
+        let links: Vec<Link> = found
+            .into_iter()
+            .filter(|(_, link)| seen.insert(link.url.to_lowercase()))
+            .map(|(position, mut link)| {
+                link.page = Some(pages.page_at(position));
+                link
+            })
+            .collect();
+
+        info!("链接提取完成，共 {} 个", links.len());
+        links
+    }
+
+    /// 本文的代码仓库链接（GitHub、GitLab、Hugging Face），按出现顺序去重
+    ///
+    /// 只收录摘要中的、「Code is available at ...」这类句子中的，以及前两页脚注中的链接，
+    /// 正文其他位置的仓库多是引用的基线实现。
+    pub fn code_repositories(&self, abstract_text: Option<&str>, full_text: &str) -> Vec<String> {
+        let mut candidates: Vec<String> = Vec::new();
+        if let Some(abstract_text) = abstract_text {
+            candidates.extend(self.url.find_iter(abstract_text).map(|m| m.as_str().to_string()));
+        }
+
+        let pages = PageIndex::new(full_text);
+        let mut offset = 0;
+        for line in full_text.split_inclusive('\n') {
+            let page = pages.page_at(offset);
+            offset += line.len();
+            let line = line.trim();
+            let footnote = page <= 2 && line.len() <= 200 && self.footnote.is_match(line);
+            for m in self.url.find_iter(line) {
+                let before = &line[line.floor_char_boundary(m.start().saturating_sub(150))..m.start()];
+                if footnote || self.code_context.is_match(before) {
+                    candidates.push(m.as_str().to_string());
+                }
+            }
+        }
+
+        let mut seen: HashSet<String> = HashSet::new();
+        candidates
+            .iter()
+            .filter_map(|url| self.repository.captures(trim_trailing(url)))
+            .map(|caps| format!("https://{}", caps[1].trim_end_matches(".git").trim_end_matches('.')))
+            .filter(|url| seen.insert(url.to_lowercase()))
+            .collect()
+    }
+}
+
+fn doi_link(doi: &str) -> Link {
+    Link {
+        kind: "doi".to_string(),
+        value: doi.to_string(),
+        url: format!("https://doi.org/{}", doi),
+        page: None,
+    }
+}
+
+fn arxiv_link(id: &str) -> Link {
+    Link {
+        kind: "arxiv".to_string(),
+        value: id.to_string(),
+        url: format!("https://arxiv.org/abs/{}", id),
+        page: None,
+    }
+}
+
+/// 去掉句末标点和不成对的右括号
+fn trim_trailing(raw: &str) -> &str {
+    let mut text = raw.trim_end_matches(['.', ',', ';', ':', '\'', '"', '”', '’']);
+    while text.ends_with(')') && text.matches('(').count() < text.matches(')').count() {
+        text = text[..text.len() - 1].trim_end_matches(['.', ',', ';', ':']);
+    }
+    text
+}
@@ -0,0 +1,121 @@
+use regex::Regex;
+use tracing::debug;
+
+use super::{Section, PAGE_BREAK};
+
+/// 发表信息：在首页的页眉页脚中查找会议/期刊和本文的编号，在致谢章节中查找资助声明
+pub struct MetadataExtractor {
+    /// 按优先级排列，第一个捕获组为出处
+    venue_patterns: Vec<Regex>,
+    /// 出处中缺年份时从同一行补充
+    year: Regex,
+    acknowledgments_heading: Regex,
+    funding_sentence: Regex,
+    /// 首页脚注中的资助说明：「This work was supported by ...」
+    funding_footnote: Regex,
+    arxiv_id: Regex,
+    doi: Regex,
+}
+
+impl MetadataExtractor {
+    pub fn new() -> Self {
+        Self {
+            venue_patterns: vec![
+                // Published as a conference paper at ICLR 2025
+                Regex::new(r"(?i)published\s+as\s+an?\s+(?:conference|workshop|journal)\s+paper\s+(?:at|in)\s+(?:the\s+)?([A-Za-z][^.,;]*?(?:19|20)\d{2})").unwrap(),
+                // 37th Conference on Neural Information Processing Systems (NeurIPS 2023).
+                Regex::new(r"(?:Conference|Symposium|Workshop)\s+on\s+[^()]+\(([A-Za-z][\w-]*\s*'?(?:19|20)?\d{2})\)").unwrap(),
+                // Proceedings of the 40th International Conference on Machine Learning, Honolulu, ...
+                Regex::new(r"Proceedings\s+of\s+the\s+([^,]+?)(?:,|\.|$)").unwrap(),
+                // Accepted at / To appear in ACL 2024
+                Regex::new(r"(?i)(?:accepted|to\s+appear|appearing)(?:\s+as\s+[^.]{0,40}?)?\s+(?:at|in|to|by)\s+(?:the\s+)?([A-Z][^.,;]{1,80}?(?:19|20)\d{2})").unwrap(),
+            ],
+            year: Regex::new(r"\b(?:19|20)\d{2}\b").unwrap(),
+            acknowledgments_heading: Regex::new(r"(?i)^(?:\d+\.?\s*)?(?:acknowledge?ments?|funding)\b").unwrap(),
+            funding_sentence: Regex::new(
+                r"(?i)\b(?:supported|funded|sponsored|financed)\b|\bgrants?\b|\bfunding\b|\bfellowship\b|\baward\s+(?:no\.?|number|#)",
+            )
+            .unwrap(),
+            funding_footnote: Regex::new(r"(?i)\bthis\s+(?:work|research|project)\s+(?:was|is|has\s+been)\s+(?:\w+\s+){0,2}(?:supported|funded)\b").unwrap(),
+            arxiv_id: Regex::new(r"(?i)\barxiv:\s*(\d{4}\.\d{4,5}(?:v\d+)?)").unwrap(),
+            doi: Regex::new(r"(?i)(?:\bdoi:?\s*|doi\.org/)(10\.\d{4,9}/[^\s,;]+)").unwrap(),
+        }
+    }
+
+    /// 首页上的出处，如 "ICLR 2025"、"NeurIPS 2023"
+    pub fn venue(&self, full_text: &str) -> Option<String> {
+        let first_page = first_page(full_text);
+        for pattern in &self.venue_patterns {
+            for line in first_page.lines() {
+                let Some(caps) = pattern.captures(line.trim()) else { continue };
+                let mut venue = caps[1].trim().to_string();
+                if !self.year.is_match(&venue) {
+                    if let Some(year) = self.year.find(line) {
+                        venue = format!("{} {}", venue, year.as_str());
+                    }
+                }
+                debug!("识别到出处: {}", venue);
+                return Some(venue);
+            }
+        }
+        None
+    }
+
+    /// 首页上本文的 arXiv 编号，如 "2401.12345v2"
+    pub fn arxiv_id(&self, full_text: &str) -> Option<String> {
+        let caps = self.arxiv_id.captures(first_page(full_text))?;
+        Some(caps[1].to_string())
+    }
+
+    /// 首页上标注的 DOI（「DOI: 10.1145/...」或 doi.org 链接），去掉句末标点
+    pub fn doi(&self, full_text: &str) -> Option<String> {
+        let caps = self.doi.captures(first_page(full_text))?;
+        let doi = caps[1].trim_end_matches(['.', ')', ']']);
+        Some(doi.to_string())
+    }
+
+    /// 致谢章节中提到资助的句子，以及首页脚注中的资助说明
+    pub fn funding(&self, full_text: &str, sections: &[Section]) -> Vec<String> {
+        let mut statements: Vec<String> = sections
+            .iter()
+            .filter(|s| self.acknowledgments_heading.is_match(&s.heading))
+            .flat_map(|s| split_sentences(&s.body))
+            .filter(|sentence| self.funding_sentence.is_match(sentence))
+            .collect();
+
+        for sentence in first_page(full_text).lines().flat_map(split_sentences) {
+            if self.funding_footnote.is_match(&sentence) && !statements.contains(&sentence) {
+                statements.push(sentence);
+            }
+        }
+        statements
+    }
+}
+
+/// 第一页的文本（没有分页符时为全文）
+fn first_page(full_text: &str) -> &str {
+    full_text.split(PAGE_BREAK).next().unwrap_or_default()
+}
+
+/// 按句号切分句子，忽略 "et al."、"e.g." 和 "No. 123" 之类的缩写
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let words: Vec<&str> = text.split_whitespace().collect();
+    for (i, word) in words.iter().enumerate() {
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+        let abbreviation = ["al.", "e.g.", "i.e.", "No.", "no.", "Dr.", "Prof.", "Inc.", "Co.", "Ltd."].contains(word)
+            || (word.len() == 2 && word.ends_with('.') && word.starts_with(char::is_uppercase));
+        let next_starts_sentence = words.get(i + 1).map(|w| w.starts_with(char::is_uppercase)).unwrap_or(true);
+        if word.ends_with(['.', '!', '?']) && !abbreviation && next_starts_sentence {
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.trim().is_empty() {
+        sentences.push(current);
+    }
+    sentences
+}
@@ -1,23 +1,91 @@
+pub mod citation_linker;
 pub mod pdf_parser;
 pub mod formula_extractor;
 pub mod image_analyzer;
+pub mod keyphrase_extractor;
+pub mod latex;
+pub mod layout;
+pub mod link_extractor;
+pub mod metadata_extractor;
+pub mod normalizer;
+pub mod pdf_loader;
+pub mod rasterizer;
+pub mod reference_extractor;
 pub mod table_parser;
 
+pub use citation_linker::CitationLinker;
 pub use pdf_parser::PdfParser;
-pub use formula_extractor::FormulaExtractor;
+pub use formula_extractor::{FormulaExtractor, EQUATION_REFERENCE_PATTERN, MAX_STORED_FORMULAS};
 pub use image_analyzer::ImageAnalyzer;
+pub use keyphrase_extractor::KeyphraseExtractor;
+pub use link_extractor::LinkExtractor;
+pub use metadata_extractor::MetadataExtractor;
+pub use rasterizer::Rasterizer;
+pub use reference_extractor::ReferenceExtractor;
 pub use table_parser::TableParser;
 
 use anyhow::Result;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use tracing::{info, warn};
 
+use crate::config::ParserConfig;
+use crate::storage::images::ImageStore;
+
+/// 缓存文本的格式版本，v2 起各页之间带分页符
+const TEXT_CACHE_VERSION: u32 = 2;
+
+/// 全文中的分页符，`PdfParser` 在相邻两页的文本之间单独占一行
+pub const PAGE_BREAK: char = '\x0c';
+
+/// 全文的分页位置，用于把字节偏移或行号换算为页码（从 1 开始）
+pub struct PageIndex {
+    breaks: Vec<usize>,
+}
+
+impl PageIndex {
+    pub fn new(full_text: &str) -> Self {
+        Self {
+            breaks: full_text.match_indices(PAGE_BREAK).map(|(i, _)| i).collect(),
+        }
+    }
+
+    pub fn page_at(&self, offset: usize) -> usize {
+        self.breaks.partition_point(|&b| b < offset) + 1
+    }
+
+    /// 与 `full_text.lines()` 一一对应的页码
+    pub fn line_pages(&self, full_text: &str) -> Vec<usize> {
+        let mut offset = 0;
+        full_text
+            .split_inclusive('\n')
+            .map(|line| {
+                let page = self.page_at(offset);
+                offset += line.len();
+                page
+            })
+            .collect()
+    }
+}
+
+/// 旧数据没有可信度字段，视为完全可信，不会被 `min_confidence` 过滤
+fn full_confidence() -> f32 {
+    1.0
+}
+
 /// 论文章节
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Section {
     pub heading: String,
     pub level: u8,
     pub body: String,
+    /// 章节标题所在页
+    #[serde(default)]
+    pub page: Option<usize>,
+    /// 标题识别的可信度（0~1），取决于命中的标题模式
+    #[serde(default = "full_confidence")]
+    pub confidence: f32,
 }
 
 /// 论文元数据
@@ -28,6 +96,71 @@ pub struct PaperMetadata {
     pub authors: Vec<String>,
     pub abstract_text: Option<String>,
     pub abstract_zh: Option<String>,
+    /// LLM 生成的要点，由爬取阶段写入数据库，生成报告时注入
+    #[serde(default)]
+    pub key_points: Vec<String>,
+    /// 本文的代码仓库（GitHub、GitLab、Hugging Face）
+    #[serde(default)]
+    pub code_urls: Vec<String>,
+    /// 发表的会议或期刊，如 "ICLR 2025"
+    #[serde(default)]
+    pub venue: Option<String>,
+    /// 致谢中的资助声明
+    #[serde(default)]
+    pub funding: Vec<String>,
+    /// 首页上本文的 arXiv 编号（页边的 "arXiv:2401.12345v2 [cs.CL]"）和 DOI，导入本地PDF时据此查询元数据
+    #[serde(default)]
+    pub arxiv_id: Option<String>,
+    #[serde(default)]
+    pub doi: Option<String>,
+    /// 爬取时与订阅的相关度（0~1），生成报告时注入
+    #[serde(default)]
+    pub relevance: Option<f32>,
+    /// 相关度评分依据，生成报告时注入
+    #[serde(default)]
+    pub relevance_reasons: Vec<String>,
+    /// 数据库中的来源、发布日期和所属订阅，用于报告分组和排序
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub publish_date: Option<String>,
+    #[serde(default)]
+    pub subscription: Option<String>,
+    /// 数据库中的已读和星标状态，报告中淡化已读论文、星标论文排在最前
+    #[serde(default)]
+    pub read: bool,
+    #[serde(default)]
+    pub starred: bool,
+}
+
+impl PaperMetadata {
+    /// 用爬虫获得的标题核对提取的标题：两者的单词重合度过低时（多半取到了页眉或水印），改用爬虫标题
+    pub fn cross_check_title(&mut self, known_title: &str) {
+        if known_title.trim().is_empty() {
+            return;
+        }
+        let matches = self.title.as_deref().map(|t| titles_match(t, known_title)).unwrap_or(false);
+        if !matches {
+            info!("提取的标题 {:?} 与爬取的标题不符，改用: {}", self.title, known_title);
+            self.title = Some(known_title.trim().to_string());
+        }
+    }
+}
+
+/// 两个标题的单词（小写、去标点）重合比例不低于 0.6 视为同一标题
+pub fn titles_match(a: &str, b: &str) -> bool {
+    let words = |text: &str| -> HashSet<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+    let shared = a.intersection(&b).count() as f64;
+    shared / a.len().max(b.len()) as f64 >= 0.6
 }
 
 /// 提取的公式
@@ -35,16 +168,48 @@ pub struct PaperMetadata {
 pub struct Formula {
     pub raw: String,
     pub context: String,
+    /// 命中的匹配模式
+    #[serde(default)]
+    pub kind: String,
+    /// 公式编号，如 "3"、"2.1"
+    #[serde(default)]
+    pub number: Option<String>,
+    /// 去重合并前在全文中匹配到的次数
+    #[serde(default)]
+    pub occurrences: usize,
+    /// 正文中是否引用了该编号公式（Eq. (n)）
+    #[serde(default)]
+    pub referenced: bool,
+    /// 首次出现的页码
+    #[serde(default)]
+    pub page: Option<usize>,
+    /// 由 `raw` 转换的 LaTeX（不含公式编号），用于在报告中渲染
+    #[serde(default)]
+    pub latex: Option<String>,
+    /// 确为公式的可信度（0~1），取决于匹配模式、编号和引用情况
+    #[serde(default = "full_confidence")]
+    pub confidence: f32,
+}
+
+impl Formula {
+    /// 是否为独立成行的展示公式
+    pub fn is_display(&self) -> bool {
+        matches!(self.kind.as_str(), "equation" | "display_latex") || self.number.is_some()
+    }
 }
 
 /// 提取的图片
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractedImage {
+    /// 图片存储中的哈希键（旧数据可能是文件路径，可用 relocate-data 迁移）
     pub filename: String,
     pub page: usize,
     pub width: u32,
     pub height: u32,
     pub format: String,
+    /// 同一页上对应的图注（Figure N: ...）
+    #[serde(default)]
+    pub caption: Option<String>,
 }
 
 /// 提取的表格
@@ -53,6 +218,83 @@ pub struct Table {
     pub caption: Option<String>,
     pub headers: Vec<String>,
     pub rows: Vec<Vec<String>>,
+    /// 表格（或其标题）所在页
+    #[serde(default)]
+    pub page: Option<usize>,
+    /// 表格结构的可信度（0~1），取决于是否有标题和各行列数是否一致
+    #[serde(default = "full_confidence")]
+    pub confidence: f32,
+}
+
+impl Table {
+    /// 结构是否可信：至少两列，每行列数与表头一致，空单元格不超过三成
+    pub fn is_well_formed(&self) -> bool {
+        let columns = self.headers.len();
+        if columns < 2 || self.rows.is_empty() || self.rows.iter().any(|r| r.len() != columns) {
+            return false;
+        }
+        let cells = columns * self.rows.len();
+        let empty = self.rows.iter().flatten().filter(|c| c.trim().is_empty()).count();
+        empty * 10 <= cells * 3
+    }
+}
+
+/// 参考文献条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reference {
+    /// 文中编号，如 `[12]` 中的 "12"；作者-年份格式为 None
+    pub label: Option<String>,
+    /// 合并换行后的原文
+    pub raw: String,
+    pub authors: Vec<String>,
+    pub year: Option<i32>,
+    pub title: Option<String>,
+    pub venue: Option<String>,
+    pub arxiv_id: Option<String>,
+    pub doi: Option<String>,
+}
+
+/// 关键短语及其 RAKE 得分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keyphrase {
+    /// 小写形式
+    pub phrase: String,
+    pub score: f64,
+    /// 在正文中出现的次数
+    pub occurrences: usize,
+}
+
+/// 正文中出现的 DOI、arXiv ID 或网址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Link {
+    /// "doi"、"arxiv" 或 "url"
+    pub kind: String,
+    /// 原文中的标识符或网址
+    pub value: String,
+    /// 可直接打开的地址
+    pub url: String,
+    /// 首次出现的页码
+    #[serde(default)]
+    pub page: Option<usize>,
+}
+
+/// 章节中引用的一条参考文献
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitedReference {
+    /// 在 `PaperContent.references` 中的位置
+    pub position: usize,
+    /// 本章节中被引用的次数
+    pub mentions: usize,
+}
+
+/// 单个章节的文内引用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SectionCitations {
+    pub heading: String,
+    /// 按首次引用顺序排列
+    pub references: Vec<CitedReference>,
+    /// 无法对应到参考文献的引用数
+    pub unresolved: usize,
 }
 
 /// 聚合全部提取结果
@@ -60,47 +302,131 @@ pub struct Table {
 pub struct PaperContent {
     pub metadata: PaperMetadata,
     pub sections: Vec<Section>,
+    /// 按重要性排序，最多保留 MAX_STORED_FORMULAS 个
     pub formulas: Vec<Formula>,
+    /// 去重后的公式总数（含未保存的部分）
+    #[serde(default)]
+    pub formula_total: usize,
     pub images: Vec<ExtractedImage>,
     pub tables: Vec<Table>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
+    #[serde(default)]
+    pub citations: Vec<SectionCitations>,
+    #[serde(default)]
+    pub links: Vec<Link>,
+    /// 按得分排序的关键短语
+    #[serde(default)]
+    pub keyphrases: Vec<Keyphrase>,
     pub full_text: String,
 }
 
+impl PaperContent {
+    /// 去掉可信度低于 `min_confidence` 的章节、公式和表格
+    pub fn retain_confident(&mut self, min_confidence: f32) {
+        if min_confidence <= 0.0 {
+            return;
+        }
+        self.sections.retain(|s| s.confidence >= min_confidence);
+        self.formulas.retain(|f| f.confidence >= min_confidence);
+        self.tables.retain(|t| t.confidence >= min_confidence);
+    }
+}
+
 /// 统一提取管道
 pub struct ExtractionPipeline {
     pdf_parser: PdfParser,
     formula_extractor: FormulaExtractor,
     image_analyzer: ImageAnalyzer,
     table_parser: TableParser,
+    reference_extractor: ReferenceExtractor,
+    citation_linker: CitationLinker,
+    link_extractor: LinkExtractor,
+    keyphrase_extractor: KeyphraseExtractor,
+    keyphrase_count: usize,
+    metadata_extractor: MetadataExtractor,
+    /// 开启 `rasterize_figures` 且 pdfium 或栅格化命令可用时才有
+    rasterizer: Option<Rasterizer>,
 }
 
 impl ExtractionPipeline {
-    pub fn new() -> Self {
+    pub fn new(config: &ParserConfig) -> Self {
         Self {
-            pdf_parser: PdfParser::new(),
+            pdf_parser: PdfParser::with_layout(config.layout_aware),
             formula_extractor: FormulaExtractor::new(),
-            image_analyzer: ImageAnalyzer::new(),
+            image_analyzer: ImageAnalyzer::with_min_area(config.min_image_area),
             table_parser: TableParser::new(),
+            reference_extractor: ReferenceExtractor::new(),
+            citation_linker: CitationLinker::new(),
+            link_extractor: LinkExtractor::new(),
+            keyphrase_extractor: KeyphraseExtractor::new(),
+            keyphrase_count: config.keyphrase_count,
+            metadata_extractor: MetadataExtractor::new(),
+            rasterizer: config.rasterize_figures.then(|| Rasterizer::new(config)).filter(|r| {
+                let available = r.is_available();
+                if !available {
+                    warn!("栅格化命令不可用: {}，跳过矢量图提取", r.backend_name());
+                }
+                available
+            }),
         }
     }
 
     /// 处理一篇论文的PDF，返回全部提取结果
-    pub fn process(&self, pdf_path: &str, paper_id: &str, images_dir: &str) -> Result<PaperContent> {
+    pub fn process(&self, pdf_path: &str, image_store: &dyn ImageStore) -> Result<PaperContent> {
+        // 1. 提取全文
+        let full_text = self.extract_text(pdf_path)?;
+        self.process_text(full_text, pdf_path, image_store)
+    }
+
+    /// 仅提取PDF文本层，结果可缓存后交给 `process_text`
+    pub fn extract_text(&self, pdf_path: &str) -> Result<String> {
+        self.pdf_parser.extract_full_text(pdf_path)
+    }
+
+    /// pdf_texts 缓存键：PDF 哈希加提取模式和文本格式版本，切换模式或升级后不会读到旧格式的文本
+    pub fn text_cache_key(&self, pdf_hash: &str) -> String {
+        format!("{}:{}:v{}", pdf_hash, self.pdf_parser.text_mode(), TEXT_CACHE_VERSION)
+    }
+
+    /// 提取全文中的全部公式（不截断），按重要性排序
+    pub fn extract_formulas(&self, full_text: &str) -> Vec<Formula> {
+        self.formula_extractor.extract(&normalizer::normalize(full_text))
+    }
+
+    /// 基于已提取的全文运行其余提取步骤，图片仍从PDF读取
+    pub fn process_text(
+        &self,
+        full_text: String,
+        pdf_path: &str,
+        image_store: &dyn ImageStore,
+    ) -> Result<PaperContent> {
         info!("开始提取管道: {}", pdf_path);
 
-        // 1. 提取全文
-        let full_text = self.pdf_parser.extract_full_text(pdf_path)?;
+        // 合并断词和段落内换行，之后的步骤都基于规范化文本
+        let full_text = normalizer::normalize(&full_text);
 
         // 2. 结构化文本提取
-        let (metadata, sections) = self.pdf_parser.extract_structured_text(&full_text);
+        let (mut metadata, sections) = self.pdf_parser.extract_structured_text(&full_text);
         info!("提取到 {} 个章节", sections.len());
+        match layout::title_by_font_size(pdf_path) {
+            Ok(Some(title)) => metadata.title = Some(title),
+            Ok(None) => {}
+            Err(e) => warn!("按字号识别标题失败: {}，使用首行文本", e),
+        }
+        metadata.venue = self.metadata_extractor.venue(&full_text);
+        metadata.funding = self.metadata_extractor.funding(&full_text, &sections);
+        metadata.arxiv_id = self.metadata_extractor.arxiv_id(&full_text);
+        metadata.doi = self.metadata_extractor.doi(&full_text);
 
         // 3. 公式提取
-        let formulas = self.formula_extractor.extract(&full_text);
-        info!("提取到 {} 个公式", formulas.len());
+        let mut formulas = self.formula_extractor.extract(&full_text);
+        let formula_total = formulas.len();
+        formulas.truncate(MAX_STORED_FORMULAS);
+        info!("提取到 {} 个公式，保留前 {} 个", formula_total, formulas.len());
 
         // 4. 图片提取
-        let images = match self.image_analyzer.extract_images(pdf_path, paper_id, images_dir) {
+        let mut images = match self.image_analyzer.extract_images(pdf_path, image_store) {
             Ok(imgs) => {
                 info!("提取到 {} 张图片", imgs.len());
                 imgs
@@ -111,17 +437,131 @@ impl ExtractionPipeline {
             }
         };
 
+        associate_figure_captions(&full_text, &mut images);
+        if let Some(ref rasterizer) = self.rasterizer {
+            rasterize_missing_figures(rasterizer, pdf_path, &mut images, image_store);
+        }
+
         // 5. 表格解析
         let tables = self.table_parser.extract(&full_text);
         info!("提取到 {} 个表格", tables.len());
 
+        // 6. 参考文献
+        let references = self.reference_extractor.extract(&full_text);
+
+        // 7. 文内引用关联到参考文献
+        let citations = self.citation_linker.link(&sections, &references);
+
+        // 8. 正文中的 DOI、arXiv ID 和网址
+        let links = self.link_extractor.extract(&full_text);
+        metadata.code_urls = self
+            .link_extractor
+            .code_repositories(metadata.abstract_text.as_deref(), &full_text);
+
+        // 9. 关键短语
+        let keyphrases = self.keyphrase_extractor.extract(
+            metadata.title.as_deref(),
+            metadata.abstract_text.as_deref(),
+            &full_text,
+            self.keyphrase_count,
+        );
+
         Ok(PaperContent {
             metadata,
             sections,
             formulas,
+            formula_total,
             images,
             tables,
+            references,
+            citations,
+            links,
+            keyphrases,
             full_text,
         })
     }
 }
+
+/// 按页把图注分配给图片：一页只有一个图注时该页图片都使用它，
+/// 图注数与图片数相同时按顺序一一对应，其余情况不分配
+fn associate_figure_captions(full_text: &str, images: &mut [ExtractedImage]) {
+    let caption_re = Regex::new(r"^(?:Figure|Fig\.)\s*\d+[.:]").unwrap();
+    let pages = PageIndex::new(full_text);
+    let mut captions: HashMap<usize, Vec<String>> = HashMap::new();
+    for (line, page) in full_text.lines().zip(pages.line_pages(full_text)) {
+        let line = line.trim();
+        if caption_re.is_match(line) {
+            captions.entry(page).or_default().push(line.to_string());
+        }
+    }
+
+    for (page, page_captions) in captions {
+        let mut on_page: Vec<&mut ExtractedImage> = images.iter_mut().filter(|img| img.page == page).collect();
+        if page_captions.len() == 1 {
+            for image in on_page {
+                image.caption = Some(page_captions[0].clone());
+            }
+        } else if page_captions.len() == on_page.len() {
+            for (image, caption) in on_page.iter_mut().zip(page_captions) {
+                image.caption = Some(caption);
+            }
+        }
+    }
+}
+
+/// 为没有嵌入图片的「Figure N」栅格化图注上方的区域（矢量图）
+fn rasterize_missing_figures(
+    rasterizer: &Rasterizer,
+    pdf_path: &str,
+    images: &mut Vec<ExtractedImage>,
+    image_store: &dyn ImageStore,
+) {
+    let regions = match layout::figure_regions(pdf_path) {
+        Ok(regions) => regions,
+        Err(e) => {
+            warn!("定位图形区域失败: {}", e);
+            return;
+        }
+    };
+
+    let number_re = Regex::new(r"^(?:Figure|Fig\.)\s*(\d+)").unwrap();
+    let mut covered: HashSet<String> = images
+        .iter()
+        .filter_map(|img| img.caption.as_deref())
+        .filter_map(|caption| number_re.captures(caption).map(|caps| caps[1].to_string()))
+        .collect();
+
+    let mut rendered = 0;
+    for region in regions {
+        if !covered.insert(region.number.clone()) {
+            continue;
+        }
+        let data = match rasterizer.render(pdf_path, &region) {
+            Ok(data) => data,
+            Err(e) => {
+                warn!("栅格化 Figure {} 失败: {}", region.number, e);
+                continue;
+            }
+        };
+        let (width, height) = rasterizer.pixel_size(&region);
+        match image_store.put(&data, "png") {
+            Ok(filename) => {
+                images.push(ExtractedImage {
+                    filename,
+                    page: region.page,
+                    width,
+                    height,
+                    format: "png".to_string(),
+                    caption: Some(region.caption),
+                });
+                rendered += 1;
+            }
+            Err(e) => warn!("保存栅格化图片失败: {}", e),
+        }
+    }
+
+    if rendered > 0 {
+        images.sort_by_key(|img| img.page);
+        info!("栅格化 {} 张矢量图", rendered);
+    }
+}
@@ -0,0 +1,179 @@
+use super::PAGE_BREAK;
+
+/// 行长达到常见行宽的该比例才视为自动换行，更短的行是段落末行
+const WRAPPED_LINE_RATIO: f64 = 0.7;
+
+/// 规范化 PDF 提取的文本：合并行尾连字符断开的单词，把段落内的自动换行合并为空格
+///
+/// 章节标题、图表标题、列表项、表格行和公式行保持独占一行，参考文献章节只做连字符合并，
+/// 以便后续按行识别条目；空行和分页符原样保留。
+pub fn normalize(text: &str) -> String {
+    let text = text.replace('\u{00AD}', "");
+    let lines: Vec<&str> = text.lines().map(|l| l.trim_end_matches(is_blank)).collect();
+    let wrap_width = typical_line_width(&lines) as f64 * WRAPPED_LINE_RATIO;
+
+    let mut output: Vec<String> = Vec::new();
+    let mut in_references = false;
+    // 上一行是否可以与下一行合并
+    let mut open = false;
+
+    for line in lines {
+        let trimmed = line.trim_matches(is_blank);
+        if trimmed.is_empty() || trimmed.starts_with(PAGE_BREAK) {
+            output.push(line.to_string());
+            open = false;
+            continue;
+        }
+        if is_references_heading(trimmed) {
+            in_references = true;
+        }
+
+        let kind = classify(trimmed);
+        if let Some(previous) = output.last_mut().filter(|_| open) {
+            match join(previous, trimmed, kind != LineKind::Text || in_references) {
+                Some(joined) => *previous = joined,
+                None => output.push(trimmed.to_string()),
+            }
+        } else {
+            output.push(trimmed.to_string());
+        }
+
+        let current = output.last().map(String::as_str).unwrap_or_default();
+        let wrapped = line.chars().count() as f64 >= wrap_width;
+        open = kind != LineKind::Closed && (wrapped || ends_with_hyphen(current));
+    }
+
+    output.join("\n")
+}
+
+/// 合并上一行与当前行；返回 None 表示应另起一行
+///
+/// `keep_line` 为真时只合并连字符断词，不合并普通换行。
+fn join(previous: &str, line: &str, keep_line: bool) -> Option<String> {
+    let starts_lowercase = line.chars().next().map(char::is_lowercase).unwrap_or(false);
+    if ends_with_hyphen(previous) && starts_lowercase {
+        let stem = previous.trim_end_matches(['-', '\u{2010}']);
+        let last_word = stem.rsplit(char::is_whitespace).next().unwrap_or_default();
+        // "state-of-the-\nart" 这类复合词保留连字符
+        let separator = if last_word.contains('-') { "-" } else { "" };
+        return Some(format!("{}{}{}", stem, separator, line));
+    }
+    if keep_line {
+        return None;
+    }
+    Some(format!("{} {}", previous, line))
+}
+
+/// 行首尾要去掉的空白；分页符也是空白字符，但需要保留
+fn is_blank(c: char) -> bool {
+    c.is_whitespace() && c != PAGE_BREAK
+}
+
+fn ends_with_hyphen(line: &str) -> bool {
+    let mut chars = line.chars().rev();
+    matches!(chars.next(), Some('-' | '\u{2010}')) && chars.next().map(char::is_alphabetic).unwrap_or(false)
+}
+
+/// 正文行的常见宽度（字符数的 80 分位），用于区分自动换行和段落末行
+fn typical_line_width(lines: &[&str]) -> usize {
+    let mut widths: Vec<usize> = lines
+        .iter()
+        .map(|l| l.trim().chars().count())
+        .filter(|&w| w >= 20)
+        .collect();
+    if widths.is_empty() {
+        return usize::MAX;
+    }
+    widths.sort_unstable();
+    widths[widths.len() * 4 / 5]
+}
+
+fn is_references_heading(line: &str) -> bool {
+    let name = line.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.').trim().to_lowercase();
+    matches!(name.as_str(), "references" | "bibliography" | "literature cited")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LineKind {
+    /// 普通正文
+    Text,
+    /// 另起一行，但可以接续下一行：图表标题、列表项、参考文献编号
+    Block,
+    /// 独占一行：章节标题、表格行、公式行
+    Closed,
+}
+
+fn classify(line: &str) -> LineKind {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let first = words.first().copied().unwrap_or_default();
+
+    // "3 Method"、"3.1 Setup" 这类编号标题，及单独成行的常见章节名
+    let numbered_heading = first.trim_end_matches('.').split('.').all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+        && words.len() <= 8
+        && words.get(1).map(|w| w.starts_with(char::is_uppercase)).unwrap_or(false)
+        && !line.ends_with('.');
+    let named_heading = words.len() <= 3 && !line.ends_with('.') && line.starts_with(char::is_uppercase) && {
+        let name = line.to_lowercase();
+        ["abstract", "introduction", "related work", "method", "experiment", "result", "discussion", "conclusion", "acknowledg", "references", "appendix", "background"]
+            .iter()
+            .any(|known| name.starts_with(known))
+    };
+    let caption = ["Table ", "Figure ", "Fig. ", "Algorithm "].iter().any(|p| line.starts_with(p))
+        && words.get(1).map(|w| w.starts_with(|c: char| c.is_ascii_digit())).unwrap_or(false);
+    let list_item = line.starts_with(['•', '◦', '▪', '–']) || (line.starts_with('[') && line[1..].starts_with(|c: char| c.is_ascii_digit()));
+    let table_row = line.contains('\t') || line.contains("  ");
+    let equation = is_equation_line(line, &words);
+
+    if numbered_heading || named_heading || table_row || equation {
+        LineKind::Closed
+    } else if caption || list_item {
+        LineKind::Block
+    } else {
+        LineKind::Text
+    }
+}
+
+/// 以公式编号结尾，或含数学运算符且几乎没有普通单词的行
+fn is_equation_line(line: &str, words: &[&str]) -> bool {
+    let numbered = line.ends_with(')')
+        && line
+            .rsplit_once('(')
+            .map(|(_, n)| n.trim_end_matches(')').chars().all(|c| c.is_ascii_digit() || c == '.') && n.len() > 1)
+            .unwrap_or(false);
+    let has_operator = line.contains(['=', '≤', '≥', '≈', '∑', '∫', '∏', '∂', '∇']);
+    let prose_words = words
+        .iter()
+        .filter(|w| w.chars().count() >= 3 && w.chars().all(char::is_alphabetic))
+        .count();
+    has_operator && (numbered || prose_words < 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_words_split_by_line_end_hyphen() {
+        let text = "We study a convex op-\ntimization problem with sparse constraints.";
+        assert_eq!(normalize(text), "We study a convex optimization problem with sparse constraints.");
+    }
+
+    #[test]
+    fn keeps_hyphens_in_compound_words() {
+        let split = "Our approach improves over state-of-the-\nart methods on every benchmark.";
+        assert_eq!(normalize(split), "Our approach improves over state-of-the-art methods on every benchmark.");
+
+        let whole = "The results clearly surpass the state-of-the-art\nmethods on every single benchmark.";
+        assert_eq!(normalize(whole), "The results clearly surpass the state-of-the-art methods on every single benchmark.");
+    }
+
+    #[test]
+    fn keeps_page_breaks_and_paragraph_breaks() {
+        let text = "The first paragraph wraps across two lines of\nthe page here.\n\nThe second paragraph starts after a blank line\n\x0c\nand continues on the next page of the paper.";
+        let normalized = normalize(text);
+        assert_eq!(
+            normalized,
+            "The first paragraph wraps across two lines of the page here.\n\nThe second paragraph starts after a blank line\n\x0c\nand continues on the next page of the paper."
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use pdf_extract::Document;
+use regex::bytes::Regex;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+use crate::utils::BsxError;
+
+/// 打开PDF：xref 表损坏（偏移错位、截断）时按对象位置重建后重试，加密文件尝试空密码解密
+///
+/// 仍无法打开时返回 `BsxError::PdfError`，调用方据此区分「文件本身有问题」和其他提取错误。
+pub fn load_document(pdf_path: &str) -> Result<Document, BsxError> {
+    let bytes = std::fs::read(pdf_path)?;
+    let mut doc = match Document::load_mem(&bytes) {
+        // 偏移错位时常能「成功」读取，但找不到任何页面
+        Ok(doc) if !doc.get_pages().is_empty() => doc,
+        result => {
+            let e = match result {
+                Ok(_) => "找不到任何页面".to_string(),
+                Err(e) => e.to_string(),
+            };
+            warn!("PDF结构损坏: {}，尝试重建 xref 表: {}", e, pdf_path);
+            let repaired = rebuild_xref(&bytes)
+                .ok_or_else(|| BsxError::PdfError(format!("无法读取 {}: {}", pdf_path, e)))?;
+            let doc = Document::load_mem(&repaired)
+                .ok()
+                .filter(|doc| !doc.get_pages().is_empty())
+                .ok_or_else(|| BsxError::PdfError(format!("重建 xref 表后仍无法读取 {}: {}", pdf_path, e)))?;
+            info!("已重建 xref 表: {}", pdf_path);
+            doc
+        }
+    };
+
+    if doc.is_encrypted() {
+        // 只设置了权限密码（禁止复制、打印）的PDF可以用空密码打开
+        doc.decrypt("")
+            .map_err(|e| BsxError::PdfError(format!("{} 已加密且需要密码: {}", pdf_path, e)))?;
+        // 解密后去掉加密字典，避免 pdf_extract 继续把文档当作加密文件
+        doc.trailer.remove(b"Encrypt");
+        info!("已用空密码解密: {}", pdf_path);
+    }
+    Ok(doc)
+}
+
+/// 扫描 `N G obj` 的位置生成新的 xref 表和 trailer，追加到文件末尾
+///
+/// 增量更新过的文件中同一对象出现多次时取最后一次；找不到 /Root 时用 /Type /Catalog 对象代替。
+fn rebuild_xref(bytes: &[u8]) -> Option<Vec<u8>> {
+    let object = Regex::new(r"(?m)(?:^|[\r\n])(\d+)\s+(\d+)\s+obj\b").unwrap();
+    let mut offsets: BTreeMap<u32, (u16, usize)> = BTreeMap::new();
+    for caps in object.captures_iter(bytes) {
+        let id: u32 = std::str::from_utf8(&caps[1]).ok()?.parse().ok()?;
+        let generation: u16 = std::str::from_utf8(&caps[2]).ok()?.parse().ok()?;
+        offsets.insert(id, (generation, caps.get(1)?.start()));
+    }
+    let size = offsets.keys().next_back()? + 1;
+
+    let reference = |key: &str| -> Option<String> {
+        let pattern = Regex::new(&format!(r"/{}\s+(\d+\s+\d+\s+R)", key)).unwrap();
+        let caps = pattern.captures_iter(bytes).last()?;
+        Some(String::from_utf8_lossy(&caps[1]).into_owned())
+    };
+    let root = reference("Root").or_else(|| {
+        let catalog = Regex::new(r"(?s)(\d+)\s+(\d+)\s+obj\s*<<(?:[^>]|>[^>])*/Type\s*/Catalog").unwrap();
+        let caps = catalog.captures_iter(bytes).last()?;
+        Some(format!("{} {} R", String::from_utf8_lossy(&caps[1]), String::from_utf8_lossy(&caps[2])))
+    })?;
+
+    let mut trailer = format!("/Size {} /Root {}", size, root);
+    for key in ["Info", "Encrypt"] {
+        if let Some(value) = reference(key) {
+            trailer.push_str(&format!(" /{} {}", key, value));
+        }
+    }
+    let id = Regex::new(r"/ID\s*(\[[^\]]*\])").unwrap();
+    if let Some(caps) = id.captures_iter(bytes).last() {
+        trailer.push_str(&format!(" /ID {}", String::from_utf8_lossy(&caps[1])));
+    }
+
+    let mut repaired = bytes.to_vec();
+    repaired.push(b'\n');
+    let xref_start = repaired.len();
+    let mut table = format!("xref\n0 {}\n0000000000 65535 f \n", size);
+    for id in 1..size {
+        match offsets.get(&id) {
+            Some((generation, offset)) => table.push_str(&format!("{:010} {:05} n \n", offset, generation)),
+            None => table.push_str("0000000000 00000 f \n"),
+        }
+    }
+    table.push_str(&format!("trailer\n<< {} >>\nstartxref\n{}\n%%EOF\n", trailer, xref_start));
+    repaired.extend_from_slice(table.as_bytes());
+    Some(repaired)
+}
@@ -3,51 +3,72 @@ use regex::Regex;
 use tracing::{info, warn};
 use std::path::Path;
 
-use super::{Section, PaperMetadata};
+use crate::utils::BsxError;
 
-pub struct PdfParser;
+use super::{layout, pdf_loader, PageIndex, Section, PaperMetadata, PAGE_BREAK};
+
+/// 第一个章节标题之前的内容（标题、作者、摘要前的文字）的可信度
+const UNTITLED_CONFIDENCE: f32 = 0.5;
+
+/// 没有「Abstract」标题时，被视为摘要的段落的词数范围
+const MIN_ABSTRACT_WORDS: usize = 40;
+const MAX_ABSTRACT_WORDS: usize = 500;
+
+/// 版面提取得到的文本少于该长度时视为失败，回退到 pdf_extract
+const MIN_LAYOUT_TEXT_LEN: usize = 200;
+
+pub struct PdfParser {
+    /// 按文字坐标重排分栏文本
+    layout_aware: bool,
+}
 
 impl PdfParser {
-    pub fn new() -> Self {
-        Self
+    pub fn with_layout(layout_aware: bool) -> Self {
+        Self { layout_aware }
     }
 
-    /// 提取PDF前N行文本
-    pub fn extract_first_lines(&self, pdf_path: &str, num_lines: usize) -> Result<Vec<String>> {
-        info!("解析PDF: {}", pdf_path);
+    /// 文本缓存的版本标记，不同提取模式的结果分开缓存
+    pub fn text_mode(&self) -> &'static str {
+        if self.layout_aware { "layout" } else { "plain" }
+    }
 
+    /// 按页提取文本，版面模式失败或文本过少时回退到 pdf_extract
+    pub fn extract_pages(&self, pdf_path: &str) -> Result<Vec<String>> {
         if !Path::new(pdf_path).exists() {
             return Err(anyhow::anyhow!("PDF文件不存在: {}", pdf_path));
         }
 
-        let text = pdf_extract::extract_text(pdf_path)?;
-
-        let lines: Vec<String> = text
-            .lines()
-            .filter(|line| !line.trim().is_empty())
-            .take(num_lines)
-            .map(|s| s.trim().to_string())
-            .collect();
-
-        if lines.is_empty() {
-            warn!("PDF中未提取到文本内容");
-        } else {
-            info!("成功提取 {} 行文本", lines.len());
+        if self.layout_aware {
+            match layout::extract_pages(pdf_path) {
+                Ok(pages) => {
+                    let len: usize = pages.iter().map(|p| p.trim().len()).sum();
+                    if len >= MIN_LAYOUT_TEXT_LEN {
+                        return Ok(pages);
+                    }
+                    warn!("版面提取文本过少，回退到普通提取");
+                }
+                Err(e) => warn!("版面提取失败: {}，回退到普通提取", e),
+            }
         }
 
-        Ok(lines)
+        let doc = pdf_loader::load_document(pdf_path)?;
+        let mut pages = Vec::new();
+        for page_num in doc.get_pages().into_keys() {
+            let mut text = String::new();
+            pdf_extract::output_doc_page(&doc, &mut pdf_extract::PlainTextOutput::new(&mut text), page_num)
+                .map_err(|e| BsxError::PdfError(format!("第 {} 页文本提取失败: {}", page_num, e)))?;
+            pages.push(text);
+        }
+        Ok(pages)
     }
 
-    /// 提取完整文本
+    /// 提取完整文本，各页之间以单独一行的分页符 `PAGE_BREAK` 分隔
     pub fn extract_full_text(&self, pdf_path: &str) -> Result<String> {
         info!("提取PDF完整文本: {}", pdf_path);
 
-        if !Path::new(pdf_path).exists() {
-            return Err(anyhow::anyhow!("PDF文件不存在: {}", pdf_path));
-        }
-
-        let text = pdf_extract::extract_text(pdf_path)?;
-        info!("提取文本长度: {} 字符", text.len());
+        let pages = self.extract_pages(pdf_path)?;
+        let text = pages.join(&format!("\n{}\n", PAGE_BREAK));
+        info!("提取文本长度: {} 字符, {} 页", text.len(), pages.len());
 
         Ok(text)
     }
@@ -55,6 +76,7 @@ impl PdfParser {
     /// 结构化文本提取：识别章节标题，分割为 Section 列表，同时提取元数据
     pub fn extract_structured_text(&self, full_text: &str) -> (PaperMetadata, Vec<Section>) {
         let lines: Vec<&str> = full_text.lines().collect();
+        let line_pages = PageIndex::new(full_text).line_pages(full_text);
 
         // 章节标题模式
         let heading_patterns = [
@@ -70,15 +92,20 @@ impl PdfParser {
         let mut current_heading = String::new();
         let mut current_level: u8 = 0;
         let mut current_body = String::new();
+        let mut current_page = line_pages.first().copied();
+        // 第一个标题之前的内容（标题、作者等）没有可识别的标题
+        let mut current_confidence = UNTITLED_CONFIDENCE;
 
-        // Extract title from first non-empty line
+        // 没有字号信息时的后备：第一个像标题的行，跳过 arXiv 水印和页眉
+        let watermark = Regex::new(r"(?i)^(?:arxiv:\s*\d{4}\.\d{4,5}|preprint\b|published\s+as\b|under\s+review\b)").unwrap();
         let title = lines.iter()
-            .find(|l| !l.trim().is_empty())
-            .map(|l| l.trim().to_string());
+            .map(|l| l.trim())
+            .find(|l| l.chars().filter(|c| c.is_alphabetic()).count() >= 3 && !watermark.is_match(l))
+            .map(|l| l.to_string());
 
         let mut abstract_text: Option<String> = None;
 
-        for line in &lines {
+        for (line, page) in lines.iter().zip(line_pages.iter().copied().map(Some)) {
             let trimmed = line.trim();
             if trimmed.is_empty() {
                 if !current_body.is_empty() {
@@ -91,27 +118,35 @@ impl PdfParser {
 
             // Check numbered heading "1. Title" or "1 Title"
             if let Some(caps) = heading_patterns[0].captures(trimmed) {
-                Self::push_section(&mut sections, &current_heading, current_level, &current_body);
+                Self::push_section(&mut sections, &current_heading, current_level, &current_body, current_page, current_confidence);
                 current_heading = trimmed.to_string();
+                current_page = page;
                 current_level = 1;
+                current_confidence = if heading_patterns[2].is_match(caps[2].trim()) {
+                    0.95
+                } else {
+                    numbered_heading_confidence(&caps[2], 0.8)
+                };
                 current_body.clear();
                 matched_heading = true;
-                let _ = caps;
             }
             // Check sub-heading "1.1 Title"
             else if let Some(caps) = heading_patterns[1].captures(trimmed) {
-                Self::push_section(&mut sections, &current_heading, current_level, &current_body);
+                Self::push_section(&mut sections, &current_heading, current_level, &current_body, current_page, current_confidence);
                 current_heading = trimmed.to_string();
+                current_page = page;
                 current_level = 2;
+                current_confidence = numbered_heading_confidence(&caps[2], 0.7);
                 current_body.clear();
                 matched_heading = true;
-                let _ = caps;
             }
             // Check known section names
             else if heading_patterns[2].is_match(trimmed) {
-                Self::push_section(&mut sections, &current_heading, current_level, &current_body);
+                Self::push_section(&mut sections, &current_heading, current_level, &current_body, current_page, current_confidence);
                 current_heading = trimmed.to_string();
+                current_page = page;
                 current_level = 1;
+                current_confidence = 0.9;
                 current_body.clear();
                 matched_heading = true;
             }
@@ -125,11 +160,14 @@ impl PdfParser {
         }
 
         // Push last section
-        Self::push_section(&mut sections, &current_heading, current_level, &current_body);
+        Self::push_section(&mut sections, &current_heading, current_level, &current_body, current_page, current_confidence);
 
         // Extract abstract from sections
         if let Some(abs_section) = sections.iter().find(|s| s.heading.to_lowercase() == "abstract") {
             abstract_text = Some(abs_section.body.clone());
+        } else if let Some(front) = sections.first().filter(|s| s.heading == "(untitled)") {
+            // 没有单独的「Abstract」标题：第一个章节之前的内容中找内联标记或最像摘要的段落
+            abstract_text = Self::inline_abstract(&front.body).or_else(|| Self::abstract_paragraph(&front.body));
         }
 
         let metadata = PaperMetadata {
@@ -138,12 +176,53 @@ impl PdfParser {
             authors: Vec::new(), // Author extraction from PDF text is unreliable
             abstract_text,
             abstract_zh: None,
+            key_points: Vec::new(),
+            code_urls: Vec::new(),
+            venue: None,
+            funding: Vec::new(),
+            arxiv_id: None,
+            doi: None,
+            relevance: None,
+            relevance_reasons: Vec::new(),
+            source: None,
+            publish_date: None,
+            subscription: None,
+            read: false,
+            starred: false,
         };
 
         (metadata, sections)
     }
 
-    fn push_section(sections: &mut Vec<Section>, heading: &str, level: u8, body: &str) {
+    /// 「Abstract—We propose ...」「ABSTRACT: ...」这类与正文同行的摘要标记，取标记之后的段落
+    fn inline_abstract(front_matter: &str) -> Option<String> {
+        let marker = Regex::new(r"(?i)(?:^|\n)\s*abstract\s*[.:—–-]\s*").unwrap();
+        let m = marker.find(front_matter)?;
+        let text = front_matter[m.end()..].split('\n').next()?.trim();
+        let words = text.split_whitespace().count();
+        (words >= MIN_ABSTRACT_WORDS / 2).then(|| text.to_string())
+    }
+
+    /// 标题、作者与第一个章节之间长度合适（摘要通常 100~300 词）的段落，有多个时取最长的
+    fn abstract_paragraph(front_matter: &str) -> Option<String> {
+        front_matter
+            .split('\n')
+            .map(str::trim)
+            .filter(|p| (MIN_ABSTRACT_WORDS..=MAX_ABSTRACT_WORDS).contains(&p.split_whitespace().count()))
+            // 作者和单位段落多为逗号分隔的短语，摘要由完整句子组成
+            .filter(|p| p.matches(". ").count() >= 1 || p.ends_with('.'))
+            .max_by_key(|p| p.split_whitespace().count())
+            .map(str::to_string)
+    }
+
+    fn push_section(
+        sections: &mut Vec<Section>,
+        heading: &str,
+        level: u8,
+        body: &str,
+        page: Option<usize>,
+        confidence: f32,
+    ) {
         let body_trimmed = body.trim();
         if heading.is_empty() && body_trimmed.is_empty() {
             return;
@@ -152,6 +231,17 @@ impl PdfParser {
             heading: if heading.is_empty() { "(untitled)".to_string() } else { heading.to_string() },
             level,
             body: body_trimmed.to_string(),
+            page,
+            confidence,
         });
     }
 }
+
+/// 编号标题的可信度：标题越长越可能是以数字开头的正文行（如表格行、列表项）
+fn numbered_heading_confidence(title: &str, base: f32) -> f32 {
+    match title.split_whitespace().count() {
+        0..=6 => base,
+        7..=10 => base - 0.25,
+        _ => base - 0.4,
+    }
+}
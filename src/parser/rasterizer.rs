@@ -0,0 +1,161 @@
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+use std::process::Command;
+use tracing::debug;
+
+use super::layout::FigureRegion;
+use crate::config::ParserConfig;
+
+/// 把页面上的矩形区域栅格化为 PNG
+///
+/// 用于矢量图：这类图由绘图指令构成，PDF 中没有可直接提取的图片对象。
+/// 以 `pdfium` 特性编译且能加载 libpdfium 时在进程内渲染，否则调用外部命令（poppler 的 `pdftoppm`）。
+pub struct Rasterizer {
+    backend: Backend,
+    dpi: u32,
+}
+
+enum Backend {
+    #[cfg(feature = "pdfium")]
+    Pdfium(pdfium_render::prelude::Pdfium),
+    Command(String),
+}
+
+impl Rasterizer {
+    pub fn new(config: &ParserConfig) -> Self {
+        Self {
+            backend: Backend::from_config(config),
+            dpi: config.rasterize_dpi.max(36),
+        }
+    }
+
+    /// 实际使用的渲染方式，用于日志和 doctor
+    pub fn backend_name(&self) -> String {
+        match &self.backend {
+            #[cfg(feature = "pdfium")]
+            Backend::Pdfium(_) => "pdfium".to_string(),
+            Backend::Command(command) => command.clone(),
+        }
+    }
+
+    /// 能否渲染：pdfium 已加载，或栅格化命令可以运行
+    pub fn is_available(&self) -> bool {
+        match &self.backend {
+            #[cfg(feature = "pdfium")]
+            Backend::Pdfium(_) => true,
+            Backend::Command(command) => Command::new(command).arg("-v").output().is_ok(),
+        }
+    }
+
+    /// 输出图片的像素尺寸
+    pub fn pixel_size(&self, region: &FigureRegion) -> (u32, u32) {
+        (self.to_pixels(region.width), self.to_pixels(region.height))
+    }
+
+    /// 渲染区域，返回 PNG 数据
+    pub fn render(&self, pdf_path: &str, region: &FigureRegion) -> Result<Vec<u8>> {
+        let data = match &self.backend {
+            #[cfg(feature = "pdfium")]
+            Backend::Pdfium(pdfium) => self.render_pdfium(pdfium, pdf_path, region)?,
+            Backend::Command(command) => self.render_command(command, pdf_path, region)?,
+        };
+        let (width, height) = self.pixel_size(region);
+        debug!("栅格化第 {} 页 Figure {}: {}x{}", region.page, region.number, width, height);
+        Ok(data)
+    }
+
+    /// 渲染整页后裁出区域；区域坐标与 pdftoppm 的 -x/-y 一致，以页面左上角为原点
+    #[cfg(feature = "pdfium")]
+    fn render_pdfium(
+        &self,
+        pdfium: &pdfium_render::prelude::Pdfium,
+        pdf_path: &str,
+        region: &FigureRegion,
+    ) -> Result<Vec<u8>> {
+        use pdfium_render::prelude::PdfRenderConfig;
+
+        let document = pdfium
+            .load_pdf_from_file(pdf_path, None)
+            .with_context(|| format!("pdfium 无法打开 {}", pdf_path))?;
+        let index = u16::try_from(region.page.saturating_sub(1)).context("页码超出范围")?;
+        let page = document.pages().get(index).with_context(|| format!("pdfium 无法读取第 {} 页", region.page))?;
+        let config = PdfRenderConfig::new().scale_page_by_factor(self.dpi as f32 / 72.0);
+        let bitmap = page.render_with_config(&config).context("pdfium 渲染页面失败")?;
+
+        let (width, height) = self.pixel_size(region);
+        let figure = bitmap
+            .as_image()
+            .crop_imm(self.to_pixels(region.x), self.to_pixels(region.y), width, height);
+        let mut png = Vec::new();
+        figure
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .context("编码 PNG 失败")?;
+        Ok(png)
+    }
+
+    fn render_command(&self, command: &str, pdf_path: &str, region: &FigureRegion) -> Result<Vec<u8>> {
+        let prefix = temp_prefix();
+        let (width, height) = self.pixel_size(region);
+        let output = Command::new(command)
+            .args(["-f", &region.page.to_string(), "-l", &region.page.to_string()])
+            .args(["-r", &self.dpi.to_string()])
+            .args(["-x", &self.to_pixels(region.x).to_string(), "-y", &self.to_pixels(region.y).to_string()])
+            .args(["-W", &width.to_string(), "-H", &height.to_string()])
+            .args(["-png", "-singlefile"])
+            .arg(pdf_path)
+            .arg(&prefix)
+            .output()
+            .with_context(|| format!("无法运行栅格化命令: {}", command))?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "栅格化失败 ({}): {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        let png_path = prefix.with_extension("png");
+        let data = std::fs::read(&png_path).with_context(|| format!("读取栅格化结果失败: {}", png_path.display()))?;
+        let _ = std::fs::remove_file(&png_path);
+        Ok(data)
+    }
+
+    fn to_pixels(&self, points: f64) -> u32 {
+        (points.max(0.0) * self.dpi as f64 / 72.0).round() as u32
+    }
+}
+
+impl Backend {
+    #[cfg(feature = "pdfium")]
+    fn from_config(config: &ParserConfig) -> Self {
+        use pdfium_render::prelude::Pdfium;
+
+        let library = config.pdfium_library.trim();
+        let bindings = if library.is_empty() {
+            Pdfium::bind_to_system_library()
+        } else {
+            Pdfium::bind_to_library(Pdfium::pdfium_platform_library_name_at_path(library))
+        };
+        match bindings {
+            Ok(bindings) => Backend::Pdfium(Pdfium::new(bindings)),
+            Err(e) => {
+                tracing::warn!("无法加载 libpdfium（{}），改用栅格化命令 {}", e, config.rasterizer_command);
+                Backend::Command(config.rasterizer_command.clone())
+            }
+        }
+    }
+
+    #[cfg(not(feature = "pdfium"))]
+    fn from_config(config: &ParserConfig) -> Self {
+        Backend::Command(config.rasterizer_command.clone())
+    }
+}
+
+/// 临时输出文件前缀（不含扩展名）
+fn temp_prefix() -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "bsxbot_raster_{}_{}",
+        std::process::id(),
+        fastrand::u64(..)
+    ))
+}
@@ -0,0 +1,316 @@
+use regex::Regex;
+use tracing::{debug, info};
+
+use super::Reference;
+
+/// 单条参考文献的最大长度，超出说明切分失败（多条粘在一起）
+const MAX_ENTRY_LEN: usize = 1500;
+
+pub struct ReferenceExtractor {
+    /// References / Bibliography 标题行
+    heading: Regex,
+    /// 参考文献之后的章节（附录等）
+    trailing_heading: Regex,
+    /// `[12] ...` 编号
+    bracket_label: Regex,
+    /// `12. ...` 编号
+    dotted_label: Regex,
+    /// 作者-年份格式中一条新文献的开头：`Surname, A.` 或 `A. Surname,`
+    author_start: Regex,
+    year: Regex,
+    arxiv: Regex,
+    doi: Regex,
+    /// IEEE 风格的引号标题
+    quoted_title: Regex,
+    initials: Regex,
+    author_separator: Regex,
+    /// 作者段末尾的年份，如 `Smith, J. 2020` 或 `Smith, J. (2020)`
+    trailing_year: Regex,
+}
+
+impl ReferenceExtractor {
+    pub fn new() -> Self {
+        Self {
+            heading: Regex::new(r"(?i)^(?:\d+\.?\s*)?(?:references|bibliography|literature\s+cited)$").unwrap(),
+            trailing_heading: Regex::new(
+                r"(?i)^(?:appendix|appendices|supplementary\s+material|[A-Z]\s+(?:appendix|proofs?|additional\s+\w+))\b",
+            )
+            .unwrap(),
+            bracket_label: Regex::new(r"^\[(\d{1,4})\]\s*").unwrap(),
+            dotted_label: Regex::new(r"^(\d{1,4})\.\s+").unwrap(),
+            author_start: Regex::new(r"^(?:[A-Z][A-Za-z'\-]+,\s+[A-Z]\.|[A-Z]\.\s*(?:[A-Z]\.\s*)*[A-Z][A-Za-z'\-]+,)").unwrap(),
+            year: Regex::new(r"\b((?:19|20)\d{2})[a-z]?\b").unwrap(),
+            arxiv: Regex::new(r"(?i)(?:arxiv(?:\s+preprint)?:?\s*|arxiv\.org/(?:abs|pdf)/)(\d{4}\.\d{4,5})(?:v\d+)?").unwrap(),
+            doi: Regex::new(r"\b(10\.\d{4,9}/[^\s,;]+)").unwrap(),
+            quoted_title: Regex::new(r#"[“"](.+?)[,.]?[”"]"#).unwrap(),
+            initials: Regex::new(r"^(?:[A-Z]\.\s*)+$").unwrap(),
+            author_separator: Regex::new(r",\s*(?:and\s+|&\s*)?|\s+and\s+|\s*&\s*|;\s*").unwrap(),
+            trailing_year: Regex::new(r"\s*\(?(?:19|20)\d{2}[a-z]?\)?$").unwrap(),
+        }
+    }
+
+    /// 定位参考文献章节，切分并解析每一条
+    pub fn extract(&self, full_text: &str) -> Vec<Reference> {
+        let lines: Vec<&str> = full_text.lines().map(str::trim).collect();
+
+        // 正文中也可能出现单独一行的 "References"，取最后一个
+        let Some(start) = lines.iter().rposition(|l| self.heading.is_match(l)) else {
+            debug!("未找到参考文献章节");
+            return Vec::new();
+        };
+        let end = lines[start + 1..]
+            .iter()
+            .position(|l| self.trailing_heading.is_match(l))
+            .map(|offset| start + 1 + offset)
+            .unwrap_or(lines.len());
+
+        let body: Vec<&str> = lines[start + 1..end].iter().copied().filter(|l| !l.is_empty()).collect();
+        let references: Vec<Reference> = self
+            .split_entries(&body)
+            .into_iter()
+            .filter(|(_, raw)| raw.len() >= 20 && raw.len() <= MAX_ENTRY_LEN)
+            .map(|(label, raw)| self.parse_entry(label, raw))
+            .collect();
+
+        info!("参考文献提取完成，共 {} 条", references.len());
+        references
+    }
+
+    /// 按编号或作者-年份格式切分条目，返回 (编号, 合并后的原文)
+    fn split_entries(&self, lines: &[&str]) -> Vec<(Option<String>, String)> {
+        let bracketed = lines.iter().filter(|l| self.bracket_label.is_match(l)).count();
+        let dotted = lines.iter().filter(|l| self.dotted_label.is_match(l)).count();
+        let label_pattern = if bracketed >= 3 {
+            Some(&self.bracket_label)
+        } else if dotted >= 3 {
+            Some(&self.dotted_label)
+        } else {
+            None
+        };
+
+        let mut entries: Vec<(Option<String>, String)> = Vec::new();
+        for line in lines {
+            let starts_entry = match label_pattern {
+                Some(pattern) => pattern.captures(line).map(|caps| {
+                    let label = caps[1].to_string();
+                    (Some(label), line[caps.get(0).unwrap().end()..].to_string())
+                }),
+                // 无编号：上一条以句点结束且本行像作者名开头时视为新条目
+                None => {
+                    let previous_ended = entries.last().map(|(_, raw)| raw.ends_with('.')).unwrap_or(true);
+                    (previous_ended && self.author_start.is_match(line)).then(|| (None, line.to_string()))
+                }
+            };
+
+            match (starts_entry, entries.last_mut()) {
+                (Some(entry), _) => entries.push(entry),
+                (None, Some((_, raw))) => append_line(raw, line),
+                (None, None) => {}
+            }
+        }
+        entries
+    }
+
+    fn parse_entry(&self, label: Option<String>, raw: String) -> Reference {
+        let year = self.year.captures(&raw).and_then(|caps| caps[1].parse().ok());
+        let arxiv_id = self.arxiv.captures(&raw).map(|caps| caps[1].to_string());
+        let doi = self
+            .doi
+            .captures(&raw)
+            .map(|caps| caps[1].trim_end_matches(['.', ')']).to_string());
+
+        let (authors_part, title, venue) = match self.quoted_title.captures(&raw) {
+            // IEEE: A. Author, B. Author, “Title,” in Venue, 2020.
+            Some(caps) => {
+                let whole = caps.get(0).unwrap();
+                let venue = raw[whole.end()..]
+                    .trim_start_matches([',', '.', ' '])
+                    .trim_start_matches("in ")
+                    .to_string();
+                (raw[..whole.start()].to_string(), Some(caps[1].trim().to_string()), Some(venue))
+            }
+            None => self.split_segments(&raw),
+        };
+
+        Reference {
+            label,
+            authors: self.parse_authors(&authors_part),
+            year,
+            title: title.filter(|t| !t.is_empty()),
+            venue: venue.map(|v| clean_venue(&v)).filter(|v| !v.is_empty()),
+            arxiv_id,
+            doi,
+            raw,
+        }
+    }
+
+    /// 按句点切分为作者、标题、出处；`Author (2020). Title.` 格式先去掉括号中的年份
+    fn split_segments(&self, raw: &str) -> (String, Option<String>, Option<String>) {
+        let mut segments = split_sentences(raw);
+        if segments.is_empty() {
+            return (String::new(), None, None);
+        }
+
+        let authors = segments.remove(0);
+        let authors = self.trailing_year.replace(&authors, "").to_string();
+        // 标题不能只是年份，如 "Smith, J. 2020. Title."
+        if segments.first().map(|s| self.year.is_match(s) && s.len() <= 6).unwrap_or(false) {
+            segments.remove(0);
+        }
+
+        let mut rest = segments.into_iter();
+        (authors, rest.next(), rest.next())
+    }
+
+    fn parse_authors(&self, text: &str) -> Vec<String> {
+        let text = text.replace("et al.", "").replace("et al", "");
+        let mut authors: Vec<String> = Vec::new();
+        for piece in self.author_separator.split(&text).map(str::trim).filter(|p| !p.is_empty()) {
+            let piece = piece.trim_end_matches('.');
+            // "Smith, J." 被逗号切开后把缩写并回姓氏
+            if self.initials.is_match(&format!("{}.", piece)) {
+                if let Some(last) = authors.last_mut() {
+                    last.push_str(&format!(", {}.", piece));
+                    continue;
+                }
+            }
+            authors.push(piece.to_string());
+        }
+        authors
+    }
+}
+
+/// 合并换行：行尾连字符断词直接拼接，否则加空格
+fn append_line(raw: &mut String, line: &str) {
+    if raw.ends_with('-') && line.starts_with(|c: char| c.is_lowercase()) {
+        raw.pop();
+    } else if !raw.is_empty() {
+        raw.push(' ');
+    }
+    raw.push_str(line);
+}
+
+/// 在 ". " 处切分句子，单个大写字母（姓名缩写）后的句点不切分
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = text.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        current.push(c);
+        if c != '.' || !chars.get(i + 1).map(|n| n.is_whitespace()).unwrap_or(true) {
+            continue;
+        }
+        let word: String = current
+            .trim_end_matches('.')
+            .chars()
+            .rev()
+            .take_while(|c| c.is_alphanumeric())
+            .collect();
+        let is_initial = word.chars().count() == 1 && word.chars().all(|c| c.is_uppercase());
+        if !is_initial {
+            let segment = current.trim().trim_end_matches('.').trim().to_string();
+            if !segment.is_empty() {
+                segments.push(segment);
+            }
+            current.clear();
+        }
+    }
+    let rest = current.trim().trim_end_matches('.').trim();
+    if !rest.is_empty() {
+        segments.push(rest.to_string());
+    }
+    segments
+}
+
+/// 去掉出处末尾的年份、页码等
+fn clean_venue(venue: &str) -> String {
+    let venue = venue.trim().trim_start_matches("In ").trim_start_matches("in ");
+    if venue.to_lowercase().starts_with("arxiv") {
+        return "arXiv".to_string();
+    }
+    let venue = [", pages", ", pp", ", vol", ", volume", ", no."]
+        .iter()
+        .filter_map(|marker| venue.find(marker))
+        .min()
+        .map(|i| &venue[..i])
+        .unwrap_or(venue);
+    let cut = venue
+        .find(|c: char| c.is_ascii_digit())
+        .map(|i| &venue[..i])
+        .unwrap_or(venue);
+    cut.trim().trim_end_matches([',', '.', '(', ':', ';']).trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NUMBERED: &str = "\
+5 Conclusion
+We showed that attention suffices.
+References
+[1] A. Vaswani, N. Shazeer, and N. Parmar, “Attention is all you need,” in Advances in Neural
+Information Processing Systems, 2017, pp. 5998–6008.
+[2] J. Devlin, M. Chang, K. Lee, and K. Toutanova, “BERT: Pre-training of deep bidirectional trans-
+formers for language understanding,” arXiv preprint arXiv:1810.04805, 2018.
+[3] K. He, X. Zhang, S. Ren, and J. Sun, “Deep residual learning for image recognition,” in CVPR,
+2016, doi: 10.1109/CVPR.2016.90.
+Appendix A Proofs
+[4] This line belongs to the appendix and is not a reference entry.";
+
+    const AUTHOR_YEAR: &str = "\
+References
+Bahdanau, D., Cho, K., and Bengio, Y. (2015). Neural machine translation by jointly
+learning to align and translate. In International Conference on Learning Representations.
+Kingma, D. P. and Ba, J. (2015). Adam: A method for stochastic optimization. In
+International Conference on Learning Representations.
+Smith, J., Doe, A., et al. (2023). Scaling laws for retrieval. Journal of Machine Learning Research, 24(1):1-30.";
+
+    #[test]
+    fn extracts_numbered_references() {
+        let references = ReferenceExtractor::new().extract(NUMBERED);
+        assert_eq!(references.len(), 3);
+
+        let first = &references[0];
+        assert_eq!(first.label.as_deref(), Some("1"));
+        assert_eq!(first.authors, vec!["A. Vaswani", "N. Shazeer", "N. Parmar"]);
+        assert_eq!(first.title.as_deref(), Some("Attention is all you need"));
+        assert_eq!(first.year, Some(2017));
+        assert_eq!(first.venue.as_deref(), Some("Advances in Neural Information Processing Systems"));
+
+        // 跨行的断词合并回原文
+        let second = &references[1];
+        assert_eq!(second.title.as_deref(), Some("BERT: Pre-training of deep bidirectional transformers for language understanding"));
+        assert_eq!(second.arxiv_id.as_deref(), Some("1810.04805"));
+        assert_eq!(second.venue.as_deref(), Some("arXiv"));
+
+        assert_eq!(references[2].doi.as_deref(), Some("10.1109/CVPR.2016.90"));
+    }
+
+    #[test]
+    fn extracts_author_year_references() {
+        let references = ReferenceExtractor::new().extract(AUTHOR_YEAR);
+        assert_eq!(references.len(), 3);
+        assert!(references.iter().all(|r| r.label.is_none()));
+
+        let first = &references[0];
+        assert_eq!(first.authors, vec!["Bahdanau, D.", "Cho, K.", "Bengio, Y."]);
+        assert_eq!(first.year, Some(2015));
+        assert_eq!(first.title.as_deref(), Some("Neural machine translation by jointly learning to align and translate"));
+        assert_eq!(first.venue.as_deref(), Some("International Conference on Learning Representations"));
+
+        assert_eq!(references[1].authors, vec!["Kingma, D. P.", "Ba, J."]);
+        assert_eq!(references[1].title.as_deref(), Some("Adam: A method for stochastic optimization"));
+
+        let last = &references[2];
+        assert_eq!(last.authors, vec!["Smith, J.", "Doe, A."]);
+        assert_eq!(last.year, Some(2023));
+        assert_eq!(last.venue.as_deref(), Some("Journal of Machine Learning Research"));
+    }
+
+    #[test]
+    fn no_references_section() {
+        assert!(ReferenceExtractor::new().extract("1 Introduction\nNo bibliography here.").is_empty());
+    }
+}
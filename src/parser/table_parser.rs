@@ -1,7 +1,8 @@
 use regex::Regex;
+use std::collections::HashMap;
 use tracing::{info, debug};
 
-use super::Table;
+use super::{PageIndex, Table};
 
 pub struct TableParser;
 
@@ -13,6 +14,8 @@ impl TableParser {
     /// 从全文中检测并提取表格
     pub fn extract(&self, full_text: &str) -> Vec<Table> {
         let lines: Vec<&str> = full_text.lines().collect();
+        let line_pages = PageIndex::new(full_text).line_pages(full_text);
+        let page_of = |i: usize| line_pages.get(i).copied();
         let mut tables: Vec<Table> = Vec::new();
         let table_caption_re = Regex::new(r"(?i)^Table\s+(\d+)[.:]?\s*(.*)$").unwrap();
 
@@ -23,6 +26,7 @@ impl TableParser {
             // Look for "Table N" caption lines
             if table_caption_re.is_match(trimmed) {
                 let caption = Some(trimmed.to_string());
+                let page = page_of(i);
                 i += 1;
 
                 // Skip blank lines after caption
@@ -57,7 +61,8 @@ impl TableParser {
                 if raw_rows.len() >= 2 {
                     if let Some((headers, rows)) = Self::parse_rows(&raw_rows) {
                         debug!("检测到表格: {:?}, {} 行", caption, rows.len());
-                        tables.push(Table { caption, headers, rows });
+                        let confidence = confidence(true, &headers, &rows);
+                        tables.push(Table { caption, headers, rows, page, confidence });
                     }
                 }
                 continue;
@@ -91,10 +96,13 @@ impl TableParser {
                 if raw_rows.len() >= 3 {
                     if let Some((headers, rows)) = Self::parse_rows(&raw_rows) {
                         debug!("检测到无标题表格: {} 列, {} 行", headers.len(), rows.len());
+                        let confidence = confidence(false, &headers, &rows);
                         tables.push(Table {
                             caption: None,
                             headers,
                             rows,
+                            page: page_of(start),
+                            confidence,
                         });
                     }
                 }
@@ -156,6 +164,111 @@ impl TableParser {
             return None;
         }
 
+        let (headers, rows) = normalize(headers, rows);
+        if rows.is_empty() {
+            return None;
+        }
         Some((headers, rows))
     }
 }
+
+/// 规范化行列：按多数行的列数对齐表头，多出的单元格合并，续行并入上一行，
+/// 缺失的行标签沿用上一行（纵向合并单元格），丢弃空行和混入的正文
+fn normalize(mut headers: Vec<String>, rows: Vec<Vec<String>>) -> (Vec<String>, Vec<Vec<String>>) {
+    let columns = modal_width(&headers, &rows);
+    // 行标签列没有表头时补空表头
+    while headers.len() < columns {
+        headers.insert(0, String::new());
+    }
+    merge_cells(&mut headers, columns);
+
+    let mut normalized: Vec<Vec<String>> = Vec::new();
+    for mut row in rows {
+        if is_junk_row(&row) {
+            continue;
+        }
+        // 只有一个较短的文本单元格：上一行某个单元格的换行
+        if row.len() == 1 && columns > 1 && !is_numeric(&row[0]) && row[0].len() <= 40 && !row[0].ends_with('.') {
+            if let Some(previous) = normalized.last_mut() {
+                let cell = &mut previous[0];
+                cell.push(' ');
+                cell.push_str(&row[0]);
+                continue;
+            }
+        }
+        merge_cells(&mut row, columns);
+        if row.len() < columns {
+            let missing = columns - row.len();
+            let label_missing = row.first().map(|c| is_numeric(c)).unwrap_or(true);
+            if label_missing {
+                // 纵向合并的行标签：沿用上一行的值
+                let inherited: Vec<String> = match normalized.last() {
+                    Some(previous) => previous[..missing].to_vec(),
+                    None => vec![String::new(); missing],
+                };
+                row.splice(0..0, inherited);
+            } else {
+                row.extend(std::iter::repeat_n(String::new(), missing));
+            }
+        }
+        normalized.push(row);
+    }
+    (headers, normalized)
+}
+
+/// 表头和各行中出现最多的列数（并列时取较大值）
+/// 表格可信度：有「Table N」标题的起点更高，各行列数与表头一致的比例越高越可信，
+/// 数值单元格多的（实验结果表）再略微加分
+fn confidence(captioned: bool, headers: &[String], rows: &[Vec<String>]) -> f32 {
+    if rows.is_empty() {
+        return 0.0;
+    }
+    let consistent = rows.iter().filter(|r| r.len() == headers.len()).count() as f32 / rows.len() as f32;
+    let cells: Vec<&String> = rows.iter().flatten().collect();
+    let numeric = cells.iter().filter(|c| is_numeric(c)).count() as f32 / cells.len().max(1) as f32;
+    let base = if captioned { 0.5 } else { 0.2 };
+    let columns = if headers.len() >= 2 { 0.1 } else { 0.0 };
+    (base + columns + 0.3 * consistent + 0.1 * numeric).min(1.0)
+}
+
+fn modal_width(headers: &[String], rows: &[Vec<String>]) -> usize {
+    let mut counts: HashMap<usize, usize> = HashMap::new();
+    for len in std::iter::once(headers.len()).chain(rows.iter().map(Vec::len)).filter(|&n| n >= 2) {
+        *counts.entry(len).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(len, count)| (count, len))
+        .map(|(len, _)| len)
+        .unwrap_or(headers.len())
+}
+
+/// 单元格多于列数时合并：优先合并相邻的两个文本单元格（被空格拆开的标签），否则合并最后两个
+fn merge_cells(row: &mut Vec<String>, columns: usize) {
+    while row.len() > columns.max(1) {
+        let at = (0..row.len() - 1)
+            .find(|&i| !is_numeric(&row[i]) && !is_numeric(&row[i + 1]))
+            .unwrap_or(row.len() - 2);
+        let next = row.remove(at + 1);
+        row[at] = format!("{} {}", row[at], next);
+    }
+}
+
+/// 空行、单独的页码、混入表格的正文句子
+fn is_junk_row(row: &[String]) -> bool {
+    let text = row.join(" ");
+    let text = text.trim();
+    text.is_empty()
+        || (row.len() == 1 && text.chars().all(|c| c.is_ascii_digit()))
+        || (row.len() == 1 && text.contains(' ') && text.ends_with('.'))
+}
+
+/// 数值单元格：数字、百分比、带 ± 的结果、"-" 占位等
+fn is_numeric(cell: &str) -> bool {
+    let cell = cell.trim();
+    !cell.is_empty()
+        && cell.chars().any(|c| c.is_ascii_digit() || c == '-' || c == '–')
+        && cell
+            .chars()
+            .all(|c| c.is_ascii_digit() || " .,%±+-–×x()*†‡".contains(c))
+}
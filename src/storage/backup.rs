@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::storage::Database;
+
+const BACKUP_PREFIX: &str = "papers_";
+const BACKUP_SUFFIX: &str = ".db.gz";
+
+/// 备份数据库到 `dir/papers_<时间>.db.gz`，返回备份文件路径
+///
+/// 先用 VACUUM INTO 生成一致的快照（爬取进行中也可以备份），再压缩，不直接复制数据库文件。
+pub async fn create(db: &Database, dir: &Path) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir).with_context(|| format!("无法创建备份目录: {}", dir.display()))?;
+    let stamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let snapshot = dir.join(format!("{}{}.db.tmp", BACKUP_PREFIX, stamp));
+    let output = dir.join(format!("{}{}{}", BACKUP_PREFIX, stamp, BACKUP_SUFFIX));
+
+    db.snapshot_to(&snapshot.to_string_lossy()).await?;
+    let result = compress(&snapshot, &output);
+    if let Err(e) = std::fs::remove_file(&snapshot) {
+        warn!("删除临时快照失败 {}: {}", snapshot.display(), e);
+    }
+    result?;
+    Ok(output)
+}
+
+fn compress(input: &Path, output: &Path) -> Result<()> {
+    let mut reader = File::open(input)?;
+    let file = File::create(output).with_context(|| format!("无法创建备份文件: {}", output.display()))?;
+    let mut encoder = GzEncoder::new(file, Compression::default());
+    std::io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?;
+    Ok(())
+}
+
+/// 只保留最新的 `keep` 个备份（0 表示全部保留），返回删除的文件
+pub fn rotate(dir: &Path, keep: usize) -> Result<Vec<PathBuf>> {
+    if keep == 0 || !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups = list(dir)?;
+    if backups.len() <= keep {
+        return Ok(Vec::new());
+    }
+    // 文件名中的时间戳按字典序即按时间排列
+    let expired = backups.drain(..backups.len() - keep).collect::<Vec<_>>();
+    for path in &expired {
+        std::fs::remove_file(path).with_context(|| format!("删除旧备份失败: {}", path.display()))?;
+        info!("已删除旧备份: {}", path.display());
+    }
+    Ok(expired)
+}
+
+/// 备份目录中的备份文件，从旧到新
+pub fn list(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy())
+                .is_some_and(|name| name.starts_with(BACKUP_PREFIX) && name.ends_with(BACKUP_SUFFIX))
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
@@ -0,0 +1,260 @@
+use anyhow::Result;
+use sqlx::SqliteConnection;
+use tracing::info;
+
+use crate::config::dirs;
+use crate::parser::{self, PaperContent};
+use crate::storage::models::{BackfillProgress, Paper, PaperKeyphrase, PaperReference, SectionCitation, TranslationStatus};
+use crate::storage::{images, tables, writes, Database};
+use crate::translator::KeyPoints;
+
+/// 爬取一篇论文产生的全部写入
+///
+/// 翻译、下载和解析都在写入前完成，[`commit`] 在一个事务中写入，中途崩溃不会留下只写了一半的论文。
+pub struct PaperWrite {
+    pub source: String,
+    pub source_id: String,
+    pub target: PaperTarget,
+    pub translation: Option<TranslationWrite>,
+    /// 相对数据目录的PDF路径
+    pub pdf_path: Option<String>,
+    /// PDF无法打开（损坏、加密）的原因
+    pub pdf_error: Option<String>,
+    /// 解析结果；写入后论文标记为已处理
+    pub content: Option<ContentWrite>,
+}
+
+/// 写入新论文，或修复已有的论文
+pub enum PaperTarget {
+    New(Box<NewPaper>),
+    Existing(i64),
+}
+
+/// 新论文；爬取的论文附带所属订阅，本地导入的没有
+pub struct NewPaper {
+    pub paper: Paper,
+    pub subscription: Option<SubscriptionMatch>,
+}
+
+/// 论文匹配的订阅及相关度
+pub struct SubscriptionMatch {
+    pub name: String,
+    pub relevance_score: f32,
+    /// 评分依据，如命中的关键词、相似的星标论文
+    pub relevance_reasons: Vec<String>,
+    pub relevance_label: Option<String>,
+}
+
+/// 翻译结果
+pub enum TranslationWrite {
+    Done {
+        title: String,
+        abstract_text: String,
+        provider: String,
+    },
+    SameLanguage,
+    Status(TranslationStatus),
+}
+
+/// PDF的提取结果
+pub struct ContentWrite {
+    pub content: PaperContent,
+    /// 来源提供的摘要，其中的代码链接一并保存
+    pub summary: Option<String>,
+    /// LLM 生成的要点
+    pub key_points: Option<KeyPoints>,
+}
+
+/// 在一个事务中写入一批论文，回溯爬取时连同该页的进度一起提交；返回各论文的ID
+///
+/// 表格CSV在事务提交后导出，导出失败只记录日志。
+pub async fn commit(db: &Database, papers: &[PaperWrite], progress: Option<&BackfillProgress>) -> Result<Vec<i64>> {
+    let mut tx = db.pool().begin().await?;
+    let mut ids = Vec::with_capacity(papers.len());
+    for paper in papers {
+        ids.push(apply(&mut tx, paper).await?);
+    }
+    if let Some(p) = progress {
+        writes::save_backfill_progress(&mut tx, &p.subscription, &p.source, &p.month, p.fetched, p.total_matched, p.completed)
+            .await?;
+    }
+    tx.commit().await?;
+
+    for (paper, &paper_id) in papers.iter().zip(&ids) {
+        if matches!(paper.target, PaperTarget::New(_)) {
+            info!("论文已保存到数据库，ID: {}", paper_id);
+        }
+        if let Some(ref content) = paper.content {
+            write_tables(paper_id, &content.content);
+        }
+    }
+    Ok(ids)
+}
+
+/// 在一个事务中保存论文的提取结果（重新解析时使用），`summary` 为来源提供的摘要
+pub async fn save_content(db: &Database, paper_id: i64, content: &PaperContent, summary: Option<&str>) -> Result<()> {
+    let mut tx = db.pool().begin().await?;
+    apply_content(&mut tx, paper_id, content, summary).await?;
+    tx.commit().await?;
+
+    write_tables(paper_id, content);
+    Ok(())
+}
+
+async fn apply(conn: &mut SqliteConnection, write: &PaperWrite) -> Result<i64> {
+    let paper_id = match write.target {
+        PaperTarget::New(ref new) => {
+            let paper_id = writes::save_paper(conn, &new.paper).await?;
+            if let Some(ref sub) = new.subscription {
+                writes::save_relevance(
+                    conn,
+                    paper_id,
+                    &sub.name,
+                    sub.relevance_score,
+                    &sub.relevance_reasons,
+                    sub.relevance_label.as_deref(),
+                )
+                .await?;
+                writes::add_tag(conn, paper_id, &sub.name, "subscription").await?;
+            }
+            paper_id
+        }
+        PaperTarget::Existing(paper_id) => paper_id,
+    };
+
+    match write.translation {
+        Some(TranslationWrite::Done { ref title, ref abstract_text, ref provider }) => {
+            writes::update_translation(conn, &write.source, &write.source_id, title, abstract_text, provider).await?
+        }
+        Some(TranslationWrite::SameLanguage) => writes::mark_same_language(conn, &write.source, &write.source_id).await?,
+        Some(TranslationWrite::Status(status)) => {
+            writes::set_translation_status(conn, &write.source, &write.source_id, status).await?
+        }
+        None => {}
+    }
+
+    if let Some(ref pdf_path) = write.pdf_path {
+        writes::update_pdf_path(conn, &write.source, &write.source_id, pdf_path).await?;
+    }
+    if let Some(ref error) = write.pdf_error {
+        writes::set_pdf_error(conn, paper_id, Some(error)).await?;
+    }
+    if let Some(ref content) = write.content {
+        apply_content(conn, paper_id, &content.content, content.summary.as_deref()).await?;
+        if let Some(ref key_points) = content.key_points {
+            writes::save_key_points(conn, paper_id, &key_points.points, &key_points.provider).await?;
+        }
+        writes::mark_paper_processed(conn, &write.source, &write.source_id).await?;
+    }
+
+    Ok(paper_id)
+}
+
+async fn apply_content(
+    conn: &mut SqliteConnection,
+    paper_id: i64,
+    content: &PaperContent,
+    summary: Option<&str>,
+) -> Result<()> {
+    writes::set_pdf_error(conn, paper_id, None).await?;
+    // 序列化存入数据库
+    writes::save_extracted_content(
+        conn,
+        paper_id,
+        &serde_json::to_string(&content.formulas).unwrap_or_default(),
+        &serde_json::to_string(&content.images).unwrap_or_default(),
+        &serde_json::to_string(&content.tables).unwrap_or_default(),
+        &serde_json::to_string(&content.sections).unwrap_or_default(),
+        &serde_json::to_string(&content.links).unwrap_or_default(),
+    )
+    .await?;
+    writes::save_paper_images(conn, paper_id, &images::paper_image_refs(paper_id, &content.images)).await?;
+    writes::save_references(conn, paper_id, &reference_rows(paper_id, &content.references)).await?;
+
+    // arXiv 摘要（常附带代码链接）和PDF中找到的代码仓库
+    let mut code_urls = parser::LinkExtractor::new().code_repositories(summary, "");
+    for url in &content.metadata.code_urls {
+        if !code_urls.iter().any(|u| u.eq_ignore_ascii_case(url)) {
+            code_urls.push(url.clone());
+        }
+    }
+    writes::set_code_urls(conn, paper_id, &code_urls).await?;
+    writes::set_publication_info(conn, paper_id, content.metadata.venue.as_deref(), &content.metadata.funding).await?;
+    if let Some(ref abstract_text) = content.metadata.abstract_text {
+        if writes::fill_missing_abstract(conn, paper_id, abstract_text).await? {
+            info!("论文缺少摘要，已使用PDF中识别的摘要");
+        }
+    }
+    writes::save_keyphrases(conn, paper_id, &keyphrase_rows(paper_id, &content.keyphrases)).await?;
+    writes::save_section_citations(conn, paper_id, &citation_rows(paper_id, content)).await?;
+
+    Ok(())
+}
+
+fn write_tables(paper_id: i64, content: &PaperContent) {
+    if content.tables.is_empty() {
+        return;
+    }
+    if let Err(e) = tables::write_paper_tables(&dirs::get().tables_dir(), paper_id, &content.tables) {
+        info!("导出表格CSV失败: {}", e);
+    }
+}
+
+/// 提取的参考文献转为数据库记录
+fn reference_rows(paper_id: i64, references: &[parser::Reference]) -> Vec<PaperReference> {
+    references
+        .iter()
+        .enumerate()
+        .map(|(position, r)| PaperReference {
+            paper_id,
+            position: position as i64,
+            label: r.label.clone(),
+            raw: r.raw.clone(),
+            authors: serde_json::to_string(&r.authors).unwrap_or_else(|_| "[]".to_string()),
+            year: r.year.map(i64::from),
+            title: r.title.clone(),
+            venue: r.venue.clone(),
+            arxiv_id: r.arxiv_id.clone(),
+            doi: r.doi.clone(),
+        })
+        .collect()
+}
+
+/// 关键短语转为数据库记录
+fn keyphrase_rows(paper_id: i64, keyphrases: &[parser::Keyphrase]) -> Vec<PaperKeyphrase> {
+    keyphrases
+        .iter()
+        .enumerate()
+        .map(|(position, k)| PaperKeyphrase {
+            paper_id,
+            position: position as i64,
+            phrase: k.phrase.clone(),
+            score: k.score,
+            occurrences: k.occurrences as i64,
+        })
+        .collect()
+}
+
+/// 章节引用转为数据库记录，章节按标题对应到其在论文中的顺序
+fn citation_rows(paper_id: i64, content: &PaperContent) -> Vec<SectionCitation> {
+    let mut rows = Vec::new();
+    let mut search_from = 0;
+    for section in &content.citations {
+        let section_position = content.sections[search_from..]
+            .iter()
+            .position(|s| s.heading == section.heading)
+            .map(|offset| search_from + offset)
+            .unwrap_or(search_from);
+        search_from = section_position + 1;
+        for cited in &section.references {
+            rows.push(SectionCitation {
+                paper_id,
+                section_position: section_position as i64,
+                heading: section.heading.clone(),
+                reference_position: cited.position as i64,
+                mentions: cited.mentions as i64,
+            });
+        }
+    }
+    rows
+}
@@ -1,50 +1,88 @@
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-use chrono::{DateTime, Utc, Duration};
+use anyhow::Result;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::time::Duration;
 
-#[derive(Clone)]
-pub struct CacheEntry<T> {
-    pub data: T,
-    pub expires_at: DateTime<Utc>,
-}
+use crate::storage::Database;
 
-pub struct Cache<T: Clone> {
-    store: Arc<RwLock<HashMap<String, CacheEntry<T>>>>,
+/// 持久化缓存（generic_cache 表），按命名空间和键保存任意内容，压缩存储
+///
+/// 命名空间区分用途（如 `arxiv_feed`），过期的条目读取时视为不存在，由 `clear_expired` 统一删除。
+#[derive(Clone)]
+pub struct Cache {
+    db: Database,
     ttl: Duration,
 }
 
-impl<T: Clone> Cache<T> {
-    pub fn new(ttl_days: i64) -> Self {
+impl Cache {
+    /// 默认有效期取 `storage.cache_ttl_days`，为 0 时不缓存
+    pub fn new(db: Database, ttl_days: u32) -> Self {
         Self {
-            store: Arc::new(RwLock::new(HashMap::new())),
-            ttl: Duration::days(ttl_days),
+            db,
+            ttl: Duration::from_secs(ttl_days as u64 * 24 * 3600),
         }
     }
 
-    pub fn get(&self, key: &str) -> Option<T> {
-        let store = self.store.read().unwrap();
-        if let Some(entry) = store.get(key) {
-            if entry.expires_at > Utc::now() {
-                return Some(entry.data.clone());
-            }
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    /// 读取未过期的条目
+    pub async fn get(&self, namespace: &str, key: &str) -> Result<Option<Vec<u8>>> {
+        if !self.is_enabled() {
+            return Ok(None);
         }
-        None
+        let row = sqlx::query_scalar::<_, Vec<u8>>(
+            "SELECT value_gz FROM generic_cache WHERE namespace = ? AND key = ? AND expires_at > datetime('now')"
+        )
+        .bind(namespace)
+        .bind(key)
+        .fetch_optional(self.db.pool())
+        .await?;
+
+        let Some(compressed) = row else {
+            return Ok(None);
+        };
+        let mut value = Vec::new();
+        GzDecoder::new(compressed.as_slice()).read_to_end(&mut value)?;
+        Ok(Some(value))
     }
 
-    pub fn set(&self, key: String, data: T) {
-        let mut store = self.store.write().unwrap();
-        store.insert(
-            key,
-            CacheEntry {
-                data,
-                expires_at: Utc::now() + self.ttl,
-            },
-        );
+    /// 按指定有效期写入，不超过默认有效期；已有条目被覆盖
+    pub async fn set_with_ttl(&self, namespace: &str, key: &str, value: &[u8], ttl: Duration) -> Result<()> {
+        let ttl = ttl.min(self.ttl);
+        if ttl.is_zero() {
+            return Ok(());
+        }
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(value)?;
+        let compressed = encoder.finish()?;
+
+        sqlx::query(
+            r#"INSERT INTO generic_cache (namespace, key, value_gz, expires_at)
+               VALUES (?, ?, ?, datetime('now', ?))
+               ON CONFLICT(namespace, key) DO UPDATE SET
+                   value_gz = excluded.value_gz,
+                   created_at = CURRENT_TIMESTAMP,
+                   expires_at = excluded.expires_at"#
+        )
+        .bind(namespace)
+        .bind(key)
+        .bind(compressed)
+        .bind(format!("+{} seconds", ttl.as_secs()))
+        .execute(self.db.pool())
+        .await?;
+
+        Ok(())
     }
 
-    pub fn clear_expired(&self) {
-        let mut store = self.store.write().unwrap();
-        let now = Utc::now();
-        store.retain(|_, entry| entry.expires_at > now);
+    /// 删除过期条目，返回删除数
+    pub async fn clear_expired(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM generic_cache WHERE expires_at <= datetime('now')")
+            .execute(self.db.pool())
+            .await?;
+        Ok(result.rows_affected())
     }
 }
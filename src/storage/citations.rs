@@ -0,0 +1,187 @@
+use anyhow::Result;
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+
+use crate::storage::dedup::normalize_title;
+use crate::storage::models::PaperReference;
+use crate::storage::Database;
+
+/// 按标题标识被引文献时，规范化后至少需要的词数，过短的标题容易误匹配
+const MIN_TITLE_WORDS: usize = 3;
+
+/// 库中被引用的论文及引用它的论文数
+#[derive(Debug, serde::Serialize, sqlx::FromRow)]
+pub struct CitedPaper {
+    pub id: i64,
+    pub title: String,
+    pub citations: i64,
+}
+
+/// 被引文献的标识：优先 `arxiv:<ID>`（不含版本号），其次 `doi:<DOI>`（小写），
+/// 都没有时用规范化的标题 `title:<标题>`
+pub fn cited_id(arxiv_id: Option<&str>, doi: Option<&str>, title: Option<&str>) -> Option<String> {
+    if let Some(arxiv_id) = arxiv_id.map(strip_arxiv_version).filter(|id| !id.is_empty()) {
+        return Some(format!("arxiv:{}", arxiv_id));
+    }
+    if let Some(doi) = doi.map(str::trim).filter(|doi| !doi.is_empty()) {
+        return Some(format!("doi:{}", doi.to_lowercase()));
+    }
+    let title = normalize_title(title?);
+    (title.split(' ').count() >= MIN_TITLE_WORDS).then(|| format!("title:{}", title))
+}
+
+/// 去掉 arXiv ID 的版本号，如 `2401.12345v2` → `2401.12345`
+fn strip_arxiv_version(id: &str) -> &str {
+    let id = id.trim();
+    match id.rfind('v') {
+        Some(pos) if pos > 0 && pos + 1 < id.len() && id[pos + 1..].chars().all(|c| c.is_ascii_digit()) => &id[..pos],
+        _ => id,
+    }
+}
+
+/// 用PDF中提取的参考文献替换论文的引用记录，在调用方的事务中执行；被引论文留待 [`resolve`] 关联
+pub(crate) async fn replace_from_references(
+    conn: &mut SqliteConnection,
+    paper_id: i64,
+    references: &[PaperReference],
+) -> Result<()> {
+    sqlx::query("DELETE FROM citations WHERE citing_paper_id = ? AND source = 'pdf'")
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+    for reference in references {
+        let Some(cited) = cited_id(reference.arxiv_id.as_deref(), reference.doi.as_deref(), reference.title.as_deref())
+        else {
+            continue;
+        };
+        sqlx::query("INSERT OR IGNORE INTO citations (citing_paper_id, cited_id, source) VALUES (?, ?, 'pdf')")
+            .bind(paper_id)
+            .bind(cited)
+            .execute(&mut *conn)
+            .await?;
+    }
+    Ok(())
+}
+
+/// 把尚未关联的被引文献关联到库中的论文（按 arXiv ID、DOI 或标题），返回新关联的引用数
+///
+/// 新论文入库后，之前爬取的论文对它的引用也能关联上，所以每次爬取结束后都会执行。
+pub async fn resolve(db: &Database) -> Result<u64> {
+    let pool = db.pool();
+    let unresolved = sqlx::query_scalar::<_, String>("SELECT DISTINCT cited_id FROM citations WHERE cited_paper_id IS NULL")
+        .fetch_all(pool)
+        .await?;
+    if unresolved.is_empty() {
+        return Ok(0);
+    }
+
+    let mut known: HashMap<String, i64> = HashMap::new();
+    for (source, source_id, paper_id) in sqlx::query_as::<_, (String, String, i64)>(
+        "SELECT source, source_id, paper_id FROM paper_sources WHERE source IN ('arxiv', 'doi')"
+    )
+    .fetch_all(pool)
+    .await?
+    {
+        let id = if source == "arxiv" {
+            cited_id(Some(&source_id), None, None)
+        } else {
+            cited_id(None, Some(&source_id), None)
+        };
+        if let Some(id) = id {
+            known.entry(id).or_insert(paper_id);
+        }
+    }
+    for (paper_id, title) in sqlx::query_as::<_, (i64, String)>("SELECT id, title FROM papers").fetch_all(pool).await? {
+        if let Some(id) = cited_id(None, None, Some(&title)) {
+            known.entry(id).or_insert(paper_id);
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    let mut resolved = 0;
+    for cited in unresolved {
+        let Some(&paper_id) = known.get(&cited) else {
+            continue;
+        };
+        // 不记录论文对自身的引用
+        resolved += sqlx::query(
+            "UPDATE citations SET cited_paper_id = ? WHERE cited_id = ? AND cited_paper_id IS NULL AND citing_paper_id != ?"
+        )
+        .bind(paper_id)
+        .bind(&cited)
+        .bind(paper_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+    tx.commit().await?;
+    Ok(resolved)
+}
+
+/// 最近 `days` 天入库的论文中引用了指定文献（`cited_id` 的标识）的篇数，用于评估尚未入库论文的引用速度
+pub async fn count_citing(db: &Database, cited: &str, days: u32) -> Result<i64> {
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"SELECT COUNT(DISTINCT c.citing_paper_id)
+           FROM citations c
+           JOIN papers p ON p.id = c.citing_paper_id
+           WHERE c.cited_id = ? AND p.deleted_at IS NULL
+             AND p.created_at >= datetime('now', ?)"#
+    )
+    .bind(cited)
+    .bind(format!("-{} days", days))
+    .fetch_one(db.pool())
+    .await?;
+
+    Ok(count)
+}
+
+/// 最近 `days` 天入库的论文引用了库中哪些论文，按引用次数从多到少
+pub async fn cited_by_recent(db: &Database, days: u32) -> Result<Vec<CitedPaper>> {
+    let papers = sqlx::query_as::<_, CitedPaper>(
+        r#"SELECT p.id, p.title, COUNT(DISTINCT c.citing_paper_id) AS citations
+           FROM citations c
+           JOIN papers citing ON citing.id = c.citing_paper_id
+           JOIN papers p ON p.id = c.cited_paper_id
+           WHERE citing.created_at >= datetime('now', ?)
+             AND citing.deleted_at IS NULL AND p.deleted_at IS NULL
+           GROUP BY p.id
+           ORDER BY citations DESC, p.id"#
+    )
+    .bind(format!("-{} days", days))
+    .fetch_all(db.pool())
+    .await?;
+
+    Ok(papers)
+}
+
+/// 论文引用的、库中已有的论文
+pub async fn cited_papers(db: &Database, paper_id: i64) -> Result<Vec<(i64, String)>> {
+    let papers = sqlx::query_as::<_, (i64, String)>(
+        r#"SELECT DISTINCT p.id, p.title
+           FROM citations c
+           JOIN papers p ON p.id = c.cited_paper_id
+           WHERE c.citing_paper_id = ? AND p.deleted_at IS NULL
+           ORDER BY p.id"#
+    )
+    .bind(paper_id)
+    .fetch_all(db.pool())
+    .await?;
+
+    Ok(papers)
+}
+
+/// 库中引用了该论文的论文
+pub async fn citing_papers(db: &Database, paper_id: i64) -> Result<Vec<(i64, String)>> {
+    let papers = sqlx::query_as::<_, (i64, String)>(
+        r#"SELECT DISTINCT p.id, p.title
+           FROM citations c
+           JOIN papers p ON p.id = c.citing_paper_id
+           WHERE c.cited_paper_id = ? AND p.deleted_at IS NULL
+           ORDER BY p.id"#
+    )
+    .bind(paper_id)
+    .fetch_all(db.pool())
+    .await?;
+
+    Ok(papers)
+}
@@ -1,268 +1,1380 @@
-use sqlx::{SqlitePool, sqlite::SqlitePoolOptions};
-use anyhow::Result;
+use sqlx::{SqlitePool, sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePoolOptions, SqliteSynchronous}};
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::time::Duration;
 use tracing::info;
-use crate::storage::models::Paper;
+use crate::storage::{migrations, writes};
+use crate::storage::models::{
+    ApiCall, BackfillProgress, CrawlRun, Paper, PaperFilter, PaperImage, PaperReference, PaperSort, PaperSource,
+    ReportRecord, SavedSearch, SectionPref, SubscriptionRecord, TranslationStatus, TriageDecision,
+};
+use crate::config::keywords::Subscription;
+use crate::config::StorageConfig;
 
+#[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
 }
 
 impl Database {
-    pub async fn new(database_url: &str) -> Result<Self> {
+    /// 按 `[storage]` 配置连接数据库
+    pub async fn open(storage: &StorageConfig) -> Result<Self> {
+        let database_url = storage.database_url();
+        let sqlite = &storage.sqlite;
         // 确保使用create_if_missing选项
+        let mut options = database_url
+            .parse::<SqliteConnectOptions>()?
+            .create_if_missing(true)
+            .journal_mode(sqlite.journal_mode.parse::<SqliteJournalMode>().context("[storage.sqlite] journal_mode 无效")?)
+            .synchronous(sqlite.synchronous.parse::<SqliteSynchronous>().context("[storage.sqlite] synchronous 无效")?)
+            .busy_timeout(Duration::from_secs(sqlite.busy_timeout_secs));
+        for (key, value) in &sqlite.pragmas {
+            options = options.pragma(key.clone(), value.clone());
+        }
         let pool = SqlitePoolOptions::new()
-            .max_connections(5)
-            .connect_with(
-                database_url.parse::<sqlx::sqlite::SqliteConnectOptions>()?
-                    .create_if_missing(true)
-            )
+            .max_connections(sqlite.max_connections.max(1))
+            .connect_with(options)
             .await?;
 
         info!("数据库连接成功: {}", database_url);
+        migrations::run(&pool).await?;
         Ok(Self { pool })
     }
 
-    pub async fn init_schema(&self) -> Result<()> {
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS papers (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                title TEXT NOT NULL,
-                title_zh TEXT,
-                authors TEXT,
-                abstract TEXT,
-                abstract_zh TEXT,
-                publish_date TEXT,
-                source TEXT NOT NULL,
-                source_id TEXT NOT NULL,
-                pdf_url TEXT,
-                pdf_path TEXT,
-                processed INTEGER DEFAULT 0,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                UNIQUE(source, source_id)
+    /// 数据库当前的结构版本
+    pub async fn schema_version(&self) -> Result<i64> {
+        migrations::current_version(&self.pool).await
+    }
+
+    pub fn pool(&self) -> &SqlitePool {
+        &self.pool
+    }
+
+    /// 将数据库一致性快照写入指定文件
+    pub async fn snapshot_to(&self, path: &str) -> Result<()> {
+        sqlx::query("VACUUM INTO ?")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 获取论文的所有来源，论文自身的来源在前
+    pub async fn get_paper_sources(&self, paper_id: i64) -> Result<Vec<PaperSource>> {
+        let sources = sqlx::query_as::<_, PaperSource>(
+            r#"SELECT s.paper_id, s.source, s.source_id, s.pdf_url, s.created_at
+               FROM paper_sources s
+               JOIN papers p ON p.id = s.paper_id
+               WHERE s.paper_id = ?
+               ORDER BY (s.source = p.source AND s.source_id = p.source_id) DESC, s.created_at"#
+        )
+        .bind(paper_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(sources)
+    }
+
+    /// 获取合并进来的其他来源，按论文ID索引（不含论文自身的来源）
+    pub async fn get_alternate_sources(&self) -> Result<std::collections::HashMap<i64, Vec<PaperSource>>> {
+        let sources = sqlx::query_as::<_, PaperSource>(
+            r#"SELECT s.paper_id, s.source, s.source_id, s.pdf_url, s.created_at
+               FROM paper_sources s
+               JOIN papers p ON p.id = s.paper_id
+               WHERE NOT (s.source = p.source AND s.source_id = p.source_id)
+               ORDER BY s.paper_id, s.created_at"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut map: std::collections::HashMap<i64, Vec<PaperSource>> = std::collections::HashMap::new();
+        for source in sources {
+            map.entry(source.paper_id).or_default().push(source);
+        }
+        Ok(map)
+    }
+
+    /// 检查论文是否已存在
+    pub async fn paper_exists(&self, source: &str, source_id: &str) -> Result<bool> {
+        let result = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM paper_sources WHERE source = ? AND source_id = ?"
+        )
+        .bind(source)
+        .bind(source_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(result > 0)
+    }
+
+    /// 按ID获取论文
+    pub async fn get_paper_by_id(&self, paper_id: i64) -> Result<Option<Paper>> {
+        let paper = sqlx::query_as::<_, Paper>(
+            r#"SELECT id, title, title_zh, authors,
+                      abstract AS abstract_text, abstract_zh,
+                      publish_date, source, source_id,
+                      pdf_url, pdf_path, processed, created_at, translation_status, code_urls, venue, funding
+               FROM papers
+               WHERE id = ? AND deleted_at IS NULL"#
+        )
+        .bind(paper_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(paper)
+    }
+
+    /// 按来源和来源ID获取论文
+    pub async fn get_paper_by_source(&self, source: &str, source_id: &str) -> Result<Option<Paper>> {
+        let paper = sqlx::query_as::<_, Paper>(
+            r#"SELECT id, title, title_zh, authors,
+                      abstract AS abstract_text, abstract_zh,
+                      publish_date, source, source_id,
+                      pdf_url, pdf_path, processed, created_at, translation_status, code_urls, venue, funding
+               FROM papers
+               WHERE id = (SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?)"#
+        )
+        .bind(source)
+        .bind(source_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(paper)
+    }
+
+    /// 更新论文的PDF路径
+    pub async fn update_pdf_path(&self, source: &str, source_id: &str, pdf_path: &str) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        writes::update_pdf_path(&mut conn, source, source_id, pdf_path).await
+    }
+
+    /// 记录PDF无法打开（损坏、加密）的原因，解析成功后传 None 清除
+    pub async fn set_pdf_error(&self, paper_id: i64, error: Option<&str>) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        writes::set_pdf_error(&mut conn, paper_id, error).await
+    }
+
+    /// 更新论文的中文翻译
+    pub async fn update_translation(
+        &self,
+        source: &str,
+        source_id: &str,
+        title_zh: &str,
+        abstract_zh: &str,
+        provider: &str,
+    ) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        writes::update_translation(&mut conn, source, source_id, title_zh, abstract_zh, provider).await
+    }
+
+    /// 标题和摘要已是目标语言：原文直接作为译文，不调用翻译服务
+    pub async fn mark_same_language(&self, source: &str, source_id: &str) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        writes::mark_same_language(&mut conn, source, source_id).await
+    }
+
+    /// 获取译文标题含有指定标记的论文（如无法解析时的回退标记）
+    pub async fn get_papers_with_title_zh_marker(&self, marker: &str) -> Result<Vec<Paper>> {
+        let papers = sqlx::query_as::<_, Paper>(
+            r#"SELECT id, title, title_zh, authors,
+                      abstract AS abstract_text, abstract_zh,
+                      publish_date, source, source_id,
+                      pdf_url, pdf_path, processed, created_at, translation_status, code_urls, venue, funding
+               FROM papers
+               WHERE instr(title_zh, ?) > 0 AND deleted_at IS NULL"#
+        )
+        .bind(marker)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(papers)
+    }
+
+    /// 更新论文的翻译状态
+    pub async fn set_translation_status(
+        &self,
+        source: &str,
+        source_id: &str,
+        status: TranslationStatus,
+    ) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        writes::set_translation_status(&mut conn, source, source_id, status).await
+    }
+
+    /// 按翻译状态统计论文数
+    pub async fn count_translation_statuses(&self) -> Result<Vec<(String, i64)>> {
+        let counts = sqlx::query_as::<_, (String, i64)>(
+            "SELECT translation_status, COUNT(*) FROM papers WHERE deleted_at IS NULL GROUP BY translation_status ORDER BY translation_status"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// 将论文移入回收站，提取内容等关联记录保留，可用 [`Database::restore_paper`] 恢复；
+    /// 论文不存在或已在回收站中时返回 false
+    pub async fn soft_delete_paper(&self, paper_id: i64) -> Result<bool> {
+        let result = sqlx::query("UPDATE papers SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL")
+            .bind(paper_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 从回收站恢复论文；论文不在回收站中时返回 false
+    pub async fn restore_paper(&self, paper_id: i64) -> Result<bool> {
+        let result = sqlx::query("UPDATE papers SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(paper_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 论文是否在回收站中（按任一来源ID匹配）
+    pub async fn is_paper_deleted(&self, source: &str, source_id: &str) -> Result<bool> {
+        let deleted = sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM papers
+               WHERE id = (SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?)
+                 AND deleted_at IS NOT NULL"#
+        )
+        .bind(source)
+        .bind(source_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(deleted > 0)
+    }
+
+    /// 有PDF链接但没有本地PDF的论文ID和标题（不含回收站）
+    pub async fn get_papers_without_pdf(&self) -> Result<Vec<(i64, String)>> {
+        let papers = sqlx::query_as::<_, (i64, String)>(
+            r#"SELECT id, title FROM papers
+               WHERE deleted_at IS NULL AND pdf_path IS NULL AND COALESCE(pdf_url, '') != ''
+               ORDER BY id"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(papers)
+    }
+
+    /// 有本地PDF的论文ID和标题（不含回收站），`failed_only` 时只返回PDF无法打开或没有提取内容的论文
+    pub async fn get_papers_with_pdf(&self, failed_only: bool) -> Result<Vec<(i64, String)>> {
+        let papers = sqlx::query_as::<_, (i64, String)>(
+            r#"SELECT id, title FROM papers p
+               WHERE deleted_at IS NULL AND pdf_path IS NOT NULL
+                 AND (? = 0 OR pdf_error IS NOT NULL
+                      OR NOT EXISTS (SELECT 1 FROM extracted_content e WHERE e.paper_id = p.id))
+               ORDER BY id"#
+        )
+        .bind(failed_only)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(papers)
+    }
+
+    /// 回收站中的论文及删除时间，最近删除的在前
+    pub async fn get_deleted_papers(&self) -> Result<Vec<(i64, String, String)>> {
+        let papers = sqlx::query_as::<_, (i64, String, String)>(
+            "SELECT id, title, deleted_at FROM papers WHERE deleted_at IS NOT NULL ORDER BY deleted_at DESC, id DESC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(papers)
+    }
+
+    /// 删除论文及其提取内容、标签、来源等全部关联记录（不删除文件），返回删除的论文数
+    pub async fn delete_papers(&self, paper_ids: &[i64]) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut deleted = 0;
+        for &paper_id in paper_ids {
+            sqlx::query(
+                r#"DELETE FROM translation_sources
+                   WHERE (source, source_id) IN (SELECT source, source_id FROM paper_sources WHERE paper_id = ?)"#
             )
-            "#,
+            .bind(paper_id)
+            .execute(&mut *tx)
+            .await?;
+            for table in [
+                "extracted_content",
+                "section_prefs",
+                "paper_sources",
+                "paper_relevance",
+                "paper_images",
+                "paper_triage",
+                r#""references""#,
+                "section_citations",
+                "paper_keyphrases",
+                "paper_tags",
+                "embeddings",
+                "provenance",
+            ] {
+                sqlx::query(&format!("DELETE FROM {} WHERE paper_id = ?", table))
+                    .bind(paper_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            sqlx::query("DELETE FROM citations WHERE citing_paper_id = ?")
+                .bind(paper_id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("UPDATE citations SET cited_paper_id = NULL WHERE cited_paper_id = ?")
+                .bind(paper_id)
+                .execute(&mut *tx)
+                .await?;
+            deleted += sqlx::query("DELETE FROM papers WHERE id = ?")
+                .bind(paper_id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    /// 清空所有缓存数据表（保留 subscriptions 和 saved_searches）
+    pub async fn clear_all_tables(&self) -> Result<()> {
+        // 先删有外键依赖的表
+        sqlx::query("DELETE FROM extracted_content").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM section_prefs").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM reports").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM paper_sources").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM papers").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM pdf_texts").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM translations_cache").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM generic_cache").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM crawl_runs").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM api_calls").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM translation_sources").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM backfill_progress").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM paper_relevance").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM paper_images").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM paper_triage").execute(&self.pool).await?;
+        sqlx::query(r#"DELETE FROM "references""#).execute(&self.pool).await?;
+        sqlx::query("DELETE FROM citations").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM embeddings").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM provenance").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM section_citations").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM paper_keyphrases").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM paper_tags").execute(&self.pool).await?;
+        sqlx::query("DELETE FROM tags").execute(&self.pool).await?;
+        info!("数据库表已清空");
+        Ok(())
+    }
+
+    /// 替换论文的图片引用
+    pub async fn save_paper_images(&self, paper_id: i64, images: &[PaperImage]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        writes::save_paper_images(&mut tx, paper_id, images).await?;
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// 获取所有论文的关键短语（论文ID → 按得分排列的短语）
+    pub async fn get_keyphrase_map(&self) -> Result<std::collections::HashMap<i64, Vec<String>>> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "SELECT paper_id, phrase FROM paper_keyphrases ORDER BY paper_id, position"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut map: std::collections::HashMap<i64, Vec<String>> = std::collections::HashMap::new();
+        for (paper_id, phrase) in rows {
+            map.entry(paper_id).or_default().push(phrase);
+        }
+        Ok(map)
+    }
+
+    /// 为论文添加标签（不存在时创建），返回是否新增；`source` 为 manual 或 subscription
+    pub async fn add_tag(&self, paper_id: i64, name: &str, source: &str) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        writes::add_tag(&mut conn, paper_id, name, source).await
+    }
+
+    /// 移除论文的标签，返回是否存在；不再被任何论文使用的标签一并删除
+    pub async fn remove_tag(&self, paper_id: i64, name: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "DELETE FROM paper_tags WHERE paper_id = ? AND tag_id IN (SELECT id FROM tags WHERE name = ?)"
         )
+        .bind(paper_id)
+        .bind(name)
         .execute(&self.pool)
         .await?;
+        sqlx::query("DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM paper_tags)")
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 获取一篇论文的标签
+    pub async fn get_paper_tags(&self, paper_id: i64) -> Result<Vec<String>> {
+        let tags = sqlx::query_scalar::<_, String>(
+            r#"SELECT t.name FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id
+               WHERE pt.paper_id = ?
+               ORDER BY pt.created_at, t.name"#
+        )
+        .bind(paper_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tags)
+    }
+
+    /// 获取所有论文的标签，按论文ID索引
+    pub async fn get_tag_map(&self) -> Result<std::collections::HashMap<i64, Vec<String>>> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            r#"SELECT pt.paper_id, t.name FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id
+               ORDER BY pt.paper_id, pt.created_at, t.name"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut map: std::collections::HashMap<i64, Vec<String>> = std::collections::HashMap::new();
+        for (paper_id, name) in rows {
+            map.entry(paper_id).or_default().push(name);
+        }
+        Ok(map)
+    }
+
+    /// 所有标签及其论文数，按论文数从多到少排列
+    pub async fn get_tag_counts(&self) -> Result<Vec<(String, i64)>> {
+        let rows = sqlx::query_as::<_, (String, i64)>(
+            r#"SELECT t.name, COUNT(pt.paper_id) AS n
+               FROM tags t LEFT JOIN paper_tags pt ON pt.tag_id = t.id
+               GROUP BY t.id
+               ORDER BY n DESC, t.name"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 带有任一指定标签的论文ID（标签名不区分大小写）
+    pub async fn get_paper_ids_with_tags(&self, tags: &[String]) -> Result<std::collections::HashSet<i64>> {
+        let mut ids = std::collections::HashSet::new();
+        for tag in tags {
+            let rows = sqlx::query_scalar::<_, i64>(
+                "SELECT pt.paper_id FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id WHERE t.name = ?"
+            )
+            .bind(tag.trim())
+            .fetch_all(&self.pool)
+            .await?;
+            ids.extend(rows);
+        }
+        Ok(ids)
+    }
+
+    /// 获取论文的参考文献，按原顺序排列
+    pub async fn get_references(&self, paper_id: i64) -> Result<Vec<PaperReference>> {
+        let references = sqlx::query_as::<_, PaperReference>(
+            r#"SELECT paper_id, position, label, raw, authors, year, title, venue, arxiv_id, doi
+               FROM "references"
+               WHERE paper_id = ?
+               ORDER BY position"#
+        )
+        .bind(paper_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(references)
+    }
+
+    /// 获取所有提取结果中的图片 JSON
+    pub async fn get_extracted_images(&self) -> Result<Vec<(i64, String)>> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "SELECT paper_id, images FROM extracted_content WHERE images IS NOT NULL AND images != ''"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 获取所有提取结果中的表格 JSON
+    pub async fn get_extracted_tables(&self) -> Result<Vec<(i64, String)>> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            "SELECT paper_id, tables FROM extracted_content WHERE tables IS NOT NULL AND tables != '' ORDER BY paper_id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 更新提取结果中的图片 JSON
+    pub async fn update_extracted_images(&self, paper_id: i64, images: &str) -> Result<()> {
+        sqlx::query("UPDATE extracted_content SET images = ? WHERE paper_id = ?")
+            .bind(images)
+            .bind(paper_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 获取所有论文的要点，按 source_id 索引
+    pub async fn get_key_points(&self) -> Result<std::collections::HashMap<String, Vec<String>>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            r#"SELECT p.source_id, e.key_points
+               FROM extracted_content e
+               JOIN papers p ON p.id = e.paper_id
+               WHERE e.key_points IS NOT NULL"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(source_id, json)| {
+                let points: Vec<String> = serde_json::from_str(&json).ok()?;
+                (!points.is_empty()).then_some((source_id, points))
+            })
+            .collect())
+    }
+
+    /// 获取尚未分拣的论文，最新入库的在前
+    pub async fn get_untriaged_papers(&self, limit: i64) -> Result<Vec<Paper>> {
+        let papers = sqlx::query_as::<_, Paper>(
+            r#"SELECT p.id, p.title, p.title_zh, p.authors,
+                      p.abstract AS abstract_text, p.abstract_zh,
+                      p.publish_date, p.source, p.source_id,
+                      p.pdf_url, p.pdf_path, p.processed, p.created_at, p.translation_status
+               FROM papers p
+               LEFT JOIN paper_triage t ON t.paper_id = p.id
+               WHERE t.paper_id IS NULL AND p.deleted_at IS NULL
+               ORDER BY p.created_at DESC, p.id DESC
+               LIMIT ?"#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(papers)
+    }
 
+    /// 记录论文的分拣决定（upsert）
+    pub async fn set_triage_decision(&self, paper_id: i64, decision: TriageDecision) -> Result<()> {
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS subscriptions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                keywords TEXT NOT NULL,
-                sources TEXT NOT NULL,
-                categories TEXT,
-                enabled INTEGER DEFAULT 1,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP
-            )
+            INSERT INTO paper_triage (paper_id, decision)
+            VALUES (?, ?)
+            ON CONFLICT(paper_id) DO UPDATE SET
+                decision = excluded.decision,
+                decided_at = CURRENT_TIMESTAMP
             "#,
         )
+        .bind(paper_id)
+        .bind(decision.as_str())
         .execute(&self.pool)
         .await?;
+        if decision == TriageDecision::Star {
+            self.set_starred(paper_id, true).await?;
+        }
+
+        Ok(())
+    }
+
+    /// 标记论文已读或未读，已读时记录当前时间；论文不存在时返回 false
+    pub async fn set_read(&self, paper_id: i64, read: bool) -> Result<bool> {
+        let sql = if read {
+            "UPDATE papers SET read_at = COALESCE(read_at, CURRENT_TIMESTAMP) WHERE id = ?"
+        } else {
+            "UPDATE papers SET read_at = NULL WHERE id = ?"
+        };
+        let result = sqlx::query(sql).bind(paper_id).execute(&self.pool).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 添加或取消星标；论文不存在时返回 false
+    pub async fn set_starred(&self, paper_id: i64, starred: bool) -> Result<bool> {
+        let result = sqlx::query("UPDATE papers SET starred = ? WHERE id = ?")
+            .bind(starred)
+            .bind(paper_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 获取所有已读论文的 source_id
+    pub async fn get_read_source_ids(&self) -> Result<std::collections::HashSet<String>> {
+        let ids = sqlx::query_scalar::<_, String>("SELECT source_id FROM papers WHERE read_at IS NOT NULL AND deleted_at IS NULL")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// 获取所有星标论文的 source_id
+    pub async fn get_starred_source_ids(&self) -> Result<std::collections::HashSet<String>> {
+        let ids = sqlx::query_scalar::<_, String>("SELECT source_id FROM papers WHERE starred = 1 AND deleted_at IS NULL")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(ids.into_iter().collect())
+    }
+
+    /// 获取所有星标论文的标题和摘要
+    pub async fn get_starred_abstracts(&self) -> Result<Vec<(String, String)>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT title, COALESCE(abstract, '') FROM papers WHERE starred = 1 AND deleted_at IS NULL"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// 论文是否已有与该订阅的相关度记录
+    pub async fn has_relevance(&self, paper_id: i64, subscription: &str) -> Result<bool> {
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM paper_relevance WHERE paper_id = ? AND subscription = ?)"
+        )
+        .bind(paper_id)
+        .bind(subscription)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    /// 记录已有论文与又一个订阅的相关度，并添加该订阅的标签
+    pub async fn save_relevance(
+        &self,
+        paper_id: i64,
+        subscription: &str,
+        score: f32,
+        reasons: &[String],
+        label: Option<&str>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        writes::save_relevance(&mut tx, paper_id, subscription, score, reasons, label).await?;
+        writes::add_tag(&mut tx, paper_id, subscription, "subscription").await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 获取所有论文的 LLM 相关度标签，按 source_id 索引；属于多个订阅时取最相关的标签
+    pub async fn get_relevance_labels(&self) -> Result<std::collections::HashMap<String, String>> {
+        // 按相关程度升序，收集到 HashMap 时后出现的记录覆盖先出现的
+        let rows = sqlx::query_as::<_, (String, String)>(
+            r#"SELECT p.source_id, r.label
+               FROM paper_relevance r
+               JOIN papers p ON p.id = r.paper_id
+               WHERE r.label IS NOT NULL
+               ORDER BY CASE r.label WHEN 'core' THEN 0 WHEN 'related' THEN 1 ELSE 2 END DESC"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// 获取所有论文与订阅的相关度（0~1），按 source_id 索引；属于多个订阅时取最高分
+    pub async fn get_relevance_scores(&self) -> Result<std::collections::HashMap<String, f32>> {
+        let rows = sqlx::query_as::<_, (String, f32)>(
+            r#"SELECT p.source_id, MAX(r.score)
+               FROM paper_relevance r
+               JOIN papers p ON p.id = r.paper_id
+               GROUP BY r.paper_id"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// 获取所有论文的相关度评分依据，按 source_id 索引；属于多个订阅时取得分最高的订阅
+    pub async fn get_relevance_reasons(&self) -> Result<std::collections::HashMap<String, Vec<String>>> {
+        let rows = sqlx::query_as::<_, (String, String)>(
+            r#"SELECT p.source_id, r.reasons
+               FROM paper_relevance r
+               JOIN papers p ON p.id = r.paper_id
+               WHERE r.reasons IS NOT NULL
+               ORDER BY r.score"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(source_id, json)| {
+                let reasons: Vec<String> = serde_json::from_str(&json).ok()?;
+                (!reasons.is_empty()).then_some((source_id, reasons))
+            })
+            .collect())
+    }
+
+    /// 获取论文所属的订阅，按论文ID索引；属于多个订阅时取得分最高的，已关联订阅ID的使用订阅表中的名称
+    pub async fn get_paper_subscriptions(&self) -> Result<std::collections::HashMap<i64, String>> {
+        let rows = sqlx::query_as::<_, (i64, String)>(
+            r#"SELECT r.paper_id, COALESCE(s.name, r.subscription) AS name
+               FROM paper_relevance r
+               LEFT JOIN subscriptions s ON s.id = r.subscription_id
+               WHERE COALESCE(s.name, r.subscription) != ''
+               ORDER BY r.score"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// 获取所有论文
+    pub async fn get_all_papers(&self) -> Result<Vec<Paper>> {
+        self.query_papers(&PaperFilter::default(), PaperSort::Id, None, 0).await
+    }
+
+    /// 按条件分页查询论文，`limit` 为 `None` 时不限数量
+    pub async fn query_papers(
+        &self,
+        filter: &PaperFilter,
+        sort: PaperSort,
+        limit: Option<i64>,
+        offset: i64,
+    ) -> Result<Vec<Paper>> {
+        let (clause, params) = paper_filter_clause(filter);
+        let sql = format!(
+            r#"SELECT id, title, title_zh, authors,
+                      abstract AS abstract_text, abstract_zh,
+                      publish_date, source, source_id,
+                      pdf_url, pdf_path, processed, created_at, translation_status, code_urls, venue, funding
+               FROM papers WHERE {}
+               ORDER BY {}
+               LIMIT ? OFFSET ?"#,
+            clause,
+            sort.order_by()
+        );
+
+        let mut q = sqlx::query_as::<_, Paper>(&sql);
+        for param in &params {
+            q = q.bind(param);
+        }
+        // SQLite 中 LIMIT -1 表示不限数量
+        let papers = q.bind(limit.unwrap_or(-1)).bind(offset).fetch_all(&self.pool).await?;
+
+        Ok(papers)
+    }
+
+    /// 满足条件的论文数，用于分页
+    pub async fn count_papers(&self, filter: &PaperFilter) -> Result<i64> {
+        let (clause, params) = paper_filter_clause(filter);
+        let sql = format!("SELECT COUNT(*) FROM papers WHERE {}", clause);
+        let mut q = sqlx::query_scalar::<_, i64>(&sql);
+        for param in &params {
+            q = q.bind(param);
+        }
+        Ok(q.fetch_one(&self.pool).await?)
+    }
+
+    /// 按入库时间从新到旧获取最近的论文
+    pub async fn get_recent_papers(&self, limit: i64) -> Result<Vec<Paper>> {
+        self.query_papers(&PaperFilter::default(), PaperSort::Newest, Some(limit), 0).await
+    }
+
+    /// 在本地论文库中搜索，每个词都需命中标题或摘要（含译文）
+    pub async fn search_papers(&self, query: &str, limit: i64) -> Result<Vec<Paper>> {
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|t| format!("%{}%", t))
+            .collect();
+
+        let mut sql = String::from(
+            r#"SELECT id, title, title_zh, authors,
+                      abstract AS abstract_text, abstract_zh,
+                      publish_date, source, source_id,
+                      pdf_url, pdf_path, processed, created_at, translation_status, code_urls, venue, funding
+               FROM papers WHERE deleted_at IS NULL"#,
+        );
+        for _ in &terms {
+            sql.push_str(
+                " AND (title LIKE ? OR abstract LIKE ? OR title_zh LIKE ? OR abstract_zh LIKE ?)",
+            );
+        }
+        sql.push_str(" ORDER BY publish_date DESC LIMIT ?");
+
+        let mut q = sqlx::query_as::<_, Paper>(&sql);
+        for term in &terms {
+            q = q.bind(term).bind(term).bind(term).bind(term);
+        }
+        let papers = q.bind(limit).fetch_all(&self.pool).await?;
+
+        Ok(papers)
+    }
+
+    /// 保存搜索条件，已存在时返回原有ID
+    pub async fn save_search(&self, query: &str) -> Result<i64> {
+        sqlx::query("INSERT INTO saved_searches (query) VALUES (?) ON CONFLICT(query) DO NOTHING")
+            .bind(query)
+            .execute(&self.pool)
+            .await?;
+
+        let id = sqlx::query_scalar::<_, i64>("SELECT id FROM saved_searches WHERE query = ?")
+            .bind(query)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(id)
+    }
+
+    /// 获取所有已保存的搜索
+    pub async fn get_saved_searches(&self) -> Result<Vec<SavedSearch>> {
+        let searches = sqlx::query_as::<_, SavedSearch>(
+            "SELECT id, query, promoted, last_run_at, created_at FROM saved_searches ORDER BY id"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(searches)
+    }
+
+    /// 按ID获取已保存的搜索
+    pub async fn get_saved_search(&self, id: i64) -> Result<Option<SavedSearch>> {
+        let search = sqlx::query_as::<_, SavedSearch>(
+            "SELECT id, query, promoted, last_run_at, created_at FROM saved_searches WHERE id = ?"
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(search)
+    }
+
+    /// 记录已保存搜索的最近运行时间
+    pub async fn touch_saved_search(&self, id: i64) -> Result<()> {
+        sqlx::query("UPDATE saved_searches SET last_run_at = CURRENT_TIMESTAMP WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 标记已保存搜索已提升为订阅
+    pub async fn mark_search_promoted(&self, id: i64) -> Result<()> {
+        sqlx::query("UPDATE saved_searches SET promoted = 1 WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
 
+        Ok(())
+    }
+
+    /// 删除已保存的搜索
+    pub async fn delete_saved_search(&self, id: i64) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM saved_searches WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 记录一次爬取的匹配数、处理结果、错误和耗时
+    pub async fn record_crawl_run(&self, run: &CrawlRun) -> Result<()> {
         sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS extracted_content (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                paper_id INTEGER NOT NULL,
-                formulas TEXT,
-                images TEXT,
-                tables TEXT,
-                key_points TEXT,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (paper_id) REFERENCES papers(id),
-                UNIQUE(paper_id)
-            )
-            "#,
+            r#"INSERT INTO crawl_runs (subscription, subscription_id, source, total_matched, fetched,
+                                      saved, existing, rejected, errors, error, duration_ms)
+               VALUES (?, (SELECT id FROM subscriptions WHERE name = ?), ?, ?, ?, ?, ?, ?, ?, ?, ?)"#
         )
+        .bind(&run.subscription)
+        .bind(&run.subscription)
+        .bind(&run.source)
+        .bind(run.total_matched)
+        .bind(run.fetched)
+        .bind(run.saved)
+        .bind(run.existing)
+        .bind(run.rejected)
+        .bind(run.errors)
+        .bind(&run.error)
+        .bind(run.duration_ms)
         .execute(&self.pool)
         .await?;
 
+        Ok(())
+    }
+
+    /// 记录一次 LLM API 请求
+    pub async fn record_api_call(&self, call: &ApiCall) -> Result<()> {
         sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS reports (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                subscription_id INTEGER,
-                report_date TEXT NOT NULL,
-                paper_count INTEGER,
-                ppt_path TEXT,
-                status TEXT DEFAULT 'pending',
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
-            )
-            "#,
+            r#"INSERT INTO api_calls (provider, model, purpose, success, http_status, error, duration_ms,
+                                     prompt_tokens, completion_tokens)
+               VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"#
         )
+        .bind(&call.provider)
+        .bind(&call.model)
+        .bind(&call.purpose)
+        .bind(call.success)
+        .bind(call.http_status)
+        .bind(&call.error)
+        .bind(call.duration_ms)
+        .bind(call.prompt_tokens)
+        .bind(call.completion_tokens)
         .execute(&self.pool)
         .await?;
 
-        info!("数据库表结构初始化完成");
         Ok(())
     }
 
-    pub fn pool(&self) -> &SqlitePool {
-        &self.pool
+    /// 读取某订阅某月的回溯爬取进度
+    pub async fn get_backfill_progress(
+        &self,
+        subscription: &str,
+        source: &str,
+        month: &str,
+    ) -> Result<Option<BackfillProgress>> {
+        let progress = sqlx::query_as::<_, BackfillProgress>(
+            r#"SELECT subscription, source, month, fetched, total_matched, completed, updated_at
+               FROM backfill_progress
+               WHERE subscription = ? AND source = ? AND month = ?"#
+        )
+        .bind(subscription)
+        .bind(source)
+        .bind(month)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(progress)
     }
 
-    /// 保存论文到数据库
-    pub async fn save_paper(&self, paper: &Paper) -> Result<i64> {
+    /// 保存回溯爬取进度（每页处理完后调用）
+    pub async fn save_backfill_progress(
+        &self,
+        subscription: &str,
+        source: &str,
+        month: &str,
+        fetched: i64,
+        total_matched: Option<i64>,
+        completed: bool,
+    ) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        writes::save_backfill_progress(&mut conn, subscription, source, month, fetched, total_matched, completed).await
+    }
+
+    /// 记录一次生成的报告，返回记录ID
+    pub async fn record_report(&self, report: &ReportRecord) -> Result<i64> {
         let result = sqlx::query(
             r#"
-            INSERT INTO papers (title, authors, abstract, publish_date, source, source_id, pdf_url, pdf_path)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            ON CONFLICT(source, source_id) DO UPDATE SET
-                title = excluded.title,
-                authors = excluded.authors,
-                abstract = excluded.abstract,
-                pdf_url = excluded.pdf_url,
-                pdf_path = excluded.pdf_path
+            INSERT INTO reports (report_date, paper_count, output_path, format, subscriptions, group_by, sort_by, lang, tags, status)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
-        .bind(&paper.title)
-        .bind(&paper.authors)
-        .bind(&paper.abstract_text)
-        .bind(&paper.publish_date)
-        .bind(&paper.source)
-        .bind(&paper.source_id)
-        .bind(&paper.pdf_url)
-        .bind(&paper.pdf_path)
+        .bind(&report.report_date)
+        .bind(report.paper_count)
+        .bind(&report.output_path)
+        .bind(&report.format)
+        .bind(&report.subscriptions)
+        .bind(&report.group_by)
+        .bind(&report.sort_by)
+        .bind(&report.lang)
+        .bind(&report.tags)
+        .bind(&report.status)
         .execute(&self.pool)
         .await?;
 
         Ok(result.last_insert_rowid())
     }
 
-    /// 检查论文是否已存在
-    pub async fn paper_exists(&self, source: &str, source_id: &str) -> Result<bool> {
-        let result = sqlx::query_scalar::<_, i64>(
-            "SELECT COUNT(*) FROM papers WHERE source = ? AND source_id = ?"
+    pub async fn update_report_status(&self, id: i64, status: &str) -> Result<()> {
+        sqlx::query("UPDATE reports SET status = ? WHERE id = ?")
+            .bind(status)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 最近生成的报告，新的在前
+    pub async fn get_reports(&self, limit: i64) -> Result<Vec<ReportRecord>> {
+        let reports = sqlx::query_as::<_, ReportRecord>(
+            r#"SELECT id, report_date, paper_count, output_path, format, subscriptions, group_by, sort_by, lang, tags, status, created_at
+               FROM reports
+               ORDER BY id DESC
+               LIMIT ?"#
         )
-        .bind(source)
-        .bind(source_id)
-        .fetch_one(&self.pool)
+        .bind(limit)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(result > 0)
+        Ok(reports)
     }
 
-    /// 更新论文的PDF路径
-    pub async fn update_pdf_path(&self, source: &str, source_id: &str, pdf_path: &str) -> Result<()> {
+    pub async fn get_report(&self, id: i64) -> Result<Option<ReportRecord>> {
+        let report = sqlx::query_as::<_, ReportRecord>(
+            r#"SELECT id, report_date, paper_count, output_path, format, subscriptions, group_by, sort_by, lang, tags, status, created_at
+               FROM reports
+               WHERE id = ?"#
+        )
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    /// 获取每个订阅/数据源最近一次的爬取记录
+    pub async fn get_latest_crawl_runs(&self) -> Result<Vec<CrawlRun>> {
+        let runs = sqlx::query_as::<_, CrawlRun>(
+            r#"SELECT id, subscription, source, total_matched, fetched, created_at,
+                      saved, existing, rejected, errors, error, duration_ms
+               FROM crawl_runs
+               WHERE id IN (SELECT MAX(id) FROM crawl_runs GROUP BY subscription, source)
+               ORDER BY subscription"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    /// 按PDF哈希读取缓存的全文文本
+    pub async fn get_pdf_text(&self, pdf_hash: &str) -> Result<Option<String>> {
+        let row = sqlx::query_scalar::<_, Vec<u8>>("SELECT text_gz FROM pdf_texts WHERE pdf_hash = ?")
+            .bind(pdf_hash)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(compressed) = row else {
+            return Ok(None);
+        };
+        let mut text = String::new();
+        GzDecoder::new(compressed.as_slice()).read_to_string(&mut text)?;
+        Ok(Some(text))
+    }
+
+    /// 压缩保存PDF全文文本（upsert）
+    pub async fn save_pdf_text(&self, pdf_hash: &str, text: &str) -> Result<()> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes())?;
+        let compressed = encoder.finish()?;
+
         sqlx::query(
-            "UPDATE papers SET pdf_path = ? WHERE source = ? AND source_id = ?"
+            r#"
+            INSERT INTO pdf_texts (pdf_hash, text_gz, text_len)
+            VALUES (?, ?, ?)
+            ON CONFLICT(pdf_hash) DO UPDATE SET
+                text_gz = excluded.text_gz,
+                text_len = excluded.text_len
+            "#,
         )
-        .bind(pdf_path)
-        .bind(source)
-        .bind(source_id)
+        .bind(pdf_hash)
+        .bind(compressed)
+        .bind(text.len() as i64)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    /// 保存提取内容到 extracted_content 表（upsert）
-    pub async fn save_extracted_content(
-        &self,
-        paper_id: i64,
-        formulas: &str,
-        images: &str,
-        tables: &str,
-        key_points: &str,
-    ) -> Result<()> {
+    /// 设置单篇论文的章节偏好（同一标题模式后设置的覆盖先设置的）
+    pub async fn set_section_pref(&self, paper_id: i64, pattern: &str, action: &str) -> Result<()> {
         sqlx::query(
             r#"
-            INSERT INTO extracted_content (paper_id, formulas, images, tables, key_points)
-            VALUES (?, ?, ?, ?, ?)
-            ON CONFLICT(paper_id) DO UPDATE SET
-                formulas = excluded.formulas,
-                images = excluded.images,
-                tables = excluded.tables,
-                key_points = excluded.key_points
+            INSERT INTO section_prefs (paper_id, pattern, action)
+            VALUES (?, ?, ?)
+            ON CONFLICT(paper_id, pattern) DO UPDATE SET action = excluded.action
             "#,
         )
         .bind(paper_id)
-        .bind(formulas)
-        .bind(images)
-        .bind(tables)
-        .bind(key_points)
+        .bind(pattern)
+        .bind(action)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    /// 标记论文已处理
-    pub async fn mark_paper_processed(&self, source: &str, source_id: &str) -> Result<()> {
-        sqlx::query(
-            "UPDATE papers SET processed = 1 WHERE source = ? AND source_id = ?"
+    /// 清除单篇论文的章节偏好
+    pub async fn clear_section_prefs(&self, paper_id: i64) -> Result<()> {
+        sqlx::query("DELETE FROM section_prefs WHERE paper_id = ?")
+            .bind(paper_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 获取所有章节偏好
+    pub async fn get_section_prefs(&self) -> Result<Vec<SectionPref>> {
+        let prefs = sqlx::query_as::<_, SectionPref>(
+            "SELECT paper_id, pattern, action FROM section_prefs ORDER BY id"
         )
-        .bind(source)
-        .bind(source_id)
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
 
-        Ok(())
+        Ok(prefs)
     }
 
-    /// 更新论文的中文翻译
-    pub async fn update_translation(
+    /// 查询翻译缓存
+    pub async fn get_cached_translation(
         &self,
-        source: &str,
-        source_id: &str,
-        title_zh: &str,
-        abstract_zh: &str,
+        provider: &str,
+        model: &str,
+        text_hash: &str,
+    ) -> Result<Option<String>> {
+        let response = sqlx::query_scalar::<_, String>(
+            "SELECT response FROM translations_cache WHERE provider = ? AND model = ? AND text_hash = ?"
+        )
+        .bind(provider)
+        .bind(model)
+        .bind(text_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(response)
+    }
+
+    /// 写入翻译缓存（upsert）
+    pub async fn save_cached_translation(
+        &self,
+        provider: &str,
+        model: &str,
+        text_hash: &str,
+        response: &str,
     ) -> Result<()> {
         sqlx::query(
-            "UPDATE papers SET title_zh = ?, abstract_zh = ? WHERE source = ? AND source_id = ?"
+            r#"
+            INSERT INTO translations_cache (provider, model, text_hash, response)
+            VALUES (?, ?, ?, ?)
+            ON CONFLICT(provider, model, text_hash) DO UPDATE SET
+                response = excluded.response,
+                created_at = CURRENT_TIMESTAMP
+            "#,
         )
-        .bind(title_zh)
-        .bind(abstract_zh)
-        .bind(source)
-        .bind(source_id)
+        .bind(provider)
+        .bind(model)
+        .bind(text_hash)
+        .bind(response)
         .execute(&self.pool)
         .await?;
 
         Ok(())
     }
 
-    /// 获取未翻译的论文列表
-    pub async fn get_untranslated_papers(&self) -> Result<Vec<Paper>> {
-        let papers = sqlx::query_as::<_, Paper>(
-            r#"SELECT id, title, title_zh, authors,
-                      abstract AS abstract_text, abstract_zh,
-                      publish_date, source, source_id,
-                      pdf_url, pdf_path, processed, created_at
-               FROM papers
-               WHERE title_zh IS NULL AND abstract IS NOT NULL"#
+    /// 新增或更新订阅（按名称），返回订阅ID
+    pub async fn upsert_subscription(&self, sub: &Subscription) -> Result<i64> {
+        sqlx::query(
+            r#"
+            INSERT INTO subscriptions (name, description, keywords, sources, categories, enabled, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+            ON CONFLICT(name) DO UPDATE SET
+                description = excluded.description,
+                keywords = excluded.keywords,
+                sources = excluded.sources,
+                categories = excluded.categories,
+                enabled = excluded.enabled,
+                updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(&sub.name)
+        .bind(&sub.description)
+        .bind(serde_json::to_string(&sub.keywords)?)
+        .bind(serde_json::to_string(&sub.sources)?)
+        .bind(serde_json::to_string(&sub.categories)?)
+        .bind(sub.enabled)
+        .execute(&self.pool)
+        .await?;
+
+        let id = sqlx::query_scalar("SELECT id FROM subscriptions WHERE name = ?")
+            .bind(&sub.name)
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(id)
+    }
+
+    /// 获取所有订阅，按ID排列
+    pub async fn get_subscriptions(&self) -> Result<Vec<SubscriptionRecord>> {
+        let subs = sqlx::query_as::<_, SubscriptionRecord>(
+            r#"SELECT id, name, description, keywords, sources, categories, COALESCE(enabled, 1) AS enabled,
+                      created_at, updated_at
+               FROM subscriptions
+               ORDER BY id"#
         )
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(papers)
+        Ok(subs)
     }
 
-    /// 清空所有缓存数据表（保留 subscriptions）
-    pub async fn clear_all_tables(&self) -> Result<()> {
-        // 先删有外键依赖的表
-        sqlx::query("DELETE FROM extracted_content").execute(&self.pool).await?;
-        sqlx::query("DELETE FROM reports").execute(&self.pool).await?;
-        sqlx::query("DELETE FROM papers").execute(&self.pool).await?;
-        info!("数据库表已清空");
-        Ok(())
+    /// 按名称获取订阅
+    pub async fn get_subscription_by_name(&self, name: &str) -> Result<Option<SubscriptionRecord>> {
+        let sub = sqlx::query_as::<_, SubscriptionRecord>(
+            r#"SELECT id, name, description, keywords, sources, categories, COALESCE(enabled, 1) AS enabled,
+                      created_at, updated_at
+               FROM subscriptions
+               WHERE name = ?"#
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(sub)
     }
 
-    /// 获取所有论文
-    pub async fn get_all_papers(&self) -> Result<Vec<Paper>> {
-        let papers = sqlx::query_as::<_, Paper>(
-            r#"SELECT id, title, title_zh, authors,
-                      abstract AS abstract_text, abstract_zh,
-                      publish_date, source, source_id,
-                      pdf_url, pdf_path, processed, created_at
-               FROM papers"#
+    /// 启用或停用订阅，订阅不存在时返回 false
+    pub async fn set_subscription_enabled(&self, name: &str, enabled: bool) -> Result<bool> {
+        let result = sqlx::query("UPDATE subscriptions SET enabled = ?, updated_at = CURRENT_TIMESTAMP WHERE name = ?")
+            .bind(enabled)
+            .bind(name)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 删除订阅；论文和爬取记录保留订阅名称，只解除ID关联
+    pub async fn delete_subscription(&self, name: &str) -> Result<bool> {
+        let mut tx = self.pool.begin().await?;
+        for table in ["paper_relevance", "crawl_runs"] {
+            sqlx::query(&format!(
+                "UPDATE {} SET subscription_id = NULL WHERE subscription_id = (SELECT id FROM subscriptions WHERE name = ?)",
+                table
+            ))
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+        }
+        let result = sqlx::query("DELETE FROM subscriptions WHERE name = ?")
+            .bind(name)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// 以 keywords.toml 为准同步订阅表
+    ///
+    /// 配置中的订阅逐个写入；配置中已删除的订阅停用而不删除，以保留历史论文的关联。
+    /// 之后按名称为尚未关联的相关度和爬取记录补全订阅ID。返回新增和停用的订阅数。
+    pub async fn sync_subscriptions(&self, subs: &[Subscription]) -> Result<(usize, usize)> {
+        let existing = self.get_subscriptions().await?;
+        let mut added = 0;
+        for sub in subs {
+            self.upsert_subscription(sub).await?;
+            if !existing.iter().any(|record| record.name == sub.name) {
+                added += 1;
+            }
+        }
+
+        let configured: std::collections::HashSet<&str> = subs.iter().map(|s| s.name.as_str()).collect();
+        let mut disabled = 0;
+        for record in existing.iter().filter(|r| r.enabled && !configured.contains(r.name.as_str())) {
+            if self.set_subscription_enabled(&record.name, false).await? {
+                disabled += 1;
+            }
+        }
+
+        for table in ["paper_relevance", "crawl_runs"] {
+            sqlx::query(&format!(
+                r#"UPDATE {table} SET subscription_id = (SELECT id FROM subscriptions WHERE name = {table}.subscription)
+                   WHERE subscription_id IS NULL"#
+            ))
+            .execute(&self.pool)
+            .await?;
+        }
+
+        Ok((added, disabled))
+    }
+
+    /// 读取订阅的扩展检索词缓存（关键词变化后自动失效）
+    pub async fn get_query_expansion(&self, subscription: &str, keywords_hash: &str) -> Result<Option<Vec<String>>> {
+        let terms = sqlx::query_scalar::<_, String>(
+            "SELECT terms FROM query_expansions WHERE subscription = ? AND keywords_hash = ?"
         )
-        .fetch_all(&self.pool)
+        .bind(subscription)
+        .bind(keywords_hash)
+        .fetch_optional(&self.pool)
         .await?;
 
-        Ok(papers)
+        Ok(terms.and_then(|t| serde_json::from_str(&t).ok()))
+    }
+
+    /// 保存订阅的扩展检索词
+    pub async fn save_query_expansion(&self, subscription: &str, keywords_hash: &str, terms: &[String]) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO query_expansions (subscription, keywords_hash, terms)
+            VALUES (?, ?, ?)
+            ON CONFLICT(subscription, keywords_hash) DO UPDATE SET
+                terms = excluded.terms,
+                created_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(subscription)
+        .bind(keywords_hash)
+        .bind(serde_json::to_string(terms)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+/// 把论文筛选条件转换为 WHERE 子句；ID 和布尔条件直接写入，文本条件以参数绑定
+fn paper_filter_clause(filter: &PaperFilter) -> (String, Vec<String>) {
+    let mut conditions = vec![if filter.deleted { "deleted_at IS NOT NULL" } else { "deleted_at IS NULL" }.to_string()];
+    let mut params = Vec::new();
+
+    if !filter.ids.is_empty() {
+        let ids: Vec<String> = filter.ids.iter().map(|id| id.to_string()).collect();
+        conditions.push(format!("id IN ({})", ids.join(", ")));
+    }
+    if let Some(source) = &filter.source {
+        conditions.push("id IN (SELECT paper_id FROM paper_sources WHERE source = ?)".to_string());
+        params.push(source.clone());
+    }
+    // 发表日期可能带时间，只比较日期部分
+    if let Some(since) = &filter.since {
+        conditions.push("substr(publish_date, 1, 10) >= ?".to_string());
+        params.push(since.clone());
     }
+    if let Some(until) = &filter.until {
+        conditions.push("substr(publish_date, 1, 10) <= ?".to_string());
+        params.push(until.clone());
+    }
+    if let Some(processed) = filter.processed {
+        conditions.push(if processed { "processed = 1" } else { "(processed IS NULL OR processed = 0)" }.to_string());
+    }
+    if let Some(downloaded) = filter.downloaded {
+        conditions.push(if downloaded { "pdf_path IS NOT NULL" } else { "pdf_path IS NULL" }.to_string());
+    }
+    match filter.translated {
+        Some(true) => conditions.push("title_zh IS NOT NULL".to_string()),
+        Some(false) => conditions.push(
+            "title_zh IS NULL AND abstract IS NOT NULL AND translation_status IN ('pending', 'failed')".to_string(),
+        ),
+        None => {}
+    }
+    if let Some(status) = filter.translation_status {
+        conditions.push(format!("translation_status = '{}'", status.as_str()));
+    }
+    if let Some(has_abstract) = filter.has_abstract {
+        let condition = "abstract IS NOT NULL AND TRIM(abstract) != ''";
+        conditions.push(if has_abstract { condition.to_string() } else { format!("NOT ({})", condition) });
+    }
+    if !filter.tags.is_empty() {
+        let placeholders = vec!["?"; filter.tags.len()].join(", ");
+        conditions.push(format!(
+            "id IN (SELECT pt.paper_id FROM paper_tags pt JOIN tags t ON t.id = pt.tag_id WHERE t.name IN ({}))",
+            placeholders
+        ));
+        params.extend(filter.tags.iter().map(|tag| tag.trim().to_string()));
+    }
+
+    (conditions.join(" AND "), params)
 }
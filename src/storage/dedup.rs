@@ -0,0 +1,123 @@
+use std::collections::HashSet;
+
+/// 标题相似度达到该值、且作者相符时视为同一篇论文
+const TITLE_THRESHOLD: f64 = 0.9;
+/// 缺少作者信息时只能依据标题，要求几乎一致
+const TITLE_ONLY_THRESHOLD: f64 = 0.97;
+
+/// 判断两条记录是否为同一篇论文（如 arXiv 预印本与期刊正式版）
+///
+/// 标题忽略大小写、标点和空白后按字符二元组比较相似度；作者按姓氏比较，
+/// 第一作者的姓出现在对方作者中，或共同作者超过较少一方的一半即视为相符。
+pub fn is_duplicate(title_a: &str, authors_a: Option<&str>, title_b: &str, authors_b: Option<&str>) -> bool {
+    let similarity = title_similarity(title_a, title_b);
+    let (surnames_a, surnames_b) = (surnames(authors_a), surnames(authors_b));
+    if surnames_a.is_empty() || surnames_b.is_empty() {
+        return similarity >= TITLE_ONLY_THRESHOLD;
+    }
+    similarity >= TITLE_THRESHOLD && authors_match(&surnames_a, &surnames_b)
+}
+
+/// 标题相似度（Dice 系数，0~1）
+pub fn title_similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (normalize_title(a), normalize_title(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    let (bigrams_a, bigrams_b) = (bigrams(&a), bigrams(&b));
+    if bigrams_a.is_empty() || bigrams_b.is_empty() {
+        return 0.0;
+    }
+    let mut remaining = bigrams_b.clone();
+    let mut common = 0;
+    for bigram in &bigrams_a {
+        if let Some(pos) = remaining.iter().position(|b| b == bigram) {
+            remaining.swap_remove(pos);
+            common += 1;
+        }
+    }
+    2.0 * common as f64 / (bigrams_a.len() + bigrams_b.len()) as f64
+}
+
+/// 小写，只保留字母数字，连续的其他字符合并为一个空格
+pub fn normalize_title(title: &str) -> String {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn bigrams(text: &str) -> Vec<(char, char)> {
+    let chars: Vec<char> = text.chars().collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// 作者以逗号分隔，取每位作者的最后一个词作为姓；「姓, 名」格式无法区分，按同样方式处理
+fn surnames(authors: Option<&str>) -> Vec<String> {
+    authors
+        .unwrap_or_default()
+        .split(',')
+        .filter_map(|author| author.split_whitespace().last())
+        .map(|surname| surname.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|surname| !surname.is_empty())
+        .collect()
+}
+
+fn authors_match(a: &[String], b: &[String]) -> bool {
+    let (set_a, set_b): (HashSet<&String>, HashSet<&String>) = (a.iter().collect(), b.iter().collect());
+    if set_b.contains(&a[0]) || set_a.contains(&b[0]) {
+        return true;
+    }
+    let common = set_a.intersection(&set_b).count();
+    common * 2 > set_a.len().min(set_b.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preprint_matches_journal_version() {
+        // 期刊版标题大小写、标点略有不同，作者顺序不同
+        assert!(is_duplicate(
+            "Attention Is All You Need",
+            Some("Ashish Vaswani, Noam Shazeer, Niki Parmar"),
+            "Attention is all you need.",
+            Some("Noam Shazeer, Ashish Vaswani"),
+        ));
+        // 预印本作者写全名，期刊版只有名字缩写
+        assert!(is_duplicate(
+            "BERT: Pre-training of Deep Bidirectional Transformers for Language Understanding",
+            Some("Jacob Devlin, Ming-Wei Chang, Kenton Lee, Kristina Toutanova"),
+            "BERT: Pre-Training of Deep Bidirectional Transformers for Language Understanding",
+            Some("J. Devlin, M. Chang"),
+        ));
+    }
+
+    #[test]
+    fn same_title_with_different_authors_is_distinct() {
+        assert!(!is_duplicate(
+            "A Survey of Large Language Models",
+            Some("Wayne Xin Zhao, Kun Zhou"),
+            "A Survey of Large Language Models",
+            Some("Humza Naveed, Asad Ullah Khan"),
+        ));
+    }
+
+    #[test]
+    fn missing_authors_require_near_identical_titles() {
+        assert!(is_duplicate("Graph Neural Networks: A Review", None, "Graph neural networks - a review", Some("Jie Zhou")));
+        assert!(!is_duplicate(
+            "Graph Neural Networks for Molecules",
+            None,
+            "Graph Neural Networks for Materials",
+            Some("Jie Zhou"),
+        ));
+        assert!(!is_duplicate("", None, "", None));
+    }
+}
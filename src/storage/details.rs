@@ -0,0 +1,208 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::PathBuf;
+
+use crate::config::dirs;
+use crate::storage::images::resolve_image_path;
+use crate::storage::models::{Paper, PaperSource};
+use crate::storage::provenance::{self, Provenance};
+use crate::storage::{citations, Database};
+
+/// `show` 展示的单篇论文的全部信息
+#[derive(Debug, Serialize)]
+pub struct PaperDetails {
+    pub paper: Paper,
+    pub starred: bool,
+    pub read_at: Option<String>,
+    /// 移入回收站的时间
+    pub deleted_at: Option<String>,
+    /// PDF无法打开（损坏、加密）的原因
+    pub pdf_error: Option<String>,
+    pub sources: Vec<PaperSource>,
+    pub tags: Vec<String>,
+    /// 与各订阅的相关度，得分高的在前
+    pub relevance: Vec<Relevance>,
+    pub triage: Option<Triage>,
+    /// 最近一次保存提取结果的时间，未解析时为空
+    pub extracted_at: Option<String>,
+    pub counts: ExtractionCounts,
+    pub key_points: Vec<String>,
+    /// 得分最高的关键短语
+    pub keyphrases: Vec<String>,
+    pub files: PaperFiles,
+    /// 入库、翻译、解析等各步骤最近一次的结果，最近的在前
+    pub events: Vec<Provenance>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Relevance {
+    pub subscription: String,
+    pub score: f64,
+    pub label: Option<String>,
+}
+
+/// 交互式分拣的决定
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Triage {
+    pub decision: String,
+    pub decided_at: Option<String>,
+}
+
+/// 解析PDF得到的各类内容的数量，未解析时均为 0
+#[derive(Debug, Default, Serialize)]
+pub struct ExtractionCounts {
+    pub sections: usize,
+    pub formulas: usize,
+    pub images: usize,
+    pub tables: usize,
+    pub links: usize,
+    pub references: i64,
+    pub keyphrases: i64,
+    /// 引用的论文（按参考文献解析）
+    pub cites: usize,
+    /// 库中引用本文的论文
+    pub cited_by: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PaperFiles {
+    pub pdf: Option<FileStatus>,
+    pub images: usize,
+    /// 文件不存在的图片数
+    pub images_missing: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileStatus {
+    pub path: PathBuf,
+    /// 文件不存在时为空
+    pub size: Option<u64>,
+}
+
+/// 显示的关键短语数
+const KEYPHRASE_LIMIT: i64 = 10;
+
+/// 汇总一篇论文的信息，回收站中的论文同样可以查看；论文不存在时返回 None
+pub async fn load(db: &Database, paper_id: i64) -> Result<Option<PaperDetails>> {
+    let pool = db.pool();
+    let paper = sqlx::query_as::<_, Paper>(
+        r#"SELECT id, title, title_zh, authors,
+                  abstract AS abstract_text, abstract_zh,
+                  publish_date, source, source_id,
+                  pdf_url, pdf_path, processed, created_at, translation_status, code_urls, venue, funding
+           FROM papers
+           WHERE id = ?"#
+    )
+    .bind(paper_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some(paper) = paper else {
+        return Ok(None);
+    };
+
+    let (starred, read_at, deleted_at, pdf_error) = sqlx::query_as::<_, (bool, Option<String>, Option<String>, Option<String>)>(
+        "SELECT starred, read_at, deleted_at, pdf_error FROM papers WHERE id = ?"
+    )
+    .bind(paper_id)
+    .fetch_one(pool)
+    .await?;
+
+    let relevance = sqlx::query_as::<_, Relevance>(
+        "SELECT subscription, score, label FROM paper_relevance WHERE paper_id = ? ORDER BY score DESC, subscription"
+    )
+    .bind(paper_id)
+    .fetch_all(pool)
+    .await?;
+    let triage = sqlx::query_as::<_, Triage>("SELECT decision, decided_at FROM paper_triage WHERE paper_id = ?")
+        .bind(paper_id)
+        .fetch_optional(pool)
+        .await?;
+
+    type ContentRow = (
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    );
+    let content = sqlx::query_as::<_, ContentRow>(
+        "SELECT created_at, sections, formulas, images, tables, links, key_points FROM extracted_content WHERE paper_id = ?"
+    )
+    .bind(paper_id)
+    .fetch_optional(pool)
+    .await?;
+    let mut counts = ExtractionCounts::default();
+    let mut key_points = Vec::new();
+    let mut extracted_at = None;
+    if let Some((created_at, sections, formulas, images, tables, links, points)) = content {
+        extracted_at = Some(created_at.unwrap_or_default());
+        counts.sections = json_len(sections.as_deref());
+        counts.formulas = json_len(formulas.as_deref());
+        counts.images = json_len(images.as_deref());
+        counts.tables = json_len(tables.as_deref());
+        counts.links = json_len(links.as_deref());
+        key_points = points
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default();
+    }
+    counts.references = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "references" WHERE paper_id = ?"#)
+        .bind(paper_id)
+        .fetch_one(pool)
+        .await?;
+    counts.keyphrases = sqlx::query_scalar("SELECT COUNT(*) FROM paper_keyphrases WHERE paper_id = ?")
+        .bind(paper_id)
+        .fetch_one(pool)
+        .await?;
+    counts.cites = citations::cited_papers(db, paper_id).await?.len();
+    counts.cited_by = citations::citing_papers(db, paper_id).await?.len();
+
+    let keyphrases = sqlx::query_scalar(
+        "SELECT phrase FROM paper_keyphrases WHERE paper_id = ? ORDER BY score DESC, position LIMIT ?"
+    )
+    .bind(paper_id)
+    .bind(KEYPHRASE_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let image_keys = sqlx::query_scalar::<_, String>("SELECT image_key FROM paper_images WHERE paper_id = ? ORDER BY position")
+        .bind(paper_id)
+        .fetch_all(pool)
+        .await?;
+    let files = PaperFiles {
+        pdf: paper.pdf_path.as_deref().map(|stored| {
+            let path = dirs::get().resolve_data_path(stored);
+            let size = std::fs::metadata(&path).ok().map(|m| m.len());
+            FileStatus { path, size }
+        }),
+        images: image_keys.len(),
+        images_missing: image_keys.iter().filter(|key| !resolve_image_path(key).exists()).count(),
+    };
+
+    Ok(Some(PaperDetails {
+        starred,
+        read_at,
+        deleted_at,
+        pdf_error,
+        sources: db.get_paper_sources(paper_id).await?,
+        tags: db.get_paper_tags(paper_id).await?,
+        relevance,
+        triage,
+        extracted_at,
+        counts,
+        key_points,
+        keyphrases,
+        files,
+        events: provenance::for_paper(db, paper_id).await?,
+        paper,
+    }))
+}
+
+/// JSON 数组的元素个数，空值或无法解析时为 0
+fn json_len(json: Option<&str>) -> usize {
+    json.and_then(|json| serde_json::from_str::<Vec<serde_json::Value>>(json).ok())
+        .map(|items| items.len())
+        .unwrap_or(0)
+}
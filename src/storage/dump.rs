@@ -0,0 +1,320 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+use crate::storage::Database;
+
+/// 导出文件的格式版本，字段有不兼容的变化时递增
+const DUMP_VERSION: u32 = 1;
+
+/// 数据库的 JSON 导出
+///
+/// 论文以来源和来源ID标识，不含数据库内部ID，可以导入任意结构版本的数据库；
+/// PDF 和图片文件不在其中，需要另行复制数据目录或重新下载。
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DatabaseDump {
+    pub version: u32,
+    /// 导出时数据库的结构版本，仅供参考
+    pub schema_version: i64,
+    pub exported_at: String,
+    pub papers: Vec<PaperDump>,
+    pub subscriptions: Vec<SubscriptionDump>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct PaperDump {
+    #[serde(skip)]
+    pub id: i64,
+    pub title: String,
+    pub title_zh: Option<String>,
+    pub authors: Option<String>,
+    #[serde(rename = "abstract")]
+    pub abstract_text: Option<String>,
+    pub abstract_zh: Option<String>,
+    pub publish_date: Option<String>,
+    pub source: String,
+    pub source_id: String,
+    pub pdf_url: Option<String>,
+    pub pdf_path: Option<String>,
+    pub processed: bool,
+    pub created_at: Option<String>,
+    pub translation_status: String,
+    pub code_urls: Option<String>,
+    pub venue: Option<String>,
+    pub funding: Option<String>,
+    pub pdf_error: Option<String>,
+    pub read_at: Option<String>,
+    pub starred: bool,
+    /// 在回收站中的论文的删除时间
+    #[serde(default)]
+    pub deleted_at: Option<String>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub extracted: Option<ExtractedDump>,
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub tags: Vec<TagDump>,
+    /// 合并进来的其他来源
+    #[sqlx(skip)]
+    #[serde(default)]
+    pub sources: Vec<SourceDump>,
+}
+
+/// 提取内容，各字段为数据库中保存的 JSON 文本
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ExtractedDump {
+    #[serde(skip)]
+    pub paper_id: i64,
+    pub formulas: Option<String>,
+    pub images: Option<String>,
+    pub tables: Option<String>,
+    pub key_points: Option<String>,
+    pub sections: Option<String>,
+    pub links: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TagDump {
+    pub name: String,
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceDump {
+    pub source: String,
+    pub source_id: String,
+    pub pdf_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SubscriptionDump {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub keywords: String,
+    pub sources: String,
+    pub categories: Option<String>,
+    pub enabled: bool,
+    pub created_at: Option<String>,
+}
+
+/// 导入结果统计
+#[derive(Debug, Default)]
+pub struct ImportStats {
+    pub papers: usize,
+    /// 来源ID已存在而跳过的论文
+    pub skipped: usize,
+    pub subscriptions: usize,
+}
+
+/// 导出论文（含提取内容、标签和其他来源）与订阅
+pub async fn export(db: &Database) -> Result<DatabaseDump> {
+    let pool = db.pool();
+    let mut papers = sqlx::query_as::<_, PaperDump>(
+        r#"SELECT id, title, title_zh, authors, abstract AS abstract_text, abstract_zh, publish_date,
+                  source, source_id, pdf_url, pdf_path, COALESCE(processed, 0) AS processed, created_at, translation_status,
+                  code_urls, venue, funding, pdf_error, read_at, starred, deleted_at
+           FROM papers
+           ORDER BY id"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut extracted: HashMap<i64, ExtractedDump> = sqlx::query_as::<_, ExtractedDump>(
+        "SELECT paper_id, formulas, images, tables, key_points, sections, links FROM extracted_content"
+    )
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|e| (e.paper_id, e))
+    .collect();
+
+    let mut tags: HashMap<i64, Vec<TagDump>> = HashMap::new();
+    let rows = sqlx::query_as::<_, (i64, String, String)>(
+        r#"SELECT pt.paper_id, t.name, pt.source
+           FROM paper_tags pt
+           JOIN tags t ON t.id = pt.tag_id
+           ORDER BY pt.paper_id, t.name"#
+    )
+    .fetch_all(pool)
+    .await?;
+    for (paper_id, name, source) in rows {
+        tags.entry(paper_id).or_default().push(TagDump { name, source });
+    }
+
+    let mut sources: HashMap<i64, Vec<SourceDump>> = HashMap::new();
+    for source in db.get_alternate_sources().await?.into_values().flatten() {
+        sources.entry(source.paper_id).or_default().push(SourceDump {
+            source: source.source,
+            source_id: source.source_id,
+            pdf_url: source.pdf_url,
+        });
+    }
+
+    for paper in &mut papers {
+        paper.extracted = extracted.remove(&paper.id);
+        paper.tags = tags.remove(&paper.id).unwrap_or_default();
+        paper.sources = sources.remove(&paper.id).unwrap_or_default();
+    }
+
+    let subscriptions = sqlx::query_as::<_, SubscriptionDump>(
+        r#"SELECT name, description, keywords, sources, categories, COALESCE(enabled, 1) AS enabled, created_at
+           FROM subscriptions
+           ORDER BY id"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(DatabaseDump {
+        version: DUMP_VERSION,
+        schema_version: db.schema_version().await?,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        papers,
+        subscriptions,
+    })
+}
+
+/// 写入导出文件
+pub fn write(dump: &DatabaseDump, path: &str) -> Result<()> {
+    if let Some(parent) = Path::new(path).parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_vec_pretty(dump)?).with_context(|| format!("写入导出文件失败: {}", path))
+}
+
+/// 读取导出文件
+pub fn read(path: &str) -> Result<DatabaseDump> {
+    let content = std::fs::read_to_string(path).with_context(|| format!("读取导出文件失败: {}", path))?;
+    let dump: DatabaseDump = serde_json::from_str(&content).with_context(|| format!("导出文件格式错误: {}", path))?;
+    if dump.version > DUMP_VERSION {
+        bail!("导出文件格式版本 {} 高于当前程序支持的 {}，请升级 bsxbot", dump.version, DUMP_VERSION);
+    }
+    Ok(dump)
+}
+
+/// 在一个事务中导入；来源ID已存在的论文跳过，同名订阅不重复添加
+pub async fn import(db: &Database, dump: &DatabaseDump) -> Result<ImportStats> {
+    let mut stats = ImportStats::default();
+    let mut tx = db.pool().begin().await?;
+
+    for paper in &dump.papers {
+        let existing: Option<i64> =
+            sqlx::query_scalar("SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?")
+                .bind(&paper.source)
+                .bind(&paper.source_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+        if existing.is_some() {
+            stats.skipped += 1;
+            continue;
+        }
+
+        let paper_id = sqlx::query(
+            r#"
+            INSERT INTO papers (title, title_zh, authors, abstract, abstract_zh, publish_date, source, source_id,
+                                pdf_url, pdf_path, processed, created_at, translation_status, code_urls, venue,
+                                funding, pdf_error, read_at, starred, deleted_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(&paper.title)
+        .bind(&paper.title_zh)
+        .bind(&paper.authors)
+        .bind(&paper.abstract_text)
+        .bind(&paper.abstract_zh)
+        .bind(&paper.publish_date)
+        .bind(&paper.source)
+        .bind(&paper.source_id)
+        .bind(&paper.pdf_url)
+        .bind(&paper.pdf_path)
+        .bind(paper.processed)
+        .bind(&paper.created_at)
+        .bind(&paper.translation_status)
+        .bind(&paper.code_urls)
+        .bind(&paper.venue)
+        .bind(&paper.funding)
+        .bind(&paper.pdf_error)
+        .bind(&paper.read_at)
+        .bind(paper.starred)
+        .bind(&paper.deleted_at)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        let own_source = SourceDump {
+            source: paper.source.clone(),
+            source_id: paper.source_id.clone(),
+            pdf_url: paper.pdf_url.clone(),
+        };
+        for source in std::iter::once(&own_source).chain(&paper.sources) {
+            sqlx::query("INSERT OR IGNORE INTO paper_sources (source, source_id, paper_id, pdf_url) VALUES (?, ?, ?, ?)")
+                .bind(&source.source)
+                .bind(&source.source_id)
+                .bind(paper_id)
+                .bind(&source.pdf_url)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        if let Some(e) = &paper.extracted {
+            sqlx::query(
+                r#"INSERT INTO extracted_content (paper_id, formulas, images, tables, key_points, sections, links)
+                   VALUES (?, ?, ?, ?, ?, ?, ?)"#
+            )
+            .bind(paper_id)
+            .bind(&e.formulas)
+            .bind(&e.images)
+            .bind(&e.tables)
+            .bind(&e.key_points)
+            .bind(&e.sections)
+            .bind(&e.links)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for tag in &paper.tags {
+            sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+                .bind(&tag.name)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query(
+                "INSERT OR IGNORE INTO paper_tags (paper_id, tag_id, source) SELECT ?, id, ? FROM tags WHERE name = ?"
+            )
+            .bind(paper_id)
+            .bind(&tag.source)
+            .bind(&tag.name)
+            .execute(&mut *tx)
+            .await?;
+        }
+        stats.papers += 1;
+    }
+
+    for sub in &dump.subscriptions {
+        let result = sqlx::query(
+            r#"INSERT INTO subscriptions (name, description, keywords, sources, categories, enabled, created_at)
+               SELECT ?, ?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP)
+               WHERE NOT EXISTS (SELECT 1 FROM subscriptions WHERE name = ?)"#
+        )
+        .bind(&sub.name)
+        .bind(&sub.description)
+        .bind(&sub.keywords)
+        .bind(&sub.sources)
+        .bind(&sub.categories)
+        .bind(sub.enabled)
+        .bind(&sub.created_at)
+        .bind(&sub.name)
+        .execute(&mut *tx)
+        .await?;
+        stats.subscriptions += result.rows_affected() as usize;
+    }
+
+    tx.commit().await?;
+    info!(
+        "导入完成: 论文 {} 篇（已存在跳过 {} 篇），订阅 {} 个",
+        stats.papers, stats.skipped, stats.subscriptions
+    );
+    Ok(stats)
+}
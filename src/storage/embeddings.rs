@@ -0,0 +1,130 @@
+use anyhow::{bail, Result};
+
+use crate::storage::Database;
+
+/// 相似论文
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Neighbor {
+    pub paper_id: i64,
+    /// 余弦相似度，-1~1
+    pub similarity: f32,
+}
+
+/// 保存论文在指定模型下的向量（embeddings 表），已有时覆盖
+///
+/// 向量按小端 f32 存为 BLOB；同一模型的向量维数须一致，查询时维数不符的记录会被忽略。
+pub async fn upsert(db: &Database, paper_id: i64, model: &str, vector: &[f32]) -> Result<()> {
+    if vector.is_empty() {
+        bail!("论文 [{}] 的向量为空", paper_id);
+    }
+    sqlx::query(
+        r#"INSERT INTO embeddings (paper_id, model, dims, vector)
+           VALUES (?, ?, ?, ?)
+           ON CONFLICT(paper_id, model) DO UPDATE SET
+               dims = excluded.dims,
+               vector = excluded.vector,
+               created_at = CURRENT_TIMESTAMP"#
+    )
+    .bind(paper_id)
+    .bind(model)
+    .bind(vector.len() as i64)
+    .bind(encode(vector))
+    .execute(db.pool())
+    .await?;
+
+    Ok(())
+}
+
+/// 论文在指定模型下的向量
+pub async fn get(db: &Database, paper_id: i64, model: &str) -> Result<Option<Vec<f32>>> {
+    let blob = sqlx::query_scalar::<_, Vec<u8>>("SELECT vector FROM embeddings WHERE paper_id = ? AND model = ?")
+        .bind(paper_id)
+        .bind(model)
+        .fetch_optional(db.pool())
+        .await?;
+
+    Ok(blob.map(|blob| decode(&blob)))
+}
+
+/// 还没有该模型向量的论文ID（不含回收站中的论文），用于增量生成
+pub async fn missing_paper_ids(db: &Database, model: &str) -> Result<Vec<i64>> {
+    let ids = sqlx::query_scalar::<_, i64>(
+        r#"SELECT id FROM papers
+           WHERE deleted_at IS NULL
+             AND id NOT IN (SELECT paper_id FROM embeddings WHERE model = ?)
+           ORDER BY id"#
+    )
+    .bind(model)
+    .fetch_all(db.pool())
+    .await?;
+
+    Ok(ids)
+}
+
+/// 与查询向量最相似的论文，按余弦相似度从高到低，最多 `limit` 篇
+///
+/// 逐条计算（暴力搜索），论文库规模在数万篇以内时足够快；`exclude` 用于排除查询论文自身。
+pub async fn nearest(
+    db: &Database,
+    model: &str,
+    query: &[f32],
+    limit: usize,
+    exclude: Option<i64>,
+) -> Result<Vec<Neighbor>> {
+    let query_norm = norm(query);
+    if query_norm == 0.0 {
+        return Ok(Vec::new());
+    }
+    let rows = sqlx::query_as::<_, (i64, Vec<u8>)>(
+        r#"SELECT e.paper_id, e.vector
+           FROM embeddings e
+           JOIN papers p ON p.id = e.paper_id
+           WHERE e.model = ? AND e.dims = ? AND p.deleted_at IS NULL"#
+    )
+    .bind(model)
+    .bind(query.len() as i64)
+    .fetch_all(db.pool())
+    .await?;
+
+    let mut neighbors: Vec<Neighbor> = rows
+        .into_iter()
+        .filter(|(paper_id, _)| Some(*paper_id) != exclude)
+        .filter_map(|(paper_id, blob)| {
+            let vector = decode(&blob);
+            let vector_norm = norm(&vector);
+            (vector_norm > 0.0).then(|| Neighbor {
+                paper_id,
+                similarity: dot(query, &vector) / (query_norm * vector_norm),
+            })
+        })
+        .collect();
+    neighbors.sort_by(|a, b| b.similarity.total_cmp(&a.similarity).then(a.paper_id.cmp(&b.paper_id)));
+    neighbors.truncate(limit);
+    Ok(neighbors)
+}
+
+/// 与指定论文最相似的论文；论文还没有该模型的向量时返回空列表
+pub async fn nearest_to_paper(db: &Database, paper_id: i64, model: &str, limit: usize) -> Result<Vec<Neighbor>> {
+    match get(db, paper_id, model).await? {
+        Some(vector) => nearest(db, model, &vector, limit, Some(paper_id)).await,
+        None => Ok(Vec::new()),
+    }
+}
+
+fn encode(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn decode(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+fn norm(vector: &[f32]) -> f32 {
+    dot(vector, vector).sqrt()
+}
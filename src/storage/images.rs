@@ -0,0 +1,207 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::config::{dirs, AppConfig};
+use crate::parser::ExtractedImage;
+use crate::storage::models::{PaperFilter, PaperImage, PaperSort};
+use crate::storage::Database;
+use crate::utils::hash::sha256_hex;
+use crate::utils::paths::is_safe_relative_path;
+
+/// 提取图片的存储后端
+///
+/// 图片按内容哈希命名，数据库和提取结果中只保存存储键（`<sha256>.<扩展名>`），
+/// 不依赖数据目录的位置。
+pub trait ImageStore: Send + Sync {
+    /// 保存图片数据，返回存储键；内容相同的图片只保存一份
+    fn put(&self, data: &[u8], extension: &str) -> Result<String>;
+
+    /// 存储键对应的本地文件路径
+    fn path(&self, key: &str) -> PathBuf;
+
+    fn exists(&self, key: &str) -> bool {
+        self.path(key).exists()
+    }
+}
+
+/// 保存在本地目录（默认为数据目录下的 images/）的图片存储
+pub struct LocalImageStore {
+    root: PathBuf,
+}
+
+impl LocalImageStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl ImageStore for LocalImageStore {
+    fn put(&self, data: &[u8], extension: &str) -> Result<String> {
+        let key = format!("{}.{}", sha256_hex(data), extension);
+        let path = self.path(&key);
+        if !path.exists() {
+            std::fs::create_dir_all(&self.root)?;
+            let partial = self.root.join(format!("{}.part", key));
+            std::fs::write(&partial, data)
+                .with_context(|| format!("写入图片失败: {}", partial.display()))?;
+            std::fs::rename(&partial, &path)?;
+        }
+        Ok(key)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+/// 是否为存储键（哈希文件名），而不是旧版本保存的文件路径
+pub fn is_store_key(filename: &str) -> bool {
+    if filename.contains(['/', '\\']) {
+        return false;
+    }
+    match filename.split_once('.') {
+        Some((stem, _)) => stem.len() == 64 && stem.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+/// 数据库中图片引用对应的本地文件；旧版本以文件路径保存图片，可用 relocate-data 改为存储键
+pub fn resolve_image_path(key: &str) -> PathBuf {
+    let dirs = dirs::get();
+    if is_store_key(key) {
+        dirs.images_dir().join(key)
+    } else {
+        dirs.resolve_data_path(key)
+    }
+}
+
+/// 提取结果中的图片转为数据库引用
+pub fn paper_image_refs(paper_id: i64, images: &[ExtractedImage]) -> Vec<PaperImage> {
+    images
+        .iter()
+        .enumerate()
+        .map(|(position, img)| PaperImage {
+            paper_id,
+            position: position as i64,
+            image_key: img.filename.clone(),
+            page: img.page as i64,
+            width: img.width as i64,
+            height: img.height as i64,
+            format: img.format.clone(),
+        })
+        .collect()
+}
+
+/// 迁移结果统计
+#[derive(Debug, Default)]
+pub struct RelocateSummary {
+    pub images_rewritten: usize,
+    pub images_missing: usize,
+    pub pdfs_rewritten: usize,
+    pub pdfs_missing: usize,
+}
+
+/// 数据目录移动后重写数据库中的文件引用
+///
+/// 旧版本以文件路径保存的图片会被导入图片存储并改为哈希键；
+/// PDF 路径改写为相对数据目录的 `papers/<文件名>`。`from` 为原数据目录，
+/// 用于把旧的绝对路径映射到当前数据目录。
+pub async fn relocate_data(from: Option<&Path>) -> Result<RelocateSummary> {
+    let app_config = AppConfig::load()?;
+    let db = Database::open(&app_config.storage).await?;
+
+    let dirs = dirs::get();
+    let store = LocalImageStore::new(dirs.images_dir());
+    let mut summary = RelocateSummary::default();
+
+    for (paper_id, images_json) in db.get_extracted_images().await? {
+        let mut images: Vec<ExtractedImage> = match serde_json::from_str(&images_json) {
+            Ok(images) => images,
+            Err(e) => {
+                warn!("论文 {} 的图片记录无法解析: {}", paper_id, e);
+                continue;
+            }
+        };
+
+        let mut changed = false;
+        for img in &mut images {
+            if is_store_key(&img.filename) && store.exists(&img.filename) {
+                continue;
+            }
+            let Some(path) = locate_legacy_file(&img.filename, from, &dirs.images_dir()) else {
+                warn!("找不到图片文件: {}", img.filename);
+                summary.images_missing += 1;
+                continue;
+            };
+
+            let data = std::fs::read(&path)?;
+            let extension = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_else(|| img.format.clone());
+            let key = store.put(&data, &extension)?;
+
+            // 旧文件在图片目录内时删除，避免同一张图片保存两份
+            if path.starts_with(dirs.images_dir()) && path != store.path(&key) {
+                if let Err(e) = std::fs::remove_file(&path) {
+                    warn!("删除旧图片文件失败 {}: {}", path.display(), e);
+                }
+            }
+
+            img.filename = key;
+            changed = true;
+            summary.images_rewritten += 1;
+        }
+
+        if changed {
+            db.update_extracted_images(paper_id, &serde_json::to_string(&images)?).await?;
+            db.save_paper_images(paper_id, &paper_image_refs(paper_id, &images)).await?;
+        }
+    }
+
+    let downloaded = PaperFilter { downloaded: Some(true), ..Default::default() };
+    for paper in db.query_papers(&downloaded, PaperSort::Id, None, 0).await? {
+        let Some(ref stored) = paper.pdf_path else { continue };
+        if is_safe_relative_path(stored) && dirs.data_dir.join(stored).exists() {
+            continue;
+        }
+        match locate_legacy_file(stored, from, &dirs.papers_dir()) {
+            Some(path) if path.starts_with(dirs.papers_dir()) => {
+                let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                db.update_pdf_path(&paper.source, &paper.source_id, &format!("papers/{}", file_name))
+                    .await?;
+                summary.pdfs_rewritten += 1;
+            }
+            _ => {
+                warn!("找不到PDF文件或不在数据目录内: {}", stored);
+                summary.pdfs_missing += 1;
+            }
+        }
+    }
+
+    info!(
+        "图片: 重写 {} 个引用，缺失 {} 个；PDF: 重写 {} 个路径，缺失 {} 个",
+        summary.images_rewritten, summary.images_missing, summary.pdfs_rewritten, summary.pdfs_missing
+    );
+    Ok(summary)
+}
+
+/// 按旧路径查找文件：原样路径、原数据目录映射到当前数据目录、当前目录下的同名文件
+fn locate_legacy_file(stored: &str, from: Option<&Path>, fallback_dir: &Path) -> Option<PathBuf> {
+    let stored_path = PathBuf::from(stored.replace('\\', "/"));
+    let dirs = dirs::get();
+
+    let mut candidates = vec![stored_path.clone(), dirs.data_dir.join(&stored_path)];
+    if let Ok(rest) = stored_path.strip_prefix("data") {
+        candidates.push(dirs.data_dir.join(rest));
+    }
+    if let Some(rest) = from.and_then(|from| stored_path.strip_prefix(from).ok()) {
+        candidates.push(dirs.data_dir.join(rest));
+    }
+    if let Some(name) = stored_path.file_name() {
+        candidates.push(fallback_dir.join(name));
+    }
+
+    candidates.into_iter().find(|p| p.is_file())
+}
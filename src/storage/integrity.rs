@@ -0,0 +1,145 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+use crate::config::dirs;
+use crate::crawler::arxiv::is_complete_pdf;
+use crate::storage::images::resolve_image_path;
+use crate::storage::Database;
+
+/// 文件类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    Pdf,
+    Image,
+}
+
+/// 文件问题
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileProblem {
+    /// 文件不存在
+    Missing,
+    /// 文件为 0 字节
+    Empty,
+    /// 不以 PDF 文件头开头，多为下载中断或保存了错误页面
+    NotPdf,
+}
+
+impl FileProblem {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Missing => "缺失",
+            Self::Empty => "空文件",
+            Self::NotPdf => "不是有效的PDF",
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileIssue {
+    pub paper_id: i64,
+    pub title: String,
+    pub kind: FileKind,
+    pub path: PathBuf,
+    pub problem: FileProblem,
+}
+
+/// `doctor --files` 的检查结果，不含回收站中的论文
+#[derive(Debug, Serialize)]
+pub struct FileReport {
+    pub pdfs_checked: usize,
+    pub images_checked: usize,
+    pub issues: Vec<FileIssue>,
+}
+
+impl FileReport {
+    /// PDF有问题、需要重新下载的论文
+    pub fn redownload_ids(&self) -> Vec<i64> {
+        let ids: BTreeSet<i64> = self
+            .issues
+            .iter()
+            .filter(|issue| issue.kind == FileKind::Pdf)
+            .map(|issue| issue.paper_id)
+            .collect();
+        ids.into_iter().collect()
+    }
+
+    /// PDF完好但图片有问题、重新提取即可修复的论文
+    pub fn reextract_ids(&self) -> Vec<i64> {
+        let redownload = self.redownload_ids();
+        let ids: BTreeSet<i64> = self
+            .issues
+            .iter()
+            .filter(|issue| issue.kind == FileKind::Image && !redownload.contains(&issue.paper_id))
+            .map(|issue| issue.paper_id)
+            .collect();
+        ids.into_iter().collect()
+    }
+}
+
+/// 核对数据库中记录的PDF和图片文件是否存在、是否为空，不做修改
+pub async fn check_files(db: &Database) -> Result<FileReport> {
+    let pool = db.pool();
+    let dirs = dirs::get();
+    let mut issues = Vec::new();
+
+    let papers = sqlx::query_as::<_, (i64, String, String)>(
+        "SELECT id, title, pdf_path FROM papers WHERE pdf_path IS NOT NULL AND deleted_at IS NULL ORDER BY id"
+    )
+    .fetch_all(pool)
+    .await?;
+    for (paper_id, title, stored) in &papers {
+        let path = dirs.resolve_data_path(stored);
+        let problem = match std::fs::metadata(&path) {
+            Err(_) => Some(FileProblem::Missing),
+            Ok(metadata) if metadata.len() == 0 => Some(FileProblem::Empty),
+            Ok(_) if !is_complete_pdf(&path) => Some(FileProblem::NotPdf),
+            Ok(_) => None,
+        };
+        if let Some(problem) = problem {
+            issues.push(FileIssue {
+                paper_id: *paper_id,
+                title: title.clone(),
+                kind: FileKind::Pdf,
+                path,
+                problem,
+            });
+        }
+    }
+
+    let images = sqlx::query_as::<_, (i64, String, String)>(
+        r#"SELECT i.paper_id, p.title, i.image_key
+           FROM paper_images i
+           JOIN papers p ON p.id = i.paper_id
+           WHERE p.deleted_at IS NULL
+           ORDER BY i.paper_id, i.position"#
+    )
+    .fetch_all(pool)
+    .await?;
+    for (paper_id, title, key) in &images {
+        let path = resolve_image_path(key);
+        let problem = match std::fs::metadata(&path) {
+            Err(_) => Some(FileProblem::Missing),
+            Ok(metadata) if metadata.len() == 0 => Some(FileProblem::Empty),
+            Ok(_) => None,
+        };
+        if let Some(problem) = problem {
+            issues.push(FileIssue {
+                paper_id: *paper_id,
+                title: title.clone(),
+                kind: FileKind::Image,
+                path,
+                problem,
+            });
+        }
+    }
+
+    Ok(FileReport {
+        pdfs_checked: papers.len(),
+        images_checked: images.len(),
+        issues,
+    })
+}
@@ -0,0 +1,681 @@
+use anyhow::{bail, Result};
+use sqlx::{SqliteConnection, SqlitePool};
+use tracing::info;
+
+use crate::storage::citations;
+
+/// 按版本号递增的结构迁移，`Database::open` 连接后自动执行尚未应用的迁移
+///
+/// 已发布的迁移不要再修改，结构变化一律追加新的版本；每个迁移在单独的事务中执行，
+/// 失败时整体回滚，下次启动重试。
+const MIGRATIONS: &[(i64, &str)] = &[
+    (1, "初始表结构"),
+    (2, "extracted_content 增加 sections 列，迁出 key_points 中的章节数据"),
+    (3, "reports 记录输出文件、格式、订阅、分组排序方式和语言"),
+    (4, "标签：tags 与 paper_tags，已有论文按所属订阅打标签，reports 记录筛选标签"),
+    (5, "papers 增加已读时间 read_at 和星标 starred，分拣时标星的论文设为星标"),
+    (6, "paper_sources 记录论文在各来源的ID，跨来源的重复论文合并为一条"),
+    (7, "subscriptions 按名称唯一并记录研究问题描述，相关度和爬取记录关联订阅ID"),
+    (8, "为常用查询（来源ID、解析状态、待翻译、入库时间、提取内容）建立索引"),
+    (9, "crawl_runs 记录入库、跳过、剔除、错误数和耗时，api_calls 记录 LLM 调用的耗时、错误和 token 用量"),
+    (10, "generic_cache 持久化缓存 arXiv 检索结果等外部请求的响应"),
+    (11, "papers 增加删除时间 deleted_at，删除的论文先移入回收站"),
+    (12, "citations 记录论文间的引用关系，由已提取的参考文献生成"),
+    (13, "embeddings 保存论文在各模型下的向量，用于相似论文检索"),
+    (14, "provenance 记录入库记录、译文、提取内容和要点由哪个数据源、服务商或程序版本产生"),
+    (15, "paper_relevance 增加相关度评分依据 reasons"),
+    (16, "paper_relevance 改为按论文和订阅记录，同一论文可属于多个订阅"),
+];
+
+/// 初始表结构；引入迁移之前创建的数据库同样从这里升级，所以全部使用 IF NOT EXISTS
+const BASELINE: &[&str] = &[
+    r#"
+        CREATE TABLE IF NOT EXISTS papers (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            title_zh TEXT,
+            authors TEXT,
+            abstract TEXT,
+            abstract_zh TEXT,
+            publish_date TEXT,
+            source TEXT NOT NULL,
+            source_id TEXT NOT NULL,
+            pdf_url TEXT,
+            pdf_path TEXT,
+            processed INTEGER DEFAULT 0,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            translation_status TEXT NOT NULL DEFAULT 'pending',
+            code_urls TEXT,
+            venue TEXT,
+            funding TEXT,
+            pdf_error TEXT,
+            UNIQUE(source, source_id)
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS subscriptions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL,
+            keywords TEXT NOT NULL,
+            sources TEXT NOT NULL,
+            categories TEXT,
+            enabled INTEGER DEFAULT 1,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS extracted_content (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            paper_id INTEGER NOT NULL,
+            formulas TEXT,
+            images TEXT,
+            tables TEXT,
+            key_points TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (paper_id) REFERENCES papers(id),
+            UNIQUE(paper_id)
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS reports (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            subscription_id INTEGER,
+            report_date TEXT NOT NULL,
+            paper_count INTEGER,
+            ppt_path TEXT,
+            status TEXT DEFAULT 'pending',
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (subscription_id) REFERENCES subscriptions(id)
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS saved_searches (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            query TEXT NOT NULL UNIQUE,
+            promoted INTEGER DEFAULT 0,
+            last_run_at TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS crawl_runs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            subscription TEXT NOT NULL,
+            source TEXT NOT NULL,
+            total_matched INTEGER,
+            fetched INTEGER NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS pdf_texts (
+            pdf_hash TEXT PRIMARY KEY,
+            text_gz BLOB NOT NULL,
+            text_len INTEGER NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS section_prefs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            paper_id INTEGER NOT NULL,
+            pattern TEXT NOT NULL,
+            action TEXT NOT NULL CHECK (action IN ('pin', 'hide')),
+            FOREIGN KEY (paper_id) REFERENCES papers(id),
+            UNIQUE(paper_id, pattern)
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS translations_cache (
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            text_hash TEXT NOT NULL,
+            response TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (provider, model, text_hash)
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS query_expansions (
+            subscription TEXT NOT NULL,
+            keywords_hash TEXT NOT NULL,
+            terms TEXT NOT NULL,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (subscription, keywords_hash)
+        )
+        "#,
+    // 记录每篇论文的译文由哪个服务商/模型产出
+    r#"
+        CREATE TABLE IF NOT EXISTS translation_sources (
+            source TEXT NOT NULL,
+            source_id TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            translated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (source, source_id)
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS paper_images (
+            paper_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            image_key TEXT NOT NULL,
+            page INTEGER NOT NULL,
+            width INTEGER NOT NULL,
+            height INTEGER NOT NULL,
+            format TEXT NOT NULL,
+            PRIMARY KEY (paper_id, position),
+            FOREIGN KEY (paper_id) REFERENCES papers(id)
+        )
+        "#,
+    // 论文与订阅的相关度：关键词评分，以及可选的 LLM 标签
+    r#"
+        CREATE TABLE IF NOT EXISTS paper_relevance (
+            paper_id INTEGER PRIMARY KEY,
+            subscription TEXT NOT NULL,
+            score REAL NOT NULL,
+            label TEXT,
+            created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (paper_id) REFERENCES papers(id)
+        )
+        "#,
+    // 论文的参考文献，arxiv_id / doi 用于构建引用关系
+    // references 是 SQL 关键字，表名需加引号
+    r#"
+        CREATE TABLE IF NOT EXISTS "references" (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            paper_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            label TEXT,
+            raw TEXT NOT NULL,
+            authors TEXT NOT NULL DEFAULT '[]',
+            year INTEGER,
+            title TEXT,
+            venue TEXT,
+            arxiv_id TEXT,
+            doi TEXT,
+            FOREIGN KEY (paper_id) REFERENCES papers(id)
+        )
+        "#,
+    r#"CREATE INDEX IF NOT EXISTS idx_references_paper ON "references"(paper_id)"#,
+    r#"CREATE INDEX IF NOT EXISTS idx_references_arxiv ON "references"(arxiv_id)"#,
+    r#"CREATE INDEX IF NOT EXISTS idx_references_doi ON "references"(doi)"#,
+    // 每个章节引用了哪些参考文献
+    r#"
+        CREATE TABLE IF NOT EXISTS section_citations (
+            paper_id INTEGER NOT NULL,
+            section_position INTEGER NOT NULL,
+            heading TEXT NOT NULL,
+            reference_position INTEGER NOT NULL,
+            mentions INTEGER NOT NULL DEFAULT 1,
+            PRIMARY KEY (paper_id, section_position, reference_position),
+            FOREIGN KEY (paper_id) REFERENCES papers(id)
+        )
+        "#,
+    // 每篇论文的关键短语，按 phrase 索引以便统计研究趋势
+    r#"
+        CREATE TABLE IF NOT EXISTS paper_keyphrases (
+            paper_id INTEGER NOT NULL,
+            position INTEGER NOT NULL,
+            phrase TEXT NOT NULL,
+            score REAL NOT NULL,
+            occurrences INTEGER NOT NULL DEFAULT 1,
+            PRIMARY KEY (paper_id, position),
+            FOREIGN KEY (paper_id) REFERENCES papers(id)
+        )
+        "#,
+    "CREATE INDEX IF NOT EXISTS idx_paper_keyphrases_phrase ON paper_keyphrases(phrase)",
+    // 交互式分拣的决定，没有记录的论文视为未分拣
+    r#"
+        CREATE TABLE IF NOT EXISTS paper_triage (
+            paper_id INTEGER PRIMARY KEY,
+            decision TEXT NOT NULL,
+            decided_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (paper_id) REFERENCES papers(id)
+        )
+        "#,
+    r#"
+        CREATE TABLE IF NOT EXISTS backfill_progress (
+            subscription TEXT NOT NULL,
+            source TEXT NOT NULL,
+            month TEXT NOT NULL,
+            fetched INTEGER NOT NULL DEFAULT 0,
+            total_matched INTEGER,
+            completed INTEGER NOT NULL DEFAULT 0,
+            updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+            PRIMARY KEY (subscription, source, month)
+        )
+        "#,
+];
+
+/// 当前程序支持的最高结构版本
+pub fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|(version, _)| *version).unwrap_or(0)
+}
+
+/// 数据库当前的结构版本，未执行过迁移时为 0
+pub async fn current_version(pool: &SqlitePool) -> Result<i64> {
+    let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
+        .fetch_one(pool)
+        .await?;
+    Ok(version.unwrap_or(0))
+}
+
+/// 执行尚未应用的迁移，返回迁移后的版本
+pub async fn run(pool: &SqlitePool) -> Result<i64> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TEXT DEFAULT CURRENT_TIMESTAMP
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let current = current_version(pool).await?;
+    if current > latest_version() {
+        bail!("数据库结构版本 {} 高于当前程序支持的 {}，请升级 bsxbot", current, latest_version());
+    }
+
+    for (version, description) in MIGRATIONS.iter().filter(|(version, _)| *version > current) {
+        let mut tx = pool.begin().await?;
+        apply(&mut tx, *version).await?;
+        sqlx::query("INSERT INTO schema_version (version, description) VALUES (?, ?)")
+            .bind(version)
+            .bind(description)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        info!("数据库结构已迁移到版本 {}: {}", version, description);
+    }
+
+    Ok(latest_version().max(current))
+}
+
+async fn apply(conn: &mut SqliteConnection, version: i64) -> Result<()> {
+    match version {
+        1 => {
+            for statement in BASELINE {
+                sqlx::query(statement).execute(&mut *conn).await?;
+            }
+            // 早期数据库补充后来增加的列，并按已有译文/摘要推断翻译状态
+            if ensure_column(conn, "papers", "translation_status", "TEXT NOT NULL DEFAULT 'pending'").await? {
+                sqlx::query("UPDATE papers SET translation_status = 'done' WHERE title_zh IS NOT NULL")
+                    .execute(&mut *conn)
+                    .await?;
+                sqlx::query(
+                    "UPDATE papers SET translation_status = 'skipped_no_abstract' \
+                     WHERE title_zh IS NULL AND (abstract IS NULL OR abstract = '')"
+                )
+                .execute(&mut *conn)
+                .await?;
+            }
+            ensure_column(conn, "papers", "code_urls", "TEXT").await?;
+            ensure_column(conn, "papers", "venue", "TEXT").await?;
+            ensure_column(conn, "papers", "funding", "TEXT").await?;
+            ensure_column(conn, "papers", "pdf_error", "TEXT").await?;
+            ensure_column(conn, "extracted_content", "links", "TEXT").await?;
+        }
+        2 => {
+            // 早期版本把章节 JSON 写在 key_points 列；已经有 sections 列的数据库不再搬移
+            if ensure_column(conn, "extracted_content", "sections", "TEXT").await? {
+                sqlx::query("UPDATE extracted_content SET sections = key_points, key_points = NULL")
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        3 => {
+            for column in ["output_path", "format", "subscriptions", "group_by", "sort_by", "lang"] {
+                ensure_column(conn, "reports", column, "TEXT").await?;
+            }
+        }
+        4 => {
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS tags (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            // source 为 manual（手动添加）或 subscription（爬取时按订阅自动添加）
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS paper_tags (
+                    paper_id INTEGER NOT NULL,
+                    tag_id INTEGER NOT NULL,
+                    source TEXT NOT NULL DEFAULT 'manual',
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (paper_id, tag_id),
+                    FOREIGN KEY (paper_id) REFERENCES papers(id),
+                    FOREIGN KEY (tag_id) REFERENCES tags(id)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_paper_tags_tag ON paper_tags(tag_id)")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("INSERT OR IGNORE INTO tags (name) SELECT DISTINCT subscription FROM paper_relevance")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query(
+                r#"INSERT OR IGNORE INTO paper_tags (paper_id, tag_id, source)
+                   SELECT r.paper_id, t.id, 'subscription'
+                   FROM paper_relevance r JOIN tags t ON t.name = r.subscription"#
+            )
+            .execute(&mut *conn)
+            .await?;
+            ensure_column(conn, "reports", "tags", "TEXT").await?;
+        }
+        5 => {
+            ensure_column(conn, "papers", "read_at", "TEXT").await?;
+            if ensure_column(conn, "papers", "starred", "INTEGER NOT NULL DEFAULT 0").await? {
+                sqlx::query("UPDATE papers SET starred = 1 WHERE id IN (SELECT paper_id FROM paper_triage WHERE decision = 'star')")
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        6 => {
+            // 每篇论文至少有一条自身来源的记录，合并进来的重复论文追加其他来源
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS paper_sources (
+                    source TEXT NOT NULL,
+                    source_id TEXT NOT NULL,
+                    paper_id INTEGER NOT NULL,
+                    pdf_url TEXT,
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (source, source_id),
+                    FOREIGN KEY (paper_id) REFERENCES papers(id)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_paper_sources_paper ON paper_sources(paper_id)")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query(
+                "INSERT OR IGNORE INTO paper_sources (source, source_id, paper_id, pdf_url) \
+                 SELECT source, source_id, id, pdf_url FROM papers"
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        7 => {
+            // 之前没有写入过 subscriptions，保险起见按名称去重后再建唯一索引
+            sqlx::query("DELETE FROM subscriptions WHERE id NOT IN (SELECT MIN(id) FROM subscriptions GROUP BY name)")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("CREATE UNIQUE INDEX IF NOT EXISTS idx_subscriptions_name ON subscriptions(name)")
+                .execute(&mut *conn)
+                .await?;
+            ensure_column(conn, "subscriptions", "description", "TEXT NOT NULL DEFAULT ''").await?;
+            ensure_column(conn, "subscriptions", "updated_at", "TEXT").await?;
+            // 订阅ID由启动时的同步按名称补全
+            ensure_column(conn, "paper_relevance", "subscription_id", "INTEGER REFERENCES subscriptions(id)").await?;
+            ensure_column(conn, "crawl_runs", "subscription_id", "INTEGER REFERENCES subscriptions(id)").await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_paper_relevance_subscription ON paper_relevance(subscription_id)")
+                .execute(&mut *conn)
+                .await?;
+        }
+        8 => {
+            // UNIQUE 约束自带索引，早期没有该约束的表才需要补建
+            ensure_index(conn, "idx_papers_source", "papers", &["source", "source_id"]).await?;
+            ensure_index(conn, "idx_extracted_content_paper", "extracted_content", &["paper_id"]).await?;
+            for statement in [
+                "CREATE INDEX IF NOT EXISTS idx_papers_processed ON papers(processed)",
+                "CREATE INDEX IF NOT EXISTS idx_papers_created_at ON papers(created_at)",
+                // 待翻译论文的查询条件都含 title_zh IS NULL，部分索引只收录这些行
+                "CREATE INDEX IF NOT EXISTS idx_papers_untranslated ON papers(translation_status) WHERE title_zh IS NULL",
+            ] {
+                sqlx::query(statement).execute(&mut *conn).await?;
+            }
+        }
+        9 => {
+            for column in ["saved", "existing", "rejected", "errors"] {
+                ensure_column(conn, "crawl_runs", column, "INTEGER NOT NULL DEFAULT 0").await?;
+            }
+            ensure_column(conn, "crawl_runs", "error", "TEXT").await?;
+            ensure_column(conn, "crawl_runs", "duration_ms", "INTEGER").await?;
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS api_calls (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    provider TEXT NOT NULL,
+                    model TEXT NOT NULL,
+                    purpose TEXT NOT NULL,
+                    success INTEGER NOT NULL,
+                    http_status INTEGER,
+                    error TEXT,
+                    duration_ms INTEGER NOT NULL,
+                    prompt_tokens INTEGER,
+                    completion_tokens INTEGER,
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_api_calls_created_at ON api_calls(created_at)")
+                .execute(&mut *conn)
+                .await?;
+        }
+        10 => {
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS generic_cache (
+                    namespace TEXT NOT NULL,
+                    key TEXT NOT NULL,
+                    value_gz BLOB NOT NULL,
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    expires_at TEXT NOT NULL,
+                    PRIMARY KEY (namespace, key)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_generic_cache_expires ON generic_cache(expires_at)")
+                .execute(&mut *conn)
+                .await?;
+        }
+        11 => {
+            ensure_column(conn, "papers", "deleted_at", "TEXT").await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_papers_deleted_at ON papers(deleted_at)")
+                .execute(&mut *conn)
+                .await?;
+        }
+        12 => {
+            // cited_id 为被引文献的标识（见 citations::cited_id），cited_paper_id 为关联到的库中论文；
+            // source 为 pdf（从PDF提取的参考文献）
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS citations (
+                    citing_paper_id INTEGER NOT NULL,
+                    cited_id TEXT NOT NULL,
+                    cited_paper_id INTEGER,
+                    source TEXT NOT NULL DEFAULT 'pdf',
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (citing_paper_id, cited_id),
+                    FOREIGN KEY (citing_paper_id) REFERENCES papers(id),
+                    FOREIGN KEY (cited_paper_id) REFERENCES papers(id)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_citations_cited_paper ON citations(cited_paper_id)")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_citations_cited_id ON citations(cited_id)")
+                .execute(&mut *conn)
+                .await?;
+            let references = sqlx::query_as::<_, (i64, Option<String>, Option<String>, Option<String>)>(
+                r#"SELECT paper_id, arxiv_id, doi, title FROM "references""#
+            )
+            .fetch_all(&mut *conn)
+            .await?;
+            for (paper_id, arxiv_id, doi, title) in references {
+                let Some(cited) = citations::cited_id(arxiv_id.as_deref(), doi.as_deref(), title.as_deref()) else {
+                    continue;
+                };
+                sqlx::query("INSERT OR IGNORE INTO citations (citing_paper_id, cited_id, source) VALUES (?, ?, 'pdf')")
+                    .bind(paper_id)
+                    .bind(cited)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        13 => {
+            // vector 为 dims 个小端 f32
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS embeddings (
+                    paper_id INTEGER NOT NULL,
+                    model TEXT NOT NULL,
+                    dims INTEGER NOT NULL,
+                    vector BLOB NOT NULL,
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (paper_id, model),
+                    FOREIGN KEY (paper_id) REFERENCES papers(id)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_embeddings_model ON embeddings(model, dims)")
+                .execute(&mut *conn)
+                .await?;
+        }
+        14 => {
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS provenance (
+                    paper_id INTEGER NOT NULL,
+                    field TEXT NOT NULL,
+                    producer TEXT NOT NULL,
+                    version TEXT,
+                    produced_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (paper_id, field),
+                    FOREIGN KEY (paper_id) REFERENCES papers(id)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            // 已有结果的程序版本未知，version 留空，按比任何版本都旧处理
+            sqlx::query(
+                r#"INSERT OR IGNORE INTO provenance (paper_id, field, producer, produced_at)
+                   SELECT id, 'record', source, created_at FROM papers"#
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query(
+                r#"INSERT OR IGNORE INTO provenance (paper_id, field, producer, produced_at)
+                   SELECT s.paper_id, 'translation', t.provider, t.translated_at
+                   FROM translation_sources t
+                   JOIN paper_sources s ON s.source = t.source AND s.source_id = t.source_id"#
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query(
+                r#"INSERT OR IGNORE INTO provenance (paper_id, field, producer, produced_at)
+                   SELECT paper_id, 'content', 'parser', NULL FROM extracted_content
+                   WHERE sections IS NOT NULL AND sections != ''"#
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        15 => {
+            // JSON 数组，之前入库的论文没有记录评分依据
+            ensure_column(conn, "paper_relevance", "reasons", "TEXT").await?;
+        }
+        16 => {
+            // SQLite 不能修改主键，重建表；已有记录每篇论文只有一个订阅，原样复制
+            sqlx::query(
+                r#"
+                CREATE TABLE paper_relevance_new (
+                    paper_id INTEGER NOT NULL,
+                    subscription TEXT NOT NULL,
+                    subscription_id INTEGER REFERENCES subscriptions(id),
+                    score REAL NOT NULL,
+                    label TEXT,
+                    reasons TEXT,
+                    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                    PRIMARY KEY (paper_id, subscription),
+                    FOREIGN KEY (paper_id) REFERENCES papers(id)
+                )
+                "#,
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query(
+                r#"INSERT INTO paper_relevance_new (paper_id, subscription, subscription_id, score, label, reasons, created_at)
+                   SELECT paper_id, subscription, subscription_id, score, label, reasons, created_at FROM paper_relevance"#
+            )
+            .execute(&mut *conn)
+            .await?;
+            sqlx::query("DROP TABLE paper_relevance").execute(&mut *conn).await?;
+            sqlx::query("ALTER TABLE paper_relevance_new RENAME TO paper_relevance")
+                .execute(&mut *conn)
+                .await?;
+            sqlx::query("CREATE INDEX IF NOT EXISTS idx_paper_relevance_subscription ON paper_relevance(subscription_id)")
+                .execute(&mut *conn)
+                .await?;
+        }
+        _ => bail!("未知的迁移版本: {}", version),
+    }
+    Ok(())
+}
+
+/// 为已有表补充列（CREATE TABLE IF NOT EXISTS 不会修改旧表），返回是否新增
+async fn ensure_column(conn: &mut SqliteConnection, table: &str, column: &str, definition: &str) -> Result<bool> {
+    let columns: Vec<String> = sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{}')", table))
+        .fetch_all(&mut *conn)
+        .await?;
+    if columns.iter().any(|c| c == column) {
+        return Ok(false);
+    }
+
+    sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+        .execute(&mut *conn)
+        .await?;
+    info!("已为 {} 表添加列 {}", table, column);
+    Ok(true)
+}
+
+/// 表上没有以 `columns` 开头的完整索引时创建，返回是否新建
+async fn ensure_index(conn: &mut SqliteConnection, name: &str, table: &str, columns: &[&str]) -> Result<bool> {
+    let rows: Vec<(String, String)> = sqlx::query_as(&format!(
+        r#"SELECT il.name, ii.name
+           FROM pragma_index_list('{}') il
+           JOIN pragma_index_info(il.name) ii
+           WHERE il.partial = 0
+           ORDER BY il.name, ii.seqno"#,
+        table
+    ))
+    .fetch_all(&mut *conn)
+    .await?;
+    let mut indexes: std::collections::BTreeMap<String, Vec<String>> = std::collections::BTreeMap::new();
+    for (index, column) in rows {
+        indexes.entry(index).or_default().push(column);
+    }
+    let columns: Vec<String> = columns.iter().map(|c| c.to_string()).collect();
+    if indexes.values().any(|indexed| indexed.starts_with(&columns)) {
+        return Ok(false);
+    }
+
+    sqlx::query(&format!("CREATE INDEX IF NOT EXISTS {} ON {}({})", name, table, columns.join(", ")))
+        .execute(&mut *conn)
+        .await?;
+    info!("已为 {} 表建立索引 {}", table, name);
+    Ok(true)
+}
@@ -1,5 +1,21 @@
+pub mod backup;
+pub mod batch;
 pub mod database;
+pub mod migrations;
 pub mod models;
 pub mod cache;
+pub mod citations;
+pub mod dedup;
+pub mod details;
+pub mod dump;
+pub mod embeddings;
+pub mod images;
+pub mod integrity;
+pub mod prune;
+pub mod provenance;
+pub mod state;
+pub mod stats;
+pub mod tables;
+pub mod writes;
 
 pub use database::Database;
@@ -16,26 +16,364 @@ pub struct Paper {
     pub pdf_path: Option<String>,
     pub processed: bool,
     pub created_at: Option<String>,
+    /// 翻译状态，取值见 [`TranslationStatus`]
+    #[sqlx(default)]
+    #[serde(default)]
+    pub translation_status: String,
+    /// 代码仓库链接（JSON 数组），解析PDF后写入
+    #[sqlx(default)]
+    #[serde(default)]
+    pub code_urls: Option<String>,
+    /// 发表的会议或期刊，解析PDF首页得到
+    #[sqlx(default)]
+    #[serde(default)]
+    pub venue: Option<String>,
+    /// 资助声明（JSON 数组）
+    #[sqlx(default)]
+    #[serde(default)]
+    pub funding: Option<String>,
 }
 
+impl Paper {
+    /// 解析 `code_urls` 列
+    pub fn code_urls(&self) -> Vec<String> {
+        self.code_urls
+            .as_deref()
+            .and_then(|json| serde_json::from_str(json).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// 论文在某个来源中的记录；跨来源的重复论文共用一条论文记录，各来源分别保存
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-pub struct ExtractedContent {
-    pub id: Option<i64>,
+pub struct PaperSource {
     pub paper_id: i64,
-    pub formulas: Option<String>,
-    pub images: Option<String>,
-    pub tables: Option<String>,
-    pub key_points: Option<String>,
+    pub source: String,
+    pub source_id: String,
+    pub pdf_url: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// [`Database::query_papers`](crate::storage::Database::query_papers) 的筛选条件，
+/// 未设置的条件不限制，设置的条件须同时满足
+#[derive(Debug, Clone, Default)]
+pub struct PaperFilter {
+    /// 论文ID，为空时不限制
+    pub ids: Vec<i64>,
+    /// 来源（含合并进来的其他来源）
+    pub source: Option<String>,
+    /// 发表日期下限（含），YYYY-MM-DD
+    pub since: Option<String>,
+    /// 发表日期上限（含），YYYY-MM-DD
+    pub until: Option<String>,
+    /// 是否已解析PDF
+    pub processed: Option<bool>,
+    /// 是否已下载PDF
+    pub downloaded: Option<bool>,
+    /// `Some(true)` 已有译文标题；`Some(false)` 待翻译：无译文、有摘要且状态为待翻译或失败
+    pub translated: Option<bool>,
+    /// 翻译状态
+    pub translation_status: Option<TranslationStatus>,
+    /// 是否有非空摘要
+    pub has_abstract: Option<bool>,
+    /// 带有任一标签（不区分大小写），为空时不限制
+    pub tags: Vec<String>,
+    /// 为 true 时只查回收站中（已删除）的论文，默认只查未删除的
+    pub deleted: bool,
+}
+
+/// 论文查询的排序方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PaperSort {
+    /// 按ID（入库顺序）
+    #[default]
+    Id,
+    /// 入库时间从新到旧
+    Newest,
+    /// 发表日期从新到旧，无日期的排在最后
+    Published,
+    /// 按标题
+    Title,
+}
+
+impl PaperSort {
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            PaperSort::Id => "id",
+            PaperSort::Newest => "created_at DESC, id DESC",
+            PaperSort::Published => "publish_date IS NULL, publish_date DESC, id DESC",
+            PaperSort::Title => "title COLLATE NOCASE, id",
+        }
+    }
+}
+
+/// 论文标题/摘要的翻译状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranslationStatus {
+    /// 尚未翻译（包括因未配置 API 而跳过的）
+    Pending,
+    /// 没有摘要，无法翻译
+    SkippedNoAbstract,
+    /// 标题和摘要已是目标语言，无需翻译
+    SkippedSameLanguage,
+    /// 调用翻译服务失败
+    Failed,
+    /// 已翻译
+    Done,
+}
+
+impl TranslationStatus {
+    pub const ALL: [TranslationStatus; 5] = [
+        TranslationStatus::Pending,
+        TranslationStatus::SkippedNoAbstract,
+        TranslationStatus::SkippedSameLanguage,
+        TranslationStatus::Failed,
+        TranslationStatus::Done,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranslationStatus::Pending => "pending",
+            TranslationStatus::SkippedNoAbstract => "skipped_no_abstract",
+            TranslationStatus::SkippedSameLanguage => "skipped_same_language",
+            TranslationStatus::Failed => "failed",
+            TranslationStatus::Done => "done",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    /// 中文显示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            TranslationStatus::Pending => "待翻译",
+            TranslationStatus::SkippedNoAbstract => "无摘要跳过",
+            TranslationStatus::SkippedSameLanguage => "已是目标语言",
+            TranslationStatus::Failed => "翻译失败",
+            TranslationStatus::Done => "已翻译",
+        }
+    }
+}
+
+/// 交互式分拣（triage 命令）对论文的处理决定
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriageDecision {
+    /// 保留待读
+    Keep,
+    /// 加星标
+    Star,
+    /// 归档，不再关注
+    Archive,
+}
+
+impl TriageDecision {
+    pub const ALL: [TriageDecision; 3] = [
+        TriageDecision::Keep,
+        TriageDecision::Star,
+        TriageDecision::Archive,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriageDecision::Keep => "keep",
+            TriageDecision::Star => "star",
+            TriageDecision::Archive => "archive",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+
+    /// 中文显示名
+    pub fn label(&self) -> &'static str {
+        match self {
+            TriageDecision::Keep => "保留",
+            TriageDecision::Star => "星标",
+            TriageDecision::Archive => "归档",
+        }
+    }
+}
+
+/// 数据库中的订阅，从 keywords.toml 同步；keywords、sources、categories 为 JSON 数组
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SubscriptionRecord {
+    pub id: i64,
+    pub name: String,
+    pub description: String,
+    pub keywords: String,
+    pub sources: String,
+    pub categories: Option<String>,
+    pub enabled: bool,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+impl SubscriptionRecord {
+    /// 解析 `keywords` 列
+    pub fn keywords(&self) -> Vec<String> {
+        serde_json::from_str(&self.keywords).unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SavedSearch {
+    pub id: Option<i64>,
+    pub query: String,
+    pub promoted: bool,
+    pub last_run_at: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// 一次爬取中单个订阅、单个来源的记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct CrawlRun {
+    pub id: Option<i64>,
+    pub subscription: String,
+    pub source: String,
+    pub total_matched: Option<i64>,
+    pub fetched: i64,
+    pub created_at: Option<String>,
+    /// 新入库的论文数
+    #[sqlx(default)]
+    pub saved: i64,
+    /// 已在库中跳过或修复的论文数
+    #[sqlx(default)]
+    pub existing: i64,
+    /// LLM 初筛剔除的论文数
+    #[sqlx(default)]
+    pub rejected: i64,
+    /// 翻译、下载或解析失败的次数
+    #[sqlx(default)]
+    pub errors: i64,
+    /// 搜索失败等导致本次爬取中断的错误
+    #[sqlx(default)]
+    pub error: Option<String>,
+    #[sqlx(default)]
+    pub duration_ms: Option<i64>,
+}
+
+impl CrawlRun {
+    /// 匹配但未获取的论文数
+    pub fn not_retrieved(&self) -> i64 {
+        self.total_matched
+            .map(|total| (total - self.fetched).max(0))
+            .unwrap_or(0)
+    }
+}
+
+/// 一次 LLM API 请求（重试的每次尝试分别记录，命中缓存的不记录）
+#[derive(Debug, Clone, Default, Serialize, Deserialize, FromRow)]
+pub struct ApiCall {
+    pub id: Option<i64>,
+    /// 配置中的服务商名称
+    pub provider: String,
+    pub model: String,
+    /// 调用用途，如 translate_paper、triage
+    pub purpose: String,
+    pub success: bool,
+    /// 服务端返回的 HTTP 状态码，连接失败时为空
+    pub http_status: Option<i64>,
+    pub error: Option<String>,
+    pub duration_ms: i64,
+    /// 服务端报告的 token 用量，未返回时为空
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
     pub created_at: Option<String>,
 }
 
+/// 一次生成的报告
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
-pub struct Report {
+pub struct ReportRecord {
     pub id: Option<i64>,
-    pub subscription_id: Option<i64>,
     pub report_date: String,
     pub paper_count: Option<i64>,
-    pub ppt_path: Option<String>,
-    pub status: String,
+    pub output_path: Option<String>,
+    /// "html" 或 "pptx"
+    pub format: Option<String>,
+    /// 报告中论文所属的订阅，逗号分隔
+    pub subscriptions: Option<String>,
+    pub group_by: Option<String>,
+    pub sort_by: Option<String>,
+    /// "bilingual" 或 "en"
+    pub lang: Option<String>,
+    /// 按标签筛选时的标签，逗号分隔
+    pub tags: Option<String>,
+    /// "generated"、"sent"、"publish_failed" 或 "send_failed"
+    pub status: Option<String>,
     pub created_at: Option<String>,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SectionPref {
+    pub paper_id: i64,
+    pub pattern: String,
+    /// "pin" 或 "hide"
+    pub action: String,
+}
+
+/// 论文引用的图片，image_key 为图片存储中的哈希键
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaperImage {
+    pub paper_id: i64,
+    /// 图片在论文中的顺序
+    pub position: i64,
+    pub image_key: String,
+    pub page: i64,
+    pub width: i64,
+    pub height: i64,
+    pub format: String,
+}
+
+/// 论文的一条参考文献，authors 为 JSON 数组
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaperReference {
+    pub paper_id: i64,
+    /// 在参考文献列表中的顺序
+    pub position: i64,
+    pub label: Option<String>,
+    pub raw: String,
+    pub authors: String,
+    pub year: Option<i64>,
+    pub title: Option<String>,
+    pub venue: Option<String>,
+    pub arxiv_id: Option<String>,
+    pub doi: Option<String>,
+}
+
+/// 章节引用的一条参考文献，reference_position 对应 references 表的 position
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SectionCitation {
+    pub paper_id: i64,
+    /// 章节在论文中的顺序
+    pub section_position: i64,
+    pub heading: String,
+    pub reference_position: i64,
+    pub mentions: i64,
+}
+
+/// 论文的一个关键短语，position 为得分排名
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PaperKeyphrase {
+    pub paper_id: i64,
+    pub position: i64,
+    pub phrase: String,
+    pub score: f64,
+    pub occurrences: i64,
+}
+
+/// 回溯爬取的按月进度，用于中断后续跑
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BackfillProgress {
+    pub subscription: String,
+    pub source: String,
+    /// 月份标识，如 `2019-01`
+    pub month: String,
+    /// 该月已获取的条数，即下一页的起始偏移
+    pub fetched: i64,
+    pub total_matched: Option<i64>,
+    pub completed: bool,
+    pub updated_at: Option<String>,
+}
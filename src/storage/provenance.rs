@@ -0,0 +1,140 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use sqlx::SqliteConnection;
+use std::cmp::Ordering;
+
+use crate::storage::Database;
+
+/// 程序版本，随派生字段一起记录，升级后据此找出需要重新生成的结果
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// 入库记录：producer 为数据源，produced_at 为最近一次爬取的时间
+pub const RECORD: &str = "record";
+/// 标题和摘要的译文：producer 为服务商/模型，原文即目标语言时为 `original`
+pub const TRANSLATION: &str = "translation";
+/// PDF 提取内容：producer 为 `parser`
+pub const CONTENT: &str = "content";
+/// LLM 生成的要点：producer 为服务商/模型
+pub const KEY_POINTS: &str = "key_points";
+
+/// 论文某个字段的来源
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Provenance {
+    pub field: String,
+    pub producer: String,
+    /// 产生该字段的程序版本，迁移前已有的结果为空
+    pub version: Option<String>,
+    pub produced_at: Option<String>,
+}
+
+/// 提取内容待重新生成的论文
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct StalePaper {
+    pub id: i64,
+    pub title: String,
+    pub version: Option<String>,
+}
+
+/// 记录字段由谁产生，在调用方的事务中执行；已有记录被覆盖
+pub(crate) async fn record(conn: &mut SqliteConnection, paper_id: i64, field: &str, producer: &str) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO provenance (paper_id, field, producer, version, produced_at)
+           VALUES (?, ?, ?, ?, CURRENT_TIMESTAMP)
+           ON CONFLICT(paper_id, field) DO UPDATE SET
+               producer = excluded.producer,
+               version = excluded.version,
+               produced_at = excluded.produced_at"#
+    )
+    .bind(paper_id)
+    .bind(field)
+    .bind(producer)
+    .bind(VERSION)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 同 [`record`]，按来源ID定位论文
+pub(crate) async fn record_for_source(
+    conn: &mut SqliteConnection,
+    source: &str,
+    source_id: &str,
+    field: &str,
+    producer: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO provenance (paper_id, field, producer, version, produced_at)
+           SELECT paper_id, ?, ?, ?, CURRENT_TIMESTAMP FROM paper_sources WHERE source = ? AND source_id = ?
+           ON CONFLICT(paper_id, field) DO UPDATE SET
+               producer = excluded.producer,
+               version = excluded.version,
+               produced_at = excluded.produced_at"#
+    )
+    .bind(field)
+    .bind(producer)
+    .bind(VERSION)
+    .bind(source)
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 论文各字段的来源，最近产生的在前
+pub async fn for_paper(db: &Database, paper_id: i64) -> Result<Vec<Provenance>> {
+    let rows = sqlx::query_as::<_, Provenance>(
+        "SELECT field, producer, version, produced_at FROM provenance WHERE paper_id = ? ORDER BY produced_at DESC, field"
+    )
+    .bind(paper_id)
+    .fetch_all(db.pool())
+    .await?;
+
+    Ok(rows)
+}
+
+/// 提取内容由早于 `version` 的程序版本产生（或版本未知）、且已下载PDF的论文，不含回收站中的论文
+pub async fn content_older_than(db: &Database, version: &str) -> Result<Vec<StalePaper>> {
+    let threshold = parse_version(version)?;
+    let papers = sqlx::query_as::<_, StalePaper>(
+        r#"SELECT p.id, p.title, v.version
+           FROM papers p
+           JOIN provenance v ON v.paper_id = p.id AND v.field = ?
+           WHERE p.deleted_at IS NULL AND p.pdf_path IS NOT NULL
+           ORDER BY p.id"#
+    )
+    .bind(CONTENT)
+    .fetch_all(db.pool())
+    .await?;
+
+    Ok(papers
+        .into_iter()
+        .filter(|paper| match paper.version.as_deref().map(parse_version) {
+            Some(Ok(produced)) => compare_versions(&produced, &threshold) == Ordering::Less,
+            _ => true,
+        })
+        .collect())
+}
+
+/// 解析版本号，如 `v0.3`、`0.3.1`
+pub fn parse_version(value: &str) -> Result<Vec<u64>> {
+    let trimmed = value.trim();
+    let digits = trimmed.strip_prefix(['v', 'V']).unwrap_or(trimmed);
+    let parts: Option<Vec<u64>> = digits.split('.').map(|part| part.parse().ok()).collect();
+    match parts {
+        Some(parts) if !parts.is_empty() => Ok(parts),
+        _ => bail!("无效的版本号 '{}'，格式如 v0.3 或 0.3.1", value),
+    }
+}
+
+/// 按段比较版本号，缺少的段按 0 处理（`0.3` 与 `0.3.0` 相同）
+fn compare_versions(a: &[u64], b: &[u64]) -> Ordering {
+    for i in 0..a.len().max(b.len()) {
+        let ordering = a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
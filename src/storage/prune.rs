@@ -0,0 +1,178 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+use crate::config::dirs;
+use crate::storage::images::is_store_key;
+use crate::storage::stats::disk_usage;
+use crate::storage::Database;
+use crate::utils::paths::is_safe_relative_path;
+
+/// 超过保留期的待清理内容
+///
+/// 回收站中的论文按删除时间计算保留期，其余按入库时间，星标论文不清理；图片按内容哈希共享，仍被保留论文引用的不删除。
+#[derive(Debug, Serialize)]
+pub struct PrunePlan {
+    pub older_than_days: u32,
+    pub papers: Vec<PrunedPaper>,
+    /// PDF、表格 CSV 和不再被引用的图片
+    pub files: Vec<PathBuf>,
+    /// 文件占用的空间（字节）
+    pub file_bytes: u64,
+    pub crawl_runs: i64,
+    pub api_calls: i64,
+    /// 翻译缓存、PDF 文本缓存和过期的通用缓存条目
+    pub cache_entries: i64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PrunedPaper {
+    pub id: i64,
+    pub title: String,
+    pub created_at: Option<String>,
+    #[serde(skip)]
+    pub pdf_path: Option<String>,
+}
+
+/// 解析保留期，如 `90d`、`12w`、`6m`（按 30 天计）；不带单位时按天
+pub fn parse_age(value: &str) -> Result<u32> {
+    let value = value.trim();
+    let (number, unit) = match value.char_indices().last() {
+        Some((pos, c)) if c.is_ascii_alphabetic() => (&value[..pos], c.to_ascii_lowercase()),
+        _ => (value, 'd'),
+    };
+    let Ok(number) = number.trim().parse::<u32>() else {
+        bail!("无效的保留期 '{}'，格式如 90d、12w、6m", value);
+    };
+    let days = match unit {
+        'd' => number,
+        'w' => number * 7,
+        'm' => number * 30,
+        _ => bail!("无效的保留期单位 '{}'，可用 d（天）、w（周）、m（月）", unit),
+    };
+    if days == 0 {
+        bail!("保留期必须大于 0");
+    }
+    Ok(days)
+}
+
+/// 统计超过 `older_than_days` 天保留期的论文、文件和记录，不做修改
+pub async fn plan(db: &Database, older_than_days: u32) -> Result<PrunePlan> {
+    let pool = db.pool();
+    let cutoff = format!("-{} days", older_than_days);
+
+    let papers = sqlx::query_as::<_, PrunedPaper>(
+        r#"SELECT id, title, created_at, pdf_path
+           FROM papers
+           WHERE CASE WHEN deleted_at IS NULL
+                      THEN created_at < datetime('now', ?1) AND COALESCE(starred, 0) = 0
+                      ELSE deleted_at < datetime('now', ?1)
+                 END
+           ORDER BY created_at, id"#
+    )
+    .bind(&cutoff)
+    .fetch_all(pool)
+    .await?;
+
+    let dirs = dirs::get();
+    let pruned: HashSet<i64> = papers.iter().map(|p| p.id).collect();
+    // 只删除数据目录内的PDF
+    let mut files: Vec<PathBuf> = papers
+        .iter()
+        .filter_map(|paper| paper.pdf_path.as_deref().filter(|p| is_safe_relative_path(p)))
+        .map(|stored| dirs.data_dir.join(stored))
+        .collect();
+    // 表格 CSV 命名为 <论文ID>_table_<n>.csv
+    if let Ok(entries) = std::fs::read_dir(dirs.tables_dir()) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let paper_id = name.split_once("_table_").and_then(|(id, _)| id.parse::<i64>().ok());
+            if name.ends_with(".csv") && paper_id.is_some_and(|id| pruned.contains(&id)) {
+                files.push(entry.path());
+            }
+        }
+    }
+
+    let mut kept_images = HashSet::new();
+    let mut pruned_images = HashSet::new();
+    for (paper_id, key) in sqlx::query_as::<_, (i64, String)>("SELECT paper_id, image_key FROM paper_images")
+        .fetch_all(pool)
+        .await?
+    {
+        if pruned.contains(&paper_id) {
+            pruned_images.insert(key);
+        } else {
+            kept_images.insert(key);
+        }
+    }
+    let mut images: Vec<String> = pruned_images.difference(&kept_images).cloned().collect();
+    images.sort();
+    files.extend(
+        images
+            .iter()
+            .filter(|key| is_store_key(key))
+            .map(|key| dirs.images_dir().join(key)),
+    );
+    files.retain(|path| path.exists());
+    let file_bytes = files.iter().map(|path| disk_usage(path)).sum();
+
+    let crawl_runs = count_older(pool, "crawl_runs", &cutoff).await?;
+    let api_calls = count_older(pool, "api_calls", &cutoff).await?;
+    let cache_entries = count_older(pool, "translations_cache", &cutoff).await?
+        + count_older(pool, "pdf_texts", &cutoff).await?
+        + sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM generic_cache WHERE expires_at <= datetime('now')")
+            .fetch_one(pool)
+            .await?;
+
+    Ok(PrunePlan {
+        older_than_days,
+        papers,
+        files,
+        file_bytes,
+        crawl_runs,
+        api_calls,
+        cache_entries,
+    })
+}
+
+/// 按 created_at 清理的表
+const DATED_TABLES: [&str; 4] = ["crawl_runs", "api_calls", "translations_cache", "pdf_texts"];
+
+async fn count_older(pool: &sqlx::SqlitePool, table: &str, cutoff: &str) -> Result<i64> {
+    let sql = format!("SELECT COUNT(*) FROM {} WHERE created_at < datetime('now', ?)", table);
+    Ok(sqlx::query_scalar(&sql).bind(cutoff).fetch_one(pool).await?)
+}
+
+/// 按计划删除文件和数据库记录，最后压缩数据库；返回数据库文件减少的字节数
+pub async fn execute(db: &Database, plan: &PrunePlan, database_file: &std::path::Path) -> Result<u64> {
+    let before = disk_usage(database_file);
+    let cutoff = format!("-{} days", plan.older_than_days);
+
+    let ids: Vec<i64> = plan.papers.iter().map(|p| p.id).collect();
+    db.delete_papers(&ids).await?;
+
+    for table in DATED_TABLES {
+        sqlx::query(&format!("DELETE FROM {} WHERE created_at < datetime('now', ?)", table))
+            .bind(&cutoff)
+            .execute(db.pool())
+            .await?;
+    }
+    sqlx::query("DELETE FROM generic_cache WHERE expires_at <= datetime('now')")
+        .execute(db.pool())
+        .await?;
+
+    // 数据库记录删除后再删文件，中途失败时不会留下指向不存在文件的记录
+    for path in &plan.files {
+        if let Err(e) = std::fs::remove_file(path) {
+            warn!("删除文件失败 {}: {}", path.display(), e);
+        }
+    }
+
+    info!("正在压缩数据库...");
+    sqlx::query("VACUUM").execute(db.pool()).await?;
+    // WAL 模式下 VACUUM 的结果先写入 -wal 文件，检查点之后数据库文件才会缩小
+    sqlx::query("PRAGMA wal_checkpoint(TRUNCATE)").execute(db.pool()).await?;
+    Ok(before.saturating_sub(disk_usage(database_file)))
+}
@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use crate::config::{dirs, AppConfig};
+use crate::storage::Database;
+use crate::utils::paths::is_safe_relative_path;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "database/papers.db";
+const CONFIG_PREFIX: &str = "config/";
+const DATA_PREFIX: &str = "data/";
+
+/// 归档清单，记录导出时的基本信息
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StateManifest {
+    pub version: String,
+    pub created_at: String,
+    pub include_pdfs: bool,
+    pub pdf_count: usize,
+}
+
+/// 导出完整应用状态（配置、关键词、数据库快照，可选PDF）为 tar.gz 归档
+pub async fn export_state(output: &str, include_pdfs: bool) -> Result<StateManifest> {
+    let app_config = AppConfig::load()?;
+
+    // 使用 VACUUM INTO 生成一致的数据库快照，避免直接复制正在使用的文件
+    let snapshot = std::env::temp_dir().join(format!(
+        "bsxbot_snapshot_{}.db",
+        chrono::Local::now().format("%Y%m%d%H%M%S%f")
+    ));
+    let db = Database::open(&app_config.storage).await?;
+    db.snapshot_to(&snapshot.to_string_lossy()).await?;
+
+    let result = write_archive(output, &snapshot, include_pdfs);
+    if let Err(e) = std::fs::remove_file(&snapshot) {
+        warn!("删除临时快照失败 {}: {}", snapshot.display(), e);
+    }
+    result
+}
+
+fn write_archive(output: &str, snapshot: &Path, include_pdfs: bool) -> Result<StateManifest> {
+    if let Some(parent) = Path::new(output).parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let file = File::create(output).with_context(|| format!("无法创建归档文件: {}", output))?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let dirs = dirs::get();
+    let pdfs = if include_pdfs { collect_pdfs(&dirs.papers_dir())? } else { Vec::new() };
+    let manifest = StateManifest {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+        include_pdfs,
+        pdf_count: pdfs.len(),
+    };
+
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest_json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+
+    // 配置文件需先于数据库写入，导入时据此确定数据库位置
+    // 归档内使用与平台无关的逻辑路径 config/... 和 data/...
+    for config_file in [dirs.settings_file(), dirs.keywords_file()] {
+        if config_file.exists() {
+            let name = archive_name(CONFIG_PREFIX, &config_file);
+            builder.append_path_with_name(&config_file, &name)?;
+            info!("已归档: {}", name);
+        } else {
+            warn!("配置文件不存在，跳过: {}", config_file.display());
+        }
+    }
+
+    builder.append_path_with_name(snapshot, DATABASE_ENTRY)?;
+    info!("已归档数据库快照");
+
+    for pdf in &pdfs {
+        let name = archive_name(&format!("{}papers/", DATA_PREFIX), pdf);
+        builder.append_path_with_name(pdf, name)?;
+    }
+    if include_pdfs {
+        info!("已归档 {} 个PDF文件", pdfs.len());
+    }
+
+    builder.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+fn archive_name(prefix: &str, path: &Path) -> String {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{}{}", prefix, file_name)
+}
+
+fn collect_pdfs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut pdfs = Vec::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(pdfs),
+    };
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().map(|e| e == "pdf").unwrap_or(false) {
+            pdfs.push(path);
+        }
+    }
+    pdfs.sort();
+    Ok(pdfs)
+}
+
+/// 从归档恢复应用状态；配置和数据库已存在时需要 `force`，
+/// 没有 `force` 时数据目录中已存在的文件（如PDF）保留不动，不被归档中的同名文件覆盖
+pub fn import_state(archive: &str, force: bool) -> Result<StateManifest> {
+    if !force {
+        let app_config = AppConfig::load()?;
+        let dirs = dirs::get();
+        let existing: Vec<String> = [
+            dirs.settings_file(),
+            dirs.keywords_file(),
+            app_config.storage.database_file(),
+        ]
+        .iter()
+        .filter(|p| p.exists())
+        .map(|p| p.display().to_string())
+        .collect();
+        if !existing.is_empty() {
+            anyhow::bail!("以下文件已存在，使用 --force 覆盖: {}", existing.join(", "));
+        }
+    }
+
+    let file = File::open(archive).with_context(|| format!("无法打开归档文件: {}", archive))?;
+    let mut tar = tar::Archive::new(GzDecoder::new(file));
+    let mut manifest: Option<StateManifest> = None;
+    let mut kept = 0;
+
+    for entry in tar.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().replace('\\', "/");
+
+        match path.as_str() {
+            MANIFEST_NAME => {
+                manifest = Some(serde_json::from_reader(&mut entry)?);
+            }
+            DATABASE_ENTRY => {
+                // 数据库恢复到导入后配置指定的位置
+                let db_path = AppConfig::load()?.storage.database_file();
+                if let Some(parent) = db_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                // 原数据库残留的 WAL 文件会被应用到恢复的数据库上，需先删除
+                for suffix in ["-wal", "-shm"] {
+                    let mut sidecar = db_path.clone().into_os_string();
+                    sidecar.push(suffix);
+                    let sidecar = PathBuf::from(sidecar);
+                    if sidecar.exists() {
+                        std::fs::remove_file(&sidecar)
+                            .with_context(|| format!("无法删除 {}", sidecar.display()))?;
+                    }
+                }
+                entry.unpack(&db_path)?;
+                info!("已恢复数据库: {}", db_path.display());
+            }
+            _ => {
+                let target = match (path.strip_prefix(CONFIG_PREFIX), path.strip_prefix(DATA_PREFIX)) {
+                    (Some(rest), _) if is_safe_relative_path(rest) => dirs::get().config_dir.join(rest),
+                    (_, Some(rest)) if is_safe_relative_path(rest) => dirs::get().data_dir.join(rest),
+                    _ => {
+                        warn!("跳过不安全或未知的归档路径: {}", path);
+                        continue;
+                    }
+                };
+                if !force && target.exists() {
+                    kept += 1;
+                    continue;
+                }
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                entry.unpack(&target)?;
+                info!("已恢复: {}", target.display());
+            }
+        }
+    }
+
+    if kept > 0 {
+        info!("{} 个文件已存在，保留本地版本（使用 --force 覆盖）", kept);
+    }
+    manifest.ok_or_else(|| anyhow::anyhow!("归档中缺少 {}，不是有效的状态归档", MANIFEST_NAME))
+}
@@ -0,0 +1,295 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+
+use crate::config::dirs;
+use crate::storage::Database;
+
+/// `stats` 命令的统计结果
+#[derive(Debug, Serialize)]
+pub struct DbStats {
+    /// 不含回收站中的论文，以下各项同
+    pub total_papers: i64,
+    /// 最近若干天每天入库的论文数，从旧到新，没有论文的日期为 0
+    pub papers_per_day: Vec<Count>,
+    pub papers_per_source: Vec<Count>,
+    /// 没有订阅记录的论文（如手动导入）不计入
+    pub papers_per_subscription: Vec<Count>,
+    pub translation: TranslationStats,
+    pub parsing: ParseStats,
+    pub starred: i64,
+    pub read: i64,
+    pub tags: i64,
+    /// 回收站中的论文数
+    pub deleted: i64,
+    /// 最近若干天的爬取
+    pub crawls: CrawlStats,
+    /// 最近若干天的 LLM API 调用
+    pub api: ApiStats,
+    pub storage: StorageStats,
+}
+
+/// 按日期、来源、订阅或状态分类的计数
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Count {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TranslationStats {
+    /// 各翻译状态的论文数
+    pub statuses: Vec<Count>,
+    /// 已有译文（已翻译或原文即目标语言）的论文占比，0~1
+    pub coverage: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ParseStats {
+    /// 已下载PDF的论文数
+    pub downloaded: i64,
+    /// 已解析的论文数
+    pub parsed: i64,
+    /// PDF 无法打开的论文数
+    pub failed: i64,
+    /// 解析失败率：failed / downloaded，0~1
+    pub failure_rate: f64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct CrawlStats {
+    /// 爬取记录数（每个订阅、来源各一条）
+    pub runs: i64,
+    /// 因搜索失败等原因中断的次数
+    pub failed_runs: i64,
+    pub fetched: i64,
+    pub saved: i64,
+    /// 翻译、下载或解析失败的次数
+    pub errors: i64,
+    pub duration_ms: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiStats {
+    /// 请求次数（含重试）
+    pub calls: i64,
+    pub failed: i64,
+    pub prompt_tokens: i64,
+    pub completion_tokens: i64,
+    /// 成功请求的平均耗时（毫秒）
+    pub avg_duration_ms: f64,
+    /// 按用途统计的请求数
+    pub per_purpose: Vec<Count>,
+}
+
+/// 占用空间（字节）
+#[derive(Debug, Serialize)]
+pub struct StorageStats {
+    pub database: u64,
+    pub papers: u64,
+    pub images: u64,
+    pub reports: u64,
+    pub backups: u64,
+}
+
+/// 统计论文库，`days` 为按天统计的天数（含今天）
+pub async fn collect(db: &Database, database_file: &Path, backup_dir: &Path, days: i64) -> Result<DbStats> {
+    let pool = db.pool();
+    let (total_papers, deleted) = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT COALESCE(SUM(deleted_at IS NULL), 0), COALESCE(SUM(deleted_at IS NOT NULL), 0) FROM papers"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    // created_at 为 UTC，按本地日期统计
+    let today = chrono::Local::now().date_naive();
+    let first_day = today - chrono::Duration::days(days.max(1) - 1);
+    let counts: std::collections::HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>(
+        r#"SELECT date(created_at, 'localtime') AS day, COUNT(*)
+           FROM papers
+           WHERE date(created_at, 'localtime') >= ? AND deleted_at IS NULL
+           GROUP BY day"#
+    )
+    .bind(first_day.format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .collect();
+    let papers_per_day = first_day
+        .iter_days()
+        .take_while(|day| *day <= today)
+        .map(|day| {
+            let name = day.format("%Y-%m-%d").to_string();
+            let count = counts.get(&name).copied().unwrap_or(0);
+            Count { name, count }
+        })
+        .collect();
+
+    let papers_per_source = sqlx::query_as::<_, Count>(
+        "SELECT source AS name, COUNT(*) AS count FROM papers WHERE deleted_at IS NULL GROUP BY source ORDER BY count DESC, source"
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let papers_per_subscription = sqlx::query_as::<_, Count>(
+        r#"SELECT COALESCE(s.name, r.subscription) AS name, COUNT(*) AS count
+           FROM paper_relevance r
+           JOIN papers p ON p.id = r.paper_id
+           LEFT JOIN subscriptions s ON s.id = r.subscription_id
+           WHERE p.deleted_at IS NULL
+           GROUP BY 1
+           ORDER BY count DESC, name"#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let statuses: Vec<Count> = db
+        .count_translation_statuses()
+        .await?
+        .into_iter()
+        .map(|(name, count)| Count { name, count })
+        .collect();
+    let translated: i64 = statuses
+        .iter()
+        .filter(|c| c.name == "done" || c.name == "skipped_same_language")
+        .map(|c| c.count)
+        .sum();
+
+    let (downloaded, parsed, failed) = sqlx::query_as::<_, (i64, i64, i64)>(
+        r#"SELECT COALESCE(SUM(pdf_path IS NOT NULL), 0),
+                  COALESCE(SUM(processed = 1), 0),
+                  COALESCE(SUM(pdf_error IS NOT NULL), 0)
+           FROM papers
+           WHERE deleted_at IS NULL"#
+    )
+    .fetch_one(pool)
+    .await?;
+
+    let (starred, read) = sqlx::query_as::<_, (i64, i64)>(
+        "SELECT COALESCE(SUM(starred = 1), 0), COALESCE(SUM(read_at IS NOT NULL), 0) FROM papers WHERE deleted_at IS NULL"
+    )
+    .fetch_one(pool)
+    .await?;
+    let tags: i64 = sqlx::query_scalar("SELECT COUNT(DISTINCT tag_id) FROM paper_tags").fetch_one(pool).await?;
+
+    let since = first_day.format("%Y-%m-%d").to_string();
+    let crawls = sqlx::query_as::<_, CrawlStats>(
+        r#"SELECT COUNT(*) AS runs,
+                  COALESCE(SUM(error IS NOT NULL), 0) AS failed_runs,
+                  COALESCE(SUM(fetched), 0) AS fetched,
+                  COALESCE(SUM(saved), 0) AS saved,
+                  COALESCE(SUM(errors), 0) AS errors,
+                  COALESCE(SUM(duration_ms), 0) AS duration_ms
+           FROM crawl_runs
+           WHERE date(created_at, 'localtime') >= ?"#
+    )
+    .bind(&since)
+    .fetch_one(pool)
+    .await?;
+
+    let (calls, failed_calls, prompt_tokens, completion_tokens, avg_duration_ms) =
+        sqlx::query_as::<_, (i64, i64, i64, i64, f64)>(
+            r#"SELECT COUNT(*),
+                      COALESCE(SUM(success = 0), 0),
+                      COALESCE(SUM(prompt_tokens), 0),
+                      COALESCE(SUM(completion_tokens), 0),
+                      COALESCE(AVG(CASE WHEN success = 1 THEN duration_ms END), 0.0)
+               FROM api_calls
+               WHERE date(created_at, 'localtime') >= ?"#
+        )
+        .bind(&since)
+        .fetch_one(pool)
+        .await?;
+    let per_purpose = sqlx::query_as::<_, Count>(
+        r#"SELECT purpose AS name, COUNT(*) AS count
+           FROM api_calls
+           WHERE date(created_at, 'localtime') >= ?
+           GROUP BY purpose
+           ORDER BY count DESC, purpose"#
+    )
+    .bind(&since)
+    .fetch_all(pool)
+    .await?;
+
+    let dirs = dirs::get();
+    Ok(DbStats {
+        total_papers,
+        papers_per_day,
+        papers_per_source,
+        papers_per_subscription,
+        translation: TranslationStats {
+            statuses,
+            coverage: ratio(translated, total_papers),
+        },
+        parsing: ParseStats {
+            downloaded,
+            parsed,
+            failed,
+            failure_rate: ratio(failed, downloaded),
+        },
+        starred,
+        read,
+        tags,
+        deleted,
+        crawls,
+        api: ApiStats {
+            calls,
+            failed: failed_calls,
+            prompt_tokens,
+            completion_tokens,
+            avg_duration_ms,
+            per_purpose,
+        },
+        storage: StorageStats {
+            database: disk_usage(database_file) + disk_usage(&wal_file(database_file)),
+            papers: disk_usage(&dirs.papers_dir()),
+            images: disk_usage(&dirs.images_dir()),
+            reports: disk_usage(&dirs.reports_dir()),
+            backups: disk_usage(backup_dir),
+        },
+    })
+}
+
+fn ratio(part: i64, total: i64) -> f64 {
+    if total > 0 {
+        part as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// 数据库的 WAL 文件（`<数据库文件>-wal`），尚未检查点写回的修改保存在其中
+fn wal_file(database_file: &Path) -> std::path::PathBuf {
+    let mut path = database_file.as_os_str().to_owned();
+    path.push("-wal");
+    path.into()
+}
+
+/// 文件或目录（递归）的大小，不存在时为 0
+pub fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| disk_usage(&entry.path())).sum())
+        .unwrap_or(0)
+}
+
+/// 以 B/KB/MB/GB 显示字节数
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::parser::Table;
+use crate::storage::Database;
+
+/// 把论文的表格写为 `<paper_id>_table_<n>.csv`（n 从 1 开始），返回写入的文件
+///
+/// 先删除该论文之前导出的文件，重新解析后表格数量变少时不会留下旧文件。
+pub fn write_paper_tables(dir: &Path, paper_id: i64, tables: &[Table]) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+    let prefix = format!("{}_table_", paper_id);
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with(&prefix) && name.ends_with(".csv") {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+
+    let mut written = Vec::new();
+    for (i, table) in tables.iter().enumerate() {
+        let path = dir.join(format!("{}{}.csv", prefix, i + 1));
+        std::fs::write(&path, to_csv(table)).with_context(|| format!("写入表格失败: {}", path.display()))?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// 导出数据库中已提取的表格，`paper_id` 为空时导出全部论文；返回写入的文件数
+pub async fn export_tables(db: &Database, dir: &Path, paper_id: Option<i64>, min_confidence: f32) -> Result<usize> {
+    let mut count = 0;
+    for (id, tables_json) in db.get_extracted_tables().await? {
+        if paper_id.is_some_and(|p| p != id) {
+            continue;
+        }
+        let mut tables: Vec<Table> = match serde_json::from_str(&tables_json) {
+            Ok(tables) => tables,
+            Err(e) => {
+                warn!("论文 {} 的表格记录无法解析: {}", id, e);
+                continue;
+            }
+        };
+        tables.retain(|t| t.confidence >= min_confidence);
+        count += write_paper_tables(dir, id, &tables)?.len();
+    }
+    Ok(count)
+}
+
+/// 按 RFC 4180 生成 CSV：首行为表头，行长不一时按最长行补齐空单元格
+///
+/// 文件以 UTF-8 BOM 开头，Excel 才能正确显示 ±、× 等非 ASCII 字符；pandas 会自动忽略 BOM。
+pub fn to_csv(table: &Table) -> String {
+    let columns = std::iter::once(&table.headers)
+        .chain(&table.rows)
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0);
+
+    let mut csv = String::from('\u{feff}');
+    for row in std::iter::once(&table.headers).chain(&table.rows) {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| escape_field(row.get(i).map(String::as_str).unwrap_or_default()))
+            .collect();
+        csv.push_str(&cells.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// 按 RFC 4180 转义单元格：含逗号、引号或换行时加引号
+pub fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
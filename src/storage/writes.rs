@@ -0,0 +1,518 @@
+use anyhow::Result;
+use sqlx::SqliteConnection;
+use tracing::info;
+
+use crate::storage::models::{Paper, PaperImage, PaperKeyphrase, PaperReference, SectionCitation, TranslationStatus};
+use crate::storage::{citations, dedup, provenance};
+
+/// 保存论文到数据库，返回论文ID
+///
+/// 已保存过的来源ID更新原记录；新论文与其他来源的已有论文标题相近且作者相符时（见 [`dedup::is_duplicate`]），
+/// 不再新建记录，而是作为该论文的另一个来源保存，并补全原记录缺少的摘要、作者等信息。
+pub async fn save_paper(conn: &mut SqliteConnection, paper: &Paper) -> Result<i64> {
+    let existing = sqlx::query_as::<_, (i64, bool)>(
+        r#"SELECT s.paper_id, (s.source = p.source AND s.source_id = p.source_id)
+           FROM paper_sources s
+           JOIN papers p ON p.id = s.paper_id
+           WHERE s.source = ? AND s.source_id = ?"#
+    )
+    .bind(&paper.source)
+    .bind(&paper.source_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+    if let Some((paper_id, own_source)) = existing {
+        // 论文自身来源的数据覆盖原记录，合并进来的其他来源只补全空缺
+        let sql = if own_source {
+            r#"UPDATE papers SET
+                   title = ?,
+                   authors = ?,
+                   abstract = ?,
+                   pdf_url = ?,
+                   pdf_path = ?
+               WHERE id = ?"#
+        } else {
+            // ?1（标题）不使用，保持与上面相同的绑定顺序
+            r#"UPDATE papers SET
+                   authors = COALESCE(authors, ?2),
+                   abstract = COALESCE(NULLIF(abstract, ''), ?3),
+                   pdf_url = COALESCE(pdf_url, ?4),
+                   pdf_path = COALESCE(pdf_path, ?5)
+               WHERE id = ?6"#
+        };
+        sqlx::query(sql)
+            .bind(&paper.title)
+            .bind(&paper.authors)
+            .bind(&paper.abstract_text)
+            .bind(&paper.pdf_url)
+            .bind(&paper.pdf_path)
+            .bind(paper_id)
+            .execute(&mut *conn)
+            .await?;
+        sqlx::query("UPDATE paper_sources SET pdf_url = ? WHERE source = ? AND source_id = ?")
+            .bind(&paper.pdf_url)
+            .bind(&paper.source)
+            .bind(&paper.source_id)
+            .execute(&mut *conn)
+            .await?;
+        provenance::record(conn, paper_id, provenance::RECORD, &paper.source).await?;
+        return Ok(paper_id);
+    }
+
+    let candidates = sqlx::query_as::<_, (i64, String, Option<String>)>(
+        "SELECT id, title, authors FROM papers WHERE source != ? AND deleted_at IS NULL"
+    )
+    .bind(&paper.source)
+    .fetch_all(&mut *conn)
+    .await?;
+    let duplicate = candidates.into_iter().find(|(_, title, authors)| {
+        dedup::is_duplicate(&paper.title, paper.authors.as_deref(), title, authors.as_deref())
+    });
+
+    let paper_id = match duplicate {
+        Some((paper_id, title, _)) => {
+            sqlx::query(
+                r#"
+                UPDATE papers SET
+                    authors = COALESCE(authors, ?),
+                    abstract = COALESCE(NULLIF(abstract, ''), ?),
+                    publish_date = COALESCE(publish_date, ?),
+                    pdf_url = COALESCE(pdf_url, ?)
+                WHERE id = ?
+                "#,
+            )
+            .bind(&paper.authors)
+            .bind(&paper.abstract_text)
+            .bind(&paper.publish_date)
+            .bind(&paper.pdf_url)
+            .bind(paper_id)
+            .execute(&mut *conn)
+            .await?;
+            info!("{} {} 与论文 {}「{}」重复，已合并", paper.source, paper.source_id, paper_id, title);
+            paper_id
+        }
+        None => {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO papers (title, authors, abstract, publish_date, source, source_id, pdf_url, pdf_path)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&paper.title)
+            .bind(&paper.authors)
+            .bind(&paper.abstract_text)
+            .bind(&paper.publish_date)
+            .bind(&paper.source)
+            .bind(&paper.source_id)
+            .bind(&paper.pdf_url)
+            .bind(&paper.pdf_path)
+            .execute(&mut *conn)
+            .await?;
+            result.last_insert_rowid()
+        }
+    };
+
+    sqlx::query("INSERT INTO paper_sources (source, source_id, paper_id, pdf_url) VALUES (?, ?, ?, ?)")
+        .bind(&paper.source)
+        .bind(&paper.source_id)
+        .bind(paper_id)
+        .bind(&paper.pdf_url)
+        .execute(&mut *conn)
+        .await?;
+    provenance::record(conn, paper_id, provenance::RECORD, &paper.source).await?;
+
+    Ok(paper_id)
+}
+
+/// 更新论文的PDF路径
+pub async fn update_pdf_path(conn: &mut SqliteConnection, source: &str, source_id: &str, pdf_path: &str) -> Result<()> {
+    if !crate::utils::paths::is_safe_relative_path(pdf_path) {
+        anyhow::bail!("拒绝保存不安全的PDF路径: {}", pdf_path);
+    }
+
+    sqlx::query(
+        "UPDATE papers SET pdf_path = ? WHERE id = (SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?)"
+    )
+    .bind(pdf_path)
+    .bind(source)
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 保存提取内容到 extracted_content 表（upsert，不覆盖已生成的要点）
+pub async fn save_extracted_content(
+    conn: &mut SqliteConnection,
+    paper_id: i64,
+    formulas: &str,
+    images: &str,
+    tables: &str,
+    sections: &str,
+    links: &str,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO extracted_content (paper_id, formulas, images, tables, sections, links)
+        VALUES (?, ?, ?, ?, ?, ?)
+        ON CONFLICT(paper_id) DO UPDATE SET
+            formulas = excluded.formulas,
+            images = excluded.images,
+            tables = excluded.tables,
+            sections = excluded.sections,
+            links = excluded.links
+        "#,
+    )
+    .bind(paper_id)
+    .bind(formulas)
+    .bind(images)
+    .bind(tables)
+    .bind(sections)
+    .bind(links)
+    .execute(&mut *conn)
+    .await?;
+    provenance::record(conn, paper_id, provenance::CONTENT, "parser").await?;
+
+    Ok(())
+}
+
+/// 保存论文的代码仓库链接
+pub async fn set_code_urls(conn: &mut SqliteConnection, paper_id: i64, urls: &[String]) -> Result<()> {
+    sqlx::query("UPDATE papers SET code_urls = ? WHERE id = ?")
+        .bind(serde_json::to_string(urls)?)
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// 保存从PDF中识别的出处和资助声明
+pub async fn set_publication_info(conn: &mut SqliteConnection, paper_id: i64, venue: Option<&str>, funding: &[String]) -> Result<()> {
+    sqlx::query("UPDATE papers SET venue = ?, funding = ? WHERE id = ?")
+        .bind(venue)
+        .bind(serde_json::to_string(funding)?)
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// 记录PDF无法打开（损坏、加密）的原因，解析成功后传 None 清除
+pub async fn set_pdf_error(conn: &mut SqliteConnection, paper_id: i64, error: Option<&str>) -> Result<()> {
+    sqlx::query("UPDATE papers SET pdf_error = ? WHERE id = ?")
+        .bind(error)
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// 论文没有摘要时（如来源不提供）用PDF中识别的摘要补上，之后即可翻译；已有摘要不覆盖
+pub async fn fill_missing_abstract(conn: &mut SqliteConnection, paper_id: i64, abstract_text: &str) -> Result<bool> {
+    let result = sqlx::query("UPDATE papers SET abstract = ? WHERE id = ? AND (abstract IS NULL OR TRIM(abstract) = '')")
+        .bind(abstract_text)
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 标记论文已处理
+pub async fn mark_paper_processed(conn: &mut SqliteConnection, source: &str, source_id: &str) -> Result<()> {
+    sqlx::query(
+        "UPDATE papers SET processed = 1 WHERE id = (SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?)"
+    )
+    .bind(source)
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 更新论文的中文翻译
+pub async fn update_translation(
+    conn: &mut SqliteConnection,
+    source: &str,
+    source_id: &str,
+    title_zh: &str,
+    abstract_zh: &str,
+    provider: &str,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE papers SET title_zh = ?, abstract_zh = ?, translation_status = 'done' WHERE id = (SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?)"
+    )
+    .bind(title_zh)
+    .bind(abstract_zh)
+    .bind(source)
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO translation_sources (source, source_id, provider, translated_at)
+        VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(source, source_id) DO UPDATE SET
+            provider = excluded.provider,
+            translated_at = excluded.translated_at
+        "#,
+    )
+    .bind(source)
+    .bind(source_id)
+    .bind(provider)
+    .execute(&mut *conn)
+    .await?;
+    provenance::record_for_source(conn, source, source_id, provenance::TRANSLATION, provider).await?;
+
+    Ok(())
+}
+
+/// 标题和摘要已是目标语言：原文直接作为译文，不调用翻译服务
+pub async fn mark_same_language(conn: &mut SqliteConnection, source: &str, source_id: &str) -> Result<()> {
+    sqlx::query(
+        r#"UPDATE papers
+           SET title_zh = title, abstract_zh = abstract, translation_status = 'skipped_same_language'
+           WHERE id = (SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?)"#
+    )
+    .bind(source)
+    .bind(source_id)
+    .execute(&mut *conn)
+    .await?;
+    provenance::record_for_source(conn, source, source_id, provenance::TRANSLATION, "original").await?;
+
+    Ok(())
+}
+
+/// 更新论文的翻译状态
+pub async fn set_translation_status(
+    conn: &mut SqliteConnection,
+    source: &str,
+    source_id: &str,
+    status: TranslationStatus,
+) -> Result<()> {
+    sqlx::query("UPDATE papers SET translation_status = ? WHERE id = (SELECT paper_id FROM paper_sources WHERE source = ? AND source_id = ?)")
+        .bind(status.as_str())
+        .bind(source)
+        .bind(source_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(())
+}
+
+/// 替换论文的图片引用
+pub async fn save_paper_images(conn: &mut SqliteConnection, paper_id: i64, images: &[PaperImage]) -> Result<()> {
+    sqlx::query("DELETE FROM paper_images WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+    for image in images {
+        sqlx::query(
+            r#"
+            INSERT INTO paper_images (paper_id, position, image_key, page, width, height, format)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(image.paper_id)
+        .bind(image.position)
+        .bind(&image.image_key)
+        .bind(image.page)
+        .bind(image.width)
+        .bind(image.height)
+        .bind(&image.format)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// 替换论文的参考文献列表，同时更新论文的引用记录
+pub async fn save_references(conn: &mut SqliteConnection, paper_id: i64, references: &[PaperReference]) -> Result<()> {
+    sqlx::query(r#"DELETE FROM "references" WHERE paper_id = ?"#)
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+    for reference in references {
+        sqlx::query(
+            r#"
+            INSERT INTO "references"
+                (paper_id, position, label, raw, authors, year, title, venue, arxiv_id, doi)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(reference.paper_id)
+        .bind(reference.position)
+        .bind(&reference.label)
+        .bind(&reference.raw)
+        .bind(&reference.authors)
+        .bind(reference.year)
+        .bind(&reference.title)
+        .bind(&reference.venue)
+        .bind(&reference.arxiv_id)
+        .bind(&reference.doi)
+        .execute(&mut *conn)
+        .await?;
+    }
+    citations::replace_from_references(conn, paper_id, references).await?;
+
+    Ok(())
+}
+
+/// 替换论文的章节引用列表
+pub async fn save_section_citations(conn: &mut SqliteConnection, paper_id: i64, citations: &[SectionCitation]) -> Result<()> {
+    sqlx::query("DELETE FROM section_citations WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+    for citation in citations {
+        sqlx::query(
+            r#"
+            INSERT INTO section_citations
+                (paper_id, section_position, heading, reference_position, mentions)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(citation.paper_id)
+        .bind(citation.section_position)
+        .bind(&citation.heading)
+        .bind(citation.reference_position)
+        .bind(citation.mentions)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// 替换论文的关键短语
+pub async fn save_keyphrases(conn: &mut SqliteConnection, paper_id: i64, keyphrases: &[PaperKeyphrase]) -> Result<()> {
+    sqlx::query("DELETE FROM paper_keyphrases WHERE paper_id = ?")
+        .bind(paper_id)
+        .execute(&mut *conn)
+        .await?;
+    for keyphrase in keyphrases {
+        sqlx::query(
+            r#"
+            INSERT INTO paper_keyphrases (paper_id, position, phrase, score, occurrences)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(keyphrase.paper_id)
+        .bind(keyphrase.position)
+        .bind(&keyphrase.phrase)
+        .bind(keyphrase.score)
+        .bind(keyphrase.occurrences)
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// 为论文添加标签（不存在时创建），返回是否新增；`source` 为 manual 或 subscription
+pub async fn add_tag(conn: &mut SqliteConnection, paper_id: i64, name: &str, source: &str) -> Result<bool> {
+    sqlx::query("INSERT OR IGNORE INTO tags (name) VALUES (?)")
+        .bind(name)
+        .execute(&mut *conn)
+        .await?;
+    let result = sqlx::query(
+        "INSERT OR IGNORE INTO paper_tags (paper_id, tag_id, source) SELECT ?, id, ? FROM tags WHERE name = ?"
+    )
+    .bind(paper_id)
+    .bind(source)
+    .bind(name)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// 保存 LLM 生成的论文要点（JSON 数组），`provider` 为产出要点的服务商/模型
+pub async fn save_key_points(conn: &mut SqliteConnection, paper_id: i64, key_points: &[String], provider: &str) -> Result<()> {
+    let json = serde_json::to_string(key_points)?;
+    sqlx::query(
+        r#"
+        INSERT INTO extracted_content (paper_id, key_points)
+        VALUES (?, ?)
+        ON CONFLICT(paper_id) DO UPDATE SET key_points = excluded.key_points
+        "#,
+    )
+    .bind(paper_id)
+    .bind(json)
+    .execute(&mut *conn)
+    .await?;
+    provenance::record(conn, paper_id, provenance::KEY_POINTS, provider).await?;
+
+    Ok(())
+}
+
+/// 保存论文与一个订阅的相关度（关键词评分及其依据，和可选的 LLM 标签），同一订阅重复保存时覆盖
+pub async fn save_relevance(
+    conn: &mut SqliteConnection,
+    paper_id: i64,
+    subscription: &str,
+    score: f32,
+    reasons: &[String],
+    label: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO paper_relevance (paper_id, subscription, subscription_id, score, reasons, label)
+        VALUES (?, ?, (SELECT id FROM subscriptions WHERE name = ?), ?, ?, ?)
+        ON CONFLICT(paper_id, subscription) DO UPDATE SET
+            subscription_id = excluded.subscription_id,
+            score = excluded.score,
+            reasons = excluded.reasons,
+            label = excluded.label
+        "#,
+    )
+    .bind(paper_id)
+    .bind(subscription)
+    .bind(subscription)
+    .bind(score)
+    .bind(serde_json::to_string(reasons)?)
+    .bind(label)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
+/// 保存回溯爬取进度（每页处理完后调用）
+pub async fn save_backfill_progress(
+    conn: &mut SqliteConnection,
+    subscription: &str,
+    source: &str,
+    month: &str,
+    fetched: i64,
+    total_matched: Option<i64>,
+    completed: bool,
+) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO backfill_progress (subscription, source, month, fetched, total_matched, completed, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+        ON CONFLICT(subscription, source, month) DO UPDATE SET
+            fetched = excluded.fetched,
+            total_matched = excluded.total_matched,
+            completed = excluded.completed,
+            updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(subscription)
+    .bind(source)
+    .bind(month)
+    .bind(fetched)
+    .bind(total_matched)
+    .bind(completed)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,176 @@
+/// 切分后的文本片段
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunk {
+    pub text: String,
+    /// 该片段后紧跟段落分隔，拼接译文时保留换行
+    pub ends_paragraph: bool,
+}
+
+/// 粗略估算 token 数：CJK 字符按 1 个计，其他字符约 4 个计 1 个
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut cjk = 0;
+    let mut other = 0usize;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + other.div_ceil(4)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x3000..=0x303F | 0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xFF00..=0xFFEF)
+}
+
+/// 按段落和句子边界把文本切分为不超过 `max_tokens` 的片段
+///
+/// 单个句子超出上限时再按空白切分，仍然超出的单词原样保留为一个片段。
+pub fn split_text(text: &str, max_tokens: usize) -> Vec<Chunk> {
+    let max_tokens = max_tokens.max(1);
+    let mut chunks = Vec::new();
+
+    for paragraph in text.split('\n').map(str::trim).filter(|p| !p.is_empty()) {
+        let mut current = String::new();
+        for piece in split_sentences(paragraph)
+            .into_iter()
+            .flat_map(|s| split_oversized(s, max_tokens))
+        {
+            if !current.is_empty() && estimate_tokens(&current) + estimate_tokens(&piece) + 1 > max_tokens {
+                chunks.push(Chunk { text: std::mem::take(&mut current), ends_paragraph: false });
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(&piece);
+        }
+        if !current.is_empty() {
+            chunks.push(Chunk { text: current, ends_paragraph: true });
+        }
+    }
+
+    chunks
+}
+
+/// 按句末标点切分句子；小数点、缩写后没有空白时不会切开
+fn split_sentences(paragraph: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = paragraph.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        let boundary = match c {
+            '。' | '！' | '？' | '；' => true,
+            '.' | '!' | '?' => chars.peek().map(|&(_, next)| next.is_whitespace()).unwrap_or(false),
+            _ => false,
+        };
+        if boundary {
+            let sentence = paragraph[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+
+    let rest = paragraph[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// 超长句子按空白切分为不超过上限的若干段
+fn split_oversized(sentence: &str, max_tokens: usize) -> Vec<String> {
+    if estimate_tokens(sentence) <= max_tokens {
+        return vec![sentence.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in sentence.split_whitespace() {
+        if !current.is_empty() && estimate_tokens(&current) + estimate_tokens(word) + 1 > max_tokens {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// 拼接各片段的译文：段落之间换行，段内按目标语言决定是否加空格
+pub fn join_translations(chunks: &[Chunk], translations: &[String], separator: &str) -> String {
+    let mut result = String::new();
+    for (chunk, translated) in chunks.iter().zip(translations) {
+        result.push_str(translated.trim());
+        if chunk.ends_paragraph {
+            result.push('\n');
+        } else {
+            result.push_str(separator);
+        }
+    }
+    result.trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_at_sentence_boundaries() {
+        let text = "First sentence here. Second one follows! Is this third? Version 2.5 stays whole.";
+        let chunks = split_text(text, 8);
+        let texts: Vec<&str> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            ["First sentence here.", "Second one follows!", "Is this third?", "Version 2.5 stays whole."]
+        );
+        assert!(chunks.iter().all(|c| estimate_tokens(&c.text) <= 8));
+        assert!(chunks.last().unwrap().ends_paragraph);
+        assert!(!chunks[0].ends_paragraph);
+    }
+
+    #[test]
+    fn keeps_sentences_together_under_limit() {
+        let chunks = split_text("One. Two.\n\nThree.", 100);
+        assert_eq!(
+            chunks,
+            [
+                Chunk { text: "One. Two.".to_string(), ends_paragraph: true },
+                Chunk { text: "Three.".to_string(), ends_paragraph: true },
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_oversized_sentence_on_whitespace() {
+        let sentence = "word ".repeat(40).trim_end().to_string() + ".";
+        let chunks = split_text(&sentence, 10);
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| estimate_tokens(&c.text) <= 10));
+        let rejoined: Vec<&str> = chunks.iter().flat_map(|c| c.text.split_whitespace()).collect();
+        assert_eq!(rejoined.join(" "), sentence);
+    }
+
+    #[test]
+    fn splits_cjk_text_on_full_width_punctuation() {
+        assert_eq!(estimate_tokens("深度学习"), 4);
+        let chunks = split_text("我们提出一种新方法。实验表明效果显著！结论如下；未来工作？", 10);
+        let texts: Vec<&str> = chunks.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["我们提出一种新方法。", "实验表明效果显著！", "结论如下；", "未来工作？"]);
+    }
+
+    #[test]
+    fn joins_translations_per_paragraph() {
+        let chunks = split_text("A. B.\nC.", 1);
+        let translations: Vec<String> = ["甲。", "乙。", "丙。"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(join_translations(&chunks, &translations, ""), "甲。乙。\n丙。");
+    }
+}
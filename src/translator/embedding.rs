@@ -0,0 +1,125 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::{is_retryable, parse_retry_after, retry_delay, Endpoint, HttpStatusError, Provider, Translator, MAX_ATTEMPTS};
+
+/// 嵌入请求体，OpenAI 兼容接口和 Ollama /api/embed 格式相同
+#[derive(Serialize)]
+struct EmbeddingRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+/// OpenAI 兼容接口的嵌入响应
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+/// Ollama /api/embed 响应
+#[derive(Deserialize)]
+struct OllamaEmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+impl Translator {
+    /// 配置的嵌入模型，未配置模型或主服务商时返回 None
+    pub fn embedding_model(&self) -> Option<&str> {
+        let model = self.config.embedding_model.trim();
+        (!model.is_empty() && self.is_configured()).then_some(model)
+    }
+
+    /// 批量生成文本向量，只使用主服务商，返回顺序与输入一致
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let Some(model) = self.embedding_model() else {
+            bail!("未配置 translator.embedding_model");
+        };
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let endpoint = &self.endpoints[0];
+        let mut last_error = None;
+        for attempt in 0..MAX_ATTEMPTS {
+            endpoint.rate_limiter.acquire().await;
+            match self.do_embed_request(endpoint, model, texts).await {
+                Ok(vectors) if vectors.len() == texts.len() => return Ok(vectors),
+                Ok(vectors) => bail!("嵌入接口返回 {} 个向量，请求了 {} 条文本", vectors.len(), texts.len()),
+                Err(e) => {
+                    warn!("[{}] 嵌入请求失败 (尝试 {}/{}): {}", endpoint.name, attempt + 1, MAX_ATTEMPTS, e);
+                    if !is_retryable(&e) {
+                        return Err(e);
+                    }
+                    if attempt + 1 < MAX_ATTEMPTS {
+                        let delay = retry_delay(&e, attempt);
+                        info!("嵌入请求重试 ({}/{})，等待 {}ms...", attempt + 2, MAX_ATTEMPTS, delay.as_millis());
+                        tokio::time::sleep(delay).await;
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("嵌入请求失败")))
+    }
+
+    async fn do_embed_request(&self, endpoint: &Endpoint, model: &str, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let body = EmbeddingRequest { model, input: texts };
+        let request = match endpoint.provider {
+            Provider::OpenAiCompatible => self
+                .client
+                .post(embeddings_url(&endpoint.api_url))
+                .header("Authorization", format!("Bearer {}", endpoint.api_key)),
+            Provider::Ollama => self
+                .client
+                .post(format!("{}/api/embed", endpoint.ollama_host.trim_end_matches('/'))),
+        };
+        let response = request.json(&body).send().await.context("发送嵌入请求失败")?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = parse_retry_after(response.headers());
+            let body = response.text().await.unwrap_or_default();
+            return Err(HttpStatusError { status, body, retry_after }.into());
+        }
+
+        match endpoint.provider {
+            Provider::OpenAiCompatible => {
+                let mut data = response.json::<EmbeddingResponse>().await.context("解析嵌入响应失败")?.data;
+                data.sort_by_key(|d| d.index);
+                Ok(data.into_iter().map(|d| d.embedding).collect())
+            }
+            Provider::Ollama => {
+                let parsed: OllamaEmbedResponse = response.json().await.context("解析 Ollama 嵌入响应失败")?;
+                Ok(parsed.embeddings)
+            }
+        }
+    }
+}
+
+/// 由 chat/completions 地址得到同一服务的 embeddings 地址
+fn embeddings_url(api_url: &str) -> String {
+    let base = api_url.trim_end_matches('/');
+    format!("{}/embeddings", base.strip_suffix("/chat/completions").unwrap_or(base))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embeddings_url_replaces_chat_completions() {
+        assert_eq!(
+            embeddings_url("https://api.openai.com/v1/chat/completions"),
+            "https://api.openai.com/v1/embeddings"
+        );
+        assert_eq!(embeddings_url("http://localhost:8000/v1/"), "http://localhost:8000/v1/embeddings");
+    }
+}
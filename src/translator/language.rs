@@ -0,0 +1,125 @@
+/// 目标语言，由 TranslatorConfig.target_language 解析而来
+#[derive(Debug, Clone)]
+pub struct TargetLanguage {
+    code: String,
+}
+
+impl TargetLanguage {
+    pub fn new(code: &str) -> Self {
+        let code = code.trim();
+        Self {
+            code: if code.is_empty() { "zh-CN".to_string() } else { code.to_string() },
+        }
+    }
+
+    /// 主语言子标签，如 "zh-CN" -> "zh"
+    fn primary(&self) -> String {
+        self.code
+            .split(['-', '_'])
+            .next()
+            .unwrap_or("")
+            .to_lowercase()
+    }
+
+    pub fn is_chinese(&self) -> bool {
+        self.primary() == "zh"
+    }
+
+    /// 粗略判断文本是否已经是目标语言，按文字系统统计字母占比
+    ///
+    /// 只能区分文字系统不同的语言；德语、法语等拉丁字母语言无法与英文区分，始终返回 false。
+    pub fn is_written_in(&self, text: &str) -> bool {
+        let Some(detected) = detect_script_language(text) else {
+            return false;
+        };
+        let primary = self.primary();
+        detected == primary || (detected == "latin" && primary == "en")
+    }
+
+    /// 分段译文在段内拼接时使用的分隔符，中日文不加空格
+    pub fn sentence_separator(&self) -> &'static str {
+        match self.primary().as_str() {
+            "zh" | "ja" => "",
+            _ => " ",
+        }
+    }
+
+    /// 用于提示词的语言名称，未知代码原样返回
+    pub fn name(&self) -> String {
+        let name = match self.primary().as_str() {
+            "zh" => {
+                if self.code.to_lowercase().contains("tw") || self.code.to_lowercase().contains("hant") {
+                    "繁体中文"
+                } else {
+                    "中文"
+                }
+            }
+            "ja" => "日语",
+            "ko" => "韩语",
+            "de" => "德语",
+            "fr" => "法语",
+            "es" => "西班牙语",
+            "ru" => "俄语",
+            "pt" => "葡萄牙语",
+            "it" => "意大利语",
+            "en" => "英语",
+            _ => return self.code.clone(),
+        };
+        name.to_string()
+    }
+
+    /// 标题/摘要译文的响应标记
+    pub fn markers(&self) -> (String, String) {
+        if self.is_chinese() {
+            ("[标题翻译]".to_string(), "[摘要翻译]".to_string())
+        } else {
+            ("[TITLE]".to_string(), "[ABSTRACT]".to_string())
+        }
+    }
+
+    /// 术语处理要求，中文保留原有示例
+    pub fn terminology_rule(&self) -> String {
+        if self.is_chinese() {
+            "专业术语保留英文原文（用括号标注），如：卷积神经网络（CNN）".to_string()
+        } else {
+            format!("专业术语使用{}学界通行译法，必要时在括号中保留英文原文", self.name())
+        }
+    }
+}
+
+/// 按文字系统推断语言：返回 "zh"、"ja"、"ko"、"ru" 或 "latin"，字母太少或混杂时返回 None
+fn detect_script_language(text: &str) -> Option<&'static str> {
+    let (mut han, mut kana, mut hangul, mut cyrillic, mut latin) = (0usize, 0usize, 0usize, 0usize, 0usize);
+    for c in text.chars() {
+        match c as u32 {
+            0x4E00..=0x9FFF | 0x3400..=0x4DBF => han += 1,
+            0x3040..=0x30FF => kana += 1,
+            0xAC00..=0xD7AF | 0x1100..=0x11FF => hangul += 1,
+            0x0400..=0x04FF => cyrillic += 1,
+            _ if c.is_ascii_alphabetic() || matches!(c as u32, 0x00C0..=0x024F) => latin += 1,
+            _ => {}
+        }
+    }
+
+    // 拉丁字母按单词长度折算，避免英文字母数量压过汉字
+    let latin_weight = latin / 5;
+    let letters = han + kana + hangul + cyrillic + latin_weight;
+    if letters < 5 {
+        return None;
+    }
+    let dominant = |count: usize| count * 10 >= letters * 7;
+
+    if kana > 0 && dominant(han + kana) {
+        Some("ja")
+    } else if dominant(han) {
+        Some("zh")
+    } else if dominant(hangul) {
+        Some("ko")
+    } else if dominant(cyrillic) {
+        Some("ru")
+    } else if dominant(latin_weight) {
+        Some("latin")
+    } else {
+        None
+    }
+}
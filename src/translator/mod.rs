@@ -2,17 +2,46 @@ use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
-use crate::config::TranslatorConfig;
+use crate::config::{Glossary, TranslatorConfig};
+use crate::crawler::relevance::RelevanceLabel;
+use crate::parser::normalizer;
+use crate::storage::models::ApiCall;
+use crate::storage::Database;
+use crate::utils::hash::sha256_hex;
+
+pub mod chunk;
+mod embedding;
+pub mod language;
+pub mod provider;
+
+pub use language::TargetLanguage;
+pub use provider::{Endpoint, Provider};
 
 /// MiniMax API 请求体
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 struct ChatRequest {
+    /// 调用用途，记录到 api_calls，不发送给服务端
+    #[serde(skip)]
+    purpose: &'static str,
     model: String,
     messages: Vec<ChatMessage>,
     temperature: f32,
+    /// 要求模型输出 JSON 对象（OpenAI 兼容接口的 response_format）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<ResponseFormat>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Copy)]
+struct ResponseFormat {
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+impl ResponseFormat {
+    const JSON_OBJECT: ResponseFormat = ResponseFormat { kind: "json_object" };
+}
+
+#[derive(Serialize, Clone)]
 struct ChatMessage {
     role: String,
     content: String,
@@ -22,6 +51,14 @@ struct ChatMessage {
 #[derive(Deserialize)]
 struct ChatResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Deserialize)]
+struct Usage {
+    prompt_tokens: Option<i64>,
+    completion_tokens: Option<i64>,
 }
 
 #[derive(Deserialize)]
@@ -34,11 +71,98 @@ struct ResponseMessage {
     content: String,
 }
 
+/// Ollama /api/chat 请求体
+#[derive(Serialize)]
+struct OllamaChatRequest<'a> {
+    model: &'a str,
+    messages: &'a [ChatMessage],
+    stream: bool,
+    options: OllamaOptions,
+    /// Ollama 的 JSON 输出模式
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+struct OllamaOptions {
+    temperature: f32,
+}
+
+/// Ollama /api/chat 响应体
+#[derive(Deserialize)]
+struct OllamaChatResponse {
+    message: ResponseMessage,
+    /// 提示词 token 数
+    #[serde(default)]
+    prompt_eval_count: Option<i64>,
+    /// 输出 token 数
+    #[serde(default)]
+    eval_count: Option<i64>,
+}
+
+/// 单次请求的输出和服务端报告的 token 用量
+struct Completion {
+    content: String,
+    prompt_tokens: Option<i64>,
+    completion_tokens: Option<i64>,
+}
+
+/// 一次 API 调用的结果及实际产出它的服务商
+struct ApiReply {
+    content: String,
+    provider: String,
+}
+
+/// 服务端返回的非成功状态码，用于判断是否切换备用服务商
+#[derive(Debug)]
+pub struct HttpStatusError {
+    pub status: reqwest::StatusCode,
+    body: String,
+    /// 服务端通过 Retry-After 要求的等待时间
+    retry_after: Option<std::time::Duration>,
+}
+
+impl std::fmt::Display for HttpStatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "API 返回错误 {}: {}", self.status, self.body)
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// 论文标题和摘要的译文
+#[derive(Debug, Clone)]
+pub struct TranslatedPaper {
+    pub title: String,
+    pub abstract_text: String,
+    /// 产出译文的服务商和模型，如 `minimax/MiniMax-M2.5`
+    pub provider: String,
+}
+
+/// LLM 总结的论文要点
+pub struct KeyPoints {
+    pub points: Vec<String>,
+    /// 产出要点的服务商和模型
+    pub provider: String,
+}
+
 pub struct Translator {
     client: reqwest::Client,
     config: TranslatorConfig,
+    /// 主服务商在前，备用服务商按配置顺序排列
+    endpoints: Vec<Endpoint>,
+    language: TargetLanguage,
+    cache: Option<Database>,
+    /// 跳过缓存读取，重新调用 API 并覆盖缓存
+    refresh_cache: bool,
+    glossary: Glossary,
+    /// 记录每次 API 请求的耗时、错误和 token 用量
+    usage_log: Option<Database>,
 }
 
+/// 无法解析译文时附加在原标题后的标记
+pub const FALLBACK_MARKER: &str = "(翻译失败，请重试)";
+
 impl Translator {
     pub fn new(config: TranslatorConfig) -> Self {
         let mut builder = reqwest::Client::builder()
@@ -57,32 +181,137 @@ impl Translator {
         }
 
         let client = builder.build().expect("Failed to create HTTP client");
-        Self { client, config }
+        let language = TargetLanguage::new(&config.target_language);
+
+        let mut endpoints = vec![Endpoint::primary(&config)];
+        for fallback in &config.fallbacks {
+            let endpoint = Endpoint::fallback(fallback, config.max_concurrent);
+            if endpoint.is_configured() {
+                endpoints.push(endpoint);
+            } else {
+                warn!("备用服务商 '{}' 配置不完整，已忽略", fallback.api_provider);
+            }
+        }
+
+        Self {
+            client,
+            config,
+            endpoints,
+            language,
+            cache: None,
+            refresh_cache: false,
+            glossary: Glossary::default(),
+            usage_log: None,
+        }
+    }
+
+    /// 使用术语表约束译法
+    pub fn with_glossary(mut self, glossary: Glossary) -> Self {
+        self.glossary = glossary;
+        self
+    }
+
+    /// 术语要求：目标语言的通用规则，加上原文中出现的术语表条目
+    fn terminology_rule(&self, source: &str) -> String {
+        let rule = self.language.terminology_rule();
+        match self.glossary.prompt_rule(source) {
+            Some(glossary_rule) => format!("{}；{}", rule, glossary_rule),
+            None => rule,
+        }
+    }
+
+    /// 校验译文是否遵守术语表，不符时记录警告
+    fn check_glossary(&self, source: &str, output: &str) {
+        let violations = self.glossary.violations(source, output);
+        if !violations.is_empty() {
+            warn!("译文未遵守术语表: {}", violations.join(", "));
+        }
+    }
+
+    /// 启用 translations_cache：相同 (provider, model, 提示内容) 不再重复调用 API
+    pub fn with_cache(mut self, db: Database) -> Self {
+        self.cache = Some(db);
+        self
     }
 
-    /// 检查 API key 是否已配置
+    /// 把每次 API 请求写入 api_calls 表
+    pub fn with_usage_log(mut self, db: Database) -> Self {
+        self.usage_log = Some(db);
+        self
+    }
+
+    /// 忽略已缓存的响应重新翻译（如更换模型后），新结果仍写入缓存
+    pub fn refresh_cache(mut self) -> Self {
+        self.refresh_cache = true;
+        self
+    }
+
+    /// 允许同时进行的请求数
+    pub fn max_concurrent(&self) -> usize {
+        self.config.max_concurrent.max(1)
+    }
+
+    /// 标题和摘要是否已经是目标语言，是则无需翻译
+    pub fn is_target_language(&self, title: &str, abstract_text: &str) -> bool {
+        self.language.is_written_in(&format!("{}\n{}", title, abstract_text))
+    }
+
+    /// 检查翻译服务是否已配置（本地 Ollama 无需 API key）
     pub fn is_configured(&self) -> bool {
-        !self.config.api_key.is_empty()
-            && self.config.api_key != "your-api-key"
+        self.endpoints[0].is_configured()
     }
 
-    /// 翻译单段文本
-    pub async fn translate_text(&self, text: &str, context: &str) -> Result<String> {
+    /// 翻译单段文本，返回译文和产出它的服务商
+    ///
+    /// 估算 token 数超过 `max_chunk_tokens` 时按句子切分后逐段翻译再拼接，
+    /// 避免超出模型上下文或输出长度被截断。
+    async fn translate_text(&self, text: &str, context: &str) -> Result<ApiReply> {
         if text.trim().is_empty() {
-            return Ok(String::new());
+            return Ok(ApiReply { content: String::new(), provider: String::new() });
         }
+        let text = &normalizer::normalize(text);
 
+        let max_tokens = self.config.max_chunk_tokens;
+        if max_tokens == 0 || chunk::estimate_tokens(text) <= max_tokens {
+            return self.translate_chunk(text, context).await;
+        }
+
+        let chunks = chunk::split_text(text, max_tokens);
+        info!("文本过长，分 {} 段翻译", chunks.len());
+        let mut translations = Vec::with_capacity(chunks.len());
+        let mut provider = String::new();
+        for (i, piece) in chunks.iter().enumerate() {
+            let reply = self
+                .translate_chunk(&piece.text, context)
+                .await
+                .with_context(|| format!("第 {}/{} 段翻译失败", i + 1, chunks.len()))?;
+            if provider.is_empty() {
+                provider = reply.provider;
+            }
+            translations.push(reply.content);
+        }
+        Ok(ApiReply {
+            content: chunk::join_translations(&chunks, &translations, self.language.sentence_separator()),
+            provider,
+        })
+    }
+
+    /// 单次 API 调用翻译一段文本
+    async fn translate_chunk(&self, text: &str, context: &str) -> Result<ApiReply> {
         let system_prompt = format!(
-            "你是一位专业的学术翻译专家。请将以下英文学术{context}翻译为中文。\n\
+            "你是一位专业的学术翻译专家。请将以下英文学术{context}翻译为{language}。\n\
              翻译要求：\n\
              1. 保持学术风格，翻译准确流畅\n\
-             2. 专业术语保留英文原文（用括号标注），如：卷积神经网络（CNN）\n\
+             2. {terminology}\n\
              3. 不要翻译LaTeX公式、数学符号、人名\n\
              4. 不要添加任何解释，只输出翻译结果",
-            context = context
+            context = context,
+            language = self.language.name(),
+            terminology = self.terminology_rule(text),
         );
 
         let request = ChatRequest {
+            purpose: "translate_text",
             model: self.config.model.clone(),
             messages: vec![
                 ChatMessage {
@@ -95,23 +324,45 @@ impl Translator {
                 },
             ],
             temperature: 0.3,
+            response_format: None,
         };
 
-        self.call_api(&request).await
+        let mut reply = self.call_api(&request).await?;
+        reply.content = reply.content.trim().to_string();
+        self.check_glossary(text, &reply.content);
+        Ok(reply)
     }
 
-    /// 翻译论文标题和摘要（单次 API 调用）
-    pub async fn translate_paper(&self, title: &str, abstract_text: &str) -> Result<(String, String)> {
-        let system_prompt = "你是一位专业的学术翻译专家。请将英文学术论文的标题和摘要翻译为中文。\n\
+    /// 翻译论文标题和摘要：通常一次 API 调用完成；
+    /// 超过 `max_chunk_tokens` 时标题单独翻译，摘要按句子分段翻译
+    pub async fn translate_paper(&self, title: &str, abstract_text: &str) -> Result<TranslatedPaper> {
+        // 去掉 PDF 或 API 返回文本中的硬换行和断词，避免译文在原换行处断句
+        let title = &normalizer::normalize(title).replace('\n', " ");
+        let abstract_text = &normalizer::normalize(abstract_text);
+        let source_text = format!("{}\n{}", title, abstract_text);
+
+        let max_tokens = self.config.max_chunk_tokens;
+        if max_tokens > 0 && chunk::estimate_tokens(&source_text) > max_tokens {
+            let title_reply = self.translate_chunk(title, "论文标题").await?;
+            let abstract_reply = self.translate_text(abstract_text, "论文摘要").await?;
+            return Ok(TranslatedPaper {
+                title: title_reply.content,
+                abstract_text: abstract_reply.content,
+                provider: title_reply.provider,
+            });
+        }
+
+        let system_prompt = format!(
+            "你是一位专业的学术翻译专家。请将英文学术论文的标题和摘要翻译为{language}。\n\
              翻译要求：\n\
              1. 保持学术风格，翻译准确流畅\n\
-             2. 专业术语保留英文原文（用括号标注），如：卷积神经网络（CNN）\n\
+             2. {terminology}\n\
              3. 不要翻译LaTeX公式、数学符号、人名\n\
-             4. 请严格按以下格式输出，不要添加其他内容：\n\
-             [标题翻译]\n\
-             翻译后的标题\n\
-             [摘要翻译]\n\
-             翻译后的摘要";
+             4. 只输出一个 JSON 对象，不要添加其他内容：\n\
+             {{\"title\": \"翻译后的标题\", \"abstract\": \"翻译后的摘要\"}}",
+            language = self.language.name(),
+            terminology = self.terminology_rule(&source_text),
+        );
 
         let user_content = format!(
             "请翻译以下论文：\n\n标题：{title}\n\n摘要：{abstract_text}",
@@ -120,6 +371,154 @@ impl Translator {
         );
 
         let request = ChatRequest {
+            purpose: "translate_paper",
+            model: self.config.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: system_prompt,
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: user_content,
+                },
+            ],
+            temperature: 0.3,
+            response_format: self.config.json_mode.then_some(ResponseFormat::JSON_OBJECT),
+        };
+
+        let reply = self.call_api(&request).await?;
+        let response = reply.content;
+
+        // 优先按 JSON 解析，模型未遵守时退回标记分割
+        let (title_zh, abstract_zh) = match parse_json_translation(&response) {
+            Some(parsed) => parsed,
+            None => {
+                let (title_marker, abstract_marker) = self.language.markers();
+                parse_translation_response(&response, title, &title_marker, &abstract_marker)
+            }
+        };
+        self.check_glossary(&source_text, &format!("{}\n{}", title_zh, abstract_zh));
+        Ok(TranslatedPaper {
+            title: title_zh,
+            abstract_text: abstract_zh,
+            provider: reply.provider,
+        })
+    }
+
+    /// 根据标题和摘要判断论文是否符合订阅意图（爬取阶段的低成本初筛）
+    ///
+    /// 只有模型明确回答不相关时才返回 `false`，格式不符时保守地保留论文。
+    pub async fn triage_paper(
+        &self,
+        subscription_context: &str,
+        title: &str,
+        abstract_text: &str,
+        model: Option<&str>,
+    ) -> Result<bool> {
+        let system_prompt = "你是一位科研文献筛选助手。根据用户的研究方向，判断给出的论文是否可能相关。\n\
+             判断要求：\n\
+             1. 只剔除明显无关的论文，拿不准时视为相关\n\
+             2. 只输出 YES 或 NO，不要输出其他内容";
+
+        let user_content = format!(
+            "研究方向：\n{subscription_context}\n论文标题：{title}\n\n论文摘要：{abstract_text}\n\n这篇论文是否与研究方向相关？",
+            subscription_context = subscription_context,
+            title = title,
+            abstract_text = abstract_text,
+        );
+
+        let request = ChatRequest {
+            purpose: "triage",
+            model: model
+                .filter(|m| !m.is_empty())
+                .unwrap_or(&self.config.model)
+                .to_string(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: system_prompt.to_string(),
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: user_content,
+                },
+            ],
+            temperature: 0.0,
+            response_format: None,
+        };
+
+        let response = self.call_api(&request).await?.content;
+        Ok(parse_triage_response(&response))
+    }
+
+    /// 按订阅的研究重点给论文打相关度标签，示例由订阅关键词生成
+    pub async fn classify_relevance(
+        &self,
+        subscription_context: &str,
+        keywords: &[String],
+        title: &str,
+        abstract_text: &str,
+    ) -> Result<Option<RelevanceLabel>> {
+        let keyword = keywords.first().map(|k| k.as_str()).unwrap_or("the topic");
+        let system_prompt = format!(
+            "你是一位科研文献分类助手。根据用户的研究方向，判断论文与研究重点的关系，输出以下标签之一：\n\
+             CORE：论文直接研究该方向的核心问题\n\
+             RELATED：论文涉及该方向，但只是作为工具、背景或次要部分\n\
+             OFF_TOPIC：论文与该方向无关，只是偶然出现相同词汇\n\
+             示例：\n\
+             - 标题 \"Improving {keyword} with a new training objective\" → CORE\n\
+             - 标题 \"A benchmark for medical imaging, evaluated with {keyword} baselines\" → RELATED\n\
+             - 标题 \"Soil moisture estimation from satellite data\" → OFF_TOPIC\n\
+             只输出标签，不要输出其他内容",
+            keyword = keyword,
+        );
+
+        let user_content = format!(
+            "研究方向：\n{subscription_context}\n论文标题：{title}\n\n论文摘要：{abstract_text}",
+            subscription_context = subscription_context,
+            title = title,
+            abstract_text = abstract_text,
+        );
+
+        let request = ChatRequest {
+            purpose: "relevance",
+            model: self.config.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: system_prompt,
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: user_content,
+                },
+            ],
+            temperature: 0.0,
+            response_format: None,
+        };
+
+        let response = self.call_api(&request).await?.content;
+        Ok(parse_relevance_response(&response))
+    }
+
+    /// 为订阅关键词生成同义词和常见缩写，用于扩展爬虫查询
+    pub async fn expand_keywords(&self, subscription_context: &str, keywords: &[String]) -> Result<Vec<String>> {
+        let system_prompt = "你是一位科研文献检索专家。请为给定的英文检索关键词补充同义词、常见缩写和全称，\
+             用于在 arXiv 上提高检索召回率。\n\
+             要求：\n\
+             1. 只输出英文检索词，每行一个，不要编号和解释\n\
+             2. 不要重复已有关键词，不要输出过于宽泛的词\n\
+             3. 最多输出 8 个";
+
+        let user_content = format!(
+            "{subscription_context}\n已有关键词：\n{keywords}",
+            subscription_context = subscription_context,
+            keywords = keywords.join("\n"),
+        );
+
+        let request = ChatRequest {
+            purpose: "expand_keywords",
             model: self.config.model.clone(),
             messages: vec![
                 ChatMessage {
@@ -131,36 +530,149 @@ impl Translator {
                     content: user_content,
                 },
             ],
+            temperature: 0.2,
+            response_format: None,
+        };
+
+        let response = self.call_api(&request).await?.content;
+        Ok(parse_expansion_response(&response, keywords))
+    }
+
+    /// 根据标题、摘要和正文节选总结 3–5 条关键发现，使用目标语言输出
+    ///
+    /// 提供订阅上下文时，要点优先覆盖与订阅研究问题相关的内容。
+    pub async fn summarize_key_points(
+        &self,
+        subscription_context: Option<&str>,
+        title: &str,
+        abstract_text: &str,
+        excerpt: &str,
+    ) -> Result<KeyPoints> {
+        let focus_rule = if subscription_context.is_some() {
+            "\n4. 优先总结与读者研究方向相关的发现"
+        } else {
+            ""
+        };
+        let system_prompt = format!(
+            "你是一位科研论文分析专家。请阅读论文的标题、摘要和正文节选，总结论文的关键发现。\n\
+             要求：\n\
+             1. 输出 3 到 5 条要点，每行一条，不要编号和额外解释\n\
+             2. 每条要点一句话，突出方法、结果或结论，尽量包含具体数据\n\
+             3. 使用{language}输出，{terminology}{focus_rule}",
+            language = self.language.name(),
+            terminology = self.terminology_rule(abstract_text),
+            focus_rule = focus_rule,
+        );
+
+        let mut user_content = String::new();
+        if let Some(context) = subscription_context {
+            user_content.push_str(&format!("读者的研究方向：\n{}\n", context));
+        }
+        user_content.push_str(&format!(
+            "标题：{title}\n\n摘要：{abstract_text}\n\n正文节选：\n{excerpt}",
+            title = title,
+            abstract_text = abstract_text,
+            excerpt = excerpt,
+        ));
+
+        let request = ChatRequest {
+            purpose: "key_points",
+            model: self.config.model.clone(),
+            messages: vec![
+                ChatMessage {
+                    role: "system".to_string(),
+                    content: system_prompt,
+                },
+                ChatMessage {
+                    role: "user".to_string(),
+                    content: user_content,
+                },
+            ],
             temperature: 0.3,
+            response_format: None,
         };
 
-        let response = self.call_api(&request).await?;
+        let reply = self.call_api(&request).await?;
+        Ok(KeyPoints {
+            points: parse_key_points_response(&reply.content),
+            provider: reply.provider,
+        })
+    }
 
-        // 解析结构化响应
-        let (title_zh, abstract_zh) = parse_translation_response(&response, title);
-        Ok((title_zh, abstract_zh))
+    /// 向每个已配置的服务商发送一次极短的请求，检查 API key 和模型是否可用；不重试、不使用缓存，
+    /// 返回服务商标签和响应耗时
+    pub async fn ping(&self) -> Vec<(String, Result<std::time::Duration>)> {
+        let mut results = Vec::new();
+        for endpoint in self.endpoints.iter().filter(|e| e.is_configured()) {
+            let request = ChatRequest {
+                purpose: "ping",
+                model: endpoint.model.clone(),
+                messages: vec![ChatMessage {
+                    role: "user".to_string(),
+                    content: "只回复 ok".to_string(),
+                }],
+                temperature: 0.0,
+                response_format: None,
+            };
+            let started = std::time::Instant::now();
+            let result = self.do_request(endpoint, &request).await.map(|_| started.elapsed());
+            results.push((endpoint.label(&endpoint.model), result));
+        }
+        results
     }
 
-    /// 调用 MiniMax API，带重试逻辑
-    async fn call_api(&self, request: &ChatRequest) -> Result<String> {
-        let mut last_error = None;
+    /// 调用 LLM API，先查缓存，带重试逻辑；主服务商持续返回 429/5xx 时切换备用服务商
+    async fn call_api(&self, request: &ChatRequest) -> Result<ApiReply> {
+        let cache_key = self.cache.as_ref().map(|_| cache_key(request));
+        if let (Some(db), Some(key), false) = (&self.cache, &cache_key, self.refresh_cache) {
+            for (index, endpoint) in self.endpoints.iter().enumerate() {
+                let model = self.model_for(index, request);
+                match db.get_cached_translation(&endpoint.name, model, key).await {
+                    Ok(Some(cached)) => {
+                        info!("命中翻译缓存");
+                        return Ok(ApiReply {
+                            content: cached,
+                            provider: endpoint.label(model),
+                        });
+                    }
+                    Ok(None) => {}
+                    Err(e) => warn!("读取翻译缓存失败: {}", e),
+                }
+            }
+        }
 
-        for attempt in 0..3 {
-            if attempt > 0 {
-                let delay = std::time::Duration::from_millis(500 * 2u64.pow(attempt as u32));
-                info!("API 重试 ({}/3)，等待 {}ms...", attempt + 1, delay.as_millis());
-                tokio::time::sleep(delay).await;
+        let mut last_error = None;
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            if index > 0 {
+                warn!("切换到备用服务商: {}", endpoint.name);
             }
 
-            match self.do_request(request).await {
+            let mut endpoint_request = request.clone();
+            endpoint_request.model = self.model_for(index, request).to_string();
+
+            match self.call_api_uncached(endpoint, &endpoint_request).await {
                 Ok(content) => {
-                    // 速率限制：每次调用后等待 500ms
-                    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
-                    return Ok(content);
+                    if let (Some(db), Some(key)) = (&self.cache, &cache_key) {
+                        if !content.trim().is_empty() {
+                            if let Err(e) = db
+                                .save_cached_translation(&endpoint.name, &endpoint_request.model, key, &content)
+                                .await
+                            {
+                                warn!("写入翻译缓存失败: {}", e);
+                            }
+                        }
+                    }
+                    return Ok(ApiReply {
+                        content,
+                        provider: endpoint.label(&endpoint_request.model),
+                    });
                 }
                 Err(e) => {
-                    warn!("API 调用失败 (尝试 {}/3): {}", attempt + 1, e);
+                    let transient = is_transient(&e);
                     last_error = Some(e);
+                    if !transient {
+                        break;
+                    }
                 }
             }
         }
@@ -168,11 +680,91 @@ impl Translator {
         Err(last_error.unwrap_or_else(|| anyhow::anyhow!("API 调用失败")))
     }
 
-    async fn do_request(&self, request: &ChatRequest) -> Result<String> {
+    /// 主服务商沿用请求中的模型（可能是初筛模型），备用服务商使用各自配置的模型
+    fn model_for<'a>(&'a self, index: usize, request: &'a ChatRequest) -> &'a str {
+        if index == 0 {
+            &request.model
+        } else {
+            &self.endpoints[index].model
+        }
+    }
+
+    async fn call_api_uncached(&self, endpoint: &Endpoint, request: &ChatRequest) -> Result<String> {
+        let mut last_error = None;
+
+        for attempt in 0..MAX_ATTEMPTS {
+            endpoint.rate_limiter.acquire().await;
+            let started = std::time::Instant::now();
+            let result = self.do_request(endpoint, request).await;
+            self.log_usage(endpoint, request, &result, started.elapsed()).await;
+            match result {
+                Ok(completion) => return Ok(completion.content),
+                Err(e) => {
+                    warn!("[{}] API 调用失败 (尝试 {}/{}): {}", endpoint.name, attempt + 1, MAX_ATTEMPTS, e);
+                    // 认证失败、请求格式错误等重试也不会成功
+                    if !is_retryable(&e) {
+                        return Err(e);
+                    }
+                    if attempt + 1 < MAX_ATTEMPTS {
+                        let delay = retry_delay(&e, attempt);
+                        info!("API 重试 ({}/{})，等待 {}ms...", attempt + 2, MAX_ATTEMPTS, delay.as_millis());
+                        tokio::time::sleep(delay).await;
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow::anyhow!("API 调用失败")))
+    }
+
+    /// 写入 api_calls，失败只记录警告
+    async fn log_usage(
+        &self,
+        endpoint: &Endpoint,
+        request: &ChatRequest,
+        result: &Result<Completion>,
+        elapsed: std::time::Duration,
+    ) {
+        let Some(db) = &self.usage_log else { return };
+        let mut call = ApiCall {
+            provider: endpoint.name.clone(),
+            model: request.model.clone(),
+            purpose: request.purpose.to_string(),
+            duration_ms: elapsed.as_millis() as i64,
+            ..Default::default()
+        };
+        match result {
+            Ok(completion) => {
+                call.success = true;
+                call.prompt_tokens = completion.prompt_tokens;
+                call.completion_tokens = completion.completion_tokens;
+            }
+            Err(e) => {
+                call.http_status = e.downcast_ref::<HttpStatusError>().map(|http| http.status.as_u16() as i64);
+                // 连接错误的完整链条很长，只保留最外层说明和根本原因
+                let root = e.root_cause().to_string();
+                let message = e.to_string();
+                call.error = Some(if root == message { message } else { format!("{}: {}", message, root) });
+            }
+        }
+        if let Err(e) = db.record_api_call(&call).await {
+            warn!("记录 API 调用失败: {}", e);
+        }
+    }
+
+    async fn do_request(&self, endpoint: &Endpoint, request: &ChatRequest) -> Result<Completion> {
+        match endpoint.provider {
+            Provider::OpenAiCompatible => self.do_openai_request(endpoint, request).await,
+            Provider::Ollama => self.do_ollama_request(endpoint, request).await,
+        }
+    }
+
+    async fn do_openai_request(&self, endpoint: &Endpoint, request: &ChatRequest) -> Result<Completion> {
         let response = self
             .client
-            .post(&self.config.api_url)
-            .header("Authorization", format!("Bearer {}", self.config.api_key))
+            .post(&endpoint.api_url)
+            .header("Authorization", format!("Bearer {}", endpoint.api_key))
             .header("Content-Type", "application/json")
             .json(request)
             .send()
@@ -181,8 +773,9 @@ impl Translator {
 
         let status = response.status();
         if !status.is_success() {
+            let retry_after = parse_retry_after(response.headers());
             let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("API 返回错误 {}: {}", status, body);
+            return Err(HttpStatusError { status, body, retry_after }.into());
         }
 
         let chat_response: ChatResponse = response
@@ -190,6 +783,7 @@ impl Translator {
             .await
             .context("解析 API 响应失败")?;
 
+        let usage = chat_response.usage;
         let content = chat_response
             .choices
             .into_iter()
@@ -197,23 +791,170 @@ impl Translator {
             .map(|c| c.message.content)
             .unwrap_or_default();
 
-        Ok(content)
+        Ok(Completion {
+            content,
+            prompt_tokens: usage.as_ref().and_then(|u| u.prompt_tokens),
+            completion_tokens: usage.as_ref().and_then(|u| u.completion_tokens),
+        })
+    }
+
+    async fn do_ollama_request(&self, endpoint: &Endpoint, request: &ChatRequest) -> Result<Completion> {
+        let url = format!("{}/api/chat", endpoint.ollama_host.trim_end_matches('/'));
+        let body = OllamaChatRequest {
+            model: &request.model,
+            messages: &request.messages,
+            stream: false,
+            options: OllamaOptions {
+                temperature: request.temperature,
+            },
+            format: request.response_format.map(|_| "json"),
+        };
+
+        let response = self
+            .client
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .with_context(|| format!("连接 Ollama 失败: {}", url))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = parse_retry_after(response.headers());
+            let body = response.text().await.unwrap_or_default();
+            return Err(HttpStatusError { status, body, retry_after }.into());
+        }
+
+        let chat_response: OllamaChatResponse = response
+            .json()
+            .await
+            .context("解析 Ollama 响应失败")?;
+
+        Ok(Completion {
+            content: chat_response.message.content,
+            prompt_tokens: chat_response.prompt_eval_count,
+            completion_tokens: chat_response.eval_count,
+        })
+    }
+}
+
+/// 单个服务商的最大尝试次数
+const MAX_ATTEMPTS: u32 = 3;
+/// Retry-After 等待上限，避免单次请求被挂起过久
+const MAX_RETRY_AFTER: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// 是否值得重试：400 类错误（429、408 除外）重试也不会成功
+fn is_retryable(error: &anyhow::Error) -> bool {
+    match error.downcast_ref::<HttpStatusError>() {
+        Some(http) => {
+            http.status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                || http.status == reqwest::StatusCode::REQUEST_TIMEOUT
+                || !http.status.is_client_error()
+        }
+        None => true,
     }
 }
 
-/// 解析 translate_paper 的结构化响应
-fn parse_translation_response(response: &str, fallback_title: &str) -> (String, String) {
+/// 重试等待时间：优先遵守 Retry-After，否则指数退避；再叠加最多 50% 的随机抖动，
+/// 避免并发请求在同一时刻重试
+fn retry_delay(error: &anyhow::Error, attempt: u32) -> std::time::Duration {
+    let base = error
+        .downcast_ref::<HttpStatusError>()
+        .and_then(|http| http.retry_after)
+        .unwrap_or_else(|| std::time::Duration::from_millis(1000 * 2u64.pow(attempt)));
+    let jitter_ms = fastrand::u64(0..=(base.as_millis() as u64 / 2).max(1));
+    base + std::time::Duration::from_millis(jitter_ms)
+}
+
+/// 解析 Retry-After 头：支持秒数和 HTTP 日期两种格式
+fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<std::time::Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    let delay = match value.parse::<u64>() {
+        Ok(seconds) => std::time::Duration::from_secs(seconds),
+        Err(_) => {
+            let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+            (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default()
+        }
+    };
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+/// 限流、服务端错误和连接失败视为暂时性故障，可切换备用服务商
+fn is_transient(error: &anyhow::Error) -> bool {
+    if let Some(http) = error.downcast_ref::<HttpStatusError>() {
+        return http.status == reqwest::StatusCode::TOO_MANY_REQUESTS || http.status.is_server_error();
+    }
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .map(|e| e.is_connect() || e.is_timeout())
+            .unwrap_or(false)
+    })
+}
+
+/// 缓存键：对全部消息内容和温度取 SHA-256，提示词或目标语言变化时自然失效
+fn cache_key(request: &ChatRequest) -> String {
+    let mut text = format!("temperature={}\n", request.temperature);
+    for message in &request.messages {
+        text.push_str(&message.role);
+        text.push('\n');
+        text.push_str(&message.content);
+        text.push('\n');
+    }
+    sha256_hex(text.as_bytes())
+}
+
+/// translate_paper 的 JSON 输出
+#[derive(Deserialize)]
+struct PaperTranslation {
+    title: String,
+    #[serde(rename = "abstract")]
+    abstract_text: String,
+}
+
+/// 按 JSON 解析 translate_paper 的响应，容忍推理内容和 Markdown 代码块包裹
+fn parse_json_translation(response: &str) -> Option<(String, String)> {
+    let answer = match response.rfind("</think>") {
+        Some(pos) => &response[pos + "</think>".len()..],
+        None => response,
+    };
+    let start = answer.find('{')?;
+    let end = answer.rfind('}')?;
+    if end <= start {
+        return None;
+    }
+
+    let parsed: PaperTranslation = serde_json::from_str(&answer[start..=end]).ok()?;
+    let title = parsed.title.trim();
+    let abstract_text = parsed.abstract_text.trim();
+    if title.is_empty() || abstract_text.is_empty() {
+        return None;
+    }
+    Some((title.to_string(), abstract_text.to_string()))
+}
+
+/// 按标题/摘要标记解析 translate_paper 的响应（JSON 解析失败时的备选）
+fn parse_translation_response(
+    response: &str,
+    fallback_title: &str,
+    title_marker: &str,
+    abstract_marker: &str,
+) -> (String, String) {
     let response = response.trim();
 
-    // 尝试按 [标题翻译] / [摘要翻译] 标记分割
+    // 尝试按标题/摘要标记分割
     if let (Some(title_start), Some(abstract_start)) = (
-        response.find("[标题翻译]"),
-        response.find("[摘要翻译]"),
+        response.find(title_marker),
+        response.find(abstract_marker),
     ) {
-        let title_zh = response[title_start + "[标题翻译]".len()..abstract_start]
+        if title_start >= abstract_start {
+            warn!("翻译响应标记顺序异常，使用整体响应");
+            return (format!("{} {}", fallback_title, FALLBACK_MARKER), response.to_string());
+        }
+        let title_zh = response[title_start + title_marker.len()..abstract_start]
             .trim()
             .to_string();
-        let abstract_zh = response[abstract_start + "[摘要翻译]".len()..]
+        let abstract_zh = response[abstract_start + abstract_marker.len()..]
             .trim()
             .to_string();
 
@@ -225,7 +966,165 @@ fn parse_translation_response(response: &str, fallback_title: &str) -> (String,
     // 备选方案：如果格式不对，将整个响应作为摘要翻译，标题单独处理
     warn!("翻译响应格式不符预期，使用整体响应");
     (
-        format!("{} (翻译失败，请重试)", fallback_title),
+        format!("{} {}", fallback_title, FALLBACK_MARKER),
         response.to_string(),
     )
 }
+
+/// 解析初筛响应：仅在明确回答 NO 时判定为不相关
+fn parse_triage_response(response: &str) -> bool {
+    // 部分模型会先输出 <think>...</think> 推理内容，只看最后的结论
+    let answer = match response.rfind("</think>") {
+        Some(pos) => &response[pos + "</think>".len()..],
+        None => response,
+    };
+    let answer = answer.trim().trim_start_matches(['*', '`', '"', '\'']).to_uppercase();
+    !(answer.starts_with("NO") || answer.starts_with("否") || answer.starts_with("不相关"))
+}
+
+/// 解析要点：每行一条，去掉项目符号和编号，最多保留 5 条
+fn parse_key_points_response(response: &str) -> Vec<String> {
+    let answer = match response.rfind("</think>") {
+        Some(pos) => &response[pos + "</think>".len()..],
+        None => response,
+    };
+
+    answer
+        .lines()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | ')' | '、' | '-' | '*' | '•'))
+                .trim()
+                .to_string()
+        })
+        .filter(|line| !line.is_empty())
+        .take(5)
+        .collect()
+}
+
+/// 解析相关度标签，无法识别时返回 `None`
+fn parse_relevance_response(response: &str) -> Option<RelevanceLabel> {
+    let answer = match response.rfind("</think>") {
+        Some(pos) => &response[pos + "</think>".len()..],
+        None => response,
+    };
+    let answer = answer.trim().trim_start_matches(['*', '`', '"', '\'']).to_uppercase();
+    if answer.starts_with("CORE") {
+        Some(RelevanceLabel::Core)
+    } else if answer.starts_with("RELATED") {
+        Some(RelevanceLabel::Related)
+    } else if answer.starts_with("OFF_TOPIC") || answer.starts_with("OFF-TOPIC") || answer.starts_with("OFF TOPIC") {
+        Some(RelevanceLabel::OffTopic)
+    } else {
+        None
+    }
+}
+
+/// 解析扩展检索词：每行一个，去掉编号/符号，排除已有关键词
+fn parse_expansion_response(response: &str, existing: &[String]) -> Vec<String> {
+    let answer = match response.rfind("</think>") {
+        Some(pos) => &response[pos + "</think>".len()..],
+        None => response,
+    };
+
+    let mut seen: std::collections::HashSet<String> =
+        existing.iter().map(|k| k.trim().to_lowercase()).collect();
+    let mut terms = Vec::new();
+    for line in answer.lines() {
+        let term = line
+            .trim()
+            .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | ')' | '-' | '*' | '•'))
+            .trim()
+            .trim_matches(|c: char| matches!(c, '"' | '\'' | '`' | ','))
+            .trim();
+        if term.is_empty() || term.len() > 60 || !term.is_ascii() {
+            continue;
+        }
+        if seen.insert(term.to_lowercase()) {
+            terms.push(term.to_string());
+        }
+        if terms.len() >= 8 {
+            break;
+        }
+    }
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    fn retry_after(value: &str) -> Option<Duration> {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+        parse_retry_after(&headers)
+    }
+
+    fn http_error(status: StatusCode, retry_after: Option<Duration>) -> anyhow::Error {
+        HttpStatusError { status, body: String::new(), retry_after }.into()
+    }
+
+    #[test]
+    fn parses_retry_after_seconds_and_dates() {
+        assert_eq!(retry_after("30"), Some(Duration::from_secs(30)));
+        assert_eq!(retry_after("3600"), Some(MAX_RETRY_AFTER));
+        assert_eq!(retry_after("not a date"), None);
+        assert_eq!(parse_retry_after(&HeaderMap::new()), None);
+
+        // 已过去的日期不需要等待
+        assert_eq!(retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), Some(Duration::ZERO));
+        let future = (chrono::Utc::now() + chrono::Duration::seconds(60)).to_rfc2822();
+        let delay = retry_after(&future).unwrap();
+        assert!(delay > Duration::from_secs(55) && delay <= Duration::from_secs(60), "{:?}", delay);
+        let far_future = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc2822();
+        assert_eq!(retry_after(&far_future), Some(MAX_RETRY_AFTER));
+    }
+
+    #[test]
+    fn client_errors_are_not_retried() {
+        assert!(!is_retryable(&http_error(StatusCode::BAD_REQUEST, None)));
+        assert!(!is_retryable(&http_error(StatusCode::UNAUTHORIZED, None)));
+        assert!(is_retryable(&http_error(StatusCode::REQUEST_TIMEOUT, None)));
+        assert!(is_retryable(&http_error(StatusCode::TOO_MANY_REQUESTS, None)));
+        assert!(is_retryable(&http_error(StatusCode::BAD_GATEWAY, None)));
+        assert!(is_retryable(&anyhow::anyhow!("连接被重置")));
+    }
+
+    #[test]
+    fn retry_delay_prefers_retry_after_and_adds_jitter() {
+        let error = http_error(StatusCode::TOO_MANY_REQUESTS, Some(Duration::from_secs(10)));
+        for attempt in 0..3 {
+            let delay = retry_delay(&error, attempt);
+            assert!(delay >= Duration::from_secs(10) && delay <= Duration::from_secs(15), "{:?}", delay);
+        }
+
+        // 没有 Retry-After 时指数退避：1s、2s、4s 起
+        let error = http_error(StatusCode::SERVICE_UNAVAILABLE, None);
+        for (attempt, base) in [(0, 1000), (1, 2000), (2, 4000)] {
+            let delay = retry_delay(&error, attempt).as_millis() as u64;
+            assert!(delay >= base && delay <= base * 3 / 2, "{}", delay);
+        }
+    }
+
+    #[test]
+    fn parses_relevance_labels_after_thinking_and_markup() {
+        assert_eq!(parse_relevance_response("<think>摘要提到了 CORE 方法</think>\nRELATED"), Some(RelevanceLabel::Related));
+        assert_eq!(parse_relevance_response("**CORE**"), Some(RelevanceLabel::Core));
+        assert_eq!(parse_relevance_response("`core`"), Some(RelevanceLabel::Core));
+        assert_eq!(parse_relevance_response("\"RELATED\""), Some(RelevanceLabel::Related));
+        assert_eq!(parse_relevance_response("'related' - 方法相近"), Some(RelevanceLabel::Related));
+    }
+
+    #[test]
+    fn parses_off_topic_variants() {
+        for response in ["OFF_TOPIC", "off-topic", "Off topic.", "**OFF_TOPIC**"] {
+            assert_eq!(parse_relevance_response(response), Some(RelevanceLabel::OffTopic), "{}", response);
+        }
+        assert_eq!(parse_relevance_response("不确定"), None);
+        assert_eq!(parse_relevance_response("<think>CORE</think>"), None);
+        assert_eq!(parse_relevance_response(""), None);
+    }
+}
@@ -0,0 +1,113 @@
+use crate::config::{FallbackProviderConfig, TranslatorConfig};
+use crate::utils::rate_limiter::RateLimiter;
+
+/// LLM 服务提供方
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    /// OpenAI 兼容的 chat/completions 接口（MiniMax、OpenAI 等）
+    OpenAiCompatible,
+    /// 本地 Ollama 服务，无需 API key
+    Ollama,
+}
+
+impl Provider {
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "ollama" => Provider::Ollama,
+            _ => Provider::OpenAiCompatible,
+        }
+    }
+
+    /// 是否需要配置 API key
+    pub fn requires_api_key(&self) -> bool {
+        !matches!(self, Provider::Ollama)
+    }
+
+    /// 默认每分钟请求上限，本地服务不限流
+    pub fn default_requests_per_minute(&self) -> u32 {
+        match self {
+            Provider::OpenAiCompatible => 60,
+            Provider::Ollama => 0,
+        }
+    }
+}
+
+/// 一个可调用的服务端点：主服务商或备用服务商，各自独立限流
+pub struct Endpoint {
+    /// 配置中的服务商名称，用于缓存键和记录译文来源
+    pub name: String,
+    pub provider: Provider,
+    pub api_key: String,
+    pub api_url: String,
+    pub model: String,
+    pub ollama_host: String,
+    pub rate_limiter: RateLimiter,
+}
+
+impl Endpoint {
+    pub fn primary(config: &TranslatorConfig) -> Self {
+        Self::build(
+            &config.api_provider,
+            &config.api_key,
+            &config.api_url,
+            &config.model,
+            &config.ollama_host,
+            config.requests_per_minute,
+            config.max_concurrent,
+        )
+    }
+
+    pub fn fallback(config: &FallbackProviderConfig, max_concurrent: usize) -> Self {
+        Self::build(
+            &config.api_provider,
+            &config.api_key,
+            &config.api_url,
+            &config.model,
+            &config.ollama_host,
+            config.requests_per_minute,
+            max_concurrent,
+        )
+    }
+
+    fn build(
+        name: &str,
+        api_key: &str,
+        api_url: &str,
+        model: &str,
+        ollama_host: &str,
+        requests_per_minute: u32,
+        max_concurrent: usize,
+    ) -> Self {
+        let provider = Provider::from_name(name);
+        let requests_per_minute = if requests_per_minute > 0 {
+            requests_per_minute
+        } else {
+            provider.default_requests_per_minute()
+        };
+        Self {
+            name: name.to_string(),
+            provider,
+            api_key: api_key.to_string(),
+            api_url: api_url.to_string(),
+            model: model.to_string(),
+            ollama_host: ollama_host.to_string(),
+            rate_limiter: RateLimiter::new(requests_per_minute, max_concurrent),
+        }
+    }
+
+    /// 模型和必要的 API key 都已填写
+    pub fn is_configured(&self) -> bool {
+        if self.model.trim().is_empty() {
+            return false;
+        }
+        if !self.provider.requires_api_key() {
+            return true;
+        }
+        !self.api_key.is_empty() && self.api_key != "your-api-key"
+    }
+
+    /// 记录译文来源时使用的标签，如 `openai/gpt-4o-mini`
+    pub fn label(&self, model: &str) -> String {
+        format!("{}/{}", self.name, model)
+    }
+}
@@ -0,0 +1,250 @@
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use std::path::Path;
+
+use crate::config::validate::{self, Severity};
+use crate::config::{dirs, AppConfig, ParserConfig};
+use crate::crawler::{ArxivCrawler, Fetcher};
+use crate::parser::Rasterizer;
+use crate::storage::migrations;
+use crate::translator::{HttpStatusError, Translator};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    /// 可以运行，但部分功能受影响
+    Warn,
+    Fail,
+}
+
+/// 一项环境检查的结果
+#[derive(Debug, Serialize)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// 未通过时的处理建议
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Pass, detail: detail.into(), hint: None }
+    }
+
+    fn warn(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Warn, detail: detail.into(), hint: Some(hint.into()) }
+    }
+
+    fn fail(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self { name: name.into(), status: CheckStatus::Fail, detail: detail.into(), hint: Some(hint.into()) }
+    }
+}
+
+/// 检查配置、目录权限和数据库；`check_network` 时再调用翻译服务并访问 arXiv
+///
+/// 只读检查：不创建目录和数据库，也不执行数据库迁移。
+pub async fn run(check_network: bool) -> Vec<Check> {
+    let mut checks = check_config().await;
+    let config = AppConfig::load().ok();
+
+    let dirs = dirs::get();
+    checks.push(check_dir("配置目录", &dirs.config_dir, false));
+    checks.push(check_dir("数据目录", &dirs.data_dir, true));
+    for (name, path) in [("PDF目录", dirs.papers_dir()), ("图片目录", dirs.images_dir()), ("报告目录", dirs.reports_dir())] {
+        checks.push(check_dir(name, &path, true));
+    }
+
+    let Some(config) = config else {
+        return checks;
+    };
+    checks.push(check_database(&config.storage.database_file()).await);
+    if let Some(check) = check_rasterizer(&config.parser) {
+        checks.push(check);
+    }
+    if check_network {
+        checks.extend(check_translator(&config).await);
+        checks.push(check_arxiv(&config).await);
+    }
+    checks
+}
+
+async fn check_config() -> Vec<Check> {
+    let issues = validate::validate(false).await;
+    if issues.is_empty() {
+        return vec![Check::pass("配置", "settings.toml 和 keywords.toml 可以正常加载")];
+    }
+    issues
+        .into_iter()
+        .map(|issue| {
+            let name = format!("配置 {}", issue.key);
+            let hint = if issue.key == "settings.toml" {
+                "运行 init 生成配置文件".to_string()
+            } else {
+                format!("修改 {} 或运行 config set", dirs::get().settings_file().display())
+            };
+            match issue.severity {
+                Severity::Error => Check::fail(name, issue.message, hint),
+                Severity::Warning => Check::warn(name, issue.message, hint),
+            }
+        })
+        .collect()
+}
+
+/// 目录不存在时检查能否在最近的上级目录中创建
+fn check_dir(name: &str, path: &Path, writable: bool) -> Check {
+    if !path.exists() {
+        let parent = path.ancestors().skip(1).find(|p| p.exists());
+        return match parent {
+            Some(parent) if !writable || is_writable(parent) => {
+                Check::pass(name, format!("{} 尚不存在，首次使用时创建", path.display()))
+            }
+            _ => Check::fail(
+                name,
+                format!("{} 不存在且无法创建", path.display()),
+                "检查上级目录的权限，或用 --data-dir 指定其他位置",
+            ),
+        };
+    }
+    if !path.is_dir() {
+        return Check::fail(name, format!("{} 不是目录", path.display()), "删除或改名该文件");
+    }
+    if writable && !is_writable(path) {
+        return Check::fail(
+            name,
+            format!("{} 不可写", path.display()),
+            format!("检查权限，如 chmod u+w {}", path.display()),
+        );
+    }
+    Check::pass(name, path.display().to_string())
+}
+
+/// 实际写入并删除一个临时文件，比检查权限位更可靠（只读挂载、ACL 等）
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".bsxbot-doctor-{}", std::process::id()));
+    let ok = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    ok
+}
+
+async fn check_database(path: &Path) -> Check {
+    const NAME: &str = "数据库";
+    if !path.exists() {
+        return Check::warn(NAME, format!("{} 不存在", path.display()), "运行 init 创建数据库");
+    }
+    match inspect_database(path).await {
+        Ok((version, integrity)) => {
+            let latest = migrations::latest_version();
+            if integrity != "ok" {
+                Check::fail(
+                    NAME,
+                    format!("完整性检查未通过: {}", integrity),
+                    "从最近的数据库备份（db backup 生成）恢复",
+                )
+            } else if version > latest {
+                Check::fail(
+                    NAME,
+                    format!("结构版本 {} 高于当前程序支持的 {}", version, latest),
+                    "升级 bsxbot",
+                )
+            } else if version < latest {
+                Check::warn(
+                    NAME,
+                    format!("结构版本 {}，当前程序为 {}", version, latest),
+                    "下次运行任意命令时自动迁移，可先运行 db backup 备份",
+                )
+            } else {
+                Check::pass(NAME, format!("{}（结构版本 {}）", path.display(), version))
+            }
+        }
+        Err(e) => Check::fail(
+            NAME,
+            format!("无法打开 {}: {}", path.display(), e),
+            "检查文件权限，确认没有其他程序独占该文件",
+        ),
+    }
+}
+
+/// 以只读方式打开，返回结构版本和 `PRAGMA quick_check` 的结果
+async fn inspect_database(path: &Path) -> Result<(i64, String)> {
+    let options = SqliteConnectOptions::new().filename(path).read_only(true);
+    let pool = SqlitePoolOptions::new().max_connections(1).connect_with(options).await?;
+    let has_versions: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'schema_version')",
+    )
+    .fetch_one(&pool)
+    .await?;
+    let version = if has_versions { migrations::current_version(&pool).await? } else { 0 };
+    let integrity: String = sqlx::query_scalar("PRAGMA quick_check").fetch_one(&pool).await?;
+    pool.close().await;
+    Ok((version, integrity))
+}
+
+/// 开启矢量图栅格化时检查渲染方式：pdfium（以 pdfium 特性编译时）或外部命令（默认 poppler 的 pdftoppm），未开启时不检查
+fn check_rasterizer(config: &ParserConfig) -> Option<Check> {
+    const NAME: &str = "栅格化";
+    if !config.rasterize_figures {
+        return None;
+    }
+    let rasterizer = Rasterizer::new(config);
+    Some(if rasterizer.is_available() {
+        Check::pass(NAME, rasterizer.backend_name())
+    } else {
+        Check::warn(
+            NAME,
+            format!("无法运行 {}，矢量图不会被提取", rasterizer.backend_name()),
+            "安装 poppler-utils（如 apt install poppler-utils、brew install poppler），\
+             或设置 parser.rasterizer_command 为 pdftoppm 的完整路径；\
+             也可以用 cargo build --features pdfium 编译并安装 libpdfium",
+        )
+    })
+}
+
+/// 每个已配置的服务商调用一次；未配置的服务商已在配置检查中提示
+async fn check_translator(config: &AppConfig) -> Vec<Check> {
+    let translator = Translator::new(config.translator.clone());
+    translator
+        .ping()
+        .await
+        .into_iter()
+        .map(|(label, result)| {
+            let name = format!("翻译服务 {}", label);
+            match result {
+                Ok(elapsed) => Check::pass(name, format!("响应正常（{}ms）", elapsed.as_millis())),
+                Err(e) => {
+                    let hint = match e.downcast_ref::<HttpStatusError>().map(|http| http.status.as_u16()) {
+                        Some(401 | 403) => "API key 无效或已过期，用 config set translator.api_key 更新",
+                        Some(404) => "检查 api_url 和模型名称",
+                        Some(429) => "请求过于频繁或额度已用完，稍后重试或检查账户余额",
+                        Some(_) => "服务端暂时不可用，稍后重试",
+                        None => "检查 api_url / ollama_host 和网络代理（translator.proxy）",
+                    };
+                    Check::fail(name, describe(&e), hint)
+                }
+            }
+        })
+        .collect()
+}
+
+async fn check_arxiv(config: &AppConfig) -> Check {
+    const NAME: &str = "arXiv";
+    let arxiv = ArxivCrawler::new(Fetcher::new(&config.crawler));
+    match arxiv.check_reachable().await {
+        Ok(()) => Check::pass(NAME, "API 可以访问"),
+        Err(e) => Check::fail(NAME, describe(&e), "检查网络连接；爬取 arXiv 不经过 translator.proxy"),
+    }
+}
+
+/// 网络错误的完整链条很长，只保留最外层说明和根本原因
+fn describe(error: &anyhow::Error) -> String {
+    let message = error.to_string();
+    let root = error.root_cause().to_string();
+    if root == message {
+        message
+    } else {
+        format!("{}: {}", message, root)
+    }
+}
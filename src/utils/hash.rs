@@ -0,0 +1,24 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::Path;
+
+/// 计算字节内容的 SHA-256（小写十六进制）
+pub fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// 流式计算文件的 SHA-256，避免一次性读入大文件
+pub fn sha256_file(path: impl AsRef<Path>) -> Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
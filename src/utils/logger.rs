@@ -1,8 +1,43 @@
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::{fmt::writer::BoxMakeWriter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-pub fn init_logger() {
+static LOG_FILE: OnceLock<PathBuf> = OnceLock::new();
+
+/// 初始化日志；指定 `log_file` 时同时追加写入该文件（无颜色）
+///
+/// `to_stderr` 为 true 时终端日志写到标准错误，标准输出留给 JSON 等机器可读的结果。
+pub fn init_logger(log_file: Option<&Path>, to_stderr: bool) {
+    let file_layer = log_file.and_then(|path| {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok()?;
+        }
+        let file = std::fs::OpenOptions::new().create(true).append(true).open(path).ok()?;
+        LOG_FILE.set(path.to_path_buf()).ok();
+        Some(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file)),
+        )
+    });
+
+    let console = if to_stderr { BoxMakeWriter::new(std::io::stderr) } else { BoxMakeWriter::new(std::io::stdout) };
     tracing_subscriber::registry()
         .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| "bsxbot=info".into()))
-        .with(tracing_subscriber::fmt::layer())
+        .with(tracing_subscriber::fmt::layer().with_writer(console))
+        .with(file_layer)
         .init();
 }
+
+/// 当前写入的日志文件
+pub fn log_file() -> Option<&'static Path> {
+    LOG_FILE.get().map(|p| p.as_path())
+}
+
+/// 日志文件当前的行数，用于标记一段日志的起止位置
+pub fn log_line_count() -> usize {
+    log_file()
+        .and_then(|path| std::fs::read(path).ok())
+        .map(|bytes| bytes.iter().filter(|&&b| b == b'\n').count())
+        .unwrap_or(0)
+}
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use lettre::AsyncTransport;
+use tracing::{info, warn};
+
+use super::smtp;
+use crate::config::NotifierConfig;
+
+/// 通过配置的渠道（邮件、Webhook）发送告警
+pub struct Notifier {
+    client: reqwest::Client,
+    config: NotifierConfig,
+}
+
+impl Notifier {
+    pub fn new(config: NotifierConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self { client, config }
+    }
+
+    fn email_enabled(&self) -> bool {
+        self.config.smtp.is_configured() && !self.config.email_to.is_empty()
+    }
+
+    fn webhook_enabled(&self) -> bool {
+        !self.config.webhook_url.trim().is_empty()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.email_enabled() || self.webhook_enabled()
+    }
+
+    /// 发送到所有已配置的渠道，全部失败时返回错误
+    pub async fn send(&self, subject: &str, body: &str) -> Result<()> {
+        let mut sent = 0;
+        let mut last_error = None;
+
+        if self.email_enabled() {
+            match self.send_email(subject, body).await {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    warn!("邮件通知发送失败: {:#}", e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        if self.webhook_enabled() {
+            match self.send_webhook(subject, body).await {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    warn!("Webhook 通知发送失败: {:#}", e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if sent == 0 => Err(e),
+            _ => {
+                info!("已发送通知: {}", subject);
+                Ok(())
+            }
+        }
+    }
+
+    async fn send_email(&self, subject: &str, body: &str) -> Result<()> {
+        let smtp = &self.config.smtp;
+        let message = smtp::message_builder(smtp, &self.config.email_from, &self.config.email_to, subject)?
+            .body(body.to_string())?;
+        smtp::build_transport(smtp)?.send(message).await?;
+        Ok(())
+    }
+
+    /// 以 `{"text": ...}` 发送，兼容 Slack、Mattermost 等 incoming webhook
+    async fn send_webhook(&self, subject: &str, body: &str) -> Result<()> {
+        let payload = serde_json::json!({ "text": format!("{}\n\n{}", subject, body) });
+        let response = self
+            .client
+            .post(&self.config.webhook_url)
+            .json(&payload)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Webhook 返回错误 {}: {}", status, text);
+        }
+        Ok(())
+    }
+}
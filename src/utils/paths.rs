@@ -0,0 +1,127 @@
+use std::path::{Component, Path};
+
+/// 文件名最大字节数，留出扩展名和后缀的余量
+const MAX_FILENAME_BYTES: usize = 200;
+
+/// Windows 保留设备名，不区分大小写，带扩展名同样无效
+const WINDOWS_RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 将任意标识（如 source_id、报告日期）转换为在 Windows 和 Unix 上都合法的单个文件名
+///
+/// 路径分隔符、Windows 非法字符和控制字符替换为 `_`，去掉首尾的点和空格，
+/// 保留设备名加 `_` 前缀，因此结果不会逃出所在目录。
+pub fn safe_filename(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let trimmed = replaced.trim_matches(|c: char| c == '.' || c == ' ');
+    let mut result = if trimmed.is_empty() {
+        "_".to_string()
+    } else {
+        trimmed.to_string()
+    };
+
+    let stem = result.split('.').next().unwrap_or("").to_uppercase();
+    if WINDOWS_RESERVED.contains(&stem.as_str()) {
+        result.insert(0, '_');
+    }
+
+    if result.len() > MAX_FILENAME_BYTES {
+        let mut end = MAX_FILENAME_BYTES;
+        while !result.is_char_boundary(end) {
+            end -= 1;
+        }
+        result.truncate(end);
+    }
+
+    result
+}
+
+/// 校验数据库中保存的相对路径：不能是绝对路径，也不能包含 `..`
+pub fn is_safe_relative_path(path: &str) -> bool {
+    let normalized = path.replace('\\', "/");
+    if normalized.is_empty() || normalized.starts_with('/') || normalized.contains(':') {
+        return false;
+    }
+    Path::new(&normalized)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserved_names_get_prefix() {
+        assert_eq!(safe_filename("CON"), "_CON");
+        assert_eq!(safe_filename("nul.txt"), "_nul.txt");
+        assert_eq!(safe_filename("com1.tar.gz"), "_com1.tar.gz");
+        assert_eq!(safe_filename("CONSOLE"), "CONSOLE");
+    }
+
+    #[test]
+    fn separators_and_parent_dirs_stay_in_directory() {
+        assert_eq!(safe_filename("2301.00001v2"), "2301.00001v2");
+        assert_eq!(safe_filename("hep-th/9901001"), "hep-th_9901001");
+        assert_eq!(safe_filename("a\\b"), "a_b");
+        assert_eq!(safe_filename("../etc/passwd"), "_etc_passwd");
+        assert_eq!(safe_filename(".."), "_");
+        assert_eq!(safe_filename(" . "), "_");
+        assert!(!safe_filename("../../x").contains('/'));
+    }
+
+    #[test]
+    fn drive_prefix_and_illegal_chars() {
+        assert_eq!(safe_filename("C:"), "C_");
+        assert_eq!(safe_filename("C:\\Windows"), "C__Windows");
+        assert_eq!(safe_filename("a*b?c\"d<e>f|g"), "a_b_c_d_e_f_g");
+    }
+
+    #[test]
+    fn control_chars_replaced() {
+        assert_eq!(safe_filename("a\nb\tc\0d"), "a_b_c_d");
+        assert_eq!(safe_filename("x\u{7f}"), "x_");
+    }
+
+    #[test]
+    fn long_names_truncate_on_char_boundary() {
+        let ascii = "a".repeat(300);
+        assert_eq!(safe_filename(&ascii).len(), MAX_FILENAME_BYTES);
+
+        // 每个汉字 3 字节，200 不是 3 的倍数
+        let cjk = "论".repeat(100);
+        let result = safe_filename(&cjk);
+        assert_eq!(result.len(), 198);
+        assert!(result.chars().all(|c| c == '论'));
+    }
+
+    #[test]
+    fn accepts_relative_paths() {
+        assert!(is_safe_relative_path("papers/2301.00001.pdf"));
+        assert!(is_safe_relative_path("./images/a.png"));
+        assert!(is_safe_relative_path("images\\a.png"));
+    }
+
+    #[test]
+    fn rejects_escaping_paths() {
+        assert!(!is_safe_relative_path(""));
+        assert!(!is_safe_relative_path("/etc/passwd"));
+        assert!(!is_safe_relative_path("\\\\server\\share\\a.pdf"));
+        assert!(!is_safe_relative_path("../a.pdf"));
+        assert!(!is_safe_relative_path("papers/../../a.pdf"));
+        assert!(!is_safe_relative_path("papers\\..\\..\\a.pdf"));
+        assert!(!is_safe_relative_path("C:\\a.pdf"));
+        assert!(!is_safe_relative_path("C:a.pdf"));
+    }
+}
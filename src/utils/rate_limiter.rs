@@ -0,0 +1,67 @@
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// 令牌桶限流器：按固定速率补充令牌，允许不超过容量的突发请求
+pub struct RateLimiter {
+    state: Mutex<BucketState>,
+    capacity: f64,
+    /// 每秒补充的令牌数，0 表示不限流
+    refill_per_sec: f64,
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_minute: u32, burst: usize) -> Self {
+        let capacity = burst.max(1) as f64;
+        Self {
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+            capacity,
+            refill_per_sec: requests_per_minute as f64 / 60.0,
+        }
+    }
+
+    /// 按请求间隔构造：每 `interval` 补充一个令牌，0 表示不限流
+    pub fn with_interval(interval: Duration, burst: usize) -> Self {
+        let capacity = burst.max(1) as f64;
+        let secs = interval.as_secs_f64();
+        Self {
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+            capacity,
+            refill_per_sec: if secs > 0.0 { 1.0 / secs } else { 0.0 },
+        }
+    }
+
+    /// 等待直到取得一个令牌
+    pub async fn acquire(&self) {
+        if self.refill_per_sec <= 0.0 {
+            return;
+        }
+
+        loop {
+            let wait = {
+                let mut state = self.state.lock().await;
+                let now = Instant::now();
+                let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+                state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+                state.last_refill = now;
+
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / self.refill_per_sec)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
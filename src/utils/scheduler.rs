@@ -1,27 +1,83 @@
 use tokio_cron_scheduler::{Job, JobScheduler};
 use anyhow::Result;
-use tracing::info;
-use std::sync::Arc;
+use tracing::{info, warn};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use super::logger;
+use super::notifier::Notifier;
+
+/// 连续失败达到该次数时发送告警
+const FAILURE_ALERT_THRESHOLD: usize = 2;
 
 pub struct TaskScheduler {
     scheduler: JobScheduler,
+    notifier: Arc<Notifier>,
+}
+
+/// 一次失败的执行记录
+struct FailureRecord {
+    time: String,
+    error: String,
+    /// 日志文件中本次执行的起止行号
+    log_lines: Option<(usize, usize)>,
 }
 
 impl TaskScheduler {
-    pub async fn new() -> Result<Self> {
+    pub async fn new(notifier: Notifier) -> Result<Self> {
         let scheduler = JobScheduler::new().await?;
-        Ok(Self { scheduler })
+        Ok(Self {
+            scheduler,
+            notifier: Arc::new(notifier),
+        })
     }
 
-    pub async fn add_daily_job<F>(&self, cron_expr: &str, job_fn: Arc<F>) -> Result<()>
+    /// 添加定时任务；任务连续失败两次时通过通知渠道告警，成功后重新计数
+    pub async fn add_daily_job<F, Fut>(&self, name: &str, cron_expr: &str, job_fn: F) -> Result<()>
     where
-        F: Fn() + Send + Sync + 'static,
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<()>> + Send + 'static,
     {
+        let job_fn = Arc::new(job_fn);
+        let name = name.to_string();
+        let notifier = Arc::clone(&self.notifier);
+        let failures: Arc<Mutex<Vec<FailureRecord>>> = Arc::new(Mutex::new(Vec::new()));
+
         let job = Job::new_async(cron_expr, move |_uuid, _lock| {
             let job_fn = Arc::clone(&job_fn);
+            let name = name.clone();
+            let notifier = Arc::clone(&notifier);
+            let failures = Arc::clone(&failures);
             Box::pin(async move {
-                info!("执行定时任务");
-                job_fn();
+                info!("执行定时任务: {}", name);
+                let start_line = logger::log_line_count();
+                let result = job_fn().await;
+
+                let alert = match result {
+                    Ok(()) => {
+                        failures.lock().unwrap().clear();
+                        None
+                    }
+                    Err(e) => {
+                        warn!("定时任务 {} 失败: {:#}", name, e);
+                        let mut failures = failures.lock().unwrap();
+                        failures.push(FailureRecord {
+                            time: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                            error: format!("{:#}", e),
+                            log_lines: logger::log_file().map(|_| (start_line + 1, logger::log_line_count())),
+                        });
+                        // 只在刚达到阈值时告警，持续失败不重复发送
+                        (failures.len() == FAILURE_ALERT_THRESHOLD).then(|| failure_alert(&name, &failures))
+                    }
+                };
+
+                if let Some((subject, body)) = alert {
+                    if !notifier.is_enabled() {
+                        warn!("未配置通知渠道，无法发送告警: {}", subject);
+                    } else if let Err(e) = notifier.send(&subject, &body).await {
+                        warn!("告警发送失败: {:#}", e);
+                    }
+                }
             })
         })?;
 
@@ -41,3 +97,16 @@ impl TaskScheduler {
         Ok(())
     }
 }
+
+/// 告警标题和正文：每次失败的时间、错误和对应的日志片段
+fn failure_alert(name: &str, failures: &[FailureRecord]) -> (String, String) {
+    let subject = format!("[bsxbot] 定时任务 {} 连续失败 {} 次", name, failures.len());
+    let mut body = format!("定时任务 {} 已连续失败 {} 次，请检查配置（如 API key）和网络。\n", name, failures.len());
+    for (i, failure) in failures.iter().enumerate() {
+        body.push_str(&format!("\n#{} {}\n错误: {}\n", i + 1, failure.time, failure.error));
+        if let (Some(path), Some((start, end))) = (logger::log_file(), failure.log_lines) {
+            body.push_str(&format!("日志: file://{} 第 {}-{} 行\n", path.display(), start, end));
+        }
+    }
+    (subject, body)
+}
@@ -0,0 +1,32 @@
+use anyhow::{Context, Result};
+use lettre::message::{Mailbox, MessageBuilder};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, Message, Tokio1Executor};
+
+use crate::config::SmtpConfig;
+
+/// 按配置连接 SMTP 服务器（STARTTLS 或隐式 TLS），配置了用户名时登录
+pub fn build_transport(config: &SmtpConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+    let mut transport = if config.smtp_starttls {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?
+    }
+    .port(config.smtp_port);
+    if !config.smtp_user.is_empty() {
+        transport = transport.credentials(Credentials::new(config.smtp_user.clone(), config.smtp_password.clone()));
+    }
+    Ok(transport.build())
+}
+
+/// 填好发件人、收件人和标题的邮件；`from` 留空时使用 smtp_user
+pub fn message_builder(config: &SmtpConfig, from: &str, to: &[String], subject: impl Into<String>) -> Result<MessageBuilder> {
+    let from = if from.trim().is_empty() { &config.smtp_user } else { from };
+    let mut builder = Message::builder()
+        .from(from.parse::<Mailbox>().with_context(|| format!("发件地址无效: {}", from))?)
+        .subject(subject);
+    for to in to {
+        builder = builder.to(to.parse::<Mailbox>().with_context(|| format!("收件地址无效: {}", to))?);
+    }
+    Ok(builder)
+}
@@ -0,0 +1,41 @@
+/// 终端中的显示宽度：中日韩文字和全角符号占两列，其余占一列
+pub fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if is_wide(c) { 2 } else { 1 }).sum()
+}
+
+/// 截断或补空格到恰好 `width` 列，截断时以 `…` 结尾
+pub fn fit_width(text: &str, width: usize) -> String {
+    let text_width = display_width(text);
+    if text_width <= width {
+        return format!("{}{}", text, " ".repeat(width - text_width));
+    }
+
+    let mut fitted = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = if is_wide(c) { 2 } else { 1 };
+        // 留一列给省略号
+        if used + w + 1 > width {
+            break;
+        }
+        fitted.push(c);
+        used += w;
+    }
+    fitted.push('…');
+    used += 1;
+    fitted.push_str(&" ".repeat(width.saturating_sub(used)));
+    fitted
+}
+
+fn is_wide(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{115F}'
+            | '\u{2E80}'..='\u{A4CF}'
+            | '\u{AC00}'..='\u{D7A3}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FE30}'..='\u{FE4F}'
+            | '\u{FF00}'..='\u{FF60}'
+            | '\u{FFE0}'..='\u{FFE6}'
+    )
+}